@@ -0,0 +1,193 @@
+use std::fmt::Write;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use log::LevelFilter;
+use re2shared::record::*;
+use serde::Deserialize;
+
+pub const CONFIG_PATH: &str = "re2fr.toml";
+
+/// Which parts of the game state get written to the recording file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RecordSubsystems {
+    pub characters: bool,
+    pub objects: bool,
+    pub rng: bool,
+    pub sounds: bool,
+    /// Wall-clock frame times, for finding lag
+    pub timing: bool,
+    /// Transforms of the model parts re2line uses for collision (the dog's head, G2's arm)
+    pub model_parts: bool,
+    /// Also record character fields that are usually noise, for researching enemy behavior
+    pub verbose_characters: bool,
+}
+
+impl RecordSubsystems {
+    /// Drop anything from the frame that isn't supposed to be recorded
+    pub fn filter(&self, frame_record: &mut FrameRecord) {
+        if !self.characters {
+            frame_record.character_diffs.clear();
+            frame_record.game_changes.retain(|f| !matches!(f, GameField::RoomSpawns { .. }));
+        }
+        if !self.objects {
+            frame_record.object_diffs.clear();
+        }
+        if !self.timing {
+            frame_record.game_changes.retain(|f| !matches!(f, GameField::FrameTime(_)));
+        }
+        if !self.rng {
+            // keep the roll count so the RNG position stays in sync
+            frame_record.game_changes.retain(|f| !matches!(f, GameField::RngRoll(..) | GameField::KnownRng { .. } | GameField::CharacterRng { .. }));
+        }
+        if !self.sounds {
+            frame_record.game_changes.retain(|f| !matches!(f, GameField::SoundFlags(_)));
+        }
+        if !self.model_parts {
+            for diff in frame_record.character_diffs.iter_mut().chain(frame_record.object_diffs.iter_mut()) {
+                diff.changes.retain(|f| !matches!(f, CharacterField::ModelPartTransform(..)));
+            }
+            // don't leave behind diffs that no longer change anything
+            frame_record.character_diffs.retain(|d| !d.changes.is_empty());
+            frame_record.object_diffs.retain(|d| !d.changes.is_empty());
+        }
+    }
+
+    /// Header flags describing what the recording contains
+    pub const fn header_flags(&self) -> u16 {
+        let mut flags = 0;
+        if !self.characters {
+            flags |= HEADER_FLAG_NO_CHARACTERS;
+        }
+        if !self.objects {
+            flags |= HEADER_FLAG_NO_OBJECTS;
+        }
+        if !self.rng {
+            flags |= HEADER_FLAG_NO_RNG;
+        }
+        if !self.sounds {
+            flags |= HEADER_FLAG_NO_SOUNDS;
+        }
+        if !self.timing {
+            flags |= HEADER_FLAG_NO_TIMING;
+        }
+        if !self.model_parts {
+            flags |= HEADER_FLAG_NO_MODEL_PARTS;
+        }
+        if self.verbose_characters {
+            flags |= HEADER_FLAG_VERBOSE_CHARACTERS;
+        }
+        flags
+    }
+}
+
+impl Default for RecordSubsystems {
+    fn default() -> Self {
+        Self {
+            characters: true,
+            objects: true,
+            rng: true,
+            sounds: true,
+            timing: true,
+            model_parts: true,
+            verbose_characters: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarkerHotkey {
+    pub key: i32,
+    pub name: String,
+}
+
+/// Hotkeys for controlling the recorder in-game, as Windows virtual key codes
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    pub pause: Option<i32>,
+    pub new_file: Option<i32>,
+    pub markers: Vec<MarkerHotkey>,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            pause: Some(0x76), // F7
+            new_file: Some(0x78), // F9
+            markers: vec![MarkerHotkey { key: 0x77, name: String::from("Marker") }], // F8
+        }
+    }
+}
+
+/// Recorder settings read from re2fr.toml in the game directory. Every setting is optional.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RecorderConfig {
+    /// Folder recordings are written to, relative to the game directory
+    pub output_dir: PathBuf,
+    /// Recording file name without the extension, with strftime-style date and time placeholders
+    pub filename_template: String,
+    pub log_level: String,
+    /// Start a new file each time a game is started or loaded rather than appending to the day's file
+    pub split_per_run: bool,
+    /// How often to record the full game state so a damaged file can be read from the next one.
+    /// Zero disables keyframes.
+    pub keyframe_interval: usize,
+    /// How often to make sure recorded frames are on disk, in frames, so a crash loses as little as
+    /// possible. Zero leaves it up to the OS.
+    pub flush_interval: usize,
+    pub record: RecordSubsystems,
+    pub hotkeys: HotkeyConfig,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("."),
+            filename_template: String::from("re2fr_%Y-%m-%d"),
+            log_level: String::from("info"),
+            split_per_run: false,
+            keyframe_interval: 900, // every 30 seconds
+            flush_interval: 30, // every second
+            record: RecordSubsystems::default(),
+            hotkeys: HotkeyConfig::default(),
+        }
+    }
+}
+
+impl RecorderConfig {
+    /// Load the config file, using the defaults if there isn't one
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(toml::from_str(&text)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn log_level(&self) -> LevelFilter {
+        LevelFilter::from_str(&self.log_level).unwrap_or(LevelFilter::Info)
+    }
+
+    /// Path of the recording file to use at the given time. A suffix distinguishes files that
+    /// would otherwise have the same name.
+    pub fn recording_path(&self, now: &DateTime<Local>, suffix: Option<&str>) -> Result<PathBuf> {
+        let mut name = String::new();
+        // chrono reports invalid placeholders as a formatting error
+        write!(name, "{}", now.format(&self.filename_template))
+            .map_err(|_| anyhow!("Invalid recording filename template {:?}", self.filename_template))?;
+        if let Some(suffix) = suffix {
+            name.push('_');
+            name.push_str(suffix);
+        }
+        name.push_str(".bin");
+
+        Ok(self.output_dir.join(name))
+    }
+}
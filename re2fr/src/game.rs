@@ -29,7 +29,7 @@ pub struct GameVersion {
     pub script_rng_seed: usize,
     pub sound_flags: usize,
     pub game_flags2: usize,
-    pub known_rng_rolls: [(usize, RollType); 127],
+    pub known_rng_rolls: &'static [(usize, RollType)],
 }
 
 const GAME_VERSIONS: [GameVersion; 1] = [
@@ -57,7 +57,7 @@ const GAME_VERSIONS: [GameVersion; 1] = [
         script_rng_seed: 0x00695e58,
         sound_flags: 0x00989eee,
         game_flags2: 0x00989e6c,
-        known_rng_rolls: [
+        known_rng_rolls: &[
             (0x004e3be1, RollType::Script),
             (0x00451be7, RollType::ZombieStaggerThreshold),
             (0x00451c70, RollType::ZombieStaggerThresholdHard),
@@ -231,7 +231,8 @@ pub struct Game {
 }
 
 impl Game {
-    pub unsafe fn init() -> Result<Self> {
+    /// Identify the running executable by where the RDT path template was loaded
+    unsafe fn detect_version() -> Result<&'static GameVersion> {
         // find the address of the RDT string in memory
         let [Some(rdt_path_addr)] = ByteSearcher::find_bytes_anywhere(&[RDT_STRING], None) else {
             bail!("Could not identify RE2 version: failed to find RDT string");
@@ -241,50 +242,53 @@ impl Game {
         let rdt_path_addr = rdt_path_addr as usize;
         for version in &GAME_VERSIONS {
             log::debug!("Checking version {}", version.version_name);
-            if version.rdt_path_template != rdt_path_addr {
-                continue;
+            if version.rdt_path_template == rdt_path_addr {
+                return Ok(version);
             }
-
-            log::info!("Found RE2 version: {}", version.version_name);
-            let characters = version.char_array as *const *const Character;
-            let dummy_char = version.dummy_char as *const Character;
-            let current_char = version.current_char as *const *const Character;
-            let objects = version.obj_array as *const Character;
-            let last_obj = version.last_obj as *const *const Character;
-            let rng_seed = version.rng_seed as *const u32;
-            let keys_down = version.keys_down as *const u32;
-            let keys_down_this_frame = version.keys_down_this_frame as *const u32;
-            let igt_seconds = version.igt_seconds as *const u32;
-            let igt_frames = version.igt_frames as *const u8;
-            let stage_index = version.stage_index as *const u16;
-            let room_index = version.room_index as *const u16;
-            let stage_offset = version.stage_offset as *const u32;
-            let game_flags = version.game_flags as *const u32;
-            let sound_flags = version.sound_flags as *const u8;
-            let game_flags2 = version.game_flags2 as *const u32;
-
-            return Ok(Self {
-                version,
-                characters,
-                dummy_char,
-                current_char,
-                objects,
-                last_obj,
-                rng_seed,
-                keys_down,
-                keys_down_this_frame,
-                igt_seconds,
-                igt_frames,
-                stage_index,
-                room_index,
-                stage_offset,
-                game_flags,
-                sound_flags,          
-                game_flags2,   
-            });
         }
 
-        bail!("Unsupported RE2 version (RDT address {:08X})", rdt_path_addr);
+        bail!("Unsupported RE2 version (RDT address {:08X})", rdt_path_addr)
+    }
+
+    pub unsafe fn init() -> Result<Self> {
+        let version = unsafe { Self::detect_version() }?;
+        log::info!("Found RE2 version: {}", version.version_name);
+        let characters = version.char_array as *const *const Character;
+        let dummy_char = version.dummy_char as *const Character;
+        let current_char = version.current_char as *const *const Character;
+        let objects = version.obj_array as *const Character;
+        let last_obj = version.last_obj as *const *const Character;
+        let rng_seed = version.rng_seed as *const u32;
+        let keys_down = version.keys_down as *const u32;
+        let keys_down_this_frame = version.keys_down_this_frame as *const u32;
+        let igt_seconds = version.igt_seconds as *const u32;
+        let igt_frames = version.igt_frames as *const u8;
+        let stage_index = version.stage_index as *const u16;
+        let room_index = version.room_index as *const u16;
+        let stage_offset = version.stage_offset as *const u32;
+        let game_flags = version.game_flags as *const u32;
+        let sound_flags = version.sound_flags as *const u8;
+        let game_flags2 = version.game_flags2 as *const u32;
+
+        Ok(Self {
+            version,
+            characters,
+            dummy_char,
+            current_char,
+            objects,
+            last_obj,
+            rng_seed,
+            keys_down,
+            keys_down_this_frame,
+            igt_seconds,
+            igt_frames,
+            stage_index,
+            room_index,
+            stage_offset,
+            game_flags,
+            sound_flags,          
+            game_flags2,
+        })
     }
 
     pub fn version(&self) -> &'static GameVersion {
@@ -398,7 +402,7 @@ impl Game {
     }
     
     pub fn known_rng_rolls(&self) -> &'static [(usize, RollType)] {
-        &self.version.known_rng_rolls
+        self.version.known_rng_rolls
     }
     
     pub fn current_char_index(&self) -> Option<usize> {
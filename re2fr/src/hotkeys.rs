@@ -0,0 +1,66 @@
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
+use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+use crate::config::HotkeyConfig;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyAction {
+    TogglePause,
+    Marker(String),
+    NewFile,
+}
+
+/// Polls the keyboard once per frame for the recorder's hotkeys. Keys are only picked up while the
+/// game window has focus, and each press triggers its action once.
+#[derive(Debug)]
+pub struct Hotkeys {
+    bindings: Vec<(i32, HotkeyAction)>,
+    held: Vec<bool>,
+}
+
+impl Hotkeys {
+    pub fn new(config: &HotkeyConfig) -> Self {
+        let mut bindings = Vec::new();
+        if let Some(key) = config.pause {
+            bindings.push((key, HotkeyAction::TogglePause));
+        }
+        if let Some(key) = config.new_file {
+            bindings.push((key, HotkeyAction::NewFile));
+        }
+        for marker in &config.markers {
+            // the name's length is stored in a byte
+            let mut name = marker.name.clone();
+            while name.len() > u8::MAX as usize {
+                name.pop();
+            }
+            bindings.push((marker.key, HotkeyAction::Marker(name)));
+        }
+
+        let held = vec![false; bindings.len()];
+        Self { bindings, held }
+    }
+
+    fn is_game_focused() -> bool {
+        let mut process_id = 0u32;
+        unsafe {
+            GetWindowThreadProcessId(GetForegroundWindow(), Some(&mut process_id));
+            process_id == GetCurrentProcessId()
+        }
+    }
+
+    /// Actions for hotkeys that were pressed since the last poll
+    pub fn poll(&mut self) -> Vec<HotkeyAction> {
+        let is_focused = Self::is_game_focused();
+        let mut actions = Vec::new();
+        for ((key, action), held) in self.bindings.iter().zip(self.held.iter_mut()) {
+            let is_down = is_focused && unsafe { GetAsyncKeyState(*key) } as u16 & 0x8000 != 0;
+            if is_down && !*held {
+                actions.push(action.clone());
+            }
+            *held = is_down;
+        }
+
+        actions
+    }
+}
@@ -1,26 +1,42 @@
 use std::ffi::c_void;
-use std::fs::File;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Cursor, Seek, SeekFrom, Write};
 use std::ops::DerefMut;
 use std::path::Path;
 use std::sync::{OnceLock, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
-use binrw::BinWriterExt;
-use chrono::Local;
+use binrw::{BinReaderExt, BinWriterExt};
+use chrono::{Datelike, Local, Timelike};
 use hook86::asm;
 use hook86::mem;
 use hook86::patch::patch;
 use log::LevelFilter;
-use re2shared::record::{GameField, RecordHeader};
+use re2shared::checksum::crc32_update;
+use re2shared::record::{FrameRecord, GameField, RecordHeader, HEADER_FLAG_CHECKSUM, HEADER_FLAG_SESSION_FOOTERS, RECORD_VERSION};
 use simplelog::{Config, WriteLogger};
 use windows::Win32::Foundation::HMODULE;
 use windows::Win32::System::SystemServices::{DLL_PROCESS_ATTACH, DLL_PROCESS_DETACH};
 
+mod config;
+use config::*;
 mod game;
 use game::*;
+mod hotkeys;
+use hotkeys::*;
+mod live;
+use live::*;
 mod record;
 use record::*;
 
+// how often to write a checksum of the preceding frames
+const CHECKSUM_INTERVAL: usize = 30;
+// the game logic runs at 30 FPS
+const TARGET_FRAME_DURATION: Duration = Duration::from_micros(1000000 / 30);
+// gaps longer than this are assumed to be the game being paused or minimized rather than lag
+const MAX_LAG_GAP: Duration = Duration::from_secs(1);
+
 patch! {
     pub RngTrack = [
         call tracker // we'll also grab the caller address off the stack
@@ -40,13 +56,32 @@ patch! {
 }
 
 struct FlightRecorder {
+    config: RecorderConfig,
     game: Game,
     tracker: GameTracker,
     file: Option<File>,
+    live: Option<LiveStream>,
     rng_track: RngTrack,
     frame_tick: FrameTick,
+    hotkeys: Hotkeys,
     rng_calls: Vec<GameField>,
+    storage: StorageTracker,
+    pending_markers: Vec<String>,
+    // loads, new games, and room spawns that happened while paused
+    pending_events: Vec<GameField>,
+    is_paused: bool,
     is_in_game: bool,
+    is_session_start: bool,
+    checksum: u32,
+    frames_since_checksum: usize,
+    frames_since_keyframe: usize,
+    frames_since_flush: usize,
+    // frames written in this session and the file offset of each keyframe, for the session footer
+    session_frames: u32,
+    keyframe_offsets: Vec<u32>,
+    last_tick: Option<Instant>,
+    // stage and room index of the last frame, for noticing when the player enters a room
+    last_room: Option<(u16, u16)>,
 }
 
 impl FlightRecorder {
@@ -77,9 +112,99 @@ impl FlightRecorder {
         Ok(())
     }
 
+    /// Wall-clock time since the previous tick, or None on the first tick after a break
+    fn measure_frame_time(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        self.last_tick.replace(now).map(|last_tick| now - last_tick)
+    }
+
+    /// How many whole frames were dropped in the given time between ticks
+    fn lag_frames(elapsed: Duration) -> u8 {
+        if elapsed > MAX_LAG_GAP {
+            return 0;
+        }
+
+        // round to the nearest frame so normal timing jitter isn't counted as lag
+        let frames = (elapsed.as_secs_f64() / TARGET_FRAME_DURATION.as_secs_f64()).round() as usize;
+        frames.saturating_sub(1).min(u8::MAX as usize) as u8
+    }
+
+    fn handle_hotkey(&mut self, action: HotkeyAction) -> Result<()> {
+        match action {
+            HotkeyAction::TogglePause => {
+                self.is_paused = !self.is_paused;
+                log::info!("Recording {}", if self.is_paused { "paused" } else { "resumed" });
+            }
+            HotkeyAction::Marker(name) => {
+                log::info!("Adding marker \"{}\"", name);
+                self.pending_markers.push(name);
+            }
+            HotkeyAction::NewFile => self.start_new_file()?,
+        }
+
+        Ok(())
+    }
+
     pub fn record_frame(&mut self) -> Result<()> {
+        for action in self.hotkeys.poll() {
+            self.handle_hotkey(action)?;
+        }
+
         if !self.game.is_in_game() {
             self.is_in_game = false;
+            self.last_tick = None;
+            return Ok(());
+        }
+
+        let frame_time = self.measure_frame_time();
+        let lag_frames = frame_time.map_or(0, Self::lag_frames);
+
+        let mut events = self.storage.update(&self.game);
+        let mut is_run_start = events.iter().any(|e| matches!(e, GameField::GameLoaded));
+        if !self.is_in_game {
+            events.push(GameField::NewGame);
+            self.is_in_game = true;
+            is_run_start = true;
+        }
+        if is_run_start && self.config.split_per_run && !self.is_session_start {
+            self.start_new_file()?;
+        }
+        // the characters present on the first frame in a room are the ones the init script spawned.
+        // when the recorder starts partway through a room, there's no telling what already changed.
+        let room = (self.game.stage_index(), self.game.room_index());
+        if self.last_room.replace(room).is_some_and(|last_room| last_room != room) {
+            events.push(GameField::RoomSpawns { spawns: enemy_spawns(&self.game) });
+        }
+
+        let mut extra_changes: Vec<GameField> = self.rng_calls.drain(..).collect();
+        let num_rng_rolls = extra_changes.len() as u16;
+        extra_changes.extend(events.iter().cloned());
+        if lag_frames > 0 {
+            extra_changes.push(GameField::LagFrames(lag_frames));
+        }
+        if let Some(frame_time) = frame_time {
+            extra_changes.push(GameField::FrameTime(frame_time.as_micros().min(u32::MAX as u128) as u32));
+        }
+        // hold on to markers placed while paused until there's a frame to put them in
+        if !self.is_paused {
+            extra_changes.extend(self.pending_markers.drain(..).map(|name| GameField::Marker { name }));
+        }
+
+        if let Some(ref mut live) = self.live {
+            live.send_frame(&self.game, num_rng_rolls, &extra_changes);
+        }
+
+        // the tracker isn't updated while paused, so the first frame after resuming picks up
+        // everything that changed in the meantime
+        if self.is_paused {
+            // like markers, events are held on to until there's a frame to put them in
+            for event in events {
+                // spawns only matter for the room the player is in when recording resumes
+                if matches!(event, GameField::RoomSpawns { .. }) {
+                    self.pending_events.retain(|e| !matches!(e, GameField::RoomSpawns { .. }));
+                }
+                self.pending_events.push(event);
+            }
             return Ok(());
         }
 
@@ -88,18 +213,109 @@ impl FlightRecorder {
             return Ok(());
         };
 
-        let mut frame_record = self.tracker.track_delta(&self.game);
-        frame_record.num_rng_rolls = self.rng_calls.len() as u16;
-        frame_record.game_changes.extend(self.rng_calls.drain(..));
-        if !self.is_in_game {
-            frame_record.game_changes.push(GameField::NewGame);
-            self.is_in_game = true;       
+        // a session start already records everything and resets the reader's state
+        let is_keyframe = !self.is_session_start && self.config.keyframe_interval > 0
+            && self.frames_since_keyframe >= self.config.keyframe_interval;
+        let mut frame_record = self.tracker.track_delta(&self.game, self.is_session_start, is_keyframe);
+        if self.is_session_start || is_keyframe {
+            self.frames_since_keyframe = 0;
+        }
+        self.frames_since_keyframe += 1;
+        if self.is_session_start {
+            let now = Local::now();
+            frame_record.game_changes.insert(0, GameField::SessionStart {
+                year: now.year() as u16,
+                month: now.month() as u8,
+                day: now.day() as u8,
+                hour: now.hour() as u8,
+                minute: now.minute() as u8,
+                second: now.second() as u8,
+            });
+            self.is_session_start = false;
+        }
+        frame_record.num_rng_rolls = num_rng_rolls;
+        frame_record.game_changes.extend(self.pending_events.drain(..));
+        frame_record.game_changes.extend(extra_changes);
+        // the live stream isn't affected by this
+        self.config.record.filter(&mut frame_record);
+        // a reader that skipped damage picks its checks back up at the next keyframe, so each
+        // keyframe starts a new checksum chain
+        if self.frames_since_checksum >= CHECKSUM_INTERVAL || (is_keyframe && self.frames_since_checksum > 0) {
+            frame_record.game_changes.push(GameField::Checksum(self.checksum));
+            self.checksum = 0;
+            self.frames_since_checksum = 0;
         }
-        file.write_le(&frame_record)?;
+
+        if is_keyframe {
+            self.keyframe_offsets.push(file.metadata()?.len() as u32);
+        }
+
+        let mut buf = Cursor::new(Vec::new());
+        buf.write_le(&frame_record)?;
+        let buf = buf.into_inner();
+        file.write_all(&buf)?;
+        self.checksum = crc32_update(self.checksum, &buf);
+        self.frames_since_checksum += 1;
+        self.session_frames += 1;
+
+        self.frames_since_flush += 1;
+        if self.config.flush_interval > 0 && self.frames_since_flush >= self.config.flush_interval {
+            file.sync_data()?;
+            self.frames_since_flush = 0;
+        }
+        Ok(())
+    }
+
+    /// Write the footer that marks the session as having been closed cleanly
+    fn finish_session(&mut self) -> Result<()> {
+        let Some(ref mut file) = self.file else {
+            return Ok(());
+        };
+
+        // checksum whatever was written since the last one so the whole session is covered
+        let mut game_changes = Vec::new();
+        if self.frames_since_checksum > 0 {
+            game_changes.push(GameField::Checksum(self.checksum));
+        }
+        game_changes.push(GameField::SessionEnd {
+            num_frames: self.session_frames,
+            keyframe_offsets: std::mem::take(&mut self.keyframe_offsets),
+        });
+
+        let footer = FrameRecord {
+            igt_seconds: self.game.igt_seconds(),
+            igt_frames: self.game.igt_frames(),
+            num_rng_rolls: 0,
+            game_changes,
+            character_diffs: Vec::new(),
+            object_diffs: Vec::new(),
+        };
+
+        let mut buf = Cursor::new(Vec::new());
+        buf.write_le(&footer)?;
+        file.write_all(&buf.into_inner())?;
+        file.sync_all()?;
+        self.session_frames = 0;
+        self.checksum = 0;
+        self.frames_since_checksum = 0;
+        Ok(())
+    }
+
+    /// Cut the recording, continuing in a fresh file
+    fn start_new_file(&mut self) -> Result<()> {
+        self.finish_session()?;
+        self.file = Some(open_recording_file(&self.config, false)?);
+        self.is_session_start = true;
+        self.checksum = 0;
+        self.frames_since_checksum = 0;
+        self.frames_since_flush = 0;
         Ok(())
     }
 
     pub fn close(&mut self) {
+        if let Err(e) = self.finish_session() {
+            log::error!("Failed to finish recording session: {e}");
+        }
         self.file = None;
     }
 }
@@ -138,27 +354,82 @@ extern "C" fn frame_tick() {
     }
 }
 
-fn init_recorder() -> Result<()> {
+/// Open the recording file for the current time. If it already exists, a new session is appended
+/// to it if allowed; otherwise, a file just for this session is created.
+fn open_recording_file(config: &RecorderConfig, allow_append: bool) -> Result<File> {
+    let now = Local::now();
+    fs::create_dir_all(&config.output_dir)?;
+    let path = config.recording_path(&now, None)?;
+    let header = RecordHeader::with_flags(HEADER_FLAG_CHECKSUM | HEADER_FLAG_SESSION_FOOTERS | config.record.header_flags());
+
+    let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+    if file.metadata()?.len() == 0 {
+        log::info!("Recording to {}", path.display());
+        file.write_le(&header)?;
+        return Ok(file);
+    }
+
+    if allow_append {
+        file.seek(SeekFrom::Start(0))?;
+        match file.read_le::<RecordHeader>() {
+            Ok(existing) if existing.version == RECORD_VERSION && existing.flags == header.flags => {
+                log::info!("Appending new session to {}", path.display());
+                return Ok(file);
+            }
+            // we can't mix record versions or captured features in one file
+            _ => log::warn!("Existing recording file {} has an incompatible header", path.display()),
+        }
+    }
+
+    // fall back to a file just for this session
+    let path = config.recording_path(&now, Some(&now.format("%H-%M-%S").to_string()))?;
+    log::info!("Recording to {}", path.display());
+    let mut file = File::create(path)?;
+    file.write_le(&header)?;
+    Ok(file)
+}
+
+fn init_recorder(config: RecorderConfig) -> Result<()> {
     log::info!("Initializing recorder");
 
     let game = unsafe { Game::init() }?;
-    let tracker = GameTracker::new(&game);
-
-    // use the current timestamp in the filename to make it unique
-    let now = Local::now();
-    let filename = format!("re2fr_{}.bin", now.format("%Y-%m-%d_%H-%M-%S"));
+    let tracker = GameTracker::new(&game, config.record.verbose_characters);
 
-    let mut file = File::create(filename)?;
-    file.write_le(&RecordHeader::new())?;
+    let file = open_recording_file(&config, !config.split_per_run)?;
+    let hotkeys = Hotkeys::new(&config.hotkeys);
+    // live viewing is optional, so don't fail to record if the port is taken
+    let live = match LiveStream::listen() {
+        Ok(live) => Some(live),
+        Err(e) => {
+            log::warn!("Live viewing unavailable: {e}");
+            None
+        }
+    };
 
     FLIGHT_RECORDER.set(Mutex::new(FlightRecorder {
+        config,
         game,
         tracker,
         file: Some(file),
+        live,
         rng_track: RngTrack::new(),
         frame_tick: FrameTick::new(),
+        hotkeys,
         rng_calls: Vec::new(),
+        storage: StorageTracker::default(),
+        pending_markers: Vec::new(),
+        pending_events: Vec::new(),
+        is_paused: false,
         is_in_game: false,
+        is_session_start: true,
+        checksum: 0,
+        frames_since_checksum: 0,
+        frames_since_keyframe: 0,
+        frames_since_flush: 0,
+        session_frames: 0,
+        keyframe_offsets: Vec::new(),
+        last_tick: None,
+        last_room: None,
     })).map_err(|_| anyhow!("Flight recorder was already initialized"))
 }
 
@@ -184,8 +455,15 @@ fn main(reason: u32) -> Result<()> {
         return Ok(());
     }
 
-    open_log(LevelFilter::Info, "re2fr.log")?;
-    init_recorder()?;
+    let config = RecorderConfig::load(CONFIG_PATH);
+    let log_level = config.as_ref().map(RecorderConfig::log_level).unwrap_or(LevelFilter::Info);
+    open_log(log_level, "re2fr.log")?;
+    let config = config.unwrap_or_else(|e| {
+        log::error!("Failed to load {CONFIG_PATH}; using default settings: {e}");
+        RecorderConfig::default()
+    });
+
+    init_recorder(config)?;
     recorder().apply_patches()
 }
 
@@ -0,0 +1,84 @@
+use std::io::{Cursor, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use anyhow::Result;
+use binrw::BinWriterExt;
+use re2shared::record::{GameField, RecordHeader, LIVE_PORT, RECORD_VERSION};
+
+use crate::game::Game;
+use crate::record::GameTracker;
+
+// don't let a stalled viewer hold up the game
+const WRITE_TIMEOUT: Duration = Duration::from_millis(5);
+
+/// Streams frame records to a connected re2line as the game runs. Only one viewer is connected at
+/// a time, and each viewer gets its own tracker so its first frame has the full game state.
+#[derive(Debug)]
+pub struct LiveStream {
+    listener: TcpListener,
+    client: Option<(TcpStream, GameTracker)>,
+}
+
+impl LiveStream {
+    pub fn listen() -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", LIVE_PORT))?;
+        listener.set_nonblocking(true)?;
+        log::info!("Listening for live viewers on port {}", LIVE_PORT);
+
+        Ok(Self { listener, client: None })
+    }
+
+    fn accept(&mut self, game: &Game) -> Result<bool> {
+        let mut stream = match self.listener.accept() {
+            Ok((stream, addr)) => {
+                log::info!("Live viewer connected from {}", addr);
+                stream
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+        // live frames aren't checksummed
+        let mut buf = Cursor::new(Vec::new());
+        buf.write_le(&RecordHeader { version: RECORD_VERSION, flags: 0 })?;
+        stream.write_all(&buf.into_inner())?;
+
+        // a new viewer replaces the old one
+        self.client = Some((stream, GameTracker::new(game, false)));
+        Ok(true)
+    }
+
+    /// Send the current frame to the viewer, if there is one. `extra_changes` are the changes
+    /// that aren't tracked from the game state, like RNG rolls.
+    pub fn send_frame(&mut self, game: &Game, num_rng_rolls: u16, extra_changes: &[GameField]) {
+        let is_new_client = match self.accept(game) {
+            Ok(is_new_client) => is_new_client,
+            Err(e) => {
+                log::warn!("Failed to accept live viewer: {e}");
+                false
+            }
+        };
+
+        let Some((ref mut stream, ref mut tracker)) = self.client else {
+            return;
+        };
+
+        let mut frame_record = tracker.track_delta(game, is_new_client, false);
+        frame_record.num_rng_rolls = num_rng_rolls;
+        frame_record.game_changes.extend_from_slice(extra_changes);
+
+        let mut buf = Cursor::new(Vec::new());
+        let result = buf.write_le(&frame_record)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(stream.write_all(&buf.into_inner())?));
+        if let Err(e) = result {
+            log::info!("Live viewer disconnected: {e}");
+            self.client = None;
+        }
+    }
+}
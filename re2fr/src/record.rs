@@ -4,6 +4,11 @@ use residat::re2::{Character, CharacterPart, MAX_PARTS, NUM_CHARACTERS, NUM_OBJE
 
 use crate::game::Game;
 
+// a forward jump in the game clock bigger than this many frames means a save was loaded
+const LOAD_IGT_JUMP: u32 = 30 * 5;
+// a new game starts the clock over, so going back to a time this early isn't a load
+const NEW_GAME_IGT: u32 = 30 * 2;
+
 #[derive(Debug, Clone)]
 struct Part {
     translation: VECTOR,
@@ -82,7 +87,7 @@ impl CharacterState {
         }
     }
 
-    pub fn full_delta(&self) -> Vec<CharacterField> {
+    pub fn full_delta(&self, is_verbose: bool) -> Vec<CharacterField> {
         let mut delta = vec![
             CharacterField::State(self.state.clone()),
             CharacterField::Id(self.id),
@@ -107,19 +112,25 @@ impl CharacterState {
             delta.push(CharacterField::PartSize(i as u8, part.x_size, part.y_size, part.z_size, part.size_offset));
         }
         
-        for &i in self.model_parts_needed() {
-            if i >= self.model_part_transforms.len() {
-                log::warn!("Missing model part transform {} for character {} (num transforms: {})", i, self.id, self.model_part_transforms.len());
-                continue;
+        if is_verbose {
+            for (i, transform) in self.model_part_transforms.iter().enumerate() {
+                delta.push(CharacterField::ModelPartTransform(i as u8, transform.clone()));
+            }
+        } else {
+            for &i in self.model_parts_needed() {
+                if i >= self.model_part_transforms.len() {
+                    log::warn!("Missing model part transform {} for character {} (num transforms: {})", i, self.id, self.model_part_transforms.len());
+                    continue;
+                }
+
+                delta.push(CharacterField::ModelPartTransform(i as u8, self.model_part_transforms[i].clone()));
             }
-            
-            delta.push(CharacterField::ModelPartTransform(i as u8, self.model_part_transforms[i].clone()));
         }
         
         delta
     }
 
-    pub fn track_delta(&mut self, char: &Character) -> Vec<CharacterField> {
+    pub fn track_delta(&mut self, char: &Character, is_verbose: bool) -> Vec<CharacterField> {
         let mut fields = Vec::with_capacity(MAX_CHARACTER_CHANGES);
         
         if self.flags != char.flags {
@@ -194,7 +205,7 @@ impl CharacterState {
             
             if self.model_part_transforms[i] != model_part.composite_transform {
                 self.model_part_transforms[i] = model_part.composite_transform.clone();
-                if model_parts_needed.contains(&i) {
+                if is_verbose || model_parts_needed.contains(&i) {
                     fields.push(CharacterField::ModelPartTransform(i as u8, model_part.composite_transform.clone()));
                 }
             }
@@ -205,11 +216,13 @@ impl CharacterState {
             fields.push(CharacterField::MotionAngle(char.motion_angle));
         }
 
-        // stop tracking this for now as it doesn't immediately appear to be useful
-        /*if self.motion != char.motion {
+        // this doesn't immediately appear to be useful, so it's only recorded when asked for
+        if self.motion != char.motion {
             self.motion = char.motion;
-            fields.push(CharacterField::Motion(char.motion));
-        }*/
+            if is_verbose {
+                fields.push(CharacterField::Motion(char.motion));
+            }
+        }
 
         if self.x_size != char.parts[0].x_size || self.z_size != char.parts[0].z_size {
             self.x_size = char.parts[0].x_size;
@@ -277,6 +290,21 @@ impl GameState {
         }
     }
 
+    pub fn full_delta(&self) -> Vec<GameField> {
+        vec![
+            GameField::GameFlags1(self.game_flags),
+            GameField::GameFlags2(self.game_flags2),
+            GameField::Rng(self.rng as u16),
+            GameField::KeysDown(self.keys_down),
+            GameField::KeysDownThisFrame(self.keys_down_this_frame),
+            GameField::StageIndex(self.stage_index as u8),
+            GameField::RoomIndex(self.room_index as u8),
+            GameField::StageOffset(self.stage_offset as u8),
+            GameField::Scenario(self.scenario),
+            GameField::SoundFlags(self.sound_flags),
+        ]
+    }
+
     pub fn track_delta(&mut self, game: &Game) -> Vec<GameField> {
         let mut fields = Vec::new();
 
@@ -345,23 +373,69 @@ impl GameState {
     }
 }
 
+/// Every character other than the player, as they are right now
+pub fn enemy_spawns(game: &Game) -> Vec<EnemySpawn> {
+    game.characters()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(i, char)| {
+            let char = unsafe { &*char? };
+            Some(EnemySpawn {
+                char_index: i as u8,
+                id: char.id,
+                type_: (char.type_ & 0xff) as u8,
+                state: char.state.clone(),
+                flags: char.flags,
+                pos: char.transform.t.clone(),
+                angle: char.motion_angle,
+                velocity: char.velocity.clone(),
+                health: char.health,
+            })
+        })
+        .collect()
+}
+
+/// Notices loads, which the game doesn't flag anywhere we can read
+#[derive(Debug, Default)]
+pub struct StorageTracker {
+    igt: Option<u32>,
+}
+
+impl StorageTracker {
+    pub fn update(&mut self, game: &Game) -> Vec<GameField> {
+        let mut events = Vec::new();
+        let igt = game.igt_seconds() * 30 + game.igt_frames() as u32;
+
+        // the clock never runs backwards on its own, and it stops while the game isn't being played
+        let is_load = self.igt.is_some_and(|last_igt| (igt < last_igt && igt >= NEW_GAME_IGT) || igt > last_igt + LOAD_IGT_JUMP);
+        if is_load {
+            events.push(GameField::GameLoaded);
+        }
+
+        self.igt = Some(igt);
+        events
+    }
+}
+
 #[derive(Debug)]
 pub struct GameTracker {
     state: GameState,
     characters: [Option<CharacterState>; NUM_CHARACTERS],
     objects: [Option<CharacterState>; NUM_OBJECTS],
+    is_verbose: bool,
 }
 
 impl GameTracker {
-    pub fn new(game: &Game) -> Self {
+    pub fn new(game: &Game, is_verbose: bool) -> Self {
         Self {
             state: GameState::from_game(game),
             characters: [const { None }; NUM_CHARACTERS],
             objects: [const { None }; NUM_OBJECTS],       
+            is_verbose,
         }
     }
     
-    fn track_char_change(i: usize, char: Option<*const Character>, state: &mut Option<CharacterState>, character_diffs: &mut Vec<CharacterDiff>) {
+    fn track_char_change(is_verbose: bool, i: usize, char: Option<*const Character>, state: &mut Option<CharacterState>, character_diffs: &mut Vec<CharacterDiff>) {
         match (char, state.as_mut()) {
             (None, Some(_)) => {
                 character_diffs.push(CharacterDiff::removed(i));
@@ -370,12 +444,12 @@ impl GameTracker {
             (Some(char), None) => {
                 let char = unsafe { &*char };
                 let char_state = CharacterState::from_character(char);
-                character_diffs.push(CharacterDiff::new(i, char_state.full_delta()));
+                character_diffs.push(CharacterDiff::new(i, char_state.full_delta(is_verbose)));
                 *state = Some(char_state);
             }
             (Some(char), Some(state)) => {
                 let char = unsafe { &*char };
-                let delta = state.track_delta(char);
+                let delta = state.track_delta(char, is_verbose);
                 if !delta.is_empty() {
                     character_diffs.push(CharacterDiff::new(i, delta));
                 }
@@ -384,20 +458,44 @@ impl GameTracker {
         }
     }
 
-    pub fn track_delta(&mut self, game: &Game) -> FrameRecord {
-        let igt_seconds = game.igt_seconds();
-        let igt_frames = game.igt_frames();
+    fn full_diffs(states: &[Option<CharacterState>], is_verbose: bool) -> Vec<CharacterDiff> {
+        states.iter()
+            .enumerate()
+            .filter_map(|(i, state)| state.as_ref().map(|s| CharacterDiff::new(i, s.full_delta(is_verbose))))
+            .collect()
+    }
 
+    pub fn track_delta(&mut self, game: &Game, is_session_start: bool, is_keyframe: bool) -> FrameRecord {
         let game_changes = self.state.track_delta(game);
 
         let mut character_diffs = Vec::with_capacity(NUM_CHARACTERS);
         for (i, (char, state)) in game.characters().zip(self.characters.iter_mut()).enumerate() {
-            Self::track_char_change(i, char, state, &mut character_diffs);
+            Self::track_char_change(self.is_verbose, i, char, state, &mut character_diffs);
         }
         
         let mut object_diffs = Vec::with_capacity(NUM_OBJECTS);
         for (i, (char, state)) in game.objects().zip(self.objects.iter_mut()).enumerate() {
-            Self::track_char_change(i, char, state, &mut object_diffs);       
+            Self::track_char_change(self.is_verbose, i, char, state, &mut object_diffs);       
+        }
+
+        self.frame_record(game.igt_seconds(), game.igt_frames(), game_changes, character_diffs, object_diffs, is_session_start, is_keyframe)
+    }
+
+    fn frame_record(
+        &self, igt_seconds: u32, igt_frames: u8, mut game_changes: Vec<GameField>, mut character_diffs: Vec<CharacterDiff>,
+        mut object_diffs: Vec<CharacterDiff>, is_session_start: bool, is_keyframe: bool,
+    ) -> FrameRecord {
+        if is_session_start || is_keyframe {
+            // the file may already contain earlier sessions, so the reader can't rely on starting
+            // from a blank state. the states are up to date now, so a full delta of each is
+            // everything present this frame.
+            game_changes = self.state.full_delta();
+            character_diffs = Self::full_diffs(&self.characters, self.is_verbose);
+            object_diffs = Self::full_diffs(&self.objects, self.is_verbose);
+        }
+
+        if is_keyframe {
+            game_changes.insert(0, GameField::Keyframe(KEYFRAME_SIGNATURE));
         }
 
         FrameRecord {
@@ -409,4 +507,86 @@ impl GameTracker {
             object_diffs,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idle_character(id: u8) -> CharacterState {
+        CharacterState {
+            flags: 0,
+            state: [0; 4],
+            id,
+            transform: MATRIX::zero(),
+            parts: [const { None }; MAX_PARTS],
+            part_offset_x: Fixed16(0),
+            part_offset_z: Fixed16(0),
+            model_part_transforms: Vec::new(),
+            motion_angle: Fixed16(0),
+            motion: 0,
+            x_size: UFixed16(0),
+            z_size: UFixed16(0),
+            floor: 0,
+            velocity: SVECTOR::zero(),
+            health: 100,
+            type_: 0,
+            water_level: Fixed16(0),
+        }
+    }
+
+    fn tracker() -> GameTracker {
+        let mut tracker = GameTracker {
+            state: GameState {
+                game_flags: 0,
+                game_flags2: 0,
+                rng: 0,
+                keys_down: 0,
+                keys_down_this_frame: 0,
+                stage_index: 0,
+                room_index: 0,
+                stage_offset: 0,
+                scenario: 0,
+                sound_flags: 0,
+            },
+            characters: [const { None }; NUM_CHARACTERS],
+            objects: [const { None }; NUM_OBJECTS],
+            is_verbose: false,
+        };
+        tracker.characters[0] = Some(idle_character(0));
+        tracker.characters[3] = Some(idle_character(16));
+        tracker.objects[1] = Some(idle_character(0));
+        tracker
+    }
+
+    fn diff_indexes(diffs: &[CharacterDiff]) -> Vec<u8> {
+        diffs.iter().map(|d| d.index).collect()
+    }
+
+    #[test]
+    fn test_second_session_records_unchanged_characters() {
+        let tracker = tracker();
+
+        // nothing changed since the previous frame, which was in the last session
+        let frame = tracker.frame_record(0, 0, Vec::new(), Vec::new(), Vec::new(), false, false);
+        assert!(frame.character_diffs.is_empty());
+        assert!(frame.object_diffs.is_empty());
+
+        // the reader starts the new session from a blank state, so everything has to be there
+        let frame = tracker.frame_record(0, 0, Vec::new(), Vec::new(), Vec::new(), true, false);
+        assert_eq!(diff_indexes(&frame.character_diffs), [0, 3]);
+        assert_eq!(diff_indexes(&frame.object_diffs), [1]);
+        assert!(frame.character_diffs[1].changes.iter().any(|c| matches!(c, CharacterField::Id(16))));
+        assert!(frame.game_changes.iter().any(|c| matches!(c, GameField::RoomIndex(0))));
+        assert!(!frame.game_changes.iter().any(|c| matches!(c, GameField::Keyframe(_))));
+    }
+
+    #[test]
+    fn test_keyframe_records_unchanged_characters() {
+        let tracker = tracker();
+
+        let frame = tracker.frame_record(0, 0, Vec::new(), Vec::new(), Vec::new(), false, true);
+        assert_eq!(diff_indexes(&frame.character_diffs), [0, 3]);
+        assert_eq!(diff_indexes(&frame.object_diffs), [1]);
+        assert!(matches!(frame.game_changes.first(), Some(GameField::Keyframe(_))));
+    }
+}
@@ -0,0 +1,173 @@
+use residat::re2::{Item, NUM_CHARACTERS};
+use serde::{Deserialize, Serialize};
+
+use crate::app::RoomId;
+use crate::character::CharacterType;
+use crate::record::{Recording, DAMAGE_ATTRIBUTION_FRAMES};
+
+/// Weapons that can be given an ammo budget
+pub const BUDGET_WEAPONS: [Item; 12] = [
+    Item::HandgunLeon,
+    Item::HandgunClaire,
+    Item::CustomHandgun,
+    Item::Beretta,
+    Item::ColtSaa,
+    Item::Magnum,
+    Item::CustomMagnum,
+    Item::Shotgun,
+    Item::CustomShotgun,
+    Item::Sparkshot,
+    Item::SubMachinegun,
+    Item::GatlingGun,
+];
+
+/// How many rounds of a weapon's ammo the route plans to spend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmmoBudget {
+    pub weapon_id: u16,
+    pub rounds: usize,
+}
+
+impl AmmoBudget {
+    pub fn weapon(&self) -> Option<Item> {
+        Item::try_from(self.weapon_id).ok()
+    }
+}
+
+/// Rounds one pull of the trigger uses. Ammo counts aren't recorded, so this is what spending is
+/// worked out from.
+const fn rounds_per_shot(weapon: Item) -> usize {
+    match weapon {
+        // fires a three-round burst
+        Item::CustomHandgun => 3,
+        // the shotguns use one shell per blast, and the automatic weapons are counted per burst
+        // since how long the trigger was held doesn't tell us how many rounds came out
+        _ => 1,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WeaponUsage {
+    pub weapon: Item,
+    pub shots: usize,
+    pub rounds: usize,
+    pub hits: usize,
+    pub damage: usize,
+}
+
+impl WeaponUsage {
+    const fn new(weapon: Item) -> Self {
+        Self {
+            weapon,
+            shots: 0,
+            rounds: 0,
+            hits: 0,
+            damage: 0,
+        }
+    }
+
+    pub fn damage_per_round(&self) -> Option<f32> {
+        (self.rounds > 0).then(|| self.damage as f32 / self.rounds as f32)
+    }
+
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.shots > 0).then(|| self.hits as f32 / self.shots as f32 * 100.0)
+    }
+}
+
+/// Ammo spent so far as of leaving a room in which the player fired
+#[derive(Debug, Clone)]
+pub struct AmmoCheckpoint {
+    pub room_id: RoomId,
+    pub frame_index: usize,
+    pub rounds: Vec<(Item, usize)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AmmoReport {
+    pub weapons: Vec<WeaponUsage>,
+    pub checkpoints: Vec<AmmoCheckpoint>,
+}
+
+impl AmmoReport {
+    fn usage_mut(&mut self, weapon: Item) -> &mut WeaponUsage {
+        match self.weapons.iter().position(|w| w.weapon == weapon) {
+            Some(i) => &mut self.weapons[i],
+            None => {
+                self.weapons.push(WeaponUsage::new(weapon));
+                self.weapons.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Rounds of the given weapon's budget left after the given checkpoint, or overall if there's
+    /// no checkpoint. Negative means the budget was exceeded.
+    pub fn remaining(&self, budget: &AmmoBudget, checkpoint: Option<&AmmoCheckpoint>) -> Option<isize> {
+        let weapon = budget.weapon()?;
+        let rounds = match checkpoint {
+            Some(checkpoint) => checkpoint.rounds.iter().find(|(w, _)| *w == weapon).map(|(_, rounds)| *rounds).unwrap_or(0),
+            None => self.weapons.iter().find(|w| w.weapon == weapon).map(|w| w.rounds).unwrap_or(0),
+        };
+        Some(budget.rounds as isize - rounds as isize)
+    }
+
+    /// Tally shots, rounds, hits, and damage for each weapon used in the recording. The recording's
+    /// current frame will be changed, so the caller is responsible for restoring it if necessary.
+    pub fn analyze(recording: &mut Recording) -> Self {
+        let visits: Vec<_> = recording.room_visits()
+            .into_iter()
+            .map(|(state, range)| (state.room_id(), range))
+            .collect();
+
+        let mut report = Self::default();
+        for (room_id, visit) in visits {
+            let mut last_health: [Option<i16>; NUM_CHARACTERS] = [None; NUM_CHARACTERS];
+            // the most recent shot and whether it has hit anything yet
+            let mut last_shot: Option<(usize, Item, bool)> = None;
+            let mut fired_in_room = false;
+            for i in visit.clone() {
+                let Some(state) = recording.set_index(i) else {
+                    continue;
+                };
+
+                if let Some(weapon) = state.player_attack() {
+                    let usage = report.usage_mut(weapon);
+                    usage.shots += 1;
+                    usage.rounds += rounds_per_shot(weapon);
+                    last_shot = Some((i, weapon, false));
+                    fired_in_room = true;
+                }
+
+                let mut damage = 0usize;
+                for (slot, character) in state.characters().iter().enumerate().skip(1) {
+                    let health = character.as_ref()
+                        .filter(|c| c.type_() == CharacterType::Enemy)
+                        .map(|c| c.current_health());
+                    if let (Some(old), Some(new)) = (last_health[slot], health) && new < old {
+                        damage += (old as i32 - new as i32) as usize;
+                    }
+                    last_health[slot] = health;
+                }
+
+                if damage > 0 && let Some((shot_frame, weapon, is_hit)) = last_shot.as_mut() && i - *shot_frame <= DAMAGE_ATTRIBUTION_FRAMES {
+                    let usage = report.usage_mut(*weapon);
+                    usage.damage += damage;
+                    if !*is_hit {
+                        usage.hits += 1;
+                        *is_hit = true;
+                    }
+                }
+            }
+
+            if fired_in_room {
+                report.checkpoints.push(AmmoCheckpoint {
+                    room_id,
+                    frame_index: visit.end.saturating_sub(1),
+                    rounds: report.weapons.iter().map(|w| (w.weapon, w.rounds)).collect(),
+                });
+            }
+        }
+
+        report
+    }
+}
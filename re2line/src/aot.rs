@@ -8,6 +8,7 @@ use residat::re2::{
 use crate::app::{DrawParams, Floor, GameObject, ObjectType, RoomId};
 use crate::collision::Collider;
 use crate::record::State;
+use crate::units::Angle;
 
 pub const NUM_AOTS: usize = 32;
 
@@ -113,6 +114,10 @@ impl Entity {
     pub const fn id(&self) -> u8 {
         self.id
     }
+
+    pub const fn collider(&self) -> &Collider {
+        &self.collider
+    }
 }
 
 impl GameObject for Entity {
@@ -162,7 +167,7 @@ impl GameObject for Entity {
                     format!("Target X: {}", next_pos_x),
                     format!("Target Y: {}", next_pos_y),
                     format!("Target Z: {}", next_pos_z),
-                    format!("Target Angle: {:.1}°", next_cdir_y.to_degrees()),
+                    format!("Target Angle: {}", Angle(next_cdir_y)),
                     format!("Target Stage: {}", next_stage),
                     format!("Target Room: {}", next_room),
                     format!("Target Floor: {}", next_n_floor),
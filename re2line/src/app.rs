@@ -1,11 +1,12 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
 use std::fs::File;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::io::BufReader;
+use std::io::BufWriter;
 use std::str::FromStr;
-use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, bail, Result};
 use eframe::{Frame, Storage};
@@ -17,37 +18,70 @@ use epaint::{Stroke, StrokeKind};
 use re2script::ScriptFormatter;
 use re2shared::record::FrameRecord;
 use re2shared::rng::RollType;
-use residat::common::{Fixed32, UFixed16, Vec2};
+use residat::common::{Fixed32, UFixed16, Vec2, Vec3};
 use residat::re2::{CharacterId, Rdt, RdtSection, NUM_CHARACTERS, NUM_OBJECTS};
-use rfd::FileDialog;
+use web_time::Instant;
 
+use crate::ammo::{AmmoBudget, AmmoReport, BUDGET_WEAPONS};
+use crate::bandwidth::{SizeReport, DEFAULT_BUCKET_FRAMES};
+use crate::behavior::{BehaviorReport, MIN_ROLLS_FOR_DEVIATION, SUSPICIOUS_DEVIATION};
 use crate::aot::{Entity, EntityForm, NUM_AOTS};
-use crate::character::{Character, Object, PositionedAiZone, WeaponRangeVisualization};
+use crate::bundle::{Bundle, BUNDLE_EXTENSION};
+use crate::bookmark::Bookmark;
+use crate::capture::CaptureInfo;
+use crate::checklist::{ChecklistItem, ChecklistTask, RunChecklist};
+use crate::character::{AimReadout, BehaviorType, Character, GrabPrediction, Object, PositionedAiZone, WeaponRangeVisualization};
 use crate::collision::Collider;
-use crate::compare::{Checkpoint, Comparison, RoomFilter};
+use crate::contact::{ContactEvent, PushEvent};
+use crate::compare::{Alignment, Checkpoint, Comparison, RoomFilter, SegmentBounds};
+use crate::dialog;
 use crate::draw::{VAlign, text_box};
-use crate::rdt::RdtExt;
-use crate::record::{PlayerSound, Recording, RngDescription, RollCategory, State, FRAME_DURATION};
-use crate::rng::{RNG_SEQUENCE, ROLL_DESCRIPTIONS};
+use crate::drill::Drill;
+use crate::encounter::{EnemyFate, EnemyLifetime};
+use crate::fight::FightSummary;
+use crate::ghost::{GhostRecording, GHOST_COLORS};
+use crate::index::{GameIndex, IndexKind};
+use crate::knowledge::ZoneCard;
+use crate::inspect::{read_rdt_as, DecodeAs, RawRdt, RdtWarnings};
+use crate::profile::{DrawLayer, FrameProfiler, LayerTimings};
+use crate::progress::{ProgressReport, ROLLING_AVERAGE_ATTEMPTS};
+use crate::plan::{CornerCut, DrawnLeg, DrawnRoute, PlannedRoute};
+use crate::notes::write_notes;
+use crate::notify::{CuePlayer, NotificationRule, RuleCondition, RuleTracker, SoundCue};
+use crate::overlap::{AotOverlapReport, OverlapKind};
+use crate::plugin::{registered_plugins, Plugin, PluginAction, PluginContext};
+use crate::rdt::{RdtExt, RdtFormat};
+use crate::record::{DamageKind, DoorTimes, Menu, MessagePrompt, PlayerSound, Recording, RngDescription, RollCategory, State, FRAME_DURATION};
+use crate::rng::{RngBookmark, RngPreset, RNG_SEQUENCE, ROLL_DESCRIPTIONS};
+use crate::rngdiff::{ComparedRoll, RngDiff};
+use crate::route::{RouteMatch, RouteTemplate};
+use crate::savestate::{SaveStateLink, SAVE_STATE_EXTENSIONS};
+use crate::server::{PlaybackServer, PlaybackSnapshot};
+use crate::live::LiveConnection;
+use crate::loader::RecordingLoader;
+use crate::setup::SetupCard;
+use crate::trick::{TrickAttempt, TrickDefinition, TrickInput, TrickTrigger, BUILTIN_TRICKS};
+use crate::timing::TimingReport;
+use crate::units::{Angle, AngleUnit, Degrees, Distance, DistanceUnit};
 
 mod config;
 mod game;
 mod layer;
 
-use config::Config;
+use config::{CharacterSettings, Config, DetailLayout, PlaybackSpeed, SavedCharacterSettings};
 pub use config::RoomId;
 pub use game::{DrawParams, Floor, GameObject, ObjectType, WorldPos};
 use layer::Layer;
 
 pub const APP_NAME: &str = "re2line";
 
-const DETAIL_MAX_ROWS: usize = 4;
-const FAST_FORWARD: isize = 30;
-const MAX_SOUND_AGE: usize = 100;
+const ONE_SECOND_FRAMES: isize = 30;
 
 const INPUT_MARGIN: f32 = 2.0;
 const INPUT_SIZE: f32 = 30.0;
 const INPUT_OFFSET: f32 = INPUT_SIZE + INPUT_MARGIN;
+// distance from the right edge of the view to the center of the aiming readout
+const AIM_READOUT_OFFSET: f32 = 110.0;
 
 const TEXT_BOX_DARK: Color32 = Color32::from_rgb(0x30, 0x30, 0x30);
 const TEXT_BOX_LIGHT: Color32 = Color32::from_rgb(0xe0, 0xe0, 0xe0);
@@ -58,6 +92,78 @@ const TOOLTIP_HOVER_SECONDS: f32 = 1.0;
 const COMPARISON_PATH_WIDTH: f32 = 0.0125;
 const COMPARISON_PATH_EMPHASIS_WIDTH: f32 = 0.025;
 
+const PREDICTED_PATH_COLOR: Color32 = Color32::from_rgb(0xE0, 0x40, 0xFB);
+const DIVERGENCE_MARKER_COLOR: Color32 = Color32::from_rgb(0xFF, 0x30, 0x30);
+const DIVERGENCE_MARKER_RADIUS: f32 = 4.0;
+
+// ghost enemies are faded so the ghost's player stands out
+const GHOST_ENEMY_FADE: f32 = 0.5;
+
+const HIT_ZONE_FLASH_COLOR: Color32 = Color32::from_rgb(0xE0, 0x10, 0x10);
+const HIT_ZONE_FLASH_SECONDS: f32 = 0.5;
+const HIT_ZONE_FLASH_WIDTH: f32 = 6.0;
+
+const NOTIFICATION_SECONDS: f32 = 2.0;
+const NOTIFICATION_COLOR: Color32 = Color32::from_rgb(0xFF, 0xC0, 0x20);
+const NOTIFICATION_FONT_SIZE: f32 = 20.0;
+
+const DETACHED_TAB_WIDTH: f32 = 450.0;
+const DETACHED_TAB_HEIGHT: f32 = 700.0;
+
+// frames replayed per UI update while indexing a recording for seeking
+const SNAPSHOT_FRAMES_PER_UPDATE: usize = 20000;
+
+const CAPTURE_KEY: Key = Key::F9;
+const SPEED_UP_KEY: Key = Key::Equals;
+const SPEED_DOWN_KEY: Key = Key::Minus;
+// most recording frames played in a single UI update when playing faster than the UI redraws
+const MAX_FRAMES_PER_UPDATE: usize = 8;
+const CAPTURE_MARGIN: f32 = 10.0;
+
+const SAFE_AREA_COLOR: Color32 = Color32::from_rgba_premultiplied(0x10, 0x50, 0x10, 0x50);
+const SAFE_AREA_CELL_SIZE: i32 = 250;
+const PART_HITBOX_COLOR: Color32 = Color32::from_rgb(0xff, 0x80, 0x00);
+const PART_HITBOX_WIDTH: f32 = 1.5;
+
+const PLANNED_ROUTE_COLOR: Color32 = Color32::from_rgb(0xFF, 0x8C, 0x00);
+const PLANNED_ROUTE_WIDTH: f32 = 2.0;
+const WAYPOINT_RADIUS: f32 = 4.0;
+
+const DRAWN_ROUTE_COLOR: Color32 = Color32::from_rgb(0x00, 0xE5, 0xFF);
+const BLOCKED_LEG_COLOR: Color32 = Color32::from_rgb(0xFF, 0x30, 0x30);
+
+const OPTIMAL_LINE_COLOR: Color32 = Color32::from_rgb(0xFF, 0xD7, 0x00);
+
+const LOOP_REGION_SHADE: Color32 = Color32::from_rgba_premultiplied(0x20, 0x40, 0x80, 0x40);
+const CONTROL_LOCK_SHADE: Color32 = Color32::from_rgba_premultiplied(0x40, 0x40, 0x40, 0x60);
+const SAVE_STATE_MARKER_COLOR: Color32 = Color32::from_rgb(0x30, 0xC0, 0x60);
+const LOAD_MARKER_COLOR: Color32 = Color32::from_rgb(0xA0, 0x40, 0xFF);
+const ENEMY_SPAWN_MARKER_COLOR: Color32 = Color32::from_rgb(0xE0, 0xE0, 0x40);
+const ENEMY_KILL_MARKER_COLOR: Color32 = Color32::from_rgb(0xE0, 0x30, 0x30);
+const PLAYER_DAMAGE_MARKER_COLOR: Color32 = Color32::from_rgb(0xFF, 0x60, 0xC0);
+const ENEMY_DAMAGE_MARKER_COLOR: Color32 = Color32::from_rgb(0xA0, 0x60, 0x30);
+const ENEMY_DESPAWN_MARKER_COLOR: Color32 = Color32::from_rgb(0x90, 0x90, 0x90);
+const USER_MARKER_COLOR: Color32 = Color32::from_rgb(0xFF, 0xFF, 0xFF);
+const BOOKMARK_MARKER_COLOR: Color32 = Color32::from_rgb(0x40, 0xE0, 0xE0);
+
+const DEAGGRO_COLOR: Color32 = Color32::from_rgb(0x60, 0xE0, 0x90);
+const DEAGGRO_WIDTH: f32 = 1.5;
+const DEAGGRO_RADIUS: f32 = 4.0;
+
+const GRAB_WARNING_COLOR: Color32 = Color32::from_rgb(0xFF, 0x40, 0x40);
+const GRAB_WARNING_FILL: Color32 = Color32::from_rgba_premultiplied(0x60, 0x10, 0x10, 0x60);
+const GRAB_WARNING_WIDTH: f32 = 2.0;
+
+const ATTACK_TRACE_COLOR: Color32 = Color32::from_rgb(0xFF, 0xA0, 0x20);
+const ATTACK_TRACE_HIT_COLOR: Color32 = Color32::from_rgb(0xFF, 0x20, 0x20);
+const ATTACK_TRACE_WIDTH: f32 = 2.0;
+const ATTACK_TRACE_RADIUS: f32 = 2.5;
+// how long a finished attack's trace stays on the map
+const ATTACK_TRACE_LINGER_FRAMES: usize = 60;
+
+const ROOM_SPAWN_COLOR: Color32 = Color32::from_rgb(0xE0, 0xE0, 0x40);
+const ROOM_SPAWN_RADIUS: f32 = 5.0;
+
 trait UiExt {
     fn draw_game_object<O: GameObject>(&self, object: &O, params: &DrawParams, state: &State) -> ShapeIdx;
 
@@ -74,6 +180,28 @@ impl UiExt for Ui {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+enum BookmarkAction {
+    Add,
+    Edit(usize),
+    Remove(usize),
+    Save,
+    Cancel,
+}
+
+#[derive(Debug, Clone)]
+struct LoopSegment {
+    path: PathBuf,
+    range: Range<usize>,
+}
+
+impl LoopSegment {
+    fn label(&self) -> String {
+        let name = self.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        format!("{} {}-{}", name, self.range.start, self.range.end - 1)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SelectedObject {
     None,
@@ -119,6 +247,13 @@ impl Default for SelectedObject {
     }
 }
 
+#[derive(Debug, Clone)]
+enum DetailLayoutChange {
+    MoveToFront(String),
+    HideGroup(String),
+    HideField(String),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum BrowserTab {
     Game,
@@ -127,11 +262,14 @@ enum BrowserTab {
     Rng,
     Recording,
     Comparison,
+    Index,
+    /// A tab contributed by the plugin at this index
+    Plugin(usize),
 }
 
 impl BrowserTab {
-    const fn list() -> [BrowserTab; 6] {
-        [BrowserTab::Game, BrowserTab::Room, BrowserTab::Comparison, BrowserTab::Recording, BrowserTab::Rng, BrowserTab::Settings]
+    const fn list() -> [BrowserTab; 7] {
+        [BrowserTab::Game, BrowserTab::Index, BrowserTab::Room, BrowserTab::Comparison, BrowserTab::Recording, BrowserTab::Rng, BrowserTab::Settings]
     }
 
     const fn name(&self) -> &'static str {
@@ -142,56 +280,14 @@ impl BrowserTab {
             Self::Rng => "RNG",
             Self::Recording => "Recording",
             Self::Comparison => "Comparison",
-        }
-    }
-}
-
-#[derive(Debug, Copy, Clone)]
-struct CharacterSettings {
-    pub show: bool,
-    pub show_tooltip: bool,
-    pub show_ai: bool,
-    pub show_path: bool,
-    pub show_rng_rolls: bool,
-}
-
-impl CharacterSettings {
-    pub const fn config_default(config: &Config) -> Self {
-        Self {
-            show: true,
-            show_tooltip: config.default_show_character_tooltips,
-            show_ai: true,
-            show_path: false,
-            show_rng_rolls: true,
+            Self::Index => "Index",
+            Self::Plugin(_) => "Plugin",
         }
     }
 
-    pub const fn show_tooltip(&self) -> bool {
-        self.show && self.show_tooltip
-    }
-
-    pub const fn show_ai(&self) -> bool {
-        self.show && self.show_ai
-    }
-
-    pub const fn show_path(&self) -> bool {
-        self.show && self.show_path
-    }
-    
-    pub const fn show_rng_rolls(&self) -> bool {
-        self.show_rng_rolls
-    }
-}
-
-impl Default for CharacterSettings {
-    fn default() -> Self {
-        Self {
-            show: true,
-            show_tooltip: true,
-            show_ai: true,
-            show_path: false,
-            show_rng_rolls: true,
-        }
+    /// Tabs that can be moved into their own window
+    const fn is_detachable(&self) -> bool {
+        matches!(self, Self::Rng | Self::Recording | Self::Comparison | Self::Index)
     }
 }
 
@@ -205,20 +301,27 @@ pub struct App {
     floors: Layer<Collider>,
     pan: egui::Vec2,
     selected_object: SelectedObject,
+    zone_card: Option<ZoneCard>,
     hover_object: SelectedObject,
     hover_pos: Option<egui::Pos2>,
     config: Config,
     tab: BrowserTab,
+    plugins: Vec<Box<dyn Plugin>>,
     leon_rooms: Vec<(PathBuf, RoomId)>,
     claire_rooms: Vec<(PathBuf, RoomId)>,
+    bundled_rooms: BTreeMap<RoomId, Vec<u8>>,
+    game_index: GameIndex,
+    index_filter: String,
     need_title_update: bool,
     active_recording: Option<Recording>,
     is_recording_playing: bool,
     last_play_tick: Instant,
-    character_settings: HashMap<(RoomId, CharacterId, usize), CharacterSettings>,
+    character_settings: HashMap<(RoomId, u8), CharacterSettings>,
     pointer_game_pos: Option<Vec2>,
     current_rdt: Option<Rdt>,
+    current_rdt_format: RdtFormat,
     error_message: Option<String>,
+    rdt_warnings: Option<RdtWarnings>,
     compare_filter: RoomFilter,
     is_compare_filter_window_open: bool,
     comparison: Option<Comparison>,
@@ -229,13 +332,107 @@ pub struct App {
     rng_selected_outcomes: HashSet<&'static str>,
     rng_selected_roll_type: Option<RollType>,
     rng_selected_index: usize,
+    rng_bookmark_name: String,
+    rng_preset_name: String,
     rng_run_threshold: f64,
     rng_run_window_size: usize,
     is_rng_explore_window_open: bool,
+    is_player_in_hit_zone: bool,
+    hit_zone_flash_start: Option<Instant>,
+    hit_zone_events: BTreeMap<usize, String>,
+    is_notification_window_open: bool,
+    is_save_state_window_open: bool,
+    is_drill_window_open: bool,
+    is_ghost_window_open: bool,
+    ghosts: Vec<GhostRecording>,
+    is_progress_window_open: bool,
+    progress_report: Option<ProgressReport>,
+    progress_segment: usize,
+    is_behavior_window_open: bool,
+    behavior_report: Option<BehaviorReport>,
+    is_aot_overlap_window_open: bool,
+    aot_overlaps: Option<AotOverlapReport>,
+    recording_loader: Option<RecordingLoader>,
+    // whether the active recording is just the beginning of the one being loaded
+    is_recording_preview: bool,
+    /// Index, name, and note of the bookmark being edited
+    bookmark_draft: Option<(usize, String, String)>,
+    drills: Vec<Drill>,
+    drill_min_attempts: usize,
+    save_states: Vec<SaveStateLink>,
+    rule_tracker: RuleTracker,
+    cue_player: CuePlayer,
+    notification_events: BTreeMap<usize, String>,
+    notification_banner: Option<(Instant, String)>,
+    need_user_attention: bool,
+    pending_capture: Option<CaptureInfo>,
+    is_capture_requested: bool,
+    object_list_filter: String,
+    door_times: DoorTimes,
+    is_trick_window_open: bool,
+    trick_results: HashMap<usize, Vec<TrickAttempt>>,
+    builtin_trick_results: Vec<Vec<TrickAttempt>>,
+    is_checklist_window_open: bool,
+    checklist_report: Option<(RoomId, Vec<RunChecklist>)>,
+    is_fight_window_open: bool,
+    fight_results: Vec<FightSummary>,
+    is_contact_window_open: bool,
+    contact_events: Vec<ContactEvent>,
+    is_enemy_timeline_window_open: bool,
+    enemy_lifetimes: Vec<EnemyLifetime>,
+    is_ammo_window_open: bool,
+    ammo_report: Option<AmmoReport>,
+    is_push_window_open: bool,
+    push_events: Vec<PushEvent>,
+    is_verify_window_open: bool,
+    is_size_window_open: bool,
+    size_report: Option<SizeReport>,
+    is_timing_window_open: bool,
+    timing_report: Option<TimingReport>,
+    is_rng_diff_window_open: bool,
+    rng_diff_path: Option<PathBuf>,
+    rng_diff_recording: Option<Recording>,
+    rng_diff_visit: usize,
+    rng_diff: Option<RngDiff>,
+    is_export_window_open: bool,
+    export_visits: HashSet<usize>,
+    is_route_window_open: bool,
+    route_matches: Vec<RouteMatch>,
+    planned_route: Option<PlannedRoute>,
+    is_route_drawing_window_open: bool,
+    is_drawing_route: bool,
+    drawn_route: DrawnRoute,
+    is_corner_window_open: bool,
+    corner_cut: Option<CornerCut>,
+    is_rdt_inspector_open: bool,
+    is_profiler_window_open: bool,
+    profiler: FrameProfiler,
+    is_server_window_open: bool,
+    playback_server: Option<PlaybackServer>,
+    live_connection: Option<LiveConnection>,
+    detached_tabs: Vec<BrowserTab>,
+    raw_rdt: Option<RawRdt>,
+    inspected_section: usize,
+    inspect_decode: DecodeAs,
+    go_to_frame_text: String,
+    loop_in: Option<usize>,
+    loop_out: Option<usize>,
+    active_recording_path: Option<PathBuf>,
+    ab_segments: [Option<LoopSegment>; 2],
+    ab_current: usize,
+    is_ab_looping: bool,
+    // the recording for the other A/B segment when the two come from different files
+    ab_parked_recording: Option<(PathBuf, Recording)>,
 }
 
 impl App {
     pub fn new() -> Result<Self> {
+        let config = Config::get()?;
+        let character_settings = config.character_settings
+            .iter()
+            .map(|saved| ((saved.room_id, saved.character_id), saved.settings))
+            .collect();
+
         Ok(Self {
             center: Vec2::zero(),
             colliders: Layer::new(),
@@ -246,20 +443,27 @@ impl App {
             floors: Layer::new(),
             pan: egui::Vec2::ZERO,
             selected_object: SelectedObject::None,
+            zone_card: None,
             hover_object: SelectedObject::None,
             hover_pos: None,
-            config: Config::get()?,
+            config,
             tab: BrowserTab::Game,
+            plugins: registered_plugins(),
             leon_rooms: Vec::new(),
             claire_rooms: Vec::new(),
+            bundled_rooms: BTreeMap::new(),
+            game_index: GameIndex::default(),
+            index_filter: String::new(),
             need_title_update: false,
             active_recording: None,
             is_recording_playing: false,
             last_play_tick: Instant::now(),
-            character_settings: HashMap::new(),
+            character_settings,
             pointer_game_pos: None,
             current_rdt: None,
+            current_rdt_format: RdtFormat::Re2,
             error_message: None,
+            rdt_warnings: None,
             compare_filter: RoomFilter::empty(),
             is_compare_filter_window_open: false,
             comparison: None,
@@ -270,9 +474,94 @@ impl App {
             rng_selected_outcomes: HashSet::new(),
             rng_selected_roll_type: None,
             rng_selected_index: 0,
+            rng_bookmark_name: String::new(),
+            rng_preset_name: String::new(),
             rng_run_threshold: 2.0 / 3.0 * 100.0,
             rng_run_window_size: 10,
             is_rng_explore_window_open: false,
+            is_player_in_hit_zone: false,
+            hit_zone_flash_start: None,
+            hit_zone_events: BTreeMap::new(),
+            is_notification_window_open: false,
+            is_save_state_window_open: false,
+            is_drill_window_open: false,
+            is_ghost_window_open: false,
+            ghosts: Vec::new(),
+            is_progress_window_open: false,
+            progress_report: None,
+            progress_segment: 0,
+            is_behavior_window_open: false,
+            behavior_report: None,
+            is_aot_overlap_window_open: false,
+            aot_overlaps: None,
+            recording_loader: None,
+            is_recording_preview: false,
+            bookmark_draft: None,
+            drills: Vec::new(),
+            drill_min_attempts: 3,
+            save_states: Vec::new(),
+            rule_tracker: RuleTracker::default(),
+            cue_player: CuePlayer::default(),
+            notification_events: BTreeMap::new(),
+            notification_banner: None,
+            need_user_attention: false,
+            pending_capture: None,
+            is_capture_requested: false,
+            object_list_filter: String::new(),
+            door_times: DoorTimes::default(),
+            is_trick_window_open: false,
+            trick_results: HashMap::new(),
+            builtin_trick_results: Vec::new(),
+            is_checklist_window_open: false,
+            checklist_report: None,
+            is_fight_window_open: false,
+            fight_results: Vec::new(),
+            is_contact_window_open: false,
+            contact_events: Vec::new(),
+            is_enemy_timeline_window_open: false,
+            enemy_lifetimes: Vec::new(),
+            is_ammo_window_open: false,
+            ammo_report: None,
+            is_push_window_open: false,
+            push_events: Vec::new(),
+            is_verify_window_open: false,
+            is_size_window_open: false,
+            size_report: None,
+            is_timing_window_open: false,
+            timing_report: None,
+            is_rng_diff_window_open: false,
+            rng_diff_path: None,
+            rng_diff_recording: None,
+            rng_diff_visit: 0,
+            rng_diff: None,
+            is_export_window_open: false,
+            export_visits: HashSet::new(),
+            is_route_window_open: false,
+            route_matches: Vec::new(),
+            planned_route: None,
+            is_route_drawing_window_open: false,
+            is_drawing_route: false,
+            drawn_route: DrawnRoute::default(),
+            is_corner_window_open: false,
+            corner_cut: None,
+            is_rdt_inspector_open: false,
+            is_profiler_window_open: false,
+            profiler: FrameProfiler::default(),
+            is_server_window_open: false,
+            playback_server: None,
+            live_connection: None,
+            detached_tabs: Vec::new(),
+            raw_rdt: None,
+            inspected_section: 0,
+            inspect_decode: DecodeAs::Hex,
+            go_to_frame_text: String::new(),
+            loop_in: None,
+            loop_out: None,
+            active_recording_path: None,
+            ab_segments: [None, None],
+            ab_current: 0,
+            is_ab_looping: false,
+            ab_parked_recording: None,
         })
     }
 
@@ -280,6 +569,31 @@ impl App {
         self.config.zoom_scale
     }
 
+    fn advance_playback(&mut self) {
+        let previous_room_id = self.config.last_rdt.unwrap();
+        let index = self.active_recording().map(Recording::index).unwrap_or(0);
+        if let Some(range) = self.playback_loop_range()
+            && (index + 1 >= range.end || index < range.start) {
+            if self.is_ab_looping {
+                self.switch_ab_segment();
+            } else {
+                // jump back to the start of the loop region
+                self.set_recording_frame(range.start);
+            }
+        } else if !self.next_recording_frame(){
+            // if we get clamped due to reaching the end of the comparison section and
+            // the other comparison paths are not playing, pause playback
+            self.is_recording_playing = false;
+        } else if let Some(player) = self.get_character(0)
+            && player.is_moving()
+            // don't try to project normal movement when the room changes
+            && self.config.last_rdt.unwrap() == previous_room_id {
+            // validate our collision logic
+            #[cfg(feature = "motion-simulation")]
+            self.simulate_motion(player);
+        }
+    }
+
     fn toggle_play_recording(&mut self) {
         if self.active_recording().is_none() {
             return;
@@ -312,6 +626,11 @@ impl App {
             return false;
         }
 
+        if let Some(filter) = self.config.ai_behavior_filter() {
+            // the global filter takes precedence over the per-character settings
+            return filter.contains(&ai_zone.ai_zone.behavior_type) && self.get_character(ai_zone.character_index).is_some();
+        }
+
         match (self.get_character(ai_zone.character_index), self.get_character_settings(ai_zone.character_index)) {
             (Some(character), Some(settings)) => {
                 self.config.should_show(character.object_type()) && settings.show_ai()
@@ -385,6 +704,7 @@ impl App {
 
     fn handle_input(&mut self, ctx: &Context) {
         let egui_wants_kb_input = ctx.wants_keyboard_input();
+        let mut need_capture = std::mem::take(&mut self.is_capture_requested);
         ctx.input(|i| {
             if i.pointer.middle_down() && !i.pointer.button_pressed(egui::PointerButton::Middle) {
                 self.pan -= i.pointer.delta();
@@ -400,7 +720,11 @@ impl App {
                     self.set_pointer_game_pos(i.pointer.interact_pos(), viewport);
                 }
                 if let Some(game_pos) = self.pointer_game_pos {
-                    self.click_select(game_pos);
+                    if self.is_drawing_route {
+                        self.drawn_route.points.push(game_pos);
+                    } else {
+                        self.click_select(game_pos);
+                    }
                 }
             }
 
@@ -421,13 +745,31 @@ impl App {
                     self.toggle_play_recording();
                 }
 
+                need_capture |= i.key_pressed(CAPTURE_KEY);
+
+                if i.key_pressed(SPEED_UP_KEY) || i.key_pressed(Key::Plus) {
+                    self.config.playback_speed = self.config.playback_speed.faster();
+                } else if i.key_pressed(SPEED_DOWN_KEY) {
+                    self.config.playback_speed = self.config.playback_speed.slower();
+                }
+
                 if self.active_recording().is_some() {
-                    if self.is_recording_playing {
+                    let step = if i.modifiers.command {
+                        Some(ONE_SECOND_FRAMES)
+                    } else if i.modifiers.shift {
+                        Some(self.config.shift_step_frames as isize)
+                    } else if self.is_recording_playing {
                         // skip forward or back in chunks
+                        Some(self.config.fast_forward_frames as isize)
+                    } else {
+                        None
+                    };
+
+                    if let Some(step) = step {
                         if i.key_pressed(Key::ArrowRight) {
-                            self.move_recording_frame(FAST_FORWARD);
+                            self.move_recording_frame(step);
                         } else if i.key_pressed(Key::ArrowLeft) {
-                            self.move_recording_frame(-FAST_FORWARD);
+                            self.move_recording_frame(-step);
                         }
                     } else {
                         // frame-by-frame
@@ -440,6 +782,54 @@ impl App {
                 }
             }
         });
+
+        if need_capture {
+            self.capture_analysis_frame(ctx);
+        }
+    }
+
+    fn capture_analysis_frame(&mut self, ctx: &Context) {
+        if Self::is_read_only() || self.pending_capture.is_some() {
+            return;
+        }
+
+        if self.config.capture_folder.is_none() {
+            self.config.capture_folder = dialog::pick_folder();
+        }
+        if self.config.capture_folder.is_none() {
+            return;
+        }
+
+        let state = self.active_recording().and_then(Recording::current_state);
+        let Some(room_id) = state.map(State::room_id).or(self.config.last_rdt) else {
+            return;
+        };
+
+        self.pending_capture = Some(CaptureInfo {
+            room_id,
+            frame_index: state.map(State::frame_index),
+            igt: self.active_recording().and_then(Recording::current_frame).map(FrameRecord::time),
+            details: self.selected_object_details(),
+        });
+        // the annotation is drawn in the view while the capture is pending so it's part of the image
+        ctx.send_viewport_cmd(ViewportCommand::Screenshot(egui::UserData::default()));
+    }
+
+    fn save_capture(&mut self, ctx: &Context) {
+        let image = ctx.input(|i| i.raw.events.iter().find_map(|event| match event {
+            egui::Event::Screenshot { image, .. } => Some(Arc::clone(image)),
+            _ => None,
+        }));
+        let (Some(image), Some(folder)) = (image, self.config.capture_folder.as_ref()) else {
+            return;
+        };
+        let Some(capture) = self.pending_capture.take() else {
+            return;
+        };
+
+        if let Err(e) = CaptureInfo::write_png(&image, folder.join(capture.file_name())) {
+            self.show_error(format!("Failed to save capture: {e}"));
+        }
     }
 
     fn calculate_origin(&mut self, ctx: &Context) -> egui::Pos2 {
@@ -456,7 +846,11 @@ impl App {
         self.center = Vec2::zero();
         self.colliders.clear();
         self.entities.clear();
+        self.aot_overlaps = None;
         self.floors.clear();
+        self.drawn_route.points.clear();
+        self.corner_cut = None;
+        self.raw_rdt = None;
         self.pan = egui::Vec2::ZERO;
         self.selected_object = SelectedObject::None;
         self.hover_object = SelectedObject::None;
@@ -475,6 +869,7 @@ impl App {
         self.center = rdt.center();
         self.colliders.set_objects(rdt.get_colliders());
         self.entities.set_objects(rdt.get_entities());
+        self.aot_overlaps = None;
         self.floors.set_objects(rdt.get_floors());
         self.pan = egui::Vec2::ZERO;
         self.selected_object = SelectedObject::None;
@@ -498,20 +893,115 @@ impl App {
     }
 
     pub fn load_rdt(&mut self, id: RoomId, path: impl AsRef<Path>) -> Result<()> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        let rdt = Rdt::read(reader)?;
+        let data = std::fs::read(path)?;
+        self.load_rdt_data(id, &data, RdtFormat::Re2)
+    }
+
+    fn load_rdt_data(&mut self, id: RoomId, data: &[u8], format: RdtFormat) -> Result<()> {
+        let (rdt, warnings) = read_rdt_as(id, data, format)?;
 
         self.set_rdt(rdt, id);
+        self.current_rdt_format = format;
+        self.rdt_warnings = warnings;
 
         Ok(())
     }
 
+    /// Open a single RDT from outside the game folder. Rooms from games other than RE2 can be viewed
+    /// this way, but recordings can only be played back in RE2 rooms.
+    fn prompt_open_rdt(&mut self, format: RdtFormat) -> Result<()> {
+        let Some(path) = dialog::pick_file(&format!("{} rooms", format.layout().name()), &["rdt", "RDT"]) else {
+            return Ok(());
+        };
+
+        let id = path.file_name()
+            .and_then(|name| format.layout().room_id_from_file_name(&name.to_string_lossy()))
+            .unwrap_or_else(RoomId::zero);
+        if format != RdtFormat::Re2 && self.active_recording.is_some() {
+            self.close_recording();
+        }
+
+        self.load_rdt_data(id, &std::fs::read(path)?, format)
+    }
+
     pub fn load_room(&mut self, id: RoomId) -> Result<()> {
+        if let Some(data) = self.bundled_rooms.get(&id) {
+            let data = data.clone();
+            return self.load_rdt_data(id, &data, RdtFormat::Re2);
+        }
+
         let path = self.get_room_path(id).ok_or_else(|| anyhow!("Could not find room"))?;
         self.load_rdt(id, path.to_path_buf())
     }
 
+    /// Whether we're running as the read-only web viewer, with no access to the filesystem
+    pub const fn is_read_only() -> bool {
+        cfg!(target_arch = "wasm32")
+    }
+
+    pub fn load_bundle(&mut self, data: &[u8]) -> Result<()> {
+        let bundle = Bundle::read(data)?;
+        let recording = Recording::read(std::io::Cursor::new(bundle.recording))?;
+
+        self.bundled_rooms = bundle.rooms.into_iter().collect();
+        self.set_recording(recording, None);
+
+        Ok(())
+    }
+
+    fn prompt_load_bundle(&mut self) -> Result<()> {
+        let Some(path) = dialog::pick_file("re2line bundles", &[BUNDLE_EXTENSION]) else {
+            return Ok(());
+        };
+
+        self.load_bundle(&std::fs::read(path)?)
+    }
+
+    fn prompt_export_bundle(&mut self) -> Result<()> {
+        let (Some(recording), Some(recording_path)) = (self.active_recording(), self.active_recording_path.as_ref()) else {
+            bail!("Only recordings opened from a file can be bundled");
+        };
+
+        let mut room_ids: Vec<RoomId> = recording.room_visits().into_iter().map(|(state, _)| state.room_id()).collect();
+        room_ids.sort();
+        room_ids.dedup();
+
+        let mut rooms = Vec::with_capacity(room_ids.len());
+        for room_id in room_ids {
+            let data = match self.bundled_rooms.get(&room_id) {
+                Some(data) => data.clone(),
+                None => {
+                    let path = self.get_room_path(room_id).ok_or_else(|| anyhow!("Could not find room {room_id}"))?;
+                    std::fs::read(path)?
+                }
+            };
+            rooms.push((room_id, data));
+        }
+        let bundle = Bundle { rooms, recording: std::fs::read(recording_path)? };
+
+        let Some(path) = dialog::save_file("re2line bundles", &[BUNDLE_EXTENSION], &format!("bundle.{BUNDLE_EXTENSION}")) else {
+            return Ok(());
+        };
+
+        bundle.write(BufWriter::new(File::create(path)?))
+    }
+
+    fn handle_dropped_files(&mut self, ctx: &Context) {
+        let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
+        for file in dropped_files {
+            let data = match (file.bytes, file.path) {
+                // in the browser, we get the file contents directly
+                (Some(bytes), _) => Ok(bytes.to_vec()),
+                (None, Some(path)) => std::fs::read(path).map_err(anyhow::Error::from),
+                (None, None) => continue,
+            };
+
+            if let Err(e) = data.and_then(|data| self.load_bundle(&data)) {
+                self.show_error(format!("Failed to open bundle {}: {e}", file.name));
+            }
+        }
+    }
+
     fn get_room_path(&self, id: RoomId) -> Option<&Path> {
         let room_list = if id.player == 0 {
             &self.leon_rooms
@@ -529,7 +1019,7 @@ impl App {
     }
 
     fn is_game_loaded(&self) -> bool {
-        !self.leon_rooms.is_empty() || !self.claire_rooms.is_empty()
+        !self.leon_rooms.is_empty() || !self.claire_rooms.is_empty() || !self.bundled_rooms.is_empty()
     }
 
     fn get_entry_case_insensitive(dir: impl AsRef<Path>, name: &str) -> Result<Option<PathBuf>> {
@@ -544,7 +1034,7 @@ impl App {
         Ok(None)
     }
 
-    fn enumerate_rdts(dir: impl AsRef<Path>, rdt_list: &mut Vec<(PathBuf, RoomId)>) -> Result<()> {
+    pub fn enumerate_rdts(dir: impl AsRef<Path>, rdt_list: &mut Vec<(PathBuf, RoomId)>) -> Result<()> {
         let rdt_dir = Self::get_entry_case_insensitive(dir, "rdt")?.ok_or_else(|| anyhow!("Could not find RDT folder"))?;
 
         for entry in rdt_dir.read_dir()? {
@@ -584,6 +1074,8 @@ impl App {
             bail!("Invalid game directory could not find RDT files");
         }
 
+        self.game_index = GameIndex::build(self.leon_rooms.iter().chain(&self.claire_rooms));
+
         self.config.rdt_folder = Some(dir);
 
         if let Some(room_id) = self.config.last_rdt {
@@ -602,7 +1094,7 @@ impl App {
     }
 
     fn prompt_load_game(&mut self) -> Result<()> {
-        let Some(folder) = FileDialog::new().pick_folder() else {
+        let Some(folder) = dialog::pick_folder() else {
             return Ok(());
         };
 
@@ -610,40 +1102,215 @@ impl App {
     }
 
     fn load_recording(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
         let file = File::open(path)?;
-        self.active_recording = Some(Recording::read(file)?);
+        let recording = Recording::read(file)?;
+        self.open_loaded_recording(recording, path);
+
+        Ok(())
+    }
+
+    fn open_loaded_recording(&mut self, mut recording: Recording, path: &Path) {
+        // a damaged bookmark file shouldn't keep the recording from opening
+        let bookmark_result = Bookmark::load(path).map(|bookmarks| recording.set_bookmarks(bookmarks));
+        self.set_recording(recording, Some(path));
+        if let Err(e) = bookmark_result {
+            self.show_error(format!("Failed to read bookmarks: {e}"));
+        }
+    }
+
+    fn update_recording_loader(&mut self, ctx: &Context) {
+        let Some(ref loader) = self.recording_loader else {
+            return;
+        };
+
+        let preview = loader.take_preview();
+        let result = loader.take_result();
+        let path = loader.path().to_path_buf();
+
+        if let Some(preview) = preview {
+            self.set_recording(preview, None);
+            self.is_recording_preview = true;
+        }
+
+        let Some(result) = result else {
+            ctx.request_repaint();
+            return;
+        };
+
+        self.recording_loader = None;
+        // pick up where the user got to in the preview
+        let resume_frame = self.is_recording_preview
+            .then(|| self.active_recording.as_ref().map(Recording::index))
+            .flatten();
+        self.is_recording_preview = false;
+        match result {
+            Ok(recording) => {
+                self.open_loaded_recording(recording, &path);
+                if let Some(frame_index) = resume_frame {
+                    self.set_recording_frame(frame_index);
+                }
+            }
+            Err(e) => self.show_error(format!("Failed to open recording: {e}")),
+        }
+    }
+
+    fn recording_loader_window(&self, ctx: &Context) {
+        let Some(ref loader) = self.recording_loader else {
+            return;
+        };
+
+        egui::Window::new("Loading Recording")
+            .collapsible(false)
+            .resizable(false)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.label(loader.path().file_name().map(|n| n.to_string_lossy()).unwrap_or_default());
+                if self.is_recording_preview {
+                    ui.label("The beginning of the recording can be played while the rest loads");
+                }
+                ui.add(egui::ProgressBar::new(loader.progress()).show_percentage());
+            });
+    }
+
+    fn set_recording(&mut self, recording: Recording, path: Option<&Path>) {
+        if let Some(path) = path {
+            self.door_times.add_recording(path, &recording);
+        }
+        // opening a recording ends live viewing
+        self.live_connection = None;
+        self.active_recording = Some(recording);
+        self.active_recording_path = path.map(Path::to_path_buf);
+        self.is_recording_preview = false;
+        self.stop_ab_loop();
         // remove any active comparison
         self.comparison = None;
         if self.tab == BrowserTab::Comparison {
             self.tab = BrowserTab::Recording;
         }
-        // reset character display settings for new recording
-        self.character_settings.clear();
+        self.hit_zone_events.clear();
+        self.notification_events.clear();
+        self.rule_tracker.reset();
+        self.trick_results.clear();
+        self.builtin_trick_results.clear();
+        self.checklist_report = None;
+        self.bookmark_draft = None;
+        self.fight_results.clear();
+        self.contact_events.clear();
+        self.enemy_lifetimes.clear();
+        self.ammo_report = None;
+        self.size_report = None;
+        self.zone_card = None;
+        self.timing_report = None;
+        self.rng_diff = None;
+        self.push_events.clear();
+        self.export_visits.clear();
+        self.clear_loop();
+        self.correlate_save_states();
         self.change_recording_frame(|r| r.set_index(0));
-
-        Ok(())
     }
 
     fn prompt_load_recording(&mut self) -> Result<()> {
-        let Some(path) = FileDialog::new().add_filter("RE2 recordings", &["bin"]).pick_file() else {
+        let Some(path) = dialog::pick_file("RE2 recordings", &["bin"]) else {
             return Ok(());
         };
 
-        self.load_recording(path)
+        // the current recording stays open until the new one is ready
+        self.recording_loader = Some(RecordingLoader::start(path));
+        Ok(())
     }
     
+    fn connect_to_game(&mut self) {
+        self.close_recording();
+        match LiveConnection::connect() {
+            Ok(connection) => self.live_connection = Some(connection),
+            Err(e) => self.show_error(format!("Failed to connect to game: {e}. Make sure the game is running with re2fr.")),
+        }
+    }
+
+    fn disconnect_from_game(&mut self) {
+        self.live_connection = None;
+        self.close_recording();
+    }
+
+    const fn is_live(&self) -> bool {
+        self.live_connection.is_some()
+    }
+
+    fn update_snapshots(&mut self, ctx: &Context) {
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        // index a little at a time so the recording can be watched while it's being indexed
+        if !recording.build_snapshots(SNAPSHOT_FRAMES_PER_UPDATE) {
+            ctx.request_repaint();
+        }
+    }
+
+    fn update_live(&mut self, ctx: &Context) {
+        let Some(ref connection) = self.live_connection else {
+            return;
+        };
+
+        let latest = connection.take_latest();
+        if !connection.is_connected() && latest.is_none() {
+            let error = connection.take_error();
+            self.disconnect_from_game();
+            self.show_error(format!("Lost connection to game: {}", error.unwrap_or_else(|| String::from("connection closed"))));
+            return;
+        }
+
+        if let Some((frame, state)) = latest {
+            // the live recording only ever holds the latest frame
+            self.active_recording = Some(Recording::live(frame, state));
+            self.update_from_state();
+        }
+
+        // keep drawing new frames even when the user isn't interacting with the window
+        ctx.request_repaint();
+    }
+
     fn close_recording(&mut self) {
         self.active_recording = None;
+        // a recording that finishes loading after this shouldn't pop back open
+        self.recording_loader = None;
+        self.is_recording_preview = false;
         self.is_recording_playing = false;
+        self.hit_zone_events.clear();
+        self.notification_events.clear();
+        self.rule_tracker.reset();
+        self.trick_results.clear();
+        self.builtin_trick_results.clear();
+        self.checklist_report = None;
+        self.bookmark_draft = None;
+        self.fight_results.clear();
+        self.contact_events.clear();
+        self.enemy_lifetimes.clear();
+        self.ammo_report = None;
+        self.size_report = None;
+        self.zone_card = None;
+        self.timing_report = None;
+        self.rng_diff = None;
+        self.push_events.clear();
+        self.export_visits.clear();
+        self.clear_loop();
+        self.active_recording_path = None;
+        self.correlate_save_states();
+        self.ab_segments = [None, None];
+        self.stop_ab_loop();
         self.objects.clear();
-        self.character_settings.clear();
         self.ai_zones.clear();
         self.characters.clear();
         if matches!(self.selected_object, SelectedObject::Character(_) | SelectedObject::Object(_)) {
             self.selected_object = SelectedObject::None;
         }
 
-        if self.tab == BrowserTab::Recording {
+        let is_plugin_tab_inactive = match self.tab {
+            BrowserTab::Plugin(i) => self.plugins.get(i).is_none_or(|p| p.needs_recording()),
+            _ => false,
+        };
+        if self.tab == BrowserTab::Recording || is_plugin_tab_inactive {
             self.tab = BrowserTab::Room;
         }
     }
@@ -651,8 +1318,10 @@ impl App {
     fn close_comparison(&mut self) {
         self.comparison = None;
         self.is_recording_playing = false;
+        self.hit_zone_events.clear();
+        self.notification_events.clear();
+        self.rule_tracker.reset();
         self.objects.clear();
-        self.character_settings.clear();
         self.ai_zones.clear();
         self.characters.clear();
         if matches!(self.selected_object, SelectedObject::Character(_) | SelectedObject::Object(_)) {
@@ -688,6 +1357,7 @@ impl App {
     }
 
     fn room_browser(&mut self, ui: &mut Ui) {
+        let mut export_setup_card = false;
         egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
             if let Some(ref recording) = self.active_recording {
                 let stats = recording.get_room_stats();
@@ -698,9 +1368,82 @@ impl App {
                 let minutes = (seconds / 60.0) as i32;
                 let seconds = seconds % 60.0;
                 ui.label(format!("Time:\t{:02}:{:05.2}", minutes, seconds));
+
+                let active_seconds = stats.active_time.as_secs_f32();
+                let active_minutes = (active_seconds / 60.0) as i32;
+                let active_seconds = active_seconds % 60.0;
+                ui.label(format!("Active time:\t{:02}:{:05.2}", active_minutes, active_seconds));
+                ui.label(format!("Locked frames:\t{}", stats.num_locked_frames));
+
+                // lag frames don't advance the game, so they only show up in real time
+                if stats.num_lag_frames > 0 {
+                    let real_seconds = (stats.total_time + FRAME_DURATION * stats.num_lag_frames as u32).as_secs_f32();
+                    let real_minutes = (real_seconds / 60.0) as i32;
+                    let real_seconds = real_seconds % 60.0;
+                    ui.label(format!("Lag frames:\t{}", stats.num_lag_frames));
+                    ui.label(format!("Real time:\t{:02}:{:05.2}", real_minutes, real_seconds));
+                }
+
+                if stats.menu_time.total() > 0 {
+                    ui.label(format!("Menu time:\t{}", Self::frames_to_time(stats.menu_time.total())));
+                    for menu in Menu::list() {
+                        let frames = stats.menu_time.frames(menu);
+                        if frames > 0 {
+                            ui.label(format!("  {}:\t{} ({})", menu, Self::frames_to_time(frames), frames));
+                        }
+                    }
+                }
                 
                 ui.label(format!("RNG rolls:\t{}", stats.num_rng_rolls));
-                ui.label(format!("RNG index:\t{}", stats.rng_position));
+                ui.horizontal(|ui| {
+                    ui.label(format!("RNG index:\t{}", stats.rng_position));
+                    if let Some(bookmark) = self.config.rng_bookmarks.iter().find(|b| b.rng_index == stats.rng_position) {
+                        ui.label(format!("({})", bookmark.name));
+                    } else if ui.small_button("Bookmark").clicked() {
+                        let name = format!("{} entry", recording.current_state().map(State::room_id).unwrap_or_else(RoomId::zero));
+                        self.config.rng_bookmarks.push(RngBookmark { name, rng_index: stats.rng_position });
+                    }
+                });
+
+                export_setup_card = ui.button("Export setup card").on_hover_text("Save the state at this room's entry as JSON").clicked();
+            }
+
+            if let (Some(route), Some(room_id)) = (self.planned_route.as_ref(), self.config.last_rdt) {
+                let speed = self.active_recording().and_then(Recording::player_run_speed);
+                let legs = route.legs(room_id, speed);
+                if !legs.is_empty() {
+                    ui.separator();
+                    ui.collapsing(format!("Planned route: {}", route.name), |ui| {
+                        for (i, leg) in legs.iter().enumerate() {
+                            let estimate = leg.total_frames.map(|f| format!(" | ~{f} frames")).unwrap_or_default();
+                            ui.label(format!("Leg {}: {}{}", i + 1, Distance(leg.distance), estimate));
+                        }
+
+                        match (legs.last().and_then(|leg| leg.total_frames), self.active_recording()) {
+                            (Some(planned), Some(recording)) => {
+                                let stats = recording.get_room_stats();
+                                let actual = stats.num_frames.saturating_sub(stats.num_locked_frames);
+                                ui.label(format!("Planned: {} frames | Actual active: {} frames ({:+})", planned, actual, actual as isize - planned as isize));
+                            }
+                            _ => {
+                                ui.label("Load a recording to estimate times");
+                            }
+                        }
+
+                        let doors = route.door_transitions(&self.door_times);
+                        if !doors.is_empty() {
+                            for (from, to, frames) in &doors {
+                                let timing = frames.map_or_else(|| String::from("no recorded transitions"), |f| format!("~{f:.0} frames"));
+                                ui.label(format!("Door {from} → {to}: {timing}"));
+                            }
+
+                            match route.total_frames(speed, &self.door_times) {
+                                Some(total) => ui.label(format!("Route total: ~{total} frames including doors")),
+                                None => ui.label("Route total: load recordings that cover every room and door"),
+                            };
+                        }
+                    });
+                }
             }
             
             if self.current_rdt.is_some() {
@@ -714,70 +1457,150 @@ impl App {
 
             ui.separator();
 
-            ui.collapsing("Floor", |ui| {
-                for i in 0..self.floors.len() {
-                    ui.selectable_value(&mut self.selected_object, SelectedObject::Floor(i), format!("Floor {}", i));
-                }
-            });
+            self.object_lists(ui);
+        });
 
-            ui.collapsing("Collision", |ui| {
-                for i in 0..self.colliders.len() {
-                    ui.selectable_value(&mut self.selected_object, SelectedObject::Collider(i), format!("Collider {}", i));
-                }
-            });
+        if export_setup_card && let Err(e) = self.prompt_export_setup_card() {
+            self.show_error(format!("Failed to export setup card: {e}"));
+        }
+    }
 
-            ui.collapsing("Door", |ui| {
-                let mut door_count = 0;
-                for (i, entity) in self.entities.objects().iter().enumerate() {
-                    if entity.object_type() != ObjectType::Door {
-                        continue;
-                    }
+    fn object_list_entries(&self) -> Vec<(&'static str, Vec<(SelectedObject, String)>)> {
+        let mut lists = vec![
+            ("Floor", (0..self.floors.len()).map(|i| (SelectedObject::Floor(i), format!("Floor {}", i))).collect()),
+            ("Collision", (0..self.colliders.len()).map(|i| (SelectedObject::Collider(i), format!("Collider {}", i))).collect()),
+        ];
+
+        let mut doors = Vec::new();
+        let mut items = Vec::new();
+        let mut aots = Vec::new();
+        for (i, entity) in self.entities.objects().iter().enumerate() {
+            let (list, prefix): (&mut Vec<_>, _) = match entity.object_type() {
+                ObjectType::Door => (&mut doors, "Door"),
+                ObjectType::Item => (&mut items, "Item"),
+                _ => (&mut aots, "AOT"),
+            };
+            list.push((SelectedObject::Entity(i), format!("{} {}", prefix, list.len())));
+        }
+        lists.push(("Door", doors));
+        lists.push(("Item", items));
+        lists.push(("AOT", aots));
 
-                    ui.selectable_value(&mut self.selected_object, SelectedObject::Entity(i), format!("Door {}", door_count));
-                    door_count += 1;
-                }
-            });
+        if self.active_recording().is_some() {
+            lists.push(("Objects", self.objects.objects().iter().map(|o| (SelectedObject::Object(o.index()), format!("Object {}", o.index()))).collect()));
+            lists.push(("Characters", self.characters.objects().iter().map(|c| (SelectedObject::Character(c.index()), format!("#{}: {}", c.index(), c.name()))).collect()));
+        }
 
-            ui.collapsing("Item", |ui| {
-                let mut item_count = 0;
-                for (i, entity) in self.entities.objects().iter().enumerate() {
-                    if entity.object_type() != ObjectType::Item {
-                        continue;
-                    }
+        let filter = self.object_list_filter.to_lowercase();
+        for (_, entries) in &mut lists {
+            entries.retain(|(_, name)| name.to_lowercase().contains(&filter));
+        }
 
-                    ui.selectable_value(&mut self.selected_object, SelectedObject::Entity(i), format!("Item {}", item_count));
-                    item_count += 1;
-                }
-            });
+        lists
+    }
 
-            ui.collapsing("AOT", |ui| {
-                let mut aot_count = 0;
-                for (i, entity) in self.entities.objects().iter().enumerate() {
-                    if matches!(entity.object_type(), ObjectType::Door | ObjectType::Item) {
-                        continue;
-                    }
+    fn object_lists(&mut self, ui: &mut Ui) {
+        let lists = self.object_list_entries();
+        let flat: Vec<SelectedObject> = lists.iter().flat_map(|(_, entries)| entries.iter().map(|(o, _)| *o)).collect();
 
-                    ui.selectable_value(&mut self.selected_object, SelectedObject::Entity(i), format!("AOT {}", aot_count));
-                    aot_count += 1;
-                }
-            });
+        let filter_response = ui.horizontal(|ui| {
+            ui.label("Filter:");
+            let response = ui.text_edit_singleline(&mut self.object_list_filter);
+            if ui.small_button("Clear").clicked() {
+                self.object_list_filter.clear();
+            }
+            response
+        }).inner;
+
+        // typing anywhere that isn't already taking text goes to the filter
+        let is_filter_focused = filter_response.has_focus();
+        if !is_filter_focused && !ui.ctx().wants_keyboard_input() {
+            let typed: String = ui.input(|i| i.events.iter().filter_map(|event| match event {
+                egui::Event::Text(text) => Some(text.as_str()),
+                _ => None,
+            }).collect());
+            // space is the play/pause key, so don't let it start a filter
+            if !typed.trim().is_empty() {
+                self.object_list_filter.push_str(&typed);
+                filter_response.request_focus();
+            }
+        }
+
+        let mut moved = false;
+        if is_filter_focused || !ui.ctx().wants_keyboard_input() {
+            let (up, down, enter) = ui.input_mut(|i| (
+                i.consume_key(egui::Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, Key::Enter),
+            ));
+
+            if (up || down) && !flat.is_empty() {
+                let current = flat.iter().position(|o| *o == self.selected_object);
+                let next = match (current, down) {
+                    (Some(i), true) => (i + 1).min(flat.len() - 1),
+                    (Some(i), false) => i.saturating_sub(1),
+                    (None, true) => 0,
+                    (None, false) => flat.len() - 1,
+                };
+                self.selected_object = flat[next];
+                moved = true;
+            }
 
-            if self.active_recording().is_some() {
-                ui.collapsing("Objects", |ui| {
-                    for object in self.objects.objects() {
-                        let i = object.index();
-                        ui.selectable_value(&mut self.selected_object, SelectedObject::Object(i), format!("Object {}", i));
+            if enter {
+                self.focus_selected_object(ui.ctx());
+            }
+        }
+
+        let is_filtering = !self.object_list_filter.is_empty();
+        for (title, entries) in lists {
+            let has_selection = entries.iter().any(|(o, _)| *o == self.selected_object);
+            // keep the headers open while filtering or when the keyboard selection moves into them
+            let open = (is_filtering || (moved && has_selection)).then_some(true);
+            egui::CollapsingHeader::new(title).open(open).show(ui, |ui| {
+                for (object, name) in entries {
+                    let response = ui.selectable_value(&mut self.selected_object, object, name);
+                    if response.double_clicked() {
+                        self.focus_selected_object(ui.ctx());
                     }
-                });
-                
-                ui.collapsing("Characters", |ui| {
-                    for character in self.characters.objects() {
-                        let i = character.index();
-                        ui.selectable_value(&mut self.selected_object, SelectedObject::Character(i), format!("#{}: {}", i, character.name()));
+                    if moved && object == self.selected_object {
+                        response.scroll_to_me(None);
                     }
-                });
+                }
+            });
+        }
+    }
+
+    fn selected_object_rect(&self, origin: egui::Pos2) -> Option<egui::Rect> {
+        let empty_state = State::empty();
+        let state = self.active_recording().and_then(Recording::current_state).unwrap_or(&empty_state);
+        let shape = match self.selected_object {
+            SelectedObject::Floor(i) => self.floors[i].gui_shape(&self.config.get_obj_draw_params(&self.floors[i], origin), state),
+            SelectedObject::Collider(i) => self.colliders[i].gui_shape(&self.config.get_obj_draw_params(&self.colliders[i], origin), state),
+            SelectedObject::Entity(i) => self.entities[i].gui_shape(&self.config.get_obj_draw_params(&self.entities[i], origin), state),
+            SelectedObject::AiZone(i) => self.ai_zones[i].gui_shape(&self.config.get_obj_draw_params(&self.ai_zones[i], origin), state),
+            SelectedObject::Object(i) => {
+                let object = self.get_object(i)?;
+                object.gui_shape(&self.config.get_obj_draw_params(object, origin), state)
             }
-        });
+            SelectedObject::Character(i) => {
+                let character = self.get_character(i)?;
+                character.gui_shape(&self.config.get_obj_draw_params(character, origin), state)
+            }
+            SelectedObject::None => return None,
+        };
+
+        Some(shape.visual_bounding_rect()).filter(|rect| rect.is_finite())
+    }
+
+    /// Pan the view so the selected object is in the center
+    fn focus_selected_object(&mut self, ctx: &Context) {
+        let origin = self.calculate_origin(ctx);
+        let Some(rect) = self.selected_object_rect(origin) else {
+            return;
+        };
+
+        let viewport = ctx.input(egui::InputState::viewport_rect);
+        self.pan += rect.center() - viewport.center();
     }
 
     fn rdt_list(&mut self, is_leon: bool, ui: &mut Ui) {
@@ -804,10 +1627,30 @@ impl App {
         }
     }
 
-    fn rdt_browser(&mut self, ui: &mut Ui) {
-        egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
-            ui.collapsing("Leon", |ui| {
-                self.rdt_list(true, ui);
+    fn bundle_rdt_list(&mut self, ui: &mut Ui) {
+        let mut room_to_load = None;
+        for id in self.bundled_rooms.keys().copied() {
+            let is_current_room = self.config.last_rdt == Some(id);
+            if ui.selectable_label(is_current_room, format!("{}", id)).clicked() && !is_current_room {
+                room_to_load = Some(id);
+            }
+        }
+
+        if let Some(id) = room_to_load
+            && let Err(e) = self.load_room(id) {
+            self.show_error(format!("Failed to load room {id}: {e}"));
+        }
+    }
+
+    fn rdt_browser(&mut self, ui: &mut Ui) {
+        egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+            if !self.bundled_rooms.is_empty() {
+                ui.collapsing("Bundle", |ui| {
+                    self.bundle_rdt_list(ui);
+                });
+            }
+            ui.collapsing("Leon", |ui| {
+                self.rdt_list(true, ui);
             });
             ui.collapsing("Claire", |ui| {
                 self.rdt_list(false, ui);
@@ -815,6 +1658,49 @@ impl App {
         });
     }
 
+    fn index_browser(&mut self, ui: &mut Ui) {
+        let mut selected_room = None;
+        egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+            if self.game_index.is_empty() {
+                ui.label("Open a game folder to index its items and enemies");
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Search:");
+                ui.text_edit_singleline(&mut self.index_filter);
+            });
+            if self.game_index.num_failed_rooms() > 0 {
+                ui.label(format!("{} room(s) couldn't be read", self.game_index.num_failed_rooms()));
+            }
+
+            for (heading, is_item) in [("Items", true), ("Enemies", false)] {
+                let groups = self.game_index.grouped(is_item, &self.index_filter);
+                ui.collapsing(format!("{} ({})", heading, groups.len()), |ui| {
+                    for (name, entries) in groups {
+                        egui::CollapsingHeader::new(format!("{} ({})", name, entries.len()))
+                            .id_salt((heading, &name))
+                            .show(ui, |ui| {
+                                for entry in entries {
+                                    let label = match entry.kind {
+                                        IndexKind::Item { count, .. } => format!("{} x{}", entry.room_id, count),
+                                        IndexKind::Enemy(_) => entry.room_id.to_string(),
+                                    };
+                                    if ui.selectable_label(self.config.last_rdt == Some(entry.room_id), label).clicked() {
+                                        selected_room = Some(entry.room_id);
+                                    }
+                                }
+                            });
+                    }
+                });
+            }
+        });
+
+        if let Some(room_id) = selected_room && let Err(e) = self.load_room(room_id) {
+            self.show_error(format!("Failed to load room {room_id}: {e}"));
+        }
+    }
+
     fn frames_to_time(frames: usize) -> String {
         let duration = FRAME_DURATION * frames as u32;
         let seconds = duration.as_secs_f32();
@@ -838,6 +1724,15 @@ impl App {
             ui.label(format!("Slowest: {} ({})", Self::frames_to_time(slowest_time), slowest_time));
             ui.label(format!("Average: {} ({})", Self::frames_to_time(average_time), average_time));
 
+            let scenario_stats = comparison.scenario_statistics();
+            if scenario_stats.len() > 1 {
+                ui.collapsing("By scenario", |ui| {
+                    for (scenario, count, average) in scenario_stats {
+                        ui.label(format!("{}: {} runs, average {} ({})", scenario, count, Self::frames_to_time(average), average));
+                    }
+                });
+            }
+
             ui.add_space(2.5);
 
             let mut include_exclusions_in_statistics = comparison.include_exclusions_in_statistics();
@@ -846,6 +1741,20 @@ impl App {
 
             ui.checkbox(&mut self.show_comparison_paths, "Show paths");
 
+            let mut alignment = comparison.alignment();
+            egui::ComboBox::from_label("Align playback by")
+                .selected_text(alignment.name())
+                .show_ui(ui, |ui| {
+                    for option in Alignment::list() {
+                        ui.selectable_value(&mut alignment, option, option.name());
+                    }
+                })
+                .response
+                .on_hover_text("Route progress keeps runs level by how far along their path they are, so a slow start doesn't throw off the rest of the comparison");
+            if alignment != comparison.alignment() {
+                comparison.set_alignment(alignment);
+            }
+
             ui.horizontal(|ui| {
                 if ui.button("Select all").clicked() {
                     for run in comparison.runs_mut() {
@@ -875,6 +1784,9 @@ impl App {
                 run.set_included(included);
 
                 ui.label(format!("  Time: {} ({})", Self::frames_to_time(run.len()), run.len()));
+                ui.label(format!("  Scenario: {}", run.scenario()));
+                ui.label(format!("  Danger: {:.2}", run.danger()));
+                ui.label(format!("  RNG: {} rolls, {} → {}", run.num_rng_rolls(), run.start_rng_position(), run.end_rng_position()));
             }
 
             if let Some(i) = selected_run {
@@ -886,22 +1798,386 @@ impl App {
         });
     }
     
+    fn save_bookmarks(&mut self) {
+        if Self::is_read_only() {
+            return;
+        }
+
+        let (Some(recording), Some(path)) = (self.active_recording.as_ref(), self.active_recording_path.as_ref()) else {
+            return;
+        };
+
+        let result = Bookmark::save(recording.bookmarks(), path);
+        if let Err(e) = result {
+            self.show_error(format!("Failed to save bookmarks: {e}"));
+        }
+    }
+
+    fn apply_bookmark_action(&mut self, action: BookmarkAction) {
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        match action {
+            BookmarkAction::Add => {
+                let name = format!("Bookmark {}", recording.bookmarks().len() + 1);
+                let i = recording.add_bookmark(Bookmark::new(recording.index(), name.clone()));
+                self.bookmark_draft = Some((i, name, String::new()));
+            }
+            BookmarkAction::Edit(i) => {
+                self.bookmark_draft = recording.bookmarks().get(i).map(|b| (i, b.name.clone(), b.note.clone()));
+                return;
+            }
+            BookmarkAction::Remove(i) => {
+                recording.remove_bookmark(i);
+                self.bookmark_draft = None;
+            }
+            BookmarkAction::Save => {
+                if let Some((i, name, note)) = self.bookmark_draft.take()
+                    && let Some(bookmark) = recording.bookmarks_mut().get_mut(i) {
+                    bookmark.name = name;
+                    bookmark.note = note;
+                }
+            }
+            BookmarkAction::Cancel => {
+                self.bookmark_draft = None;
+                return;
+            }
+        }
+
+        self.save_bookmarks();
+    }
+
     fn recording_browser(&mut self, ui: &mut Ui) {
         let mut selected_frame = None;
+        let mut bookmark_action = None;
+        let mut export_range = None;
+        let mut export_notes = false;
+        let mut ab_action = None;
+        let mut reference_route = None;
         egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
             let Some(ref recording) = self.active_recording else {
                 return;
             };
-            
-            for (i, run) in recording.timeline().into_iter().enumerate() {
-                let scenario = run[0].1.scenario();
-                ui.collapsing(format!("Run #{} - {}", i + 1, scenario), |ui| {
-                    for (timestamp, state) in run {
+
+            let omitted = recording.omitted_features();
+            if !omitted.is_empty() {
+                ui.colored_label(Color32::YELLOW, format!("⚠ Recorded without {}", omitted.join(", ")));
+            }
+            if recording.is_verbose() {
+                ui.label("Verbose recording: motion IDs and all model part transforms included");
+            }
+
+            ui.checkbox(&mut self.config.show_full_run_path, "Show full run path");
+            ui.checkbox(&mut self.config.show_safe_area, "Show safe area");
+            ui.checkbox(&mut self.config.show_part_hitboxes, "Show character part hitboxes");
+            ui.checkbox(&mut self.config.show_aim_readout, "Show aiming angles");
+            ui.checkbox(&mut self.config.show_deaggros, "Show where enemies lost you");
+            ui.checkbox(&mut self.config.show_grab_warnings, "Show zombie grab warnings");
+            ui.checkbox(&mut self.config.show_attack_traces, "Show attack traces");
+            ui.checkbox(&mut self.config.show_room_spawns, "Show room entry spawns");
+
+            ui.collapsing("A/B loop", |ui| {
+                for (slot, name) in ["A", "B"].into_iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let label = self.ab_segments[slot].as_ref().map_or_else(|| String::from("not set"), LoopSegment::label);
+                        ui.label(format!("{name}: {label}"));
+                        if ui.add_enabled(self.loop_range().is_some(), egui::Button::new("Set from loop")).clicked() {
+                            ab_action = Some(Some(slot));
+                        }
+                    });
+                }
+
+                let mut is_ab_looping = self.is_ab_looping;
+                let can_loop = self.ab_segments.iter().all(Option::is_some);
+                if ui.add_enabled(can_loop, egui::Checkbox::new(&mut is_ab_looping, "Alternate A/B")).changed() {
+                    ab_action = Some(None);
+                }
+            });
+
+            ui.collapsing("Export selection", |ui| {
+                ui.label("Save part of the recording as a standalone recording that starts from a keyframe.");
+
+                let loop_range = self.loop_range();
+                let loop_label = loop_range.as_ref().map_or_else(
+                    || String::from("Loop range (not set)"),
+                    |range| format!("Loop range ({}-{})", range.start, range.end - 1),
+                );
+                if ui.add_enabled(loop_range.is_some(), egui::Button::new(loop_label)).clicked() {
+                    export_range = loop_range;
+                }
+
+                let current_visit = recording.room_visits()
+                    .into_iter()
+                    .find(|(_, range)| range.contains(&recording.index()));
+                if let Some((state, range)) = current_visit
+                    && ui.button(format!("Current room visit: {} ({}-{})", state.room_id(), range.start, range.end - 1)).clicked() {
+                    export_range = Some(range);
+                }
+            });
+
+            if self.config.hit_zone_alerts {
+                ui.collapsing(format!("Hit zone alerts ({})", self.hit_zone_events.len()), |ui| {
+                    for (frame_index, description) in &self.hit_zone_events {
+                        if ui.selectable_label(recording.index() == *frame_index, format!("{frame_index}: {description}")).clicked() {
+                            selected_frame = Some(*frame_index);
+                        }
+                    }
+                });
+            }
+
+            let lag = recording.lag_by_room();
+            if !lag.is_empty() {
+                let total: usize = lag.iter().map(|(_, _, frames)| frames).sum();
+                ui.collapsing(format!("Lag frames ({})", total), |ui| {
+                    for (room_id, range, frames) in lag {
+                        let percent = frames as f32 / (range.len() + frames) as f32 * 100.0;
+                        let label = format!("{}: {} - {} lag frames ({:.1}%)", range.start, room_id, frames, percent);
+                        if ui.selectable_label(range.contains(&recording.index()), label).clicked() {
+                            selected_frame = Some(range.start);
+                        }
+                    }
+                });
+            }
+
+            let menu_time = recording.menu_time_by_room();
+            if !menu_time.is_empty() {
+                let total: usize = menu_time.iter().map(|(_, _, time)| time.total()).sum();
+                ui.collapsing(format!("Menu time ({})", Self::frames_to_time(total)), |ui| {
+                    for (room_id, range, time) in menu_time {
+                        let label = format!("{}: {} - {} ({})", range.start, room_id, Self::frames_to_time(time.total()), time);
+                        if ui.selectable_label(range.contains(&recording.index()), label).clicked() {
+                            selected_frame = Some(range.start);
+                        }
+                    }
+                });
+            }
+
+            let markers = recording.markers();
+            if !markers.is_empty() {
+                ui.collapsing(format!("Markers ({})", markers.len()), |ui| {
+                    for marker in markers {
+                        let label = format!("{}: {} - {}", marker.frame_index, marker.room_id, marker.name);
+                        if ui.selectable_label(recording.index() == marker.frame_index, label).clicked() {
+                            selected_frame = Some(marker.frame_index);
+                        }
+                    }
+                });
+            }
+
+            ui.collapsing(format!("Bookmarks ({})", recording.bookmarks().len()), |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Bookmark this frame").clicked() {
+                        bookmark_action = Some(BookmarkAction::Add);
+                    }
+                    export_notes = ui.button("Export notes")
+                        .on_hover_text("Save bookmarks, markers, runs, and rooms as a Markdown document")
+                        .clicked();
+                });
+
+                for (i, bookmark) in recording.bookmarks().iter().enumerate() {
+                    if let Some((draft_index, name, note)) = self.bookmark_draft.as_mut() && *draft_index == i {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{}:", bookmark.frame_index));
+                            ui.text_edit_singleline(name);
+                        });
+                        ui.add(egui::TextEdit::multiline(note).hint_text("Note").desired_rows(2));
+                        ui.horizontal(|ui| {
+                            if ui.button("Save").clicked() {
+                                bookmark_action = Some(BookmarkAction::Save);
+                            }
+                            if ui.button("Cancel").clicked() {
+                                bookmark_action = Some(BookmarkAction::Cancel);
+                            }
+                        });
+                        continue;
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui.add(egui::Button::new("⊗").fill(Color32::RED)).clicked() {
+                            bookmark_action = Some(BookmarkAction::Remove(i));
+                        }
+                        if ui.small_button("✏").on_hover_text("Edit").clicked() {
+                            bookmark_action = Some(BookmarkAction::Edit(i));
+                        }
+
+                        let label = format!("{}: {}", bookmark.frame_index, bookmark.name);
+                        if ui.selectable_label(recording.index() == bookmark.frame_index, label).clicked() {
+                            selected_frame = Some(bookmark.frame_index);
+                        }
+                    });
+                    if !bookmark.note.is_empty() {
+                        ui.label(format!("    {}", bookmark.note));
+                    }
+                }
+            });
+
+            let loads = recording.loads();
+            if !loads.is_empty() {
+                ui.collapsing(format!("Loads ({})", loads.len()), |ui| {
+                    for load in loads {
+                        let label = format!("{}: {}", load.frame_index, load.room_id);
+                        if ui.selectable_label(recording.index() == load.frame_index, label).clicked() {
+                            selected_frame = Some(load.frame_index);
+                        }
+                    }
+                });
+            }
+
+            let damage_events = recording.damage_events();
+            if !damage_events.is_empty() {
+                let player_damage: i32 = damage_events.iter().filter(|e| e.is_player()).map(|e| e.damage as i32).sum();
+                ui.collapsing(format!("Damage ({} events, {} taken by player)", damage_events.len(), player_damage), |ui| {
+                    for kind in DamageKind::list() {
+                        let count = damage_events.iter().filter(|e| e.kind == kind).count();
+                        if count > 0 {
+                            ui.label(format!("{}: {}", kind, count));
+                        }
+                    }
+                    ui.separator();
+
+                    for event in damage_events {
+                        let label = format!(
+                            "{}: {} - #{} {} took {} ({}), {} left",
+                            event.frame_index,
+                            event.room_id,
+                            event.character_index,
+                            event.character_name,
+                            event.damage,
+                            event.kind,
+                            event.health_after,
+                        );
+                        if ui.selectable_label(recording.index() == event.frame_index, label).clicked() {
+                            selected_frame = Some(event.frame_index);
+                        }
+                    }
+                });
+            }
+
+            let anomalies = recording.input_anomalies();
+            if !anomalies.is_empty() {
+                ui.collapsing(format!("Input anomalies ({})", anomalies.len()), |ui| {
+                    for anomaly in anomalies {
+                        let label = format!("{}: {} - {}", anomaly.frame_index, anomaly.kind, anomaly.description);
+                        if ui.selectable_label(recording.index() == anomaly.frame_index, label).clicked() {
+                            selected_frame = Some(anomaly.frame_index);
+                        }
+                    }
+                });
+            }
+
+            let deaggros = recording.deaggros();
+            if !deaggros.is_empty() {
+                ui.collapsing(format!("Enemies lost ({})", deaggros.len()), |ui| {
+                    let mut leashes: Vec<(CharacterId, Vec<i32>)> = Vec::new();
+                    for event in deaggros {
+                        let distance = event.distance().0;
+                        match leashes.iter_mut().find(|(id, _)| *id == event.character_id) {
+                            Some((_, distances)) => distances.push(distance),
+                            None => leashes.push((event.character_id, vec![distance])),
+                        }
+                    }
+
+                    for (id, distances) in &leashes {
+                        let min = distances.iter().copied().min().unwrap_or(0);
+                        let max = distances.iter().copied().max().unwrap_or(0);
+                        let avg = distances.iter().sum::<i32>() / distances.len() as i32;
+                        ui.label(format!(
+                            "{}: lost {} time(s) at {} to {} (average {})",
+                            id.name(), distances.len(), Distance(min), Distance(max), Distance(avg),
+                        ));
+                    }
+
+                    ui.separator();
+
+                    for event in deaggros {
+                        let label = format!(
+                            "{}: {} - #{} {} gave up at {} after {} ({} roll(s))",
+                            event.frame_index, event.room_id, event.character_index, event.character_id.name(),
+                            Distance(event.distance()), Self::frames_to_time(event.pursuit_frames()), event.num_rolls,
+                        );
+                        if ui.selectable_label(recording.index() == event.frame_index, label).clicked() {
+                            selected_frame = Some(event.frame_index);
+                        }
+                    }
+                });
+            }
+
+            let attack_traces = recording.attack_traces();
+            if !attack_traces.is_empty() {
+                ui.collapsing(format!("Attacks that hit ({})", attack_traces.len()), |ui| {
+                    for trace in attack_traces {
+                        let label = format!(
+                            "{}: {} - #{} {} {} ({} active frame(s), hit on frame {})",
+                            trace.frames.start, trace.room_id, trace.character_index, trace.character_id.name(),
+                            trace.zone_name, trace.frames.len(), trace.hit_frame - trace.frames.start + 1,
+                        );
+                        if ui.selectable_label(trace.frames.contains(&recording.index()), label).clicked() {
+                            selected_frame = Some(trace.frames.start);
+                        }
+                    }
+                });
+            }
+
+            ui.separator();
+
+            let sessions = recording.sessions();
+            let session_index = recording.session_index(recording.index());
+            let session_frames = sessions[session_index].frames.clone();
+            if sessions.len() > 1 {
+                egui::ComboBox::from_label("Session")
+                    .selected_text(sessions[session_index].name(session_index))
+                    .show_ui(ui, |ui| {
+                        for (i, session) in sessions.iter().enumerate() {
+                            if ui.selectable_label(i == session_index, session.name(i)).clicked() {
+                                selected_frame = Some(session.frames.start);
+                            }
+                        }
+                    });
+            }
+
+            for (i, (run, summary)) in recording.timeline().into_iter().zip(recording.run_summaries()).enumerate() {
+                // only list the runs from the session we're currently looking at
+                if !session_frames.contains(&summary.frames.start) {
+                    continue;
+                }
+
+                ui.collapsing(format!("Run #{} - {}", i + 1, summary.name()), |ui| {
+                    if ui.button("Use as reference route").clicked() {
+                        reference_route = Some(RouteTemplate::from_run(format!("Run #{} - {}", i + 1, summary.name()), recording, summary.frames.start));
+                    }
+
+                    ui.label(format!("Menu time: {} ({})", Self::frames_to_time(summary.menu_time.total()), summary.menu_time));
+
+                    let prompts = recording.prompts_in(summary.frames.clone());
+                    if !prompts.is_empty() {
+                        let open_frames: usize = prompts.iter().map(|p| p.frames.len()).sum();
+                        let reactions: Vec<usize> = prompts.iter().filter_map(MessagePrompt::reaction_frames).collect();
+                        let average_reaction = reactions.iter().sum::<usize>() as f32 / reactions.len().max(1) as f32;
+                        ui.collapsing(format!("Text prompts: {}, open {} ({}), {:.1} frames to first press on average", prompts.len(), Self::frames_to_time(open_frames), open_frames, average_reaction), |ui| {
+                            for prompt in prompts {
+                                let reaction = prompt.reaction_frames().map_or_else(|| String::from("no press"), |f| format!("pressed after {f}"));
+                                let label = format!("{}: {} - open {} frames, {}", prompt.frames.start, prompt.room_id, prompt.frames.len(), reaction);
+                                if ui.selectable_label(prompt.frames.contains(&recording.index()), label).clicked() {
+                                    selected_frame = Some(prompt.frames.start);
+                                }
+                            }
+                        });
+                    }
+
+                    for (j, (timestamp, state)) in run.iter().enumerate() {
                         let frame_index = state.frame_index();
                         let label = format!("{} - {} ({})", state.room_id(), timestamp, frame_index);
                         if ui.selectable_label(recording.room_range().contains(&frame_index), label).clicked() {
                             selected_frame = Some(frame_index);
                         }
+
+                        let visit = frame_index..run.get(j + 1).map_or(summary.frames.end, |(_, next)| next.frame_index());
+                        for load in recording.loads().iter().filter(|l| visit.contains(&l.frame_index)) {
+                            if ui.selectable_label(recording.index() == load.frame_index, format!("    📂 Loaded a save ({})", load.frame_index)).clicked() {
+                                selected_frame = Some(load.frame_index);
+                            }
+                        }
                     }
                 });
             }
@@ -910,8 +2186,56 @@ impl App {
         if let Some(frame_index) = selected_frame {
             self.change_recording_frame(|r| r.set_index(frame_index));
         }
+
+        if let Some(action) = bookmark_action {
+            self.apply_bookmark_action(action);
+        }
+
+        if let Some(range) = export_range && let Err(e) = self.prompt_export_range(range) {
+            self.show_error(format!("Failed to export recording: {e}"));
+        }
+
+        if export_notes && let Err(e) = self.prompt_export_notes() {
+            self.show_error(format!("Failed to export notes: {e}"));
+        }
+
+        if let Some(route) = reference_route {
+            self.config.reference_route = Some(route);
+            self.route_matches.clear();
+        }
+
+        match ab_action {
+            Some(Some(slot)) => self.mark_ab_segment(slot),
+            Some(None) if self.is_ab_looping => self.stop_ab_loop(),
+            Some(None) => self.start_ab_loop(),
+            None => (),
+        }
     }
     
+    fn plugin_browser(&mut self, ui: &mut Ui, index: usize) {
+        let Some(plugin) = self.plugins.get_mut(index) else {
+            return;
+        };
+
+        let recording = self.active_recording.as_ref();
+        let context = PluginContext {
+            room_id: self.config.last_rdt,
+            recording,
+            recording_path: self.active_recording_path.as_deref(),
+            state: recording.and_then(Recording::current_state),
+        };
+
+        let action = egui::ScrollArea::vertical()
+            .auto_shrink([false, true])
+            .show(ui, |ui| plugin.show(ui, &context))
+            .inner;
+
+        match action {
+            Some(PluginAction::JumpToFrame(frame_index)) => self.set_recording_frame(frame_index),
+            None => (),
+        }
+    }
+
     fn rng_browser(&mut self, ui: &mut Ui) {
         egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
             let Some(rng_descriptions) = self.active_recording().map(Recording::get_rng_descriptions) else {
@@ -1019,10 +2343,95 @@ impl App {
                 }
             }
             ui.checkbox(&mut self.config.show_sounds, "Show sounds");
+            ui.add_enabled(self.config.show_sounds, egui::Slider::new(&mut self.config.sound_max_age, 1..=300).text("Sound persistence (frames)"));
+            ui.add_enabled(self.config.show_sounds, egui::Slider::new(&mut self.config.sound_max_count, 1..=100).text("Max sounds shown"));
+            ui.add_enabled(self.config.show_sounds, egui::Checkbox::new(&mut self.config.fade_sounds, "Fade older sounds"));
+            ui.add_enabled(self.config.show_sounds, egui::Checkbox::new(&mut self.config.sounds_since_enemy_roll, "Only sounds since last enemy roll"));
+            ui.add(egui::Slider::new(&mut self.config.fast_forward_frames, 1..=300).text("Fast forward step (frames)"));
+            ui.add(egui::Slider::new(&mut self.config.shift_step_frames, 1..=300).text("Shift+arrow step (frames)"));
+            ui.checkbox(&mut self.config.hit_zone_alerts, "Alert on hit zone entry");
+            ui.checkbox(&mut self.config.limit_path_window, "Limit paths to time window");
+            ui.add_enabled(self.config.limit_path_window, egui::Slider::new(&mut self.config.path_window_past_seconds, 0.0..=30.0).text("Past (s)"));
+            ui.add_enabled(self.config.limit_path_window, egui::Slider::new(&mut self.config.path_window_future_seconds, 0.0..=30.0).text("Future (s)"));
             if ui.checkbox(&mut self.config.show_all_objects, "Show all objects").clicked() {
                 // re-populate objects from state when this setting is changed
                 self.update_from_state();
             }
+            if ui.add_enabled(!self.character_settings.is_empty(), egui::Button::new("Forget character display settings")).clicked() {
+                self.character_settings.clear();
+            }
+            ui.checkbox(&mut self.config.filter_ai_behaviors, "Only show AI zones of type:");
+            ui.add_enabled_ui(self.config.filter_ai_behaviors, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for behavior_type in BehaviorType::list() {
+                        let mut is_selected = self.config.ai_behavior_filter.contains(&behavior_type);
+                        if ui.checkbox(&mut is_selected, behavior_type.name()).changed() {
+                            if is_selected {
+                                self.config.ai_behavior_filter.push(behavior_type);
+                            } else {
+                                self.config.ai_behavior_filter.retain(|b| *b != behavior_type);
+                            }
+                        }
+                    }
+                });
+            });
+            ui.add(egui::Slider::new(&mut self.config.detail_max_rows, 1..=12).text("Detail panel rows"));
+            egui::ComboBox::from_label("Distance unit")
+                .selected_text(self.config.distance_unit.name())
+                .show_ui(ui, |ui| {
+                    for unit in DistanceUnit::list() {
+                        ui.selectable_value(&mut self.config.distance_unit, unit, unit.name());
+                    }
+                })
+                .response
+                .on_hover_text("Meters assume 1000 game units to the meter. Player widths are measured by the player's collision diameter.");
+            egui::ComboBox::from_label("Angle unit")
+                .selected_text(self.config.angle_unit.name())
+                .show_ui(ui, |ui| {
+                    for unit in AngleUnit::list() {
+                        ui.selectable_value(&mut self.config.angle_unit, unit, unit.name());
+                    }
+                });
+            ui.collapsing("Detail layouts", |ui| {
+                ui.label("Right-click a group or field in the detail panel to reorder or hide it");
+                for layout in &mut self.config.detail_layouts {
+                    ui.label(RichText::new(layout.object_type.name()).strong());
+                    if !layout.group_order.is_empty() {
+                        ui.label(format!("Order: {}", layout.group_order.join(", ")));
+                    }
+                    let mut shown_group = None;
+                    for (i, group) in layout.hidden_groups.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Hidden group: {group}"));
+                            if ui.small_button("Show").clicked() {
+                                shown_group = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = shown_group {
+                        layout.hidden_groups.remove(i);
+                    }
+                    let mut shown_field = None;
+                    for (i, field) in layout.hidden_fields.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Hidden field: {field}"));
+                            if ui.small_button("Show").clicked() {
+                                shown_field = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = shown_field {
+                        layout.hidden_fields.remove(i);
+                    }
+                    if ui.button("Reset").clicked() {
+                        layout.group_order.clear();
+                        layout.hidden_groups.clear();
+                        layout.hidden_fields.clear();
+                    }
+                }
+                self.config.detail_layouts.retain(|layout| !layout.is_empty());
+            });
+
             ui.separator();
 
             for (object_type, object_settings) in &mut self.config.object_settings {
@@ -1057,37 +2466,82 @@ impl App {
     fn get_character_settings(&self, index: usize) -> Option<CharacterSettings> {
         let room_id = self.active_recording().and_then(Recording::current_state).map(State::room_id)?;
         let character_id = self.get_character(index)?.id;
-        Some(self.character_settings.get(&(room_id, character_id, index)).copied().unwrap_or_else(|| CharacterSettings::config_default(&self.config)))
+        Some(self.character_settings.get(&(room_id, character_id as u8)).copied().unwrap_or_else(|| CharacterSettings::config_default(&self.config)))
     }
 
     fn get_character_settings_mut(&mut self, index: usize) -> Option<&mut CharacterSettings> {
         let room_id = self.active_recording().and_then(Recording::current_state).map(State::room_id)?;
         let character_id = self.get_character(index)?.id;
-        Some(self.character_settings.entry((room_id, character_id, index)).or_insert_with(|| CharacterSettings::config_default(&self.config)))
+        Some(self.character_settings.entry((room_id, character_id as u8)).or_insert_with(|| CharacterSettings::config_default(&self.config)))
     }
 
-    fn object_details(&mut self, ui: &mut Ui) {
-        egui::ScrollArea::horizontal().show(ui, |ui| {
-            let description = match self.selected_object {
-                SelectedObject::Floor(i) => self.floors[i].details(),
-                SelectedObject::Entity(i) => self.entities[i].details(),
-                SelectedObject::Collider(i) => self.colliders[i].details(),
-                SelectedObject::Object(i) => match self.get_object(i) {
-                    Some(object) => object.details(),
-                    None => vec![],
+    fn selected_object_type(&self) -> Option<ObjectType> {
+        Some(match self.selected_object {
+            SelectedObject::Floor(i) => self.floors[i].object_type(),
+            SelectedObject::Entity(i) => self.entities[i].object_type(),
+            SelectedObject::Collider(i) => self.colliders[i].object_type(),
+            SelectedObject::Object(i) => self.get_object(i)?.object_type(),
+            SelectedObject::AiZone(i) => self.ai_zones[i].object_type(),
+            SelectedObject::Character(i) => self.get_character(i)?.object_type(),
+            SelectedObject::None => return None,
+        })
+    }
+
+    fn selected_object_details(&self) -> Vec<(String, Vec<String>)> {
+        let details = match self.selected_object {
+            SelectedObject::Floor(i) => self.floors[i].details(),
+            SelectedObject::Entity(i) => {
+                let entity = &self.entities[i];
+                let mut details = entity.details();
+                if let (EntityForm::Door { next_stage, next_room, .. }, Some(room_id)) = (entity.form(), self.config.last_rdt) {
+                    let target_id = RoomId::new(*next_stage, *next_room, room_id.player);
+                    let timing = match self.door_times.average_frames(room_id, target_id) {
+                        Some((frames, count)) => vec![
+                            format!("Average: {:.1} frames ({:.2}s)", frames, frames * FRAME_DURATION.as_secs_f32()),
+                            format!("Samples: {}", count),
+                        ],
+                        None => vec![String::from("No data")],
+                    };
+                    details.push((format!("Door Timing ({} recordings)", self.door_times.num_recordings()), timing));
                 }
-                SelectedObject::AiZone(i) => self.ai_zones[i].details(),
-                SelectedObject::Character(i) => match self.get_character(i) {
-                    Some(character) => character.details(),
-                    None => vec![],
-                },
-                SelectedObject::None => return,
-            };
+                details
+            }
+            SelectedObject::Collider(i) => self.colliders[i].details(),
+            SelectedObject::Object(i) => match self.get_object(i) {
+                Some(object) => object.details(),
+                None => vec![],
+            }
+            SelectedObject::AiZone(i) => self.ai_zones[i].details(),
+            SelectedObject::Character(i) => match self.get_character(i) {
+                Some(character) => character.details(),
+                None => vec![],
+            },
+            SelectedObject::None => vec![],
+        };
+
+        match self.selected_object_type().and_then(|t| self.config.detail_layout(t)) {
+            Some(layout) => layout.apply(details),
+            None => details,
+        }
+    }
+
+    fn object_details(&mut self, ui: &mut Ui) {
+        let mut new_frame_index = None;
+        let mut layout_change = None;
+        let mut export_path = None;
+        let mut find_firings = false;
+
+        if let SelectedObject::AiZone(i) = self.selected_object && !self.zone_card.as_ref().is_some_and(|c| c.is_for(&self.ai_zones[i])) {
+            self.zone_card = Some(ZoneCard::new(&self.ai_zones[i]));
+        }
 
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            let description = self.selected_object_details();
             if description.is_empty() {
                 return;
             }
 
+            let max_rows = self.config.detail_max_rows.max(1);
             let mut groups = description.into_iter();
             let (mut group_name, fields) = groups.next().unwrap();
             let mut field_iter = fields.into_iter();
@@ -1098,7 +2552,15 @@ impl App {
                 loop {
                     ui.vertical(|ui| {
                         if is_group_start {
-                            ui.label(RichText::new(group_name.clone()).strong());
+                            let response = ui.add(egui::Label::new(RichText::new(group_name.clone()).strong()).sense(egui::Sense::click()));
+                            response.context_menu(|ui| {
+                                if ui.button("Show first").clicked() {
+                                    layout_change = Some(DetailLayoutChange::MoveToFront(group_name.clone()));
+                                }
+                                if ui.button("Hide group").clicked() {
+                                    layout_change = Some(DetailLayoutChange::HideGroup(group_name.clone()));
+                                }
+                            });
                             is_group_start = false;
                         } else {
                             ui.label("");
@@ -1108,16 +2570,22 @@ impl App {
                         loop {
                             match field_iter.next() {
                                 Some(field) => {
-                                    ui.label(field);
+                                    let label = DetailLayout::field_label(&field).to_string();
+                                    let response = ui.add(egui::Label::new(field).sense(egui::Sense::click()));
+                                    response.context_menu(|ui| {
+                                        if ui.button(format!("Hide \"{label}\"")).clicked() {
+                                            layout_change = Some(DetailLayoutChange::HideField(label.clone()));
+                                        }
+                                    });
                                     num_rows += 1;
 
-                                    if num_rows >= DETAIL_MAX_ROWS {
+                                    if num_rows >= max_rows {
                                         break;
                                     }
                                 }
                                 None => {
                                     is_group_end = true;
-                                    while num_rows < DETAIL_MAX_ROWS {
+                                    while num_rows < max_rows {
                                         ui.label("");
                                         num_rows += 1;
                                     }
@@ -1154,13 +2622,115 @@ impl App {
                         ui.vertical(|ui| {
                             ui.label("");
                             ui.checkbox(&mut settings.show_path, "Show path");
+                            if i != 0 {
+                                ui.checkbox(&mut settings.show_prediction, "Show predicted path");
+                            }
                         });
                     }
-                }
 
-                ui.shrink_height_to_current();
+                    if self.active_recording().is_some() && ui.button("Export path").clicked() {
+                        export_path = Some(i);
+                    }
+
+                    let show_prediction = i != 0 && self.get_character_settings(i).map(|s| s.show_prediction()).unwrap_or(false);
+                    let prediction = self.active_recording().filter(|_| show_prediction).and_then(|r| r.get_path_prediction_for_character(i));
+                    if let Some(prediction) = prediction {
+                        ui.separator();
+                        ui.vertical(|ui| {
+                            ui.label(RichText::new("Prediction").strong());
+                            ui.label(format!("Divergences: {}", prediction.divergences.len()));
+                            ui.label(format!("Max error: {}", prediction.max_error));
+                            if let Some((frame_index, _)) = prediction.divergences.last() {
+                                if ui.button(format!("Last divergence: {frame_index}")).clicked() {
+                                    new_frame_index = Some(*frame_index);
+                                }
+                            }
+                        });
+                    }
+                }
+
+                if let (SelectedObject::AiZone(_), Some(card)) = (self.selected_object, self.zone_card.as_ref()) {
+                    let character = self.characters.objects().iter().find(|c| c.index() == card.character_index);
+
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        ui.label(RichText::new("Conditions").strong());
+                        for (j, mask) in card.zone.state_mask.iter().enumerate() {
+                            let current = character.map(|c| format!(" (now {:02X})", c.state[j])).unwrap_or_default();
+                            ui.label(format!("State {j}: {}{current}", mask.describe()));
+                        }
+                        let current = character.map(|c| format!(" (now {:02X})", c.type_ & 0x3f)).unwrap_or_default();
+                        ui.label(format!("Type: {}{current}", card.zone.type_mask.describe()));
+                    });
+
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        ui.label(RichText::new("Odds").strong());
+                        if card.odds.is_empty() {
+                            ui.label("No random roll");
+                        }
+                        for odds in &card.odds {
+                            ui.label(&odds.label);
+                            for (outcome, chance) in &odds.outcomes {
+                                ui.label(format!("    {outcome}: {:.3}%", chance * 100.0));
+                            }
+                        }
+                    });
+
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        ui.label(RichText::new("Recording").strong());
+                        match card.firings {
+                            None => {
+                                if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Find frames")).clicked() {
+                                    find_firings = true;
+                                }
+                            }
+                            Some(ref firings) if firings.is_empty() => {
+                                ui.label("Never fired in this room");
+                            }
+                            Some(ref firings) => {
+                                ui.menu_button(format!("Fired {} time(s)", firings.len()), |ui| {
+                                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                                        for range in firings {
+                                            if ui.selectable_label(false, format!("Frames {}-{}", range.start, range.end - 1)).clicked() {
+                                                new_frame_index = Some(range.start);
+                                                ui.close();
+                                            }
+                                        }
+                                    });
+                                });
+                            }
+                        }
+                    });
+                }
+
+                ui.shrink_height_to_current();
             });
         });
+
+        if find_firings && let (Some(card), Some(recording)) = (self.zone_card.as_mut(), self.active_recording.as_mut()) {
+            let index = recording.index();
+            card.find_firings(recording);
+            recording.set_index(index);
+        }
+
+        if let Some(frame_index) = new_frame_index {
+            self.set_recording_frame(frame_index);
+        }
+
+        if let Some(i) = export_path && let Err(e) = self.prompt_export_character_path(i) {
+            self.show_error(format!("Failed to export path: {e}"));
+        }
+
+        if let (Some(change), Some(object_type)) = (layout_change, self.selected_object_type()) {
+            let layout = self.config.detail_layout_mut(object_type);
+            match change {
+                DetailLayoutChange::MoveToFront(group) => layout.move_to_front(&group),
+                DetailLayoutChange::HideGroup(group) => layout.hidden_groups.push(group),
+                DetailLayoutChange::HideField(field) => layout.hidden_fields.push(field),
+            }
+        }
     }
 
     fn update_from_state(&mut self) {
@@ -1206,6 +2776,15 @@ impl App {
         self.ai_zones.set_objects(ai_zones);
         self.objects.set_objects(objects);
 
+        if let Some(recording) = self.active_recording.as_ref().or_else(|| self.comparison.as_ref().map(Comparison::recording)) {
+            for ghost in &mut self.ghosts {
+                ghost.sync(recording);
+            }
+        }
+
+        self.check_hit_zone_alert();
+        self.check_notification_rules();
+
         if self.config.last_rdt != Some(new_room_id) {
             if let Err(e) = self.load_room(new_room_id) {
                 self.show_error(format!("Failed to load room {new_room_id}: {e}"));
@@ -1213,6 +2792,226 @@ impl App {
         }
     }
 
+    fn check_hit_zone_alert(&mut self) {
+        let Some(player) = self.get_character(0) else {
+            self.is_player_in_hit_zone = false;
+            return;
+        };
+
+        let pos = player.center();
+        let floor = player.floor();
+        let hit_zone = self.ai_zones.objects().iter().find(|zone| {
+            zone.ai_zone.behavior_type == BehaviorType::Hit && zone.floor.matches(floor) && zone.contains_point(pos)
+        });
+        let description = hit_zone.map(|zone| format!("#{} {}: {}", zone.character_index, zone.character_id.name(), zone.ai_zone.name));
+
+        let was_player_in_hit_zone = self.is_player_in_hit_zone;
+        self.is_player_in_hit_zone = description.is_some();
+        if !self.config.hit_zone_alerts || was_player_in_hit_zone {
+            return;
+        }
+
+        let (Some(description), Some(frame_index)) = (description, self.active_recording().map(Recording::index)) else {
+            return;
+        };
+
+        self.hit_zone_flash_start = Some(Instant::now());
+        self.hit_zone_events.insert(frame_index, description);
+    }
+
+    fn check_notification_rules(&mut self) {
+        let Some(recording) = self.active_recording.as_ref() else {
+            return;
+        };
+        let Some(state) = recording.current_state() else {
+            return;
+        };
+
+        let frame_index = recording.index();
+        let triggered = self.rule_tracker.update(&self.config.notification_rules, state);
+        for i in triggered {
+            let rule = &self.config.notification_rules[i];
+            self.notification_banner = Some((Instant::now(), rule.name.clone()));
+            self.need_user_attention |= rule.request_attention;
+            self.notification_events.insert(frame_index, rule.name.clone());
+            if let Some(cue) = rule.sound && let Err(e) = self.cue_player.play(cue) {
+                eprintln!("Failed to play notification sound: {}", e);
+            }
+        }
+    }
+
+    fn notification_window(&mut self, ctx: &Context) {
+        let mut is_notification_window_open = self.is_notification_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Notifications")
+            .open(&mut is_notification_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let mut delete = None;
+                for (i, rule) in self.config.notification_rules.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        let delete_button = egui::Button::new("⊗").fill(Color32::RED);
+                        if ui.add(delete_button).clicked() {
+                            delete = Some(i);
+                        }
+
+                        ui.checkbox(&mut rule.is_enabled, "");
+                        ui.text_edit_singleline(&mut rule.name);
+                    });
+
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt(("notification_condition", i))
+                            .selected_text(rule.condition.name())
+                            .show_ui(ui, |ui| {
+                                for condition in RuleCondition::list() {
+                                    if ui.selectable_label(rule.condition.is_same_kind(&condition), condition.name()).clicked()
+                                        && !rule.condition.is_same_kind(&condition) {
+                                        rule.condition = condition;
+                                    }
+                                }
+                            });
+
+                        match rule.condition {
+                            RuleCondition::RngIndexInRange { ref mut min, ref mut max } => {
+                                ui.add(egui::DragValue::new(min));
+                                ui.label("–");
+                                ui.add(egui::DragValue::new(max));
+                            }
+                            RuleCondition::EnemyHealthBelow(ref mut health) | RuleCondition::PlayerHealthBelow(ref mut health) => {
+                                ui.add(egui::DragValue::new(health).suffix(" HP"));
+                            }
+                            RuleCondition::EnemyCountAtLeast(ref mut count) => {
+                                ui.add(egui::DragValue::new(count).range(0..=NUM_CHARACTERS));
+                            }
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut rule.request_attention, "Request window attention");
+
+                        egui::ComboBox::from_id_salt(("notification_sound", i))
+                            .selected_text(rule.sound.map_or("No sound", |cue| cue.name()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut rule.sound, None, "No sound");
+                                for cue in SoundCue::list() {
+                                    ui.selectable_value(&mut rule.sound, Some(cue), cue.name());
+                                }
+                            });
+                        if let Some(cue) = rule.sound && ui.button("▶").on_hover_text("Preview").clicked()
+                            && let Err(e) = self.cue_player.play(cue) {
+                            eprintln!("Failed to play notification sound: {}", e);
+                        }
+                    });
+                    ui.separator();
+                }
+
+                if let Some(i) = delete {
+                    self.config.notification_rules.remove(i);
+                    self.rule_tracker.reset();
+                }
+
+                if ui.button("Add rule").clicked() {
+                    let num_rules = self.config.notification_rules.len();
+                    self.config.notification_rules.push(NotificationRule::new(format!("Rule {}", num_rules + 1)));
+                }
+
+                if !self.notification_events.is_empty() {
+                    ui.separator();
+                    let current_index = self.active_recording().map(Recording::index);
+                    ui.collapsing(format!("Triggered ({})", self.notification_events.len()), |ui| {
+                        egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                            for (frame_index, name) in &self.notification_events {
+                                if ui.selectable_label(current_index == Some(*frame_index), format!("{frame_index}: {name}")).clicked() {
+                                    selected_frame = Some(*frame_index);
+                                }
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        self.is_notification_window_open = is_notification_window_open;
+    }
+
+    /// The frames playback should repeat, if a complete loop region has been marked
+    fn loop_range(&self) -> Option<Range<usize>> {
+        match (self.loop_in, self.loop_out) {
+            (Some(start), Some(end)) if start < end => Some(start..end + 1),
+            _ => None,
+        }
+    }
+
+    fn clear_loop(&mut self) {
+        self.loop_in = None;
+        self.loop_out = None;
+    }
+
+    fn playback_loop_range(&self) -> Option<Range<usize>> {
+        if self.is_ab_looping {
+            self.ab_segments[self.ab_current].as_ref().map(|s| s.range.clone())
+        } else {
+            self.loop_range()
+        }
+    }
+
+    fn mark_ab_segment(&mut self, slot: usize) {
+        let (Some(range), Some(path)) = (self.loop_range(), self.active_recording_path.clone()) else {
+            return;
+        };
+
+        self.ab_segments[slot] = Some(LoopSegment { path, range });
+    }
+
+    fn start_ab_loop(&mut self) {
+        if self.ab_segments.iter().any(Option::is_none) {
+            return;
+        }
+
+        self.is_ab_looping = true;
+        // switching moves us to the other segment, so start from B to begin playback with A
+        self.ab_current = 1;
+        self.switch_ab_segment();
+    }
+
+    fn stop_ab_loop(&mut self) {
+        self.is_ab_looping = false;
+        self.ab_parked_recording = None;
+    }
+
+    fn switch_ab_segment(&mut self) {
+        let next = 1 - self.ab_current;
+        let Some(segment) = self.ab_segments[next].clone() else {
+            self.stop_ab_loop();
+            return;
+        };
+
+        if self.active_recording_path.as_ref() != Some(&segment.path) {
+            let recording = match self.ab_parked_recording.take() {
+                Some((path, recording)) if path == segment.path => recording,
+                _ => match File::open(&segment.path).map_err(anyhow::Error::from).and_then(Recording::read) {
+                    Ok(recording) => recording,
+                    Err(e) => {
+                        self.stop_ab_loop();
+                        self.show_error(format!("Failed to load recording for A/B loop: {e}"));
+                        return;
+                    }
+                },
+            };
+
+            let previous = self.active_recording.replace(recording);
+            let previous_path = self.active_recording_path.replace(segment.path.clone());
+            self.ab_parked_recording = previous.zip(previous_path).map(|(r, p)| (p, r));
+        }
+
+        self.ab_current = next;
+        self.set_recording_frame(segment.range.start);
+    }
+
     fn change_recording_frame<F>(&mut self, func: F)
     where F: FnOnce(&mut Recording) -> Option<&State>
     {
@@ -1312,11 +3111,14 @@ impl App {
         }
     }
 
-    fn get_sound_text_box(sound: &PlayerSound, draw_params: &DrawParams, ui: &Ui) -> egui::Shape {
+    fn get_sound_text_box(sound: &PlayerSound, fade_age: Option<usize>, draw_params: &DrawParams, ui: &Ui) -> egui::Shape {
         let (x, y, _, _) = draw_params.transform(sound.pos.x, sound.pos.z, UFixed16(0), UFixed16(0));
         let pos = egui::Pos2::new(x, y);
 
-        let age = 1.0 - (sound.age as f32 / MAX_SOUND_AGE as f32);
+        let age = match fade_age {
+            Some(max_age) => 1.0 - (sound.age as f32 / (max_age + 1) as f32),
+            None => 1.0,
+        };
 
         let bg_color = draw_params.fill_color.gamma_multiply(age);
         let text_color = draw_params.stroke.color.gamma_multiply(age);
@@ -1361,6 +3163,10 @@ impl App {
     }
 
     fn title(&self) -> String {
+        if self.current_rdt_format != RdtFormat::Re2 && let Some(id) = self.config.last_rdt {
+            return format!("{} - {} ({})", APP_NAME, id, self.current_rdt_format.layout().name());
+        }
+
         match (self.config.rdt_folder.as_ref(), self.config.last_rdt) {
             (Some(folder), Some(id)) => format!("{} - {} - {}", APP_NAME, id, folder.display()),
             (Some(folder), None) => format!("{} - {}", APP_NAME, folder.display()),
@@ -1393,6 +3199,37 @@ impl App {
         }
     }
 
+    fn rdt_warning_modal(&mut self, ctx: &Context) {
+        let Some(ref warnings) = self.rdt_warnings else {
+            return;
+        };
+
+        let response = egui::Modal::new(egui::Id::new("RDT Warning Modal")).show(ctx, |ui| {
+            ui.label(RichText::new(format!("Room {} loaded with errors", warnings.room_id)).strong());
+            ui.separator();
+            ui.label(format!("The room could not be read as-is: {}", warnings.error));
+            ui.label("The following sections were skipped:");
+            egui::Grid::new("rdt_warning_grid").striped(true).show(ui, |ui| {
+                ui.strong("Section");
+                ui.strong("Offset");
+                ui.strong("Problem");
+                ui.end_row();
+
+                for section in &warnings.skipped {
+                    ui.label(section.name);
+                    ui.monospace(format!("{:08X}", section.offset));
+                    ui.label(section.reason.description());
+                    ui.end_row();
+                }
+            });
+            ui.vertical_centered(|ui| ui.button("OK").clicked()).inner
+        });
+
+        if response.should_close() || response.inner {
+            self.rdt_warnings = None;
+        }
+    }
+
     fn connecting_rooms(&self) -> Vec<RoomId> {
         let mut connecting_rooms = Vec::new();
         let Some(this_room_id) = self.config.last_rdt else {
@@ -1479,13 +3316,13 @@ impl App {
     }
 
     fn select_comparison_recordings(&mut self) -> Result<()> {
-        let Some(recording_paths) = FileDialog::new().add_filter("RE2 recordings", &["bin"]).pick_files() else {
+        let Some(recording_paths) = dialog::pick_files("RE2 recordings", &["bin"]) else {
             // user canceled the dialog, so just bail
             return Ok(());
         };
 
         let entities = self.entities.objects();
-        let comparison = Comparison::load_runs(recording_paths, &self.compare_filter, entities)?;
+        let comparison = Comparison::load_runs(recording_paths, &self.compare_filter, entities, &mut self.door_times)?;
 
         // close any active individual recording
         self.close_recording();
@@ -1495,92 +3332,526 @@ impl App {
         Ok(())
     }
 
-    fn open_rng_explore_window(&mut self, roll_type: RollType, rng_index: usize) {
-        if self.rng_selected_roll_type != Some(roll_type) {
-            self.rng_selected_outcomes.clear();
+    fn prompt_generate_drills(&mut self) -> Result<()> {
+        let Some(recording_paths) = dialog::pick_files("RE2 recordings", &["bin"]) else {
+            return Ok(());
+        };
+
+        self.drills = Drill::generate(recording_paths, self.drill_min_attempts, &mut self.door_times)?;
+        Ok(())
+    }
+
+    fn open_drill_attempt(&mut self, path: PathBuf, frame_index: usize) -> Result<()> {
+        if self.active_recording_path.as_ref() != Some(&path) {
+            self.load_recording(&path)?;
         }
 
-        self.rng_selected_roll_type = Some(roll_type);
-        self.rng_selected_index = rng_index;
-        self.is_rng_explore_window_open = true;
+        self.set_recording_frame(frame_index);
+        Ok(())
     }
 
-    fn rng_explore_window(&mut self, ctx: &Context) {
-        let mut is_rng_explore_window_open = self.is_rng_explore_window_open;
-        
-        egui::Window::new("Explore RNG")
-            .open(&mut is_rng_explore_window_open)
+    fn drill_window(&mut self, ctx: &Context) {
+        let mut is_drill_window_open = self.is_drill_window_open;
+        let mut generate = false;
+        let mut selected_attempt = None;
+
+        egui::Window::new("Practice Drills")
+            .open(&mut is_drill_window_open)
             .order(egui::Order::Foreground)
             .show(ctx, |ui| {
-                let old_roll_type = self.rng_selected_roll_type;
-                egui::ComboBox::from_label("Roll type")
-                    .selected_text(match self.rng_selected_roll_type {
-                        Some(roll_type) => format!("{:?}", roll_type),
-                        None => "None".to_string(),
-                    })
-                    .show_ui(ui, |ui| {
-                        for (roll_type, description) in ROLL_DESCRIPTIONS.iter() {
-                            if matches!(roll_type, RollType::Partial | RollType::Invalid) {
-                                continue;
-                            }
-                            
-                            ui.selectable_value(&mut self.rng_selected_roll_type, Some(roll_type), format!("{:?} ({})", roll_type, description.label("<Character>")));
-                        }
-                    });
-                
-                if self.rng_selected_roll_type != old_roll_type {
-                    self.rng_selected_outcomes.clear();
+                ui.label("Ranks the room segments in a set of recordings by how much time an average attempt loses to your best one, plus how inconsistent the attempts are.");
+                ui.horizontal(|ui| {
+                    ui.label("Minimum attempts");
+                    ui.add(egui::DragValue::new(&mut self.drill_min_attempts).range(2..=100));
+                    generate = !Self::is_read_only() && ui.button("Choose recordings").clicked();
+                });
+
+                if self.drills.is_empty() {
+                    return;
                 }
-                
-                ui.add(egui::Slider::new(&mut self.rng_selected_index, 0..=(RNG_SEQUENCE.len() - 1)).text("RNG index"));
-                
-                let roll = match self.rng_selected_roll_type {
-                    Some(t) => {
-                        let desc = &ROLL_DESCRIPTIONS[t];
-                        RngDescription::new(desc.label("<Character>"), if desc.has_subject() { RollCategory::Character(0) } else { RollCategory::NonCharacter }, Some(t), RNG_SEQUENCE[self.rng_selected_index])
-                    }
-                    None => return,
-                };
 
                 ui.separator();
 
-                let options = roll.options();
+                egui::ScrollArea::vertical().max_height(500.0).show(ui, |ui| {
+                    for (i, drill) in self.drills.iter().enumerate() {
+                        ui.collapsing(format!("{}. {}: -{:.0} frames on average", i + 1, drill.name(), drill.average_loss()), |ui| {
+                            ui.label(format!("Attempts: {}", drill.attempts.len()));
+                            ui.label(format!("Best: {} ({})", Self::frames_to_time(drill.fastest().frames), drill.fastest().frames));
+                            ui.label(format!("Average: {:.1} frames (σ {:.1})", drill.average_frames(), drill.std_dev()));
 
-                ui.horizontal(|ui| {
-                    ui.vertical(|ui| {
-                        ui.label("Distribution");
+                            let fastest = drill.fastest();
+                            if ui.button(format!("Watch fastest: {}", fastest.identifier())).clicked() {
+                                selected_attempt = Some(fastest.clone());
+                            }
 
-                        ui.add_enabled(!self.rng_selected_outcomes.is_empty(), egui::Checkbox::new(&mut self.rng_distribution_binary, "By desired outcome"));
+                            let slowest = drill.slowest();
+                            if ui.button(format!("Watch slowest: {}", slowest.identifier())).clicked() {
+                                selected_attempt = Some(slowest.clone());
+                            }
+                        });
+                    }
+                });
+            });
 
-                        let half_range = (RNG_SEQUENCE.len() / 2) as isize;
-                        ui.add(egui::Slider::new(&mut self.rng_distribution_range_min, -half_range..=0).text("Min"));
-                        ui.add(egui::Slider::new(&mut self.rng_distribution_range_max, 0..=half_range).text("Max"));
+        if generate && let Err(e) = self.prompt_generate_drills() {
+            self.show_error(format!("Failed to generate drills: {e}"));
+        }
 
-                        let distribution = roll.distribution(self.rng_distribution_range_min, self.rng_distribution_range_max);
-                        let total = distribution.iter().map(|d| d.1).sum::<usize>() as f32;
+        if let Some(attempt) = selected_attempt
+            && let Err(e) = self.open_drill_attempt(attempt.source_path.to_path_buf(), attempt.frame_index) {
+            self.show_error(format!("Failed to open recording: {e}"));
+        }
 
-                        if self.rng_distribution_binary {
-                            let mut count_desired = 0usize;
-                            for (value, count) in distribution {
-                                if self.rng_selected_outcomes.contains(value.as_str()) {
-                                    count_desired += count;
-                                }
-                            }
+        self.is_drill_window_open = is_drill_window_open;
+    }
 
-                            let desired_percent = count_desired as f32 / total * 100.0;
-                            ui.label(format!("Desired: {desired_percent:.2}%"));
-                        } else {
-                            for (value, count) in distribution {
-                                let percent = count as f32 / total * 100.0;
-                                ui.label(format!("{value}: {percent:.2}%"));
-                            }
-                        }
-                    });
+    fn prompt_build_progress(&mut self) -> Result<()> {
+        let Some(folder) = dialog::pick_folder() else {
+            return Ok(());
+        };
 
-                    if !options.is_empty() {
-                        ui.separator();
+        let recording_paths = ProgressReport::recordings_in(&folder)?;
+        self.progress_report = Some(ProgressReport::build(recording_paths, self.drill_min_attempts, &mut self.door_times)?);
+        self.progress_segment = 0;
+        Ok(())
+    }
 
-                        ui.vertical(|ui| {
+    fn progress_window(&mut self, ctx: &Context) {
+        let mut is_progress_window_open = self.is_progress_window_open;
+        let mut build = false;
+        let mut selected_attempt = None;
+
+        egui::Window::new("Progress")
+            .open(&mut is_progress_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.label("Tracks your time on each room segment across a folder of recordings, in the order they were recorded.");
+                ui.horizontal(|ui| {
+                    ui.label("Minimum attempts");
+                    ui.add(egui::DragValue::new(&mut self.drill_min_attempts).range(2..=100));
+                    build = !Self::is_read_only() && ui.button("Choose recordings folder").clicked();
+                });
+
+                let Some(ref report) = self.progress_report else {
+                    return;
+                };
+
+                if report.num_undated > 0 {
+                    ui.label(format!("{} attempts left out because their recordings have no date", report.num_undated));
+                }
+
+                if report.segments.is_empty() {
+                    ui.label("No segments with enough attempts");
+                    return;
+                }
+
+                ui.separator();
+
+                let selected_name = report.segments.get(self.progress_segment).map(|s| s.name()).unwrap_or_default();
+                egui::ComboBox::from_label("Segment")
+                    .selected_text(selected_name)
+                    .show_ui(ui, |ui| {
+                        for (i, segment) in report.segments.iter().enumerate() {
+                            ui.selectable_value(&mut self.progress_segment, i, format!("{} ({} attempts)", segment.name(), segment.attempts.len()));
+                        }
+                    });
+
+                let Some(segment) = report.segments.get(self.progress_segment) else {
+                    return;
+                };
+
+                ui.label(format!("Attempts: {}", segment.attempts.len()));
+                if let Some(best) = segment.best() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("PB: {} ({}) on {}", Self::frames_to_time(best.attempt.frames), best.attempt.frames, best.day()));
+                        if ui.button("Watch").clicked() {
+                            selected_attempt = Some(best.attempt.clone());
+                        }
+                    });
+                }
+                if let Some(improvement) = segment.improvement() {
+                    ui.label(format!("Rolling average improved by {:.1} frames", improvement));
+                }
+
+                Plot::new("segment_progress")
+                    .x_axis_label("Attempt")
+                    .y_axis_label("Frames")
+                    .legend(egui_plot::Legend::default())
+                    .min_size(egui::Vec2::new(200.0, 100.0))
+                    .height(200.0)
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(Line::new("Time", segment.times()).color(Color32::GRAY));
+                        plot_ui.line(Line::new("PB", segment.pb_progression()).color(Color32::from_rgb(0xFF, 0xD7, 0x00)));
+                        plot_ui.line(Line::new(format!("Average of {}", ROLLING_AVERAGE_ATTEMPTS), segment.rolling_average()).color(Color32::from_rgb(0x00, 0x96, 0xFF)));
+                    });
+
+                ui.collapsing("By day", |ui| {
+                    egui::Grid::new("progress_days").striped(true).show(ui, |ui| {
+                        ui.label("Day");
+                        ui.label("Attempts");
+                        ui.label("Best");
+                        ui.label("Average");
+                        ui.end_row();
+
+                        for day in segment.daily_summaries() {
+                            ui.label(day.day);
+                            ui.label(day.num_attempts.to_string());
+                            ui.label(day.best.to_string());
+                            ui.label(format!("{:.1}", day.average));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+        if build && let Err(e) = self.prompt_build_progress() {
+            self.show_error(format!("Failed to build progress report: {e}"));
+        }
+
+        if let Some(attempt) = selected_attempt
+            && let Err(e) = self.open_drill_attempt(attempt.source_path.to_path_buf(), attempt.frame_index) {
+            self.show_error(format!("Failed to open recording: {e}"));
+        }
+
+        self.is_progress_window_open = is_progress_window_open;
+    }
+
+    fn prompt_build_behavior_report(&mut self) -> Result<()> {
+        let Some(folder) = dialog::pick_folder() else {
+            return Ok(());
+        };
+
+        let recording_paths = ProgressReport::recordings_in(&folder)?;
+        self.behavior_report = Some(BehaviorReport::build(recording_paths, &mut self.door_times)?);
+        Ok(())
+    }
+
+    fn behavior_window(&mut self, ctx: &Context) {
+        let mut is_behavior_window_open = self.is_behavior_window_open;
+        let mut build = false;
+
+        egui::Window::new("Enemy Behavior")
+            .open(&mut is_behavior_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.label("Counts how often each enemy chose each behavior across a folder of recordings, compared to the odds of each roll over the RNG's whole cycle.");
+                build = !Self::is_read_only() && ui.button("Choose recordings folder").clicked();
+
+                let Some(ref report) = self.behavior_report else {
+                    return;
+                };
+
+                ui.label(format!("{} recordings", report.num_recordings));
+                if report.tallies.is_empty() {
+                    ui.label("No behavior rolls found");
+                    return;
+                }
+
+                ui.label(format!(
+                    "Deviation is how many standard errors the observed rate is from the expected rate, shown once a roll has been made at least {MIN_ROLLS_FOR_DEVIATION} times. Large deviations are highlighted.",
+                ));
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(500.0).show(ui, |ui| {
+                    for room_id in report.rooms() {
+                        let tallies: Vec<_> = report.tallies.iter().filter(|t| t.room_id == room_id).collect();
+                        let num_suspicious = tallies.iter()
+                            .filter(|t| t.max_deviation().is_some_and(|d| d.abs() >= SUSPICIOUS_DEVIATION))
+                            .count();
+                        let heading = if num_suspicious > 0 {
+                            format!("{room_id} ({} rolls, {num_suspicious} suspicious)", tallies.len())
+                        } else {
+                            format!("{room_id} ({} rolls)", tallies.len())
+                        };
+
+                        ui.collapsing(heading, |ui| {
+                            egui::Grid::new(format!("behavior_{room_id}")).striped(true).show(ui, |ui| {
+                                ui.label("Roll");
+                                ui.label("Outcome");
+                                ui.label("Count");
+                                ui.label("Observed");
+                                ui.label("Expected");
+                                ui.label("Deviation");
+                                ui.end_row();
+
+                                for tally in tallies {
+                                    ui.label(format!("{} ({} rolls)", tally.label(), tally.total));
+                                    ui.end_row();
+
+                                    for outcome in &tally.outcomes {
+                                        ui.label("");
+                                        ui.label(&outcome.outcome);
+                                        ui.label(outcome.count.to_string());
+                                        ui.label(format!("{:.1}%", outcome.observed(tally.total) * 100.0));
+                                        ui.label(format!("{:.1}%", outcome.expected * 100.0));
+                                        match outcome.deviation(tally.total) {
+                                            Some(deviation) if deviation.abs() >= SUSPICIOUS_DEVIATION => {
+                                                ui.colored_label(Color32::RED, format!("{deviation:+.1}"));
+                                            }
+                                            Some(deviation) => {
+                                                ui.label(format!("{deviation:+.1}"));
+                                            }
+                                            None => {
+                                                ui.label("-");
+                                            }
+                                        }
+                                        ui.end_row();
+                                    }
+                                }
+                            });
+                        });
+                    }
+                });
+            });
+
+        if build && let Err(e) = self.prompt_build_behavior_report() {
+            self.show_error(format!("Failed to build behavior report: {e}"));
+        }
+
+        self.is_behavior_window_open = is_behavior_window_open;
+    }
+
+    fn open_rng_explore_window(&mut self, roll_type: RollType, rng_index: usize) {
+        if self.rng_selected_roll_type != Some(roll_type) {
+            self.rng_selected_outcomes.clear();
+        }
+
+        self.rng_selected_roll_type = Some(roll_type);
+        self.rng_selected_index = rng_index;
+        self.is_rng_explore_window_open = true;
+    }
+
+    /// Controls for jumping the explorer to a bookmarked RNG index or bookmarking the current one
+    fn rng_bookmark_picker(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let current = self.config.rng_bookmarks.iter().find(|b| b.rng_index == self.rng_selected_index);
+            egui::ComboBox::from_id_salt("rng_bookmarks")
+                .selected_text(current.map_or("Bookmarks", |b| b.name.as_str()))
+                .show_ui(ui, |ui| {
+                    if self.config.rng_bookmarks.is_empty() {
+                        ui.label("No bookmarks");
+                    }
+
+                    for bookmark in &self.config.rng_bookmarks {
+                        ui.selectable_value(&mut self.rng_selected_index, bookmark.rng_index, format!("{} ({})", bookmark.name, bookmark.rng_index));
+                    }
+                });
+
+            let existing = self.config.rng_bookmarks.iter().position(|b| b.rng_index == self.rng_selected_index);
+            match existing {
+                Some(i) => {
+                    if ui.button("Remove bookmark").clicked() {
+                        self.config.rng_bookmarks.remove(i);
+                    }
+                }
+                None => {
+                    ui.add(egui::TextEdit::singleline(&mut self.rng_bookmark_name).hint_text("Name").desired_width(120.0));
+                    if ui.add_enabled(!self.rng_bookmark_name.is_empty(), egui::Button::new("Bookmark")).clicked() {
+                        self.config.rng_bookmarks.push(RngBookmark {
+                            name: std::mem::take(&mut self.rng_bookmark_name),
+                            rng_index: self.rng_selected_index,
+                        });
+                    }
+                }
+            }
+        });
+    }
+
+    /// The explorer's current search as a preset with the given name
+    fn current_rng_preset(&self, name: String) -> Option<RngPreset> {
+        let roll_type = self.rng_selected_roll_type?;
+        let mut outcomes: Vec<_> = self.rng_selected_outcomes.iter().map(|o| String::from(*o)).collect();
+        outcomes.sort();
+
+        Some(RngPreset {
+            name,
+            roll_type: format!("{roll_type:?}"),
+            outcomes,
+            range_min: self.rng_distribution_range_min,
+            range_max: self.rng_distribution_range_max,
+            by_desired_outcome: self.rng_distribution_binary,
+            run_threshold: self.rng_run_threshold,
+            run_window_size: self.rng_run_window_size,
+        })
+    }
+
+    fn apply_rng_preset(&mut self, preset: &RngPreset) -> Result<()> {
+        let Some(roll_type) = preset.roll_type() else {
+            bail!("Unknown roll type {}", preset.roll_type);
+        };
+
+        self.rng_selected_roll_type = Some(roll_type);
+        self.rng_selected_outcomes = ROLL_DESCRIPTIONS[roll_type].options()
+            .iter()
+            .copied()
+            .filter(|option| preset.outcomes.iter().any(|o| o == option))
+            .collect();
+        self.rng_distribution_range_min = preset.range_min;
+        self.rng_distribution_range_max = preset.range_max;
+        self.rng_distribution_binary = preset.by_desired_outcome;
+        self.rng_run_threshold = preset.run_threshold;
+        self.rng_run_window_size = preset.run_window_size;
+        Ok(())
+    }
+
+    fn prompt_export_rng_presets(&self) -> Result<()> {
+        let Some(path) = dialog::save_file("RNG presets", &["json"], "rng_presets.json") else {
+            return Ok(());
+        };
+
+        RngPreset::write(&self.config.rng_presets, BufWriter::new(File::create(path)?))
+    }
+
+    fn prompt_import_rng_presets(&mut self) -> Result<()> {
+        let Some(path) = dialog::pick_file("RNG presets", &["json"]) else {
+            return Ok(());
+        };
+
+        for preset in RngPreset::load(&path)? {
+            // an imported preset replaces one with the same name
+            self.config.rng_presets.retain(|p| p.name != preset.name);
+            self.config.rng_presets.push(preset);
+        }
+        Ok(())
+    }
+
+    /// Controls for loading, saving, and sharing explorer presets
+    fn rng_preset_picker(&mut self, ui: &mut Ui) {
+        let mut selected_preset = None;
+        let mut import = false;
+        let mut export = false;
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("rng_presets")
+                .selected_text("Presets")
+                .show_ui(ui, |ui| {
+                    if self.config.rng_presets.is_empty() {
+                        ui.label("No presets");
+                    }
+
+                    for (i, preset) in self.config.rng_presets.iter().enumerate() {
+                        if ui.selectable_label(preset.name == self.rng_preset_name, &preset.name).clicked() {
+                            selected_preset = Some(i);
+                        }
+                    }
+                });
+
+            ui.add(egui::TextEdit::singleline(&mut self.rng_preset_name).hint_text("Name").desired_width(120.0));
+            let can_save = !self.rng_preset_name.is_empty() && self.rng_selected_roll_type.is_some();
+            if ui.add_enabled(can_save, egui::Button::new("Save preset")).clicked()
+                && let Some(preset) = self.current_rng_preset(self.rng_preset_name.clone()) {
+                // saving under an existing name overwrites it
+                self.config.rng_presets.retain(|p| p.name != preset.name);
+                self.config.rng_presets.push(preset);
+            }
+
+            if let Some(i) = self.config.rng_presets.iter().position(|p| p.name == self.rng_preset_name)
+                && ui.button("Delete preset").clicked() {
+                self.config.rng_presets.remove(i);
+            }
+
+            if !Self::is_read_only() {
+                import = ui.button("Import").clicked();
+                export = ui.add_enabled(!self.config.rng_presets.is_empty(), egui::Button::new("Export")).clicked();
+            }
+        });
+
+        if let Some(i) = selected_preset {
+            let preset = self.config.rng_presets[i].clone();
+            self.rng_preset_name = preset.name.clone();
+            if let Err(e) = self.apply_rng_preset(&preset) {
+                self.show_error(format!("Failed to apply preset: {e}"));
+            }
+        }
+
+        if import && let Err(e) = self.prompt_import_rng_presets() {
+            self.show_error(format!("Failed to import presets: {e}"));
+        }
+
+        if export && let Err(e) = self.prompt_export_rng_presets() {
+            self.show_error(format!("Failed to export presets: {e}"));
+        }
+    }
+
+    fn rng_explore_window(&mut self, ctx: &Context) {
+        let mut is_rng_explore_window_open = self.is_rng_explore_window_open;
+        
+        egui::Window::new("Explore RNG")
+            .open(&mut is_rng_explore_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                self.rng_preset_picker(ui);
+
+                let old_roll_type = self.rng_selected_roll_type;
+                egui::ComboBox::from_label("Roll type")
+                    .selected_text(match self.rng_selected_roll_type {
+                        Some(roll_type) => format!("{:?}", roll_type),
+                        None => "None".to_string(),
+                    })
+                    .show_ui(ui, |ui| {
+                        for (roll_type, description) in ROLL_DESCRIPTIONS.iter() {
+                            if matches!(roll_type, RollType::Partial | RollType::Invalid) {
+                                continue;
+                            }
+                            
+                            ui.selectable_value(&mut self.rng_selected_roll_type, Some(roll_type), format!("{:?} ({})", roll_type, description.label("<Character>")));
+                        }
+                    });
+                
+                if self.rng_selected_roll_type != old_roll_type {
+                    self.rng_selected_outcomes.clear();
+                }
+                
+                ui.add(egui::Slider::new(&mut self.rng_selected_index, 0..=(RNG_SEQUENCE.len() - 1)).text("RNG index"));
+                self.rng_bookmark_picker(ui);
+                
+                let roll = match self.rng_selected_roll_type {
+                    Some(t) => {
+                        let desc = &ROLL_DESCRIPTIONS[t];
+                        RngDescription::new(desc.label("<Character>"), if desc.has_subject() { RollCategory::Character(0) } else { RollCategory::NonCharacter }, Some(t), RNG_SEQUENCE[self.rng_selected_index])
+                    }
+                    None => return,
+                };
+
+                ui.separator();
+
+                let options = roll.options();
+
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.label("Distribution");
+
+                        ui.add_enabled(!self.rng_selected_outcomes.is_empty(), egui::Checkbox::new(&mut self.rng_distribution_binary, "By desired outcome"));
+
+                        let half_range = (RNG_SEQUENCE.len() / 2) as isize;
+                        ui.add(egui::Slider::new(&mut self.rng_distribution_range_min, -half_range..=0).text("Min"));
+                        ui.add(egui::Slider::new(&mut self.rng_distribution_range_max, 0..=half_range).text("Max"));
+
+                        let distribution = roll.distribution(self.rng_distribution_range_min, self.rng_distribution_range_max);
+                        let total = distribution.iter().map(|d| d.1).sum::<usize>() as f32;
+
+                        if self.rng_distribution_binary {
+                            let mut count_desired = 0usize;
+                            for (value, count) in distribution {
+                                if self.rng_selected_outcomes.contains(value.as_str()) {
+                                    count_desired += count;
+                                }
+                            }
+
+                            let desired_percent = count_desired as f32 / total * 100.0;
+                            ui.label(format!("Desired: {desired_percent:.2}%"));
+                        } else {
+                            for (value, count) in distribution {
+                                let percent = count as f32 / total * 100.0;
+                                ui.label(format!("{value}: {percent:.2}%"));
+                            }
+                        }
+                    });
+
+                    if !options.is_empty() {
+                        ui.separator();
+
+                        ui.vertical(|ui| {
                             ui.label("Desired outcomes");
 
                             for option in options {
@@ -1691,6 +3962,8 @@ impl App {
 
     fn compare_filter_window(&mut self, ctx: &Context) {
         let mut is_compare_filter_window_open = self.is_compare_filter_window_open;
+        let loop_segment = self.loop_range().zip(self.active_recording.as_ref())
+            .and_then(|(range, recording)| SegmentBounds::from_frames(recording, range));
 
         egui::Window::new("Compare Runs")
             .open(&mut is_compare_filter_window_open)
@@ -1766,26 +4039,2289 @@ impl App {
 
                 ui.separator();
 
-                if ui.button("Add trigger").clicked() {
-                    self.compare_filter.checkpoints.push(Checkpoint::Aot(0));
+                if ui.button("Add trigger").clicked() {
+                    self.compare_filter.checkpoints.push(Checkpoint::Aot(0));
+                }
+
+                ui.separator();
+
+                ui.checkbox(&mut self.compare_filter.exclude_mid_room_loads, "Exclude visits that loaded a save mid-room");
+
+                ui.separator();
+
+                ui.label(RichText::new("Segment").strong());
+
+                ui.separator();
+
+                match self.compare_filter.segment {
+                    Some(ref segment) => {
+                        ui.label(format!("Frames {} - {}", segment.start_frame, segment.end_frame));
+                        ui.label(format!("From ({}, {}) to ({}, {})", segment.start.x, segment.start.z, segment.end.x, segment.end.z));
+                    }
+                    None => {
+                        ui.label("Whole room");
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(loop_segment.is_some(), egui::Button::new("Use loop range"))
+                        .on_hover_text("Compare only the part of each visit between where the player was at the loop in and loop out frames")
+                        .clicked() {
+                        self.compare_filter.segment = loop_segment;
+                    }
+
+                    if ui.add_enabled(self.compare_filter.segment.is_some(), egui::Button::new("Clear")).clicked() {
+                        self.compare_filter.segment = None;
+                    }
+                });
+
+                ui.separator();
+
+                ui.vertical_centered(|ui| {
+                    ui.add_space(5.0);
+                    if ui.button("Confirm and select recordings").clicked() {
+                        self.is_compare_filter_window_open = false;
+                        if let Err(e) = self.select_comparison_recordings() {
+                            self.show_error(format!("Failed to open comparison recordings: {}", e));
+                        }
+                    }
+                    ui.add_space(5.0);
+                });
+            });
+
+        if self.is_compare_filter_window_open {
+            self.is_compare_filter_window_open = is_compare_filter_window_open;
+        }
+    }
+
+    fn trick_attempts_ui(ui: &mut Ui, attempts: &[TrickAttempt], selected_frame: &mut Option<usize>) {
+        if attempts.is_empty() {
+            ui.label("  Not attempted");
+        }
+
+        for attempt in attempts {
+            let label = format!("  {}: {}", attempt.trigger_frame, attempt.outcome);
+            if ui.selectable_label(false, label).clicked() {
+                *selected_frame = Some(attempt.trigger_frame);
+            }
+        }
+    }
+
+    fn analyze_tricks(&mut self) {
+        let Some(room_id) = self.config.last_rdt else {
+            return;
+        };
+
+        let entities = self.entities.objects();
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        let index = recording.index();
+        self.trick_results.clear();
+        for (i, trick) in self.config.tricks.iter().enumerate() {
+            if trick.room_id == room_id {
+                self.trick_results.insert(i, trick.find_attempts(recording, entities));
+            }
+        }
+        self.builtin_trick_results = BUILTIN_TRICKS.iter()
+            .map(|trick| trick.definition(room_id).find_attempts(recording, entities))
+            .collect();
+        recording.set_index(index);
+    }
+
+    fn trick_window(&mut self, ctx: &Context) {
+        let mut is_trick_window_open = self.is_trick_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Trick Windows")
+            .open(&mut is_trick_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let Some(room_id) = self.config.last_rdt else {
+                    ui.label("No room loaded");
+                    return;
+                };
+
+                ui.label(RichText::new(format!("Room {}", room_id)).strong());
+
+                ui.separator();
+
+                for (i, trick) in BUILTIN_TRICKS.iter().enumerate() {
+                    let summary = format!("{} within {} frame(s) of {}", trick.input, trick.window_frames, trick.trigger.name().to_lowercase());
+                    ui.label(RichText::new(trick.name).strong()).on_hover_text(trick.description);
+                    ui.label(summary);
+                    if let Some(attempts) = self.builtin_trick_results.get(i) {
+                        Self::trick_attempts_ui(ui, attempts, &mut selected_frame);
+                    }
+                    ui.separator();
+                }
+
+                let aot_names = self.aot_names();
+                let mut delete = None;
+                let mut num_tricks = 0usize;
+                for (i, trick) in self.config.tricks.iter_mut().enumerate() {
+                    if trick.room_id != room_id {
+                        continue;
+                    }
+                    num_tricks += 1;
+
+                    ui.horizontal(|ui| {
+                        let delete_button = egui::Button::new("⊗").fill(Color32::RED);
+                        if ui.add(delete_button).clicked() {
+                            delete = Some(i);
+                        }
+
+                        ui.text_edit_singleline(&mut trick.name);
+                    });
+
+                    ui.horizontal(|ui| {
+                        let trigger_text = match trick.trigger {
+                            TrickTrigger::Aot(aot) => aot_names.get(aot as usize).and_then(Option::clone).unwrap_or_else(|| format!("#{aot}")),
+                            trigger => trigger.name().to_string(),
+                        };
+                        egui::ComboBox::from_id_salt(("trick_trigger", i))
+                            .selected_text(trigger_text)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut trick.trigger, TrickTrigger::RoomEntry, TrickTrigger::RoomEntry.name());
+                                ui.selectable_value(&mut trick.trigger, TrickTrigger::ControlRegained, TrickTrigger::ControlRegained.name());
+                                for (aot, name) in aot_names.iter().enumerate() {
+                                    if let Some(name) = name {
+                                        ui.selectable_value(&mut trick.trigger, TrickTrigger::Aot(aot as u8), name);
+                                    }
+                                }
+                            });
+
+                        egui::ComboBox::from_id_salt(("trick_input", i))
+                            .selected_text(trick.input.name())
+                            .show_ui(ui, |ui| {
+                                for input in TrickInput::list() {
+                                    ui.selectable_value(&mut trick.input, input, input.name());
+                                }
+                            });
+
+                        ui.add(egui::DragValue::new(&mut trick.window_frames).range(1..=300).suffix(" frames"));
+                    });
+
+                    if let Some(attempts) = self.trick_results.get(&i) {
+                        Self::trick_attempts_ui(ui, attempts, &mut selected_frame);
+                    }
+
+                    ui.separator();
+                }
+
+                if let Some(i) = delete {
+                    self.config.tricks.remove(i);
+                    // indexes have shifted
+                    self.trick_results.clear();
+                }
+
+                if num_tricks == 0 {
+                    ui.label("No tricks defined for this room");
+                    ui.separator();
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("Add trick").clicked() {
+                        self.config.tricks.push(TrickDefinition::new(format!("Trick {}", num_tricks + 1), room_id));
+                    }
+
+                    if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked() {
+                        self.analyze_tricks();
+                    }
+                });
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_trick_window_open {
+            self.is_trick_window_open = is_trick_window_open;
+        }
+    }
+
+    fn analyze_checklist(&mut self) {
+        let Some(room_id) = self.config.last_rdt else {
+            return;
+        };
+
+        let entities = self.entities.objects();
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        let items: Vec<_> = self.config.checklist.iter().enumerate().filter(|(_, item)| item.room_id == room_id).collect();
+        let index = recording.index();
+        let runs = RunChecklist::analyze(&items, room_id, recording, entities);
+        recording.set_index(index);
+        self.checklist_report = Some((room_id, runs));
+    }
+
+    fn checklist_window(&mut self, ctx: &Context) {
+        let mut is_checklist_window_open = self.is_checklist_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Practice Checklist")
+            .open(&mut is_checklist_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let Some(room_id) = self.config.last_rdt else {
+                    ui.label("No room loaded");
+                    return;
+                };
+
+                ui.label(RichText::new(format!("Room {}", room_id)).strong());
+
+                ui.separator();
+
+                let runs = match self.checklist_report {
+                    Some((report_room_id, ref runs)) if report_room_id == room_id => Some(runs),
+                    _ => None,
+                };
+                let frame_index = self.active_recording.as_ref().map_or(0, Recording::index);
+                let current_visit = runs.and_then(|runs| runs.iter().find_map(|r| r.visit_at(frame_index)));
+
+                let aot_names = self.aot_names();
+                let mut delete = None;
+                let mut num_items = 0usize;
+                for (i, item) in self.config.checklist.iter_mut().enumerate() {
+                    if item.room_id != room_id {
+                        continue;
+                    }
+                    num_items += 1;
+
+                    ui.horizontal(|ui| {
+                        let delete_button = egui::Button::new("⊗").fill(Color32::RED);
+                        if ui.add(delete_button).clicked() {
+                            delete = Some(i);
+                        }
+
+                        // ticked off as playback passes the frame the item was done on
+                        let completion = current_visit.and_then(|v| v.completed_by(i, frame_index));
+                        let mut is_done = completion.is_some();
+                        ui.add_enabled(false, egui::Checkbox::without_text(&mut is_done));
+                        ui.text_edit_singleline(&mut item.name);
+                        if let Some(frame) = completion && ui.small_button(format!("{frame}")).clicked() {
+                            selected_frame = Some(frame);
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt(("checklist_task", i))
+                            .selected_text(item.task.name())
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut item.task, ChecklistTask::Shots(1), "Shots");
+                                ui.selectable_value(&mut item.task, ChecklistTask::Aot(0), "AOT");
+                            });
+
+                        match item.task {
+                            ChecklistTask::Shots(ref mut count) => {
+                                ui.add(egui::DragValue::new(count).range(1..=99).suffix(" hits"));
+                            }
+                            ChecklistTask::Aot(ref mut aot) => {
+                                let aot_text = aot_names.get(*aot as usize).and_then(Option::clone).unwrap_or_else(|| format!("#{aot}"));
+                                egui::ComboBox::from_id_salt(("checklist_aot", i))
+                                    .selected_text(aot_text)
+                                    .show_ui(ui, |ui| {
+                                        for (id, name) in aot_names.iter().enumerate() {
+                                            if let Some(name) = name {
+                                                ui.selectable_value(aot, id as u8, name);
+                                            }
+                                        }
+                                    });
+                            }
+                        }
+                    });
+
+                    ui.separator();
+                }
+
+                if let Some(i) = delete {
+                    self.config.checklist.remove(i);
+                    // indexes have shifted
+                    self.checklist_report = None;
+                }
+
+                if num_items == 0 {
+                    ui.label("No checklist for this room");
+                    ui.separator();
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("Add item").clicked() {
+                        self.config.checklist.push(ChecklistItem::new(format!("Item {}", num_items + 1), room_id));
+                    }
+
+                    if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked() {
+                        self.analyze_checklist();
+                    }
+                });
+
+                let Some((_, ref runs)) = self.checklist_report else {
+                    return;
+                };
+
+                ui.separator();
+
+                if runs.is_empty() {
+                    ui.label("The recording never visits this room");
+                }
+
+                for run in runs {
+                    let num_complete = run.visits.iter().filter(|v| v.num_completed() == v.completions.len()).count();
+                    ui.collapsing(format!("{}: {}/{} visits complete", run.name, num_complete, run.visits.len()), |ui| {
+                        for visit in &run.visits {
+                            let label = format!("{}: {}/{} done", visit.frames.start, visit.num_completed(), visit.completions.len());
+                            if ui.selectable_label(visit.frames.contains(&frame_index), label).clicked() {
+                                selected_frame = Some(visit.frames.start);
+                            }
+
+                            let missed: Vec<&str> = visit.completions.iter()
+                                .filter(|(_, frame)| frame.is_none())
+                                .filter_map(|(i, _)| self.config.checklist.get(*i).map(|item| item.name.as_str()))
+                                .collect();
+                            if !missed.is_empty() {
+                                ui.label(format!("  Missed: {}", missed.join(", ")));
+                            }
+                        }
+                    });
+                }
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_checklist_window_open {
+            self.is_checklist_window_open = is_checklist_window_open;
+        }
+    }
+
+    fn analyze_fights(&mut self) {
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        let index = recording.index();
+        self.fight_results = FightSummary::analyze(recording);
+        recording.set_index(index);
+    }
+
+    fn fight_window(&mut self, ctx: &Context) {
+        let mut is_fight_window_open = self.is_fight_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Shot Discipline")
+            .open(&mut is_fight_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked() {
+                    self.analyze_fights();
+                }
+
+                ui.separator();
+
+                if self.fight_results.is_empty() {
+                    ui.label("No fights analyzed");
+                    return;
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, fight) in self.fight_results.iter().enumerate() {
+                        egui::CollapsingHeader::new(fight.name())
+                            .id_salt(("fight", i))
+                            .show(ui, |ui| {
+                                if ui.selectable_label(false, format!("Frames {}-{}", fight.frames.start, fight.frames.end - 1)).clicked() {
+                                    selected_frame = Some(fight.frames.start);
+                                }
+                                ui.label(format!("Aiming: {} frames ({} with an enemy in range)", fight.aim_frames, fight.in_range_frames));
+                                let accuracy = fight.accuracy().map(|a| format!(" ({:.0}%)", a)).unwrap_or_default();
+                                ui.label(format!("Shots in range: {}/{}{}", fight.shots_in_range, fight.shots, accuracy));
+
+                                for flag in &fight.flags {
+                                    if ui.selectable_label(false, format!("  {}: {}", flag.frame_index, flag.kind)).clicked() {
+                                        selected_frame = Some(flag.frame_index);
+                                    }
+                                }
+                            });
+                    }
+                });
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_fight_window_open {
+            self.is_fight_window_open = is_fight_window_open;
+        }
+    }
+
+    fn analyze_contacts(&mut self) {
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        let index = recording.index();
+        self.contact_events = ContactEvent::analyze(recording);
+        recording.set_index(index);
+    }
+
+    fn contact_window(&mut self, ctx: &Context) {
+        let mut is_contact_window_open = self.is_contact_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Body Contacts")
+            .open(&mut is_contact_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked() {
+                    self.analyze_contacts();
+                }
+
+                ui.separator();
+
+                if self.contact_events.is_empty() {
+                    ui.label("No contacts found");
+                    return;
+                }
+
+                let total_frames: usize = self.contact_events.iter().map(|e| e.frames.len()).sum();
+                let total_distance: i32 = self.contact_events.iter().map(|e| e.player_distance().0).sum();
+                ui.label(format!("{} contact(s) over {} frames", self.contact_events.len(), total_frames));
+                ui.label(format!("Player pushed a total of {}", Distance(total_distance)));
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, event) in self.contact_events.iter().enumerate() {
+                        egui::CollapsingHeader::new(event.name())
+                            .id_salt(("contact", i))
+                            .show(ui, |ui| {
+                                if ui.selectable_label(false, format!("Frames {}-{}", event.frames.start, event.frames.end - 1)).clicked() {
+                                    selected_frame = Some(event.frames.start);
+                                }
+                                ui.label(format!("Player pushed by ({}, {})", Distance(event.player_displacement.x), Distance(event.player_displacement.z)));
+                                ui.label(format!("Enemy pushed by ({}, {})", Distance(event.enemy_displacement.x), Distance(event.enemy_displacement.z)));
+                            });
+                    }
+                });
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_contact_window_open {
+            self.is_contact_window_open = is_contact_window_open;
+        }
+    }
+
+    fn analyze_enemy_lifetimes(&mut self) {
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        let index = recording.index();
+        self.enemy_lifetimes = EnemyLifetime::analyze(recording);
+        recording.set_index(index);
+    }
+
+    fn enemy_timeline_window(&mut self, ctx: &Context) {
+        let mut is_enemy_timeline_window_open = self.is_enemy_timeline_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Enemy Timeline")
+            .open(&mut is_enemy_timeline_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked() {
+                    self.analyze_enemy_lifetimes();
+                }
+
+                ui.separator();
+
+                if self.enemy_lifetimes.is_empty() {
+                    ui.label("No enemies found");
+                    return;
+                }
+
+                let num_killed = self.enemy_lifetimes.iter().filter(|l| l.is_killed()).count();
+                let num_avoided = self.enemy_lifetimes.iter().filter(|l| l.fate == EnemyFate::Avoided).count();
+                ui.label(format!("{} enemies: {} killed, {} avoided", self.enemy_lifetimes.len(), num_killed, num_avoided));
+
+                let recording = self.active_recording();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for visit in self.enemy_lifetimes.chunk_by(|a, b| a.visit_start == b.visit_start) {
+                        let killed = visit.iter().filter(|l| l.is_killed()).count();
+                        let header = format!("{} ({}): {} of {} killed", visit[0].room_id, visit[0].visit_start, killed, visit.len());
+                        egui::CollapsingHeader::new(header)
+                            .id_salt(("enemy_visit", visit[0].visit_start))
+                            .show(ui, |ui| {
+                                if let Some(spawns) = recording.and_then(|r| r.room_spawns_at(visit[0].visit_start)) {
+                                    ui.label(RichText::new("Expected spawns").strong());
+                                    for spawn in &spawns.spawns {
+                                        let fate = visit.iter()
+                                            .find(|l| l.enemy_index == spawn.char_index as usize)
+                                            .map_or_else(|| String::from("never appeared"), |l| l.fate.to_string());
+                                        let pos = Vec3::from(&spawn.pos);
+                                        let speed = Vec2::new(spawn.velocity.vx, spawn.velocity.vz).len();
+                                        ui.label(format!(
+                                            "#{} {} at ({}, {}) facing {}, speed {}, HP {}: {}",
+                                            spawn.char_index, Self::spawn_name(spawn.id), Distance(pos.x), Distance(pos.z),
+                                            Angle(spawn.angle.to_32()), Distance(speed), spawn.health, fate,
+                                        ));
+                                    }
+                                    ui.separator();
+                                }
+
+                                for lifetime in visit {
+                                    let label = format!("{}-{}: {}", lifetime.frames.start, lifetime.frames.end - 1, lifetime.name());
+                                    if ui.selectable_label(false, label).clicked() {
+                                        selected_frame = Some(lifetime.frames.start);
+                                    }
+                                }
+                            });
+                    }
+                });
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_enemy_timeline_window_open {
+            self.is_enemy_timeline_window_open = is_enemy_timeline_window_open;
+        }
+    }
+
+    fn analyze_ammo(&mut self) {
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        let index = recording.index();
+        self.ammo_report = Some(AmmoReport::analyze(recording));
+        recording.set_index(index);
+    }
+
+    fn ammo_window(&mut self, ctx: &Context) {
+        let mut is_ammo_window_open = self.is_ammo_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Ammo Efficiency")
+            .open(&mut is_ammo_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked() {
+                    self.analyze_ammo();
+                }
+
+                ui.collapsing("Route budget", |ui| {
+                    let mut remove = None;
+                    for (i, budget) in self.config.ammo_budget.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.add(egui::Button::new("⊗").fill(Color32::RED)).clicked() {
+                                remove = Some(i);
+                            }
+
+                            let name = budget.weapon().map_or("Unknown", |w| w.name());
+                            egui::ComboBox::from_id_salt(("ammo_budget_weapon", i))
+                                .selected_text(name)
+                                .show_ui(ui, |ui| {
+                                    for weapon in BUDGET_WEAPONS {
+                                        ui.selectable_value(&mut budget.weapon_id, weapon as u16, weapon.name());
+                                    }
+                                });
+                            ui.add(egui::DragValue::new(&mut budget.rounds).range(0..=9999).suffix(" rounds"));
+                        });
+                    }
+
+                    if let Some(i) = remove {
+                        self.config.ammo_budget.remove(i);
+                    }
+
+                    if ui.button("Add weapon").clicked() {
+                        self.config.ammo_budget.push(AmmoBudget { weapon_id: BUDGET_WEAPONS[0] as u16, rounds: 0 });
+                    }
+                });
+
+                ui.separator();
+
+                let Some(ref report) = self.ammo_report else {
+                    ui.label("Not analyzed");
+                    return;
+                };
+
+                if report.weapons.is_empty() {
+                    ui.label("No shots fired");
+                    return;
+                }
+
+                ui.label("Ammo isn't recorded, so rounds are worked out from each weapon's rounds per shot. Automatic weapons count one per burst.");
+
+                egui::Grid::new("ammo_weapons").num_columns(7).striped(true).show(ui, |ui| {
+                    for heading in ["Weapon", "Shots", "Rounds", "Hits", "Damage", "Damage/round", "Budget left"] {
+                        ui.label(RichText::new(heading).strong());
+                    }
+                    ui.end_row();
+
+                    for usage in &report.weapons {
+                        ui.label(usage.weapon.name());
+                        ui.label(usage.shots.to_string());
+                        ui.label(usage.rounds.to_string());
+                        ui.label(match usage.accuracy() {
+                            Some(accuracy) => format!("{} ({:.0}%)", usage.hits, accuracy),
+                            None => usage.hits.to_string(),
+                        });
+                        ui.label(usage.damage.to_string());
+                        ui.label(usage.damage_per_round().map(|d| format!("{d:.1}")).unwrap_or_default());
+                        let budget = self.config.ammo_budget.iter().find(|b| b.weapon() == Some(usage.weapon));
+                        ui.label(budget.and_then(|b| report.remaining(b, None)).map(|r| r.to_string()).unwrap_or_else(|| String::from("-")));
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                ui.label(RichText::new("Remaining budget by room").strong());
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for checkpoint in &report.checkpoints {
+                        let remaining: Vec<String> = self.config.ammo_budget.iter()
+                            .filter_map(|b| Some(format!("{}: {}", b.weapon()?.name(), report.remaining(b, Some(checkpoint))?)))
+                            .collect();
+                        let summary = if remaining.is_empty() {
+                            checkpoint.rounds.iter().map(|(w, rounds)| format!("{}: {} fired", w.name(), rounds)).collect::<Vec<_>>().join(", ")
+                        } else {
+                            remaining.join(", ")
+                        };
+                        if ui.selectable_label(false, format!("{} ({}): {}", checkpoint.room_id, checkpoint.frame_index, summary)).clicked() {
+                            selected_frame = Some(checkpoint.frame_index);
+                        }
+                    }
+                });
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_ammo_window_open {
+            self.is_ammo_window_open = is_ammo_window_open;
+        }
+    }
+
+    fn analyze_pushes(&mut self) {
+        let Some(recording) = self.active_recording.as_mut() else {
+            return;
+        };
+
+        let loaded_room = self.config.last_rdt
+            .filter(|_| self.current_rdt.is_some())
+            .map(|room_id| (room_id, self.colliders.objects(), self.center));
+        let index = recording.index();
+        self.push_events = PushEvent::analyze(recording, loaded_room);
+        recording.set_index(index);
+    }
+
+    fn push_window(&mut self, ctx: &Context) {
+        let mut is_push_window_open = self.is_push_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Enemy Pushes")
+            .open(&mut is_push_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked() {
+                    self.analyze_pushes();
+                }
+                ui.label("Walls are only checked for sidesteps in the currently loaded room.");
+
+                ui.separator();
+
+                if self.push_events.is_empty() {
+                    ui.label("No pushes found");
+                    return;
+                }
+
+                let mut rooms: Vec<RoomId> = Vec::new();
+                for event in &self.push_events {
+                    if !rooms.contains(&event.room_id) {
+                        rooms.push(event.room_id);
+                    }
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for room_id in rooms {
+                        let room_events: Vec<_> = self.push_events.iter().filter(|e| e.room_id == room_id).collect();
+                        let push_frames: usize = room_events.iter().map(|e| e.push_frames()).sum();
+                        let savings: isize = room_events.iter().filter_map(|e| e.sidestep_savings()).filter(|s| *s > 0).sum();
+
+                        egui::CollapsingHeader::new(format!("{}: {} push(es), {} frames", room_id, room_events.len(), push_frames))
+                            .id_salt(("pushes", room_id.to_string()))
+                            .show(ui, |ui| {
+                                ui.label(format!("Frames a sidestep could have saved: {}", savings));
+                                for event in room_events {
+                                    ui.separator();
+                                    let enemy = event.enemy_name.unwrap_or("No enemy");
+                                    if ui.selectable_label(false, format!("Frames {}-{}: {}", event.frames.start, event.frames.end - 1, enemy)).clicked() {
+                                        selected_frame = Some(event.frames.start);
+                                    }
+                                    ui.label(format!("Moved {} during the push", Distance(event.displacement())));
+                                    ui.label(format!("Reached ({}, {}) after {} frames", event.target.x, event.target.z, event.recorded_frames()));
+                                    match (event.sidestep_frames, event.sidestep_savings()) {
+                                        (Some(frames), Some(saved)) => {
+                                            let walls = if event.checked_walls { "" } else { " (walls not checked)" };
+                                            let text = format!("Sidestep: {} frames, {} frames {}{}", frames, saved.abs(), if saved > 0 { "faster" } else { "slower" }, walls);
+                                            ui.label(RichText::new(text).strong());
+                                        }
+                                        _ => {
+                                            ui.label("No sidestep estimate");
+                                        }
+                                    }
+                                }
+                            });
+                    }
+                });
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_push_window_open {
+            self.is_push_window_open = is_push_window_open;
+        }
+    }
+
+    fn verify_window(&mut self, ctx: &Context) {
+        let mut is_verify_window_open = self.is_verify_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Verify Recording")
+            .open(&mut is_verify_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let Some(recording) = self.active_recording() else {
+                    ui.label("No recording loaded");
+                    return;
+                };
+
+                if let Some(ref path) = self.active_recording_path {
+                    ui.label(path.display().to_string());
+                }
+
+                for (i, session) in recording.sessions().iter().enumerate().filter(|(_, s)| !s.is_closed) {
+                    let label = format!("⚠ {} was interrupted; {} frames salvaged", session.name(i), session.frames.len());
+                    if ui.selectable_label(false, RichText::new(label).color(Color32::YELLOW)).clicked() {
+                        selected_frame = Some(session.frames.start);
+                    }
+                }
+
+                let integrity = recording.integrity();
+                for damage in &integrity.damage {
+                    let label = format!("⚠ Bytes {}-{} couldn't be read; resumed at frame {}", damage.bytes.start, damage.bytes.end, damage.frame_index);
+                    if ui.selectable_label(false, RichText::new(label).color(Color32::RED)).clicked() {
+                        selected_frame = Some(damage.frame_index);
+                    }
+                }
+
+                if !integrity.is_checksummed {
+                    ui.label(RichText::new("This recording was made without checksums and can't be verified").color(Color32::YELLOW));
+                    return;
+                }
+
+                if integrity.is_intact() {
+                    ui.label(RichText::new("All checksums match").color(Color32::GREEN));
+                } else {
+                    ui.label(RichText::new(format!("{} checksum mismatch(es); the recording is corrupted", integrity.mismatches.len())).color(Color32::RED));
+                }
+                ui.label("Checksums catch damage to the file, not deliberate edits");
+
+                ui.label(format!("Verified frames: {}", integrity.verified_frames));
+                ui.label(format!("Unverified frames: {}", integrity.unverified_frames()));
+
+                for mismatch in &integrity.mismatches {
+                    if ui.selectable_label(false, format!("  Frames {}-{}", mismatch.start, mismatch.end.saturating_sub(1))).clicked() {
+                        selected_frame = Some(mismatch.start);
+                    }
+                }
+
+                // frames after a session's last checksum, which means the file was cut off or the
+                // recorder is older than session-end checksums
+                for unverified in &integrity.unverified {
+                    let label = format!("⚠ Frames {}-{} aren't covered by a checksum", unverified.start, unverified.end.saturating_sub(1));
+                    if ui.selectable_label(false, RichText::new(label).color(Color32::YELLOW)).clicked() {
+                        selected_frame = Some(unverified.start);
+                    }
+                }
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_verify_window_open {
+            self.is_verify_window_open = is_verify_window_open;
+        }
+    }
+
+    fn size_window(&mut self, ctx: &Context) {
+        let mut is_size_window_open = self.is_size_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Recording Size")
+            .open(&mut is_size_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked()
+                    && let Some(ref recording) = self.active_recording {
+                    self.size_report = Some(SizeReport::analyze(recording, DEFAULT_BUCKET_FRAMES));
+                }
+
+                ui.separator();
+
+                let Some(ref report) = self.size_report else {
+                    ui.label("Not analyzed");
+                    return;
+                };
+
+                let total = report.total.total();
+                ui.label(format!("{} bytes over {} frames ({:.1} bytes/frame)", total, report.total.num_frames, report.bytes_per_frame()));
+
+                egui::Grid::new("size_families").num_columns(3).striped(true).show(ui, |ui| {
+                    for heading in ["Fields", "Bytes", "Share"] {
+                        ui.label(RichText::new(heading).strong());
+                    }
+                    ui.end_row();
+
+                    for (family, bytes) in &report.total.bytes {
+                        ui.label(family.name());
+                        ui.label(bytes.to_string());
+                        ui.label(format!("{:.1}%", *bytes as f32 / total.max(1) as f32 * 100.0));
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                ui.label(RichText::new("Bytes per minute of recording").strong());
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("size_buckets").striped(true).show(ui, |ui| {
+                        ui.label(RichText::new("Frames").strong());
+                        for (family, _) in &report.total.bytes {
+                            ui.label(RichText::new(family.name()).strong());
+                        }
+                        ui.label(RichText::new("Total").strong());
+                        ui.end_row();
+
+                        for bucket in &report.buckets {
+                            let label = format!("{}-{}", bucket.start_frame, bucket.start_frame + bucket.num_frames - 1);
+                            if ui.selectable_label(false, label).clicked() {
+                                selected_frame = Some(bucket.start_frame);
+                            }
+                            for bytes in bucket.bytes.values() {
+                                ui.label(bytes.to_string());
+                            }
+                            ui.label(bucket.total().to_string());
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_size_window_open {
+            self.is_size_window_open = is_size_window_open;
+        }
+    }
+
+    fn prompt_add_ghost(&mut self) -> Result<()> {
+        let Some(path) = dialog::pick_file("RE2 recordings", &["bin"]) else {
+            return Ok(());
+        };
+
+        let recording = Recording::read(File::open(&path)?)?;
+        let color = GHOST_COLORS[self.ghosts.len() % GHOST_COLORS.len()];
+        let mut ghost = GhostRecording::new(path, recording, color);
+        if let Some(recording) = self.active_recording() {
+            ghost.sync(recording);
+        }
+        self.ghosts.push(ghost);
+        Ok(())
+    }
+
+    fn aot_overlap_window(&mut self, ctx: &Context) {
+        let mut is_aot_overlap_window_open = self.is_aot_overlap_window_open;
+        let mut selected_entity = None;
+
+        if is_aot_overlap_window_open && self.aot_overlaps.is_none() && self.current_rdt.is_some() {
+            self.aot_overlaps = Some(AotOverlapReport::analyze(self.entities.objects()));
+        }
+
+        egui::Window::new("AOT Overlaps")
+            .open(&mut is_aot_overlap_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let Some(ref report) = self.aot_overlaps else {
+                    ui.label("No room loaded");
+                    return;
+                };
+
+                let entity_label = |i: usize| {
+                    let entity = &self.entities[i];
+                    let activation = if entity.is_trigger_on_action() { "action" } else { "touch" };
+                    format!("#{} {} ({})", entity.id(), entity.name(), activation)
+                };
+
+                ui.label("Player-triggered AOTs in the order the game checks them. Where AOTs overlap, the first one that fires wins.");
+                ui.collapsing(format!("Priority ({})", report.priority.len()), |ui| {
+                    for (rank, &i) in report.priority.iter().enumerate() {
+                        let is_selected = self.selected_object == SelectedObject::Entity(i);
+                        if ui.selectable_label(is_selected, format!("{}. {}", rank + 1, entity_label(i))).clicked() {
+                            selected_entity = Some(i);
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                if report.overlaps.is_empty() {
+                    ui.label("No overlapping AOTs in this room");
+                    return;
+                }
+
+                egui::Grid::new("aot_overlaps").striped(true).show(ui, |ui| {
+                    ui.label("Wins");
+                    ui.label("Loses");
+                    ui.label("Kind");
+                    ui.label("Shared");
+                    ui.end_row();
+
+                    for overlap in &report.overlaps {
+                        if ui.selectable_label(self.selected_object == SelectedObject::Entity(overlap.first), entity_label(overlap.first)).clicked() {
+                            selected_entity = Some(overlap.first);
+                        }
+                        if ui.selectable_label(self.selected_object == SelectedObject::Entity(overlap.second), entity_label(overlap.second)).clicked() {
+                            selected_entity = Some(overlap.second);
+                        }
+                        match overlap.kind {
+                            OverlapKind::Overlapping => ui.label(overlap.kind.name()),
+                            _ => ui.colored_label(Color32::YELLOW, overlap.kind.name()),
+                        };
+                        ui.label(format!("{:.0}%", overlap.shared_fraction * 100.0));
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if let Some(i) = selected_entity {
+            self.selected_object = SelectedObject::Entity(i);
+            self.focus_selected_object(ctx);
+        }
+
+        self.is_aot_overlap_window_open = is_aot_overlap_window_open;
+    }
+
+    fn ghost_window(&mut self, ctx: &Context) {
+        let mut is_ghost_window_open = self.is_ghost_window_open;
+        let mut add_ghost = false;
+
+        egui::Window::new("Ghost Recordings")
+            .open(&mut is_ghost_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.label("Ghosts play along with the current recording, lined up by room visit");
+                add_ghost = !Self::is_read_only() && ui.button("Add recording").clicked();
+
+                ui.separator();
+
+                if self.ghosts.is_empty() {
+                    ui.label("No ghosts");
+                    return;
+                }
+
+                let frame_offset = self.active_recording().and_then(|recording| {
+                    let index = recording.index();
+                    recording.room_visits().into_iter().find(|(_, visit)| visit.contains(&index)).map(|(_, visit)| index - visit.start)
+                });
+
+                let mut remove = None;
+                for (i, ghost) in self.ghosts.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.add(egui::Button::new("⊗").fill(Color32::RED)).clicked() {
+                            remove = Some(i);
+                        }
+
+                        ui.color_edit_button_srgba(&mut ghost.color);
+                        ui.label(ghost.name());
+                        ui.checkbox(&mut ghost.show_enemies, "Enemies");
+                    });
+
+                    let status = match (ghost.visit(), frame_offset) {
+                        (Some(visit), Some(offset)) if offset >= visit.len() => format!("  Left the room after {} frames", visit.len()),
+                        (Some(visit), _) => format!("  Frame {} of {} in this room", ghost.recording.index() - visit.start + 1, visit.len()),
+                        (None, _) => String::from("  Not in this room"),
+                    };
+                    ui.label(status);
+                }
+
+                if let Some(i) = remove {
+                    self.ghosts.remove(i);
+                }
+            });
+
+        if add_ghost && let Err(e) = self.prompt_add_ghost() {
+            self.show_error(format!("Failed to load ghost recording: {e}"));
+        }
+
+        self.is_ghost_window_open = is_ghost_window_open;
+    }
+
+    fn prompt_load_rng_diff_recording(&mut self) -> Result<()> {
+        let Some(path) = dialog::pick_file("RE2 recordings", &["bin"]) else {
+            return Ok(());
+        };
+
+        self.rng_diff_recording = Some(Recording::read(File::open(&path)?)?);
+        self.rng_diff_path = Some(path);
+        self.rng_diff_visit = 0;
+        self.rng_diff = None;
+        Ok(())
+    }
+
+    fn rng_diff_window(&mut self, ctx: &Context) {
+        let mut is_rng_diff_window_open = self.is_rng_diff_window_open;
+        let mut selected_frame = None;
+        let mut choose_recording = false;
+
+        egui::Window::new("Compare RNG")
+            .open(&mut is_rng_diff_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    choose_recording = !Self::is_read_only() && ui.button("Choose recording").clicked();
+                    let name = self.rng_diff_path.as_ref()
+                        .and_then(|p| p.file_name())
+                        .map_or_else(|| String::from("No recording chosen"), |n| n.to_string_lossy().into_owned());
+                    ui.label(name);
+                });
+
+                let (Some(recording), Some(other)) = (&self.active_recording, &self.rng_diff_recording) else {
+                    ui.label("Open a recording and choose another one to compare it with");
+                    return;
+                };
+                let Some(visit) = recording.room_visits().into_iter().find(|(_, range)| range.contains(&recording.index())) else {
+                    ui.label("Not in a room");
+                    return;
+                };
+                let room_id = visit.0.room_id();
+                let other_visits: Vec<_> = other.room_visits().into_iter().filter(|(state, _)| state.room_id() == room_id).collect();
+                if other_visits.is_empty() {
+                    ui.label(format!("The other recording never visits {room_id}"));
+                    return;
+                }
+
+                self.rng_diff_visit = self.rng_diff_visit.min(other_visits.len() - 1);
+                let visit_label = |i: usize, range: &Range<usize>| format!("{}. frames {}-{}", i + 1, range.start, range.end - 1);
+                egui::ComboBox::from_label("Visit to compare")
+                    .selected_text(visit_label(self.rng_diff_visit, &other_visits[self.rng_diff_visit].1))
+                    .show_ui(ui, |ui| {
+                        for (i, (_, range)) in other_visits.iter().enumerate() {
+                            ui.selectable_value(&mut self.rng_diff_visit, i, visit_label(i, range));
+                        }
+                    });
+
+                if ui.button("Compare").clicked() {
+                    self.rng_diff = Some(RngDiff::compare(recording, &visit, other, &other_visits[self.rng_diff_visit]));
+                }
+
+                ui.separator();
+
+                let Some(ref diff) = self.rng_diff else {
+                    ui.label("Not compared");
+                    return;
+                };
+
+                ui.label(format!("Frames {}-{} against {}-{}", diff.left_frames.start, diff.left_frames.end - 1, diff.right_frames.start, diff.right_frames.end - 1));
+                ui.label(format!(
+                    "RNG index at room entry: {} vs {} ({:+})",
+                    diff.left_rng_position, diff.right_rng_position, diff.left_rng_position as isize - diff.right_rng_position as isize,
+                ));
+                ui.label(format!("Rolls: {} vs {}", diff.left_rolls.len(), diff.right_rolls.len()));
+
+                match diff.first_difference {
+                    Some(i) => {
+                        let describe = |roll: Option<&ComparedRoll>| roll.map_or_else(
+                            || String::from("no roll"),
+                            |r| format!("{} ({:04X}) {} frames in", r.source.describe(), r.value, r.frame_offset),
+                        );
+                        ui.label(format!("First difference at roll {}", i + 1));
+                        ui.label(format!("  This recording: {}", describe(diff.left_rolls.get(i))));
+                        ui.label(format!("  Other recording: {}", describe(diff.right_rolls.get(i))));
+
+                        let roll = diff.left_rolls.get(i).or(diff.right_rolls.get(i));
+                        if let Some(roll) = roll && ui.button("Go to difference").clicked() {
+                            selected_frame = Some((diff.left_frames.start + roll.frame_offset).min(diff.left_frames.end - 1));
+                        }
+                    }
+                    None => {
+                        ui.label("The roll streams are identical");
+                    }
+                }
+
+                ui.label(format!("Extra rolls by the end: {:+}", diff.final_offset()));
+                Plot::new("rng_diff_offsets")
+                    .x_axis_label("Frames into room")
+                    .y_axis_label("Extra rolls")
+                    .min_size(egui::Vec2::new(200.0, 100.0))
+                    .height(150.0)
+                    .allow_zoom([true, false])
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(Line::new("offset", diff.roll_offsets.clone()).color(Color32::RED));
+                    });
+            });
+
+        if choose_recording && let Err(e) = self.prompt_load_rng_diff_recording() {
+            self.show_error(format!("Failed to open recording: {e}"));
+        }
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        self.is_rng_diff_window_open = is_rng_diff_window_open;
+    }
+
+    fn timing_window(&mut self, ctx: &Context) {
+        let mut is_timing_window_open = self.is_timing_window_open;
+        let mut selected_frame = None;
+
+        egui::Window::new("Frame Timing")
+            .open(&mut is_timing_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                if ui.add_enabled(self.active_recording.is_some(), egui::Button::new("Analyze recording")).clicked()
+                    && let Some(ref recording) = self.active_recording {
+                    self.timing_report = Some(TimingReport::analyze(recording));
+                }
+
+                ui.separator();
+
+                let Some(ref report) = self.timing_report else {
+                    ui.label("Not analyzed");
+                    return;
+                };
+
+                if report.num_timed_frames == 0 {
+                    ui.label("This recording has no frame timing information");
+                    return;
+                }
+
+                ui.label(format!(
+                    "{} frames took {:.2}s of real time ({:.2}s at 30fps, average {:.2} FPS)",
+                    report.num_timed_frames, report.real_time.as_secs_f32(), report.expected_time().as_secs_f32(), report.average_fps(),
+                ));
+
+                let target_line = Line::new("30fps", vec![
+                    [0.0, 30.0],
+                    [report.fps.last().map_or(0.0, |p| p[0]), 30.0],
+                ]).color(Color32::BLUE);
+                Plot::new("frame_timing_fps")
+                    .x_axis_label("Frame")
+                    .y_axis_label("FPS")
+                    .min_size(egui::Vec2::new(200.0, 100.0))
+                    .height(150.0)
+                    .allow_zoom([true, false])
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(Line::new("fps", report.fps.clone()).color(Color32::RED));
+                        plot_ui.line(target_line);
+                    });
+
+                ui.collapsing(format!("Lag frames ({})", report.lag_frames.len()), |ui| {
+                    egui::ScrollArea::vertical().max_height(200.0).id_salt("timing_lag").show(ui, |ui| {
+                        for (frame_index, frame_time) in &report.lag_frames {
+                            let label = format!("{}: {:.1}ms", frame_index, frame_time.as_secs_f32() * 1000.0);
+                            if ui.selectable_label(false, label).clicked() {
+                                selected_frame = Some(*frame_index);
+                            }
+                        }
+                    });
+                });
+
+                ui.collapsing(format!("Sections off 30fps ({})", report.divergent_sections.len()), |ui| {
+                    for section in &report.divergent_sections {
+                        let label = format!(
+                            "{}-{}: {:.2}s real vs {:.2}s expected ({:+.1}%)",
+                            section.frames.start, section.frames.end - 1, section.real_time.as_secs_f32(),
+                            section.expected_time().as_secs_f32(), section.drift_percent(),
+                        );
+                        if ui.selectable_label(false, label).clicked() {
+                            selected_frame = Some(section.frames.start);
+                        }
+                    }
+                });
+            });
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if self.is_timing_window_open {
+            self.is_timing_window_open = is_timing_window_open;
+        }
+    }
+
+    fn prompt_score_routes(&mut self) -> Result<()> {
+        let Some(ref route) = self.config.reference_route else {
+            return Ok(());
+        };
+
+        let Some(recording_paths) = dialog::pick_files("RE2 recordings", &["bin"]) else {
+            return Ok(());
+        };
+
+        self.route_matches = route.score_recordings(&recording_paths)?;
+        Ok(())
+    }
+
+    fn route_window(&mut self, ctx: &Context) {
+        let mut is_route_window_open = self.is_route_window_open;
+        let mut do_score = false;
+
+        egui::Window::new("Route Matching")
+            .open(&mut is_route_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let Some(ref route) = self.config.reference_route else {
+                    ui.label("No reference route. Choose one from a run in the Recording tab.");
+                    return;
+                };
+
+                ui.label(RichText::new(&route.name).strong());
+                ui.collapsing(format!("Rooms ({})", route.rooms.len()), |ui| {
+                    for room_id in &route.rooms {
+                        ui.label(room_id.to_string());
+                    }
+                });
+
+                if ui.button("Score recordings").clicked() {
+                    do_score = true;
+                }
+
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (i, route_match) in self.route_matches.iter().enumerate() {
+                        egui::CollapsingHeader::new(route_match.name())
+                            .id_salt(("route_match", i))
+                            .show(ui, |ui| {
+                                if route_match.deviations.is_empty() {
+                                    ui.label("Followed the route exactly");
+                                }
+
+                                for deviation in &route_match.deviations {
+                                    ui.label(deviation.to_string());
+                                }
+                            });
+                    }
+                });
+            });
+
+        if do_score && let Err(e) = self.prompt_score_routes() {
+            self.show_error(format!("Failed to score recordings: {e}"));
+        }
+
+        if self.is_route_window_open {
+            self.is_route_window_open = is_route_window_open;
+        }
+    }
+
+    fn prompt_export_character_path(&self, index: usize) -> Result<()> {
+        let Some(recording) = self.active_recording() else {
+            bail!("No recording loaded");
+        };
+        let Some(state) = recording.current_state() else {
+            bail!("No current frame");
+        };
+
+        let points = recording.get_path_points_for_character(index);
+        if points.is_empty() {
+            bail!("Character has no path in this room");
+        }
+
+        let room_id = state.room_id();
+        let file_name = format!("{}_char{}_path.csv", room_id, index);
+        let Some(path) = dialog::save_file("Character paths", &["csv", "json"], &file_name) else {
+            return Ok(());
+        };
+
+        PlannedRoute::export_path(&path, room_id, &points)
+    }
+
+    fn prompt_import_planned_route(&mut self) -> Result<()> {
+        let Some(path) = dialog::pick_file("Planned routes", &["json", "csv"]) else {
+            return Ok(());
+        };
+
+        self.planned_route = Some(PlannedRoute::load(&path)?);
+        Ok(())
+    }
+
+    /// Zombies that will get a grab on the player if nobody changes course
+    fn draw_grab_warnings(&self, ui: &Ui, view_center: egui::Pos2) {
+        if !self.config.show_grab_warnings {
+            return;
+        }
+
+        let Some(state) = self.active_recording().and_then(Recording::current_state) else {
+            return;
+        };
+        let Some(player) = state.characters()[0].as_ref() else {
+            return;
+        };
+
+        for zombie in state.characters().iter().skip(1).flatten() {
+            let Some(prediction) = GrabPrediction::for_zombie(zombie, player) else {
+                continue;
+            };
+            let Some(frames_until_grab) = prediction.frames_until_grab else {
+                continue;
+            };
+
+            let mut draw_params = self.config.get_draw_params(ObjectType::AiHitZone, view_center);
+            draw_params.fill_color = GRAB_WARNING_FILL;
+            draw_params.stroke = Stroke::new(GRAB_WARNING_WIDTH, GRAB_WARNING_COLOR);
+            let gui_pos = draw_params.transform_point(prediction.pos);
+            ui.painter().add(prediction.gui_shape(draw_params, state));
+
+            let mut label = if frames_until_grab == 0 {
+                String::from("Grab!")
+            } else {
+                format!("Grab in {frames_until_grab}f")
+            };
+            if prediction.are_arms_raised {
+                label.push_str(" (arms up)");
+            }
+            let (bg, text) = text_box(label, gui_pos, VAlign::Top, TEXT_BOX_DARK, GRAB_WARNING_COLOR, ui);
+            ui.painter().add(egui::Shape::Vec(vec![bg, text]));
+        }
+    }
+
+    /// Where enemies gave up chasing the player earlier in this visit to the room
+    fn draw_deaggros(&self, ui: &Ui, view_center: egui::Pos2) {
+        if !self.config.show_deaggros {
+            return;
+        }
+
+        let Some(recording) = self.active_recording() else {
+            return;
+        };
+
+        let room_range = recording.room_range();
+        let draw_params = self.config.get_draw_params(ObjectType::CharacterPath, view_center);
+        let stroke = Stroke::new(DEAGGRO_WIDTH, DEAGGRO_COLOR);
+        for event in recording.deaggros() {
+            if !room_range.contains(&event.frame_index) || event.frame_index > recording.index() {
+                continue;
+            }
+
+            let enemy_pos = draw_params.transform_point(event.pos);
+            let player_pos = draw_params.transform_point(event.player_pos);
+            ui.painter().line_segment([enemy_pos, player_pos], stroke);
+            ui.painter().circle_filled(enemy_pos, DEAGGRO_RADIUS, DEAGGRO_COLOR);
+
+            let label = format!("Lost you: {}", Distance(event.distance()));
+            let (bg, text) = text_box(label, enemy_pos, VAlign::Bottom, TEXT_BOX_DARK, DEAGGRO_COLOR, ui);
+            ui.painter().add(egui::Shape::Vec(vec![bg, text]));
+        }
+    }
+
+    /// The path of each attack that hit the player, drawn up to the current frame as playback goes
+    fn draw_attack_traces(&self, ui: &Ui, view_center: egui::Pos2) {
+        if !self.config.show_attack_traces {
+            return;
+        }
+
+        let Some(recording) = self.active_recording() else {
+            return;
+        };
+
+        let frame_index = recording.index();
+        let room_range = recording.room_range();
+        let draw_params = self.config.get_draw_params(ObjectType::CharacterPath, view_center);
+        for trace in recording.attack_traces() {
+            if !room_range.contains(&trace.frames.start) || frame_index < trace.frames.start
+                || frame_index >= trace.frames.end + ATTACK_TRACE_LINGER_FRAMES {
+                continue;
+            }
+
+            let points: Vec<_> = trace.points_until(frame_index).iter().map(|p| draw_params.transform_point(*p)).collect();
+            ui.painter().add(egui::Shape::line(points.clone(), Stroke::new(ATTACK_TRACE_WIDTH, ATTACK_TRACE_COLOR)));
+            for (i, point) in points.iter().enumerate() {
+                let color = if trace.frames.start + i == trace.hit_frame { ATTACK_TRACE_HIT_COLOR } else { ATTACK_TRACE_COLOR };
+                ui.painter().circle_filled(*point, ATTACK_TRACE_RADIUS, color);
+            }
+
+            if let Some(last) = points.last() {
+                let label = format!("{}: frame {}/{}", trace.zone_name, points.len(), trace.frames.len());
+                let (bg, text) = text_box(label, *last, VAlign::Bottom, TEXT_BOX_DARK, ATTACK_TRACE_COLOR, ui);
+                ui.painter().add(egui::Shape::Vec(vec![bg, text]));
+            }
+        }
+    }
+
+    /// Where each enemy was when the player entered the room
+    fn draw_room_spawns(&self, ui: &Ui, view_center: egui::Pos2) {
+        if !self.config.show_room_spawns {
+            return;
+        }
+
+        let Some(event) = self.active_recording().and_then(|r| r.room_spawns_at(r.index())) else {
+            return;
+        };
+
+        let draw_params = self.config.get_draw_params(ObjectType::CharacterPath, view_center);
+        let stroke = Stroke::new(1.5, ROOM_SPAWN_COLOR);
+        for spawn in &event.spawns {
+            let pos = draw_params.transform_point(Vec3::from(&spawn.pos).xz());
+            ui.painter().circle_stroke(pos, ROOM_SPAWN_RADIUS, stroke);
+
+            let angle = spawn.angle.to_32().to_radians();
+            let facing = pos + egui::Vec2::new(angle.cos(), -angle.sin()) * ROOM_SPAWN_RADIUS * 2.0;
+            ui.painter().line_segment([pos, facing], stroke);
+
+            let label = format!("Spawn #{} {}", spawn.char_index, Self::spawn_name(spawn.id));
+            let (bg, text) = text_box(label, pos, VAlign::Top, TEXT_BOX_DARK, ROOM_SPAWN_COLOR, ui);
+            ui.painter().add(egui::Shape::Vec(vec![bg, text]));
+        }
+    }
+
+    fn spawn_name(id: u8) -> &'static str {
+        CharacterId::try_from(id).map_or("Unknown", |id| id.name())
+    }
+
+    fn draw_planned_route(&self, ui: &Ui, view_center: egui::Pos2) {
+        let (Some(route), Some(room_id)) = (self.planned_route.as_ref(), self.config.last_rdt) else {
+            return;
+        };
+
+        let speed = self.active_recording().and_then(Recording::player_run_speed);
+        let draw_params = self.config.get_draw_params(ObjectType::CharacterPath, view_center);
+        let stroke = Stroke::new(PLANNED_ROUTE_WIDTH, PLANNED_ROUTE_COLOR);
+        for point in route.room_waypoints(room_id) {
+            ui.painter().circle_filled(draw_params.transform_point(point), WAYPOINT_RADIUS, PLANNED_ROUTE_COLOR);
+        }
+
+        for leg in route.legs(room_id, speed) {
+            let end = draw_params.transform_point(leg.end);
+            ui.painter().line_segment([draw_params.transform_point(leg.start), end], stroke);
+            if let Some(frames) = leg.total_frames {
+                let (bg, text) = text_box(format!("{frames}f"), end, VAlign::Bottom, TEXT_BOX_DARK, PLANNED_ROUTE_COLOR, ui);
+                ui.painter().add(egui::Shape::Vec(vec![bg, text]));
+            }
+        }
+    }
+
+    fn drawn_route_legs(&self) -> Vec<DrawnLeg> {
+        let recording = self.active_recording();
+        self.drawn_route.legs(
+            self.get_character(0),
+            self.colliders.objects(),
+            self.center,
+            recording.and_then(Recording::player_run_speed),
+            recording.and_then(Recording::player_turn_rate),
+        )
+    }
+
+    fn draw_drawn_route(&self, ui: &Ui, view_center: egui::Pos2) {
+        let draw_params = self.config.get_draw_params(ObjectType::CharacterPath, view_center);
+        for point in &self.drawn_route.points {
+            ui.painter().circle_filled(draw_params.transform_point(*point), WAYPOINT_RADIUS, DRAWN_ROUTE_COLOR);
+        }
+
+        for leg in self.drawn_route_legs() {
+            let color = if leg.blocked_at.is_some() { BLOCKED_LEG_COLOR } else { DRAWN_ROUTE_COLOR };
+            ui.painter().line_segment([draw_params.transform_point(leg.start), draw_params.transform_point(leg.end)], Stroke::new(PLANNED_ROUTE_WIDTH, color));
+            if let Some(blocked_at) = leg.blocked_at {
+                ui.painter().circle_filled(draw_params.transform_point(blocked_at), DIVERGENCE_MARKER_RADIUS, BLOCKED_LEG_COLOR);
+            }
+        }
+    }
+
+    fn route_drawing_window(&mut self, ctx: &Context) {
+        let mut is_route_drawing_window_open = self.is_route_drawing_window_open;
+        let legs = self.drawn_route_legs();
+        let has_player = self.get_character(0).is_some();
+
+        egui::Window::new("Draw Route")
+            .open(&mut is_route_drawing_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.is_drawing_route, "Click on the map to add waypoints");
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.drawn_route.points.is_empty(), egui::Button::new("Undo")).clicked() {
+                        self.drawn_route.points.pop();
+                    }
+
+                    if ui.add_enabled(!self.drawn_route.points.is_empty(), egui::Button::new("Clear")).clicked() {
+                        self.drawn_route.points.clear();
+                    }
+                });
+
+                ui.separator();
+
+                if legs.is_empty() {
+                    ui.label("Add at least two waypoints");
+                    return;
+                }
+
+                let mut total_frames = Some(0usize);
+                for (i, leg) in legs.iter().enumerate() {
+                    let frames = leg.frames.map(|f| format!(" | ~{f} frames")).unwrap_or_default();
+                    let text = format!("Leg {}: {} | Turn {}{}", i + 1, Distance(leg.distance), Angle(Degrees(leg.turn_degrees)), frames);
+                    if leg.blocked_at.is_some() {
+                        ui.label(RichText::new(format!("{text} | Blocked")).color(BLOCKED_LEG_COLOR));
+                    } else {
+                        ui.label(text);
+                    }
+                    total_frames = total_frames.zip(leg.frames).map(|(total, f)| total + f);
+                }
+
+                ui.separator();
+                match total_frames {
+                    Some(frames) => ui.label(format!("Estimated time: {} frames ({:.2}s)", frames, frames as f32 * FRAME_DURATION.as_secs_f32())),
+                    // we measure the player's speed from the recording
+                    None => ui.label("Load a recording to estimate times"),
+                };
+                if !has_player {
+                    ui.label("Collision can only be checked while a recording is loaded");
+                }
+            });
+
+        if !is_route_drawing_window_open {
+            self.is_drawing_route = false;
+        }
+
+        if self.is_route_drawing_window_open {
+            self.is_route_drawing_window_open = is_route_drawing_window_open;
+        }
+    }
+
+    fn analyze_corner(&mut self) -> Result<()> {
+        let SelectedObject::Collider(i) = self.selected_object else {
+            bail!("Select a collider first");
+        };
+        let Some(recording) = self.active_recording() else {
+            bail!("No recording loaded");
+        };
+        let Some(player) = self.get_character(0) else {
+            bail!("The player isn't in this room");
+        };
+
+        let path: Vec<_> = recording.room_range()
+            .clone()
+            .filter(|frame| !recording.is_control_locked(*frame))
+            .filter_map(|frame| Some((frame, recording.player_position(frame)?)))
+            .collect();
+        let corner_cut = CornerCut::analyze(
+            &path,
+            &self.colliders[i],
+            player,
+            self.colliders.objects(),
+            self.center,
+            recording.player_run_speed(),
+            recording.player_turn_rate(),
+        );
+
+        self.corner_cut = Some(corner_cut.ok_or_else(|| anyhow!("Couldn't find a collision-free line around this collider"))?);
+        Ok(())
+    }
+
+    fn draw_corner_cut(&self, ui: &Ui, view_center: egui::Pos2) {
+        let Some(ref corner_cut) = self.corner_cut else {
+            return;
+        };
+
+        let draw_params = self.config.get_draw_params(ObjectType::CharacterPath, view_center);
+        ui.painter().circle_filled(draw_params.transform_point(corner_cut.corner), WAYPOINT_RADIUS, OPTIMAL_LINE_COLOR);
+        for leg in &corner_cut.optimal {
+            ui.painter().line_segment([draw_params.transform_point(leg.start), draw_params.transform_point(leg.end)], Stroke::new(PLANNED_ROUTE_WIDTH, OPTIMAL_LINE_COLOR));
+        }
+    }
+
+    fn corner_window(&mut self, ctx: &Context) {
+        let mut is_corner_window_open = self.is_corner_window_open;
+        let mut selected_frame = None;
+        let mut do_analyze = false;
+
+        egui::Window::new("Corner Cutting")
+            .open(&mut is_corner_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.label("Select a collider the recorded path goes around in this room.");
+                if ui.add_enabled(matches!(self.selected_object, SelectedObject::Collider(_)), egui::Button::new("Analyze selected collider")).clicked() {
+                    do_analyze = true;
+                }
+
+                let Some(ref corner_cut) = self.corner_cut else {
+                    return;
+                };
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.selectable_label(false, format!("Entry: {}", corner_cut.entry_frame)).clicked() {
+                        selected_frame = Some(corner_cut.entry_frame);
+                    }
+                    if ui.selectable_label(false, format!("Exit: {}", corner_cut.exit_frame)).clicked() {
+                        selected_frame = Some(corner_cut.exit_frame);
+                    }
+                });
+
+                for (i, leg) in corner_cut.optimal.iter().enumerate() {
+                    ui.label(format!("Leg {}: face {} for {}", i + 1, Angle(leg.heading).both(), Distance(leg.distance)));
+                }
+
+                ui.label(format!("Recorded: {} frames", corner_cut.recorded_frames()));
+                match (corner_cut.optimal_frames(), corner_cut.lost_frames()) {
+                    (Some(optimal), Some(lost)) => {
+                        ui.label(format!("Optimal: {} frames", optimal));
+                        ui.label(RichText::new(format!("Lost: {} frames", lost)).strong());
+                    }
+                    _ => {
+                        ui.label("Not enough movement in the recording to estimate the optimal time");
+                    }
+                }
+            });
+
+        if do_analyze && let Err(e) = self.analyze_corner() {
+            self.show_error(format!("Failed to analyze corner: {e}"));
+        }
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        if !is_corner_window_open {
+            self.corner_cut = None;
+        }
+
+        if self.is_corner_window_open {
+            self.is_corner_window_open = is_corner_window_open;
+        }
+    }
+
+    fn load_raw_rdt(&mut self) -> Result<()> {
+        let Some(room_id) = self.config.last_rdt.filter(|_| self.current_rdt.is_some()) else {
+            self.raw_rdt = None;
+            return Ok(());
+        };
+
+        if self.raw_rdt.as_ref().is_some_and(|raw| raw.room_id() == room_id) {
+            return Ok(());
+        }
+
+        self.raw_rdt = None;
+        self.inspected_section = 0;
+        self.raw_rdt = Some(match self.bundled_rooms.get(&room_id) {
+            Some(data) => RawRdt::from_data(room_id, data.clone())?,
+            None => {
+                let path = self.get_room_path(room_id).ok_or_else(|| anyhow!("Could not find room"))?.to_path_buf();
+                RawRdt::read(room_id, &path)?
+            }
+        });
+
+        Ok(())
+    }
+
+    fn rdt_inspector_window(&mut self, ctx: &Context) {
+        if !self.is_rdt_inspector_open {
+            return;
+        }
+
+        if let Err(e) = self.load_raw_rdt() {
+            self.is_rdt_inspector_open = false;
+            self.show_error(format!("Failed to read RDT: {e}"));
+            return;
+        }
+
+        let mut is_rdt_inspector_open = self.is_rdt_inspector_open;
+        let mut inspected_section = self.inspected_section;
+        let mut inspect_decode = self.inspect_decode;
+
+        egui::Window::new("RDT Inspector")
+            .open(&mut is_rdt_inspector_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let Some(ref raw_rdt) = self.raw_rdt else {
+                    ui.label("No room loaded");
+                    return;
+                };
+
+                ui.label(format!("{}: {} bytes", raw_rdt.room_id(), raw_rdt.file_size()));
+                ui.horizontal_wrapped(|ui| {
+                    for (name, count) in raw_rdt.header_counts() {
+                        ui.label(format!("{}: {}", name, count));
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal_top(|ui| {
+                    egui::ScrollArea::vertical().id_salt("rdt_sections").show(ui, |ui| {
+                        egui::Grid::new("rdt_section_grid").striped(true).show(ui, |ui| {
+                            ui.strong("#");
+                            ui.strong("Section");
+                            ui.strong("Offset");
+                            ui.strong("Size");
+                            ui.end_row();
+
+                            for (i, section) in raw_rdt.sections().iter().enumerate() {
+                                ui.label(i.to_string());
+                                if ui.selectable_label(inspected_section == i, section.name).clicked() {
+                                    inspected_section = i;
+                                }
+                                if section.size > 0 {
+                                    ui.monospace(format!("{:08X}", section.offset));
+                                } else {
+                                    ui.label("-");
+                                }
+                                ui.label(section.size.to_string());
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                    ui.separator();
+
+                    ui.vertical(|ui| {
+                        egui::ComboBox::from_label("Decode as")
+                            .selected_text(inspect_decode.name())
+                            .show_ui(ui, |ui| {
+                                for decode in DecodeAs::list() {
+                                    ui.selectable_value(&mut inspect_decode, decode, decode.name());
+                                }
+                            });
+
+                        let data = raw_rdt.section_data(inspected_section);
+                        if data.is_empty() {
+                            ui.label("Section is not present in this room");
+                            return;
+                        }
+
+                        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                        egui::ScrollArea::both().id_salt("rdt_section_data").show_rows(ui, row_height, inspect_decode.num_lines(data), |ui, rows| {
+                            for line in rows {
+                                ui.monospace(inspect_decode.format_line(data, line));
+                            }
+                        });
+                    });
+                });
+            });
+
+        self.inspected_section = inspected_section;
+        self.inspect_decode = inspect_decode;
+        self.is_rdt_inspector_open = is_rdt_inspector_open;
+    }
+
+    fn publish_playback_state(&self) {
+        let Some(ref server) = self.playback_server else {
+            return;
+        };
+
+        let recording = self.active_recording.as_ref();
+        let snapshot = PlaybackSnapshot {
+            room: self.config.last_rdt.map(|id| id.to_string()),
+            frame_index: recording.map(Recording::index).unwrap_or(0),
+            num_frames: recording.map(|r| r.frames().len()).unwrap_or(0),
+            is_playing: self.is_recording_playing,
+            characters: self.characters.objects().iter().map(Into::into).collect(),
+            ai_zones: self.ai_zones.objects().iter().map(Into::into).collect(),
+        };
+        server.publish(&snapshot);
+    }
+
+    fn server_window(&mut self, ctx: &Context) {
+        let mut is_server_window_open = self.is_server_window_open;
+        let mut toggle_server = false;
+
+        egui::Window::new("Playback Server")
+            .open(&mut is_server_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.label("Serves the current playback state as JSON for external tools.");
+                ui.horizontal(|ui| {
+                    ui.label("Port");
+                    ui.add_enabled(self.playback_server.is_none(), egui::DragValue::new(&mut self.config.playback_server_port).range(1024..=65535));
+                });
+
+                match self.playback_server {
+                    Some(ref server) => {
+                        let addr = server.addr();
+                        ui.label(format!("Current state: http://{}/state", addr));
+                        ui.label(format!("Live updates: http://{}/events", addr));
+                        toggle_server = ui.button("Stop").clicked();
+                    }
+                    None => {
+                        toggle_server = ui.button("Start").clicked();
+                    }
+                }
+            });
+
+        // dropping the server stops it
+        if toggle_server && self.playback_server.take().is_none() {
+            match PlaybackServer::start(self.config.playback_server_port) {
+                Ok(server) => self.playback_server = Some(server),
+                Err(e) => self.show_error(format!("Failed to start playback server: {e}")),
+            }
+        }
+
+        self.is_server_window_open = is_server_window_open;
+    }
+
+    fn is_tab_active(&self, tab: BrowserTab) -> bool {
+        match tab {
+            BrowserTab::Recording | BrowserTab::Rng => self.active_recording.is_some(),
+            BrowserTab::Comparison => self.comparison.is_some(),
+            _ => true,
+        }
+    }
+
+    fn browser_tab(&mut self, ui: &mut Ui, tab: BrowserTab) {
+        match tab {
+            BrowserTab::Game => self.rdt_browser(ui),
+            BrowserTab::Room => self.room_browser(ui),
+            BrowserTab::Settings => self.settings_browser(ui),
+            BrowserTab::Rng => self.rng_browser(ui),
+            BrowserTab::Recording => self.recording_browser(ui),
+            BrowserTab::Comparison => self.comparison_browser(ui),
+            BrowserTab::Index => self.index_browser(ui),
+            BrowserTab::Plugin(i) => self.plugin_browser(ui, i),
+        }
+    }
+
+    fn detach_tab(&mut self, tab: BrowserTab) {
+        if !self.detached_tabs.contains(&tab) {
+            self.detached_tabs.push(tab);
+        }
+        if self.tab == tab {
+            self.tab = BrowserTab::Room;
+        }
+    }
+
+    fn detached_tab_windows(&mut self, ctx: &Context) {
+        let mut reattached = Vec::new();
+        for tab in self.detached_tabs.clone() {
+            let viewport_id = egui::ViewportId::from_hash_of(("detached_tab", tab.name()));
+            let builder = egui::ViewportBuilder::default()
+                .with_title(format!("{} - {}", tab.name(), APP_NAME))
+                .with_inner_size([DETACHED_TAB_WIDTH, DETACHED_TAB_HEIGHT]);
+            ctx.show_viewport_immediate(viewport_id, builder, |ctx, class| {
+                // platforms without multiple windows embed the viewport in the main one instead
+                if class == egui::ViewportClass::Embedded {
+                    let mut is_open = true;
+                    egui::Window::new(tab.name()).open(&mut is_open).show(ctx, |ui| self.detached_tab_contents(ui, tab));
+                    if !is_open {
+                        reattached.push(tab);
+                    }
+                    return;
+                }
+
+                egui::CentralPanel::default().show(ctx, |ui| self.detached_tab_contents(ui, tab));
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    reattached.push(tab);
+                }
+            });
+        }
+
+        self.detached_tabs.retain(|tab| !reattached.contains(tab));
+    }
+
+    /// A detached tab's contents. The window stays open while the tab has nothing to show, since
+    /// it'll fill in again when the next recording or comparison is loaded.
+    fn detached_tab_contents(&mut self, ui: &mut Ui, tab: BrowserTab) {
+        if self.is_tab_active(tab) {
+            self.browser_tab(ui, tab);
+        } else if tab == BrowserTab::Comparison {
+            ui.label("No comparison loaded");
+        } else {
+            ui.label("No recording loaded");
+        }
+    }
+
+    fn profiler_window(&mut self, ctx: &Context) {
+        let mut is_profiler_window_open = self.is_profiler_window_open;
+
+        egui::Window::new("Profiler")
+            .open(&mut is_profiler_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.config.reduced_detail, "Reduced detail");
+                ui.add_enabled(
+                    self.config.reduced_detail,
+                    egui::DragValue::new(&mut self.config.frame_budget_ms).range(1.0..=100.0).speed(0.1).suffix(" ms budget"),
+                );
+                if ui.button("Reset").clicked() {
+                    self.profiler.reset();
+                }
+
+                ui.separator();
+
+                egui::Grid::new("profiler_grid").striped(true).show(ui, |ui| {
+                    ui.strong("Layer");
+                    ui.strong("Last");
+                    ui.strong("Average");
+                    ui.strong("Max");
+                    ui.end_row();
+
+                    for layer in DrawLayer::list() {
+                        let stats = self.profiler.stats(layer);
+                        if self.profiler.is_layer_enabled(layer) {
+                            ui.label(layer.name());
+                        } else {
+                            ui.label(RichText::new(format!("{} (off)", layer.name())).color(Color32::YELLOW));
+                        }
+                        ui.monospace(format!("{:.2}", stats.last_ms));
+                        ui.monospace(format!("{:.2}", stats.average_ms));
+                        ui.monospace(format!("{:.2}", stats.max_ms));
+                        ui.end_row();
+                    }
+
+                    let total = self.profiler.total();
+                    ui.strong("Total");
+                    ui.monospace(format!("{:.2}", total.last_ms));
+                    ui.monospace(format!("{:.2}", total.average_ms));
+                    ui.monospace(format!("{:.2}", total.max_ms));
+                    ui.end_row();
+                });
+
+                let disabled = self.profiler.disabled_layers();
+                if !disabled.is_empty() {
+                    let names: Vec<_> = disabled.iter().map(DrawLayer::name).collect();
+                    ui.label(format!("Turned off to stay within budget: {}", names.join(", ")));
+                }
+            });
+
+        self.is_profiler_window_open = is_profiler_window_open;
+    }
+
+    fn correlate_save_states(&mut self) {
+        let recording = self.active_recording.as_ref();
+        for link in &mut self.save_states {
+            match recording {
+                Some(recording) => link.correlate(recording),
+                None => link.frame_index = None,
+            }
+        }
+    }
+
+    fn prompt_add_save_states(&mut self) {
+        let Some(paths) = dialog::pick_files("Save states", &SAVE_STATE_EXTENSIONS) else {
+            return;
+        };
+
+        self.save_states.extend(paths.into_iter().map(SaveStateLink::from_path));
+        self.correlate_save_states();
+    }
+
+    fn save_state_window(&mut self, ctx: &Context) {
+        let mut is_save_state_window_open = self.is_save_state_window_open;
+        let mut selected_frame = None;
+        let mut need_correlate = false;
+
+        egui::Window::new("Save States")
+            .open(&mut is_save_state_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.label("Save states are matched to the recording by room and IGT. Both are read from the file name when possible, e.g. 1010_12m34s.sav. Files without a room are matched by when they were written, if the recording has session timestamps.");
+
+                let room_ids: Vec<RoomId> = {
+                    let mut ids: Vec<_> = self.active_recording.as_ref()
+                        .map(|r| r.room_visits().into_iter().map(|(state, _)| state.room_id()).collect())
+                        .unwrap_or_default();
+                    ids.sort();
+                    ids.dedup();
+                    ids
+                };
+
+                let mut delete = None;
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (i, link) in self.save_states.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            let delete_button = egui::Button::new("⊗").fill(Color32::RED);
+                            if ui.add(delete_button).clicked() {
+                                delete = Some(i);
+                            }
+
+                            ui.label(link.name());
+                        });
+
+                        ui.horizontal(|ui| {
+                            let old_room_id = link.room_id;
+                            egui::ComboBox::from_id_salt(("save_state_room", i))
+                                .selected_text(link.room_id.map_or_else(|| String::from("Room"), |id| id.to_string()))
+                                .show_ui(ui, |ui| {
+                                    for room_id in &room_ids {
+                                        ui.selectable_value(&mut link.room_id, Some(*room_id), room_id.to_string());
+                                    }
+                                });
+                            need_correlate |= link.room_id != old_room_id;
+
+                            let mut has_igt = link.igt_seconds.is_some();
+                            if ui.checkbox(&mut has_igt, "IGT").changed() {
+                                link.igt_seconds = has_igt.then_some(0);
+                                need_correlate = true;
+                            }
+
+                            if let Some(ref mut igt) = link.igt_seconds {
+                                let response = ui.add(egui::DragValue::new(igt).custom_formatter(|s, _| {
+                                    let s = s as u32;
+                                    format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+                                }));
+                                need_correlate |= response.changed();
+                            }
+
+                            match link.frame_index {
+                                Some(frame_index) => {
+                                    if ui.button(format!("Frame {frame_index}")).clicked() {
+                                        selected_frame = Some(frame_index);
+                                    }
+                                }
+                                None => {
+                                    ui.label("No matching frame");
+                                }
+                            }
+                        });
+
+                        ui.separator();
+                    }
+                });
+
+                if let Some(i) = delete {
+                    self.save_states.remove(i);
+                }
+
+                if !Self::is_read_only() && ui.button("Add save states").clicked() {
+                    self.prompt_add_save_states();
+                }
+            });
+
+        if need_correlate {
+            self.correlate_save_states();
+        }
+
+        if let Some(frame_index) = selected_frame {
+            self.set_recording_frame(frame_index);
+        }
+
+        self.is_save_state_window_open = is_save_state_window_open;
+    }
+
+    fn prompt_export_setup_card(&mut self) -> Result<()> {
+        let Some(recording) = self.active_recording() else {
+            return Ok(());
+        };
+
+        let card = SetupCard::for_room_entry(recording, recording.index())?;
+        let Some(path) = dialog::save_file("JSON files", &["json"], &card.file_name()) else {
+            return Ok(());
+        };
+
+        card.write(BufWriter::new(File::create(path)?))
+    }
+
+    fn prompt_export_recording(&mut self) -> Result<()> {
+        let Some(recording) = self.active_recording() else {
+            return Ok(());
+        };
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (i, (_, range)) in recording.room_visits().into_iter().enumerate() {
+            if !self.export_visits.contains(&i) {
+                continue;
+            }
+
+            // merge adjacent rooms so they don't get split into separate sessions
+            match ranges.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => ranges.push(range),
+            }
+        }
+
+        if ranges.is_empty() {
+            bail!("No rooms selected");
+        }
+
+        let Some(path) = dialog::save_file("RE2 recordings", &["bin"], "export.bin") else {
+            return Ok(());
+        };
+
+        let file = BufWriter::new(File::create(path)?);
+        recording.export(&ranges, file)
+    }
+
+    fn prompt_export_notes(&mut self) -> Result<()> {
+        let Some(recording) = self.active_recording() else {
+            return Ok(());
+        };
+
+        let recording_path = self.active_recording_path.as_deref();
+        let file_name = recording_path
+            .and_then(Path::file_stem)
+            .map_or_else(|| String::from("notes.md"), |stem| format!("{}.md", stem.to_string_lossy()));
+        let Some(path) = dialog::save_file("Markdown files", &["md"], &file_name) else {
+            return Ok(());
+        };
+
+        write_notes(recording, recording_path, BufWriter::new(File::create(path)?))
+    }
+
+    fn prompt_export_range(&mut self, range: Range<usize>) -> Result<()> {
+        let Some(recording) = self.active_recording() else {
+            return Ok(());
+        };
+
+        let file_name = format!("export_{}-{}.bin", range.start, range.end - 1);
+        let Some(path) = dialog::save_file("RE2 recordings", &["bin"], &file_name) else {
+            return Ok(());
+        };
+
+        let file = BufWriter::new(File::create(path)?);
+        recording.export(&[range], file)
+    }
+
+    fn export_window(&mut self, ctx: &Context) {
+        let mut is_export_window_open = self.is_export_window_open;
+        let mut do_export = false;
+
+        egui::Window::new("Export Recording")
+            .open(&mut is_export_window_open)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let Some(recording) = self.active_recording.as_ref() else {
+                    ui.label("No recording loaded");
+                    return;
+                };
+
+                ui.label("Only the selected rooms will be exported. Session timestamps and checksums are removed.");
+
+                let visits = recording.room_visits();
+                ui.horizontal(|ui| {
+                    if ui.button("Select all").clicked() {
+                        self.export_visits = (0..visits.len()).collect();
+                    }
+
+                    if ui.button("Select none").clicked() {
+                        self.export_visits.clear();
+                    }
+                });
+
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (i, (state, range)) in visits.iter().enumerate() {
+                        let mut is_selected = self.export_visits.contains(&i);
+                        let label = format!("{} ({}-{})", state.room_id(), range.start, range.end.saturating_sub(1));
+                        if ui.checkbox(&mut is_selected, label).changed() {
+                            if is_selected {
+                                self.export_visits.insert(i);
+                            } else {
+                                self.export_visits.remove(&i);
+                            }
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                if ui.add_enabled(!self.export_visits.is_empty(), egui::Button::new("Export")).clicked() {
+                    do_export = true;
                 }
+            });
 
-                ui.separator();
+        if do_export && let Err(e) = self.prompt_export_recording() {
+            self.show_error(format!("Failed to export recording: {e}"));
+        }
 
-                ui.vertical_centered(|ui| {
-                    ui.add_space(5.0);
-                    if ui.button("Confirm and select recordings").clicked() {
-                        self.is_compare_filter_window_open = false;
-                        if let Err(e) = self.select_comparison_recordings() {
-                            self.show_error(format!("Failed to open comparison recordings: {}", e));
+        if self.is_export_window_open {
+            self.is_export_window_open = is_export_window_open;
+        }
+    }
+
+    /// Shade the parts of the player's floor that are outside every active hit and attack zone
+    fn draw_safe_area(&self, ui: &Ui, view_center: egui::Pos2) {
+        let Some(player_floor) = self.get_character(0).map(Character::floor) else {
+            return;
+        };
+
+        let danger_zones: Vec<_> = self.ai_zones.objects()
+            .iter()
+            .filter(|zone| matches!(zone.ai_zone.behavior_type, BehaviorType::Hit | BehaviorType::Attack) && zone.floor.matches(player_floor))
+            .collect();
+        let draw_params = self.config.get_draw_params(ObjectType::Floor, view_center);
+        let half_cell = SAFE_AREA_CELL_SIZE / 2;
+
+        for floor in self.floors.objects() {
+            let Collider::Rect(rect) = floor else {
+                continue;
+            };
+            let world_pos = rect.world_pos();
+            if !world_pos.floor.matches(player_floor) {
+                continue;
+            }
+
+            let (min_x, min_z) = (world_pos.pos.x.0, world_pos.pos.z.0);
+            let (max_x, max_z) = (min_x + world_pos.size.x.0, min_z + world_pos.size.z.0);
+            let mut z = min_z;
+            while z < max_z {
+                let height = SAFE_AREA_CELL_SIZE.min(max_z - z);
+                // merge consecutive safe cells in each row into a single rectangle
+                let mut run_start = None;
+                let mut x = min_x;
+                while x < max_x + SAFE_AREA_CELL_SIZE {
+                    let is_safe = x < max_x && {
+                        let center = Vec2::new(Fixed32(x + half_cell), Fixed32(z + half_cell));
+                        !danger_zones.iter().any(|zone| zone.contains_point(center))
+                    };
+
+                    match (is_safe, run_start) {
+                        (true, None) => run_start = Some(x),
+                        (false, Some(start)) => {
+                            let (sx, sy, sw, sh) = draw_params.transform(Fixed32(start), Fixed32(z), Fixed32(x.min(max_x) - start), Fixed32(height));
+                            let cell_rect = egui::Rect::from_min_size(egui::Pos2::new(sx, sy), egui::Vec2::new(sw, sh));
+                            ui.painter().rect_filled(cell_rect, 0.0, SAFE_AREA_COLOR);
+                            run_start = None;
                         }
+                        _ => (),
                     }
-                    ui.add_space(5.0);
-                });
-            });
 
-        if self.is_compare_filter_window_open {
-            self.is_compare_filter_window_open = is_compare_filter_window_open;
+                    x += SAFE_AREA_CELL_SIZE;
+                }
+
+                z += SAFE_AREA_CELL_SIZE;
+            }
+        }
+    }
+
+    fn draw_part_hitboxes(ui: &Ui, character: &Character, draw_params: &DrawParams) {
+        let stroke = Stroke::new(PART_HITBOX_WIDTH, PART_HITBOX_COLOR);
+        for (i, part) in character.parts().iter().enumerate() {
+            let Some(part) = part else {
+                continue;
+            };
+
+            let center = draw_params.transform_point(part.pos().xz());
+            let radius = part.size_offset().0 as f32 * draw_params.scale;
+            ui.painter().circle_stroke(center, radius, stroke);
+            ui.painter().circle_filled(center, PART_HITBOX_WIDTH * 2.0, PART_HITBOX_COLOR);
+            if i == 0 {
+                // the root part is what part-origin AI zones are measured from
+                ui.painter().circle_stroke(center, PART_HITBOX_WIDTH * 4.0, stroke);
+            }
         }
     }
 
@@ -1824,6 +6360,17 @@ impl App {
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+        let frame_start = Instant::now();
+        let mut layer_timings = LayerTimings::default();
+
+        self.handle_dropped_files(ctx);
+        self.save_capture(ctx);
+        self.update_live(ctx);
+        self.update_recording_loader(ctx);
+        self.update_snapshots(ctx);
+        self.config.distance_unit.make_current();
+        self.config.angle_unit.make_current();
+
         if self.need_title_update {
             ctx.send_viewport_cmd(ViewportCommand::Title(self.title()));
             self.need_title_update = false;
@@ -1832,17 +6379,79 @@ impl eframe::App for App {
         egui::TopBottomPanel::top("menu").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
                 ui.menu_button("File", |ui| {
-                    if ui.button("Open game folder").clicked() {
-                        if let Err(e) = self.prompt_load_game() {
-                            self.show_error(format!("Failed to open RDT: {e}"));
+                    if Self::is_read_only() {
+                        ui.label("Drop a bundle onto the page to open it");
+                    } else {
+                        if ui.button("Open game folder").clicked() {
+                            if let Err(e) = self.prompt_load_game() {
+                                self.show_error(format!("Failed to open RDT: {e}"));
+                            }
+                            ui.close();
+                        }
+
+                        if ui.button("Open recording").clicked() && self.is_game_loaded() {
+                            if let Err(e) = self.prompt_load_recording() {
+                                self.show_error(format!("Failed to open recording: {e}"));
+                            }
+                            ui.close();
+                        }
+
+                        ui.menu_button("Open RDT file", |ui| {
+                            for format in RdtFormat::list() {
+                                if ui.button(format.layout().name()).clicked() {
+                                    if let Err(e) = self.prompt_open_rdt(format) {
+                                        self.show_error(format!("Failed to open RDT: {e}"));
+                                    }
+                                    ui.close();
+                                }
+                            }
+                        });
+
+                        if ui.button("Open bundle").clicked() {
+                            if let Err(e) = self.prompt_load_bundle() {
+                                self.show_error(format!("Failed to open bundle: {e}"));
+                            }
+                            ui.close();
+                        }
+
+                        if self.is_live() {
+                            if ui.button("Disconnect from game").clicked() {
+                                self.disconnect_from_game();
+                                ui.close();
+                            }
+                        } else if ui.button("Connect to game").on_hover_text("View the game in real time while re2fr is running").clicked() && self.is_game_loaded() {
+                            self.connect_to_game();
+                            ui.close();
+                        }
+
+                        if ui.add_enabled(self.active_recording.is_some() && !self.is_live(), egui::Button::new("Export recording")).clicked() {
+                            self.is_export_window_open = true;
+                            ui.close();
+                        }
+
+                        if ui.add_enabled(self.active_recording_path.is_some(), egui::Button::new("Export bundle")).clicked() {
+                            if let Err(e) = self.prompt_export_bundle() {
+                                self.show_error(format!("Failed to export bundle: {e}"));
+                            }
+                            ui.close();
+                        }
+
+                        if ui.button("Import planned route").clicked() {
+                            if let Err(e) = self.prompt_import_planned_route() {
+                                self.show_error(format!("Failed to import planned route: {e}"));
+                            }
+                            ui.close();
                         }
-                        ui.close();
-                    }
 
-                    if ui.button("Open recording").clicked() && self.is_game_loaded() {
-                        if let Err(e) = self.prompt_load_recording() {
-                            self.show_error(format!("Failed to open recording: {e}"));
+                        // deferred to the next frame so the menu isn't in the capture
+                        if ui.add(egui::Button::new("Capture analysis frame").shortcut_text("F9")).clicked() {
+                            self.is_capture_requested = true;
+                            ui.close();
                         }
+                    }
+
+                    if ui.add_enabled(self.planned_route.is_some(), egui::Button::new("Clear planned route")).clicked() {
+                        self.planned_route = None;
                         ui.close();
                     }
                     
@@ -1873,6 +6482,126 @@ impl eframe::App for App {
                         self.is_rng_explore_window_open = true;
                         ui.close();
                     }
+
+                    if ui.button("Compare RNG").clicked() {
+                        self.is_rng_diff_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Trick windows").clicked() {
+                        self.is_trick_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Practice checklist").clicked() {
+                        self.is_checklist_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Shot discipline").clicked() {
+                        self.is_fight_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Body contacts").clicked() {
+                        self.is_contact_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Enemy timeline").clicked() {
+                        self.is_enemy_timeline_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Ammo efficiency").clicked() {
+                        self.is_ammo_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Enemy pushes").clicked() {
+                        self.is_push_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Verify recording").clicked() {
+                        self.is_verify_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Recording size").clicked() {
+                        self.is_size_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Frame timing").clicked() {
+                        self.is_timing_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Route matching").clicked() {
+                        self.is_route_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Draw route").clicked() {
+                        self.is_route_drawing_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Corner cutting").clicked() {
+                        self.is_corner_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("RDT inspector").clicked() {
+                        self.is_rdt_inspector_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Practice drills").clicked() {
+                        self.is_drill_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Progress").clicked() {
+                        self.is_progress_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Enemy behavior").clicked() {
+                        self.is_behavior_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("AOT overlaps").clicked() {
+                        self.is_aot_overlap_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Ghost recordings").clicked() {
+                        self.is_ghost_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Save states").clicked() {
+                        self.is_save_state_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Notifications").clicked() {
+                        self.is_notification_window_open = true;
+                        ui.close();
+                    }
+
+                    if ui.button("Profiler").clicked() {
+                        self.is_profiler_window_open = true;
+                        ui.close();
+                    }
+
+                    if !Self::is_read_only() && ui.button("Playback server").clicked() {
+                        self.is_server_window_open = true;
+                        ui.close();
+                    }
                 });
             });
         });
@@ -1881,59 +6610,198 @@ impl eframe::App for App {
             ui.vertical(|ui| {
                 ui.horizontal(|ui| {
                     for tab in BrowserTab::list() {
-                        let is_tab_inactive = (tab == BrowserTab::Recording && self.active_recording.is_none())
-                            || (tab == BrowserTab::Comparison && self.comparison.is_none())
-                            || (tab == BrowserTab::Rng && self.active_recording().is_none());
-                        
-                        if is_tab_inactive {
+                        if !self.is_tab_active(tab) || self.detached_tabs.contains(&tab) {
+                            continue;
+                        }
+
+                        let response = ui.selectable_label(self.tab == tab, tab.name());
+                        if response.clicked() {
+                            self.tab = tab;
+                        }
+                        if tab.is_detachable() {
+                            response.context_menu(|ui| {
+                                if ui.button("Open in new window").clicked() {
+                                    self.detach_tab(tab);
+                                    ui.close();
+                                }
+                            });
+                        }
+                    }
+
+                    for (i, plugin) in self.plugins.iter().enumerate() {
+                        if plugin.needs_recording() && self.active_recording.is_none() {
                             continue;
                         }
 
-                        if ui.selectable_label(self.tab == tab, tab.name()).clicked() {
+                        let tab = BrowserTab::Plugin(i);
+                        if ui.selectable_label(self.tab == tab, plugin.name()).clicked() {
                             self.tab = tab;
                         }
                     }
                 });
                 ui.separator();
-                match self.tab {
-                    BrowserTab::Game => self.rdt_browser(ui),
-                    BrowserTab::Room => self.room_browser(ui),
-                    BrowserTab::Settings => self.settings_browser(ui),
-                    BrowserTab::Rng => self.rng_browser(ui),
-                    BrowserTab::Recording => self.recording_browser(ui),
-                    BrowserTab::Comparison => self.comparison_browser(ui),
-                }
+                self.browser_tab(ui, self.tab);
             });
         });
 
+        self.detached_tab_windows(ctx);
+
         egui::TopBottomPanel::bottom("detail").show(ctx, |ui| {
             let width = ui.max_rect().width();
             ui.vertical(|ui| {
                 let mut need_toggle = false;
                 let mut new_frame_index = None;
+                let mut go_to_frame_text = std::mem::take(&mut self.go_to_frame_text);
+                let mut go_to_frame_error = None;
+                let loop_range = self.loop_range();
+                let mut loop_edit = None;
+                let save_state_frames: Vec<usize> = self.save_states.iter().filter_map(|s| s.frame_index).collect();
+                let enemy_markers: Vec<(usize, Color32)> = self.enemy_lifetimes.iter()
+                    .flat_map(|lifetime| {
+                        let end_color = match lifetime.fate {
+                            EnemyFate::Killed(_) => Some(ENEMY_KILL_MARKER_COLOR),
+                            EnemyFate::Despawned => Some(ENEMY_DESPAWN_MARKER_COLOR),
+                            EnemyFate::Avoided => None,
+                        };
+                        [
+                            Some((lifetime.frames.start, ENEMY_SPAWN_MARKER_COLOR)),
+                            end_color.map(|color| (lifetime.frames.end - 1, color)),
+                        ]
+                    })
+                    .flatten()
+                    .collect();
 
                 let play_pause = if self.is_recording_playing {
                     "⏸"
                 } else {
                     "▶"
                 };
+                let mut playback_speed = self.config.playback_speed;
 
                 if let Some(recording) = self.active_recording_mut() {
                     ui.horizontal(|ui| {
                         need_toggle = ui.button(play_pause).clicked();
+                        egui::ComboBox::from_id_salt("playback_speed")
+                            .selected_text(playback_speed.name())
+                            .width(60.0)
+                            .show_ui(ui, |ui| {
+                                for speed in PlaybackSpeed::list() {
+                                    ui.selectable_value(&mut playback_speed, speed, speed.name());
+                                }
+                            })
+                            .response
+                            .on_hover_text("Playback speed (- and + to change)");
 
                         let mut pos = recording.index();
                         let num_frames = recording.frames().len();
                         let time = recording.current_frame().map(FrameRecord::time).unwrap_or_else(|| String::from("00:00:00"));
-                        ui.style_mut().spacing.slider_width = width * 0.6;
-                        ui.add(egui::Slider::new(&mut pos, 0..=num_frames).text(time));
+                        let slider_width = width * 0.6;
+                        ui.style_mut().spacing.slider_width = slider_width;
+                        let response = ui.add(egui::Slider::new(&mut pos, 0..=num_frames).text(time));
                         if pos != recording.index() {
                             new_frame_index = Some(pos);
                         }
+
+                        // shade the parts of the timeline where the player didn't have control
+                        let slider_rect = egui::Rect::from_min_size(response.rect.min, egui::Vec2::new(slider_width, response.rect.height()));
+                        let frame_width = slider_width / num_frames.max(1) as f32;
+                        for lock_range in recording.control_locks() {
+                            let min_x = slider_rect.left() + lock_range.frames.start as f32 * frame_width;
+                            let max_x = slider_rect.left() + lock_range.frames.end as f32 * frame_width;
+                            let lock_rect = egui::Rect::from_x_y_ranges(min_x..=max_x, slider_rect.y_range());
+                            ui.painter().rect_filled(lock_rect, 0.0, CONTROL_LOCK_SHADE);
+                        }
+
+                        if let Some(ref range) = loop_range {
+                            let min_x = slider_rect.left() + range.start as f32 * frame_width;
+                            let max_x = slider_rect.left() + range.end as f32 * frame_width;
+                            let loop_rect = egui::Rect::from_x_y_ranges(min_x..=max_x, slider_rect.y_range());
+                            ui.painter().rect_filled(loop_rect, 0.0, LOOP_REGION_SHADE);
+                        }
+
+                        // enemy markers only cover the top half of the timeline so save states stay visible
+                        for (frame_index, color) in &enemy_markers {
+                            let x = slider_rect.left() + *frame_index as f32 * frame_width;
+                            ui.painter().vline(x, slider_rect.top()..=slider_rect.center().y, Stroke::new(1.0, *color));
+                        }
+
+                        // damage markers take the bottom half, opposite the enemy markers
+                        for event in recording.damage_events() {
+                            let x = slider_rect.left() + event.frame_index as f32 * frame_width;
+                            let color = if event.is_player() { PLAYER_DAMAGE_MARKER_COLOR } else { ENEMY_DAMAGE_MARKER_COLOR };
+                            ui.painter().vline(x, slider_rect.center().y..=slider_rect.bottom(), Stroke::new(1.0, color));
+                        }
+
+                        for marker in recording.markers() {
+                            let x = slider_rect.left() + marker.frame_index as f32 * frame_width;
+                            ui.painter().vline(x, slider_rect.y_range(), Stroke::new(2.0, USER_MARKER_COLOR));
+                        }
+
+                        for bookmark in recording.bookmarks() {
+                            let x = slider_rect.left() + bookmark.frame_index as f32 * frame_width;
+                            ui.painter().vline(x, slider_rect.y_range(), Stroke::new(2.0, BOOKMARK_MARKER_COLOR));
+                        }
+
+                        for load in recording.loads() {
+                            let x = slider_rect.left() + load.frame_index as f32 * frame_width;
+                            ui.painter().vline(x, slider_rect.y_range(), Stroke::new(2.0, LOAD_MARKER_COLOR));
+                        }
+
+                        for frame_index in &save_state_frames {
+                            let x = slider_rect.left() + *frame_index as f32 * frame_width;
+                            ui.painter().vline(x, slider_rect.y_range(), Stroke::new(2.0, SAVE_STATE_MARKER_COLOR));
+                        }
+
+                        let snapshot_progress = recording.snapshot_progress();
+                        if snapshot_progress < 1.0 {
+                            ui.add(egui::ProgressBar::new(snapshot_progress).desired_width(100.0).text("Indexing"))
+                                .on_hover_text("Seeking within long rooms will be faster once the recording is indexed");
+                        }
+                        if let Some(lock) = recording.control_lock_at(recording.index()) {
+                            ui.label(format!("🔒 {lock}"));
+                        }
+                        for marker in recording.markers().iter().filter(|m| m.frame_index == recording.index()) {
+                            ui.label(format!("📍 {}", marker.name));
+                        }
+                        for bookmark in recording.bookmarks().iter().filter(|b| b.frame_index == recording.index()) {
+                            ui.label(format!("🔖 {}", bookmark.name)).on_hover_text(&bookmark.note);
+                        }
+
+                        if ui.button("Loop in").on_hover_text("Start looping at this frame").clicked() {
+                            loop_edit = Some((Some(recording.index()), false));
+                        }
+                        if ui.button("Loop out").on_hover_text("Stop looping at this frame").clicked() {
+                            loop_edit = Some((Some(recording.index()), true));
+                        }
+                        if ui.add_enabled(loop_range.is_some(), egui::Button::new("Clear loop")).clicked() {
+                            loop_edit = Some((None, false));
+                        }
+
+                        ui.label("Go to frame");
+                        let go_to_response = ui.add(egui::TextEdit::singleline(&mut go_to_frame_text).desired_width(60.0));
+                        if go_to_response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                            match go_to_frame_text.trim().parse::<usize>() {
+                                Ok(index) if index < num_frames => new_frame_index = Some(index),
+                                Ok(index) => go_to_frame_error = Some(format!("Frame {index} is out of range (0-{})", num_frames.saturating_sub(1))),
+                                Err(_) => go_to_frame_error = Some(format!("Invalid frame number: {}", go_to_frame_text.trim())),
+                            }
+                        }
                     });
                     ui.separator();
                 }
 
+                self.go_to_frame_text = go_to_frame_text;
+                match loop_edit {
+                    Some((None, _)) => self.clear_loop(),
+                    Some((index, false)) => self.loop_in = index,
+                    Some((index, true)) => self.loop_out = index,
+                    None => (),
+                }
+                if let Some(message) = go_to_frame_error {
+                    self.show_error(message);
+                }
+
+                self.config.playback_speed = playback_speed;
                 if need_toggle {
                     self.toggle_play_recording();
                 }
@@ -1947,7 +6815,7 @@ impl eframe::App for App {
                 ui.separator();
                 
                 if let Some(pos) = self.pointer_game_pos {
-                    ui.label(format!("X: {}, Z: {}", pos.x, pos.z));
+                    ui.label(format!("X: {}, Z: {}", Distance(pos.x), Distance(pos.z)));
                 }
             });
         });
@@ -1960,6 +6828,7 @@ impl eframe::App for App {
             let view_center = self.calculate_origin(ctx);
             let empty_state = State::empty();
             let state = self.active_recording().and_then(Recording::current_state).unwrap_or(&empty_state);
+            let mut timer = Instant::now();
 
             for (i, floor) in self.floors.visible_objects(&self.config) {
                 let mut floor_draw_params = self.config.get_obj_draw_params(floor, view_center);
@@ -1971,6 +6840,13 @@ impl eframe::App for App {
                 ui.draw_game_object(floor, &floor_draw_params, state);
             }
 
+            layer_timings.lap(DrawLayer::Floors, &mut timer);
+
+            if self.config.show_safe_area && self.profiler.is_layer_enabled(DrawLayer::SafeArea) {
+                self.draw_safe_area(ui, view_center);
+            }
+            layer_timings.lap(DrawLayer::SafeArea, &mut timer);
+
             for (i, collider) in self.colliders.visible_objects(&self.config) {
                 let mut collider_draw_params = self.config.get_obj_draw_params(collider, view_center);
                 if self.adjust_draw_for_selection(&mut collider_draw_params, collider, i) {
@@ -1979,6 +6855,7 @@ impl eframe::App for App {
 
                 ui.draw_game_object(collider, &collider_draw_params, state);
             }
+            layer_timings.lap(DrawLayer::Colliders, &mut timer);
 
             for (i, entity) in self.entities.visible_objects(&self.config) {
                 let mut entity_draw_params = self.config.get_obj_draw_params(entity, view_center);
@@ -1988,6 +6865,7 @@ impl eframe::App for App {
 
                 ui.draw_game_object(entity, &entity_draw_params, state);
             }
+            layer_timings.lap(DrawLayer::Entities, &mut timer);
 
             for (_, object) in self.objects.visible_objects(&self.config) {
                 let mut object_draw_params = self.config.get_obj_draw_params(object, view_center);
@@ -1997,16 +6875,22 @@ impl eframe::App for App {
                 
                 ui.draw_game_object(object, &object_draw_params, state);
             }
+            layer_timings.lap(DrawLayer::Objects, &mut timer);
 
             // draw all AI zones first, then all characters, so characters are always on top of the zones
-            for (i, ai_zone) in self.ai_zones.visible_objects(&self.config) {
+            let show_ai_zones = self.profiler.is_layer_enabled(DrawLayer::AiZones);
+            for (i, ai_zone) in self.ai_zones.visible_objects(&self.config).filter(|_| show_ai_zones) {
                 let (Some(character), Some(settings)) = (state.characters()[ai_zone.character_index].as_ref(), self.get_character_settings(ai_zone.character_index)) else {
                     // the character must not be none because otherwise we wouldn't have AI zones for them
                     eprintln!("AI zone {} has no character (expected character {} at index {})", i, ai_zone.character_id.name(), ai_zone.character_index);
                     continue;
                 };
                 // if the character the AI zones belong to isn't shown here, we shouldn't show the AI zones either
-                if !self.config.should_show(character.object_type()) || !settings.show_ai() {
+                let is_shown = match self.config.ai_behavior_filter() {
+                    Some(filter) => filter.contains(&ai_zone.ai_zone.behavior_type),
+                    None => self.config.should_show(character.object_type()) && settings.show_ai(),
+                };
+                if !is_shown {
                     continue;
                 }
 
@@ -2021,11 +6905,11 @@ impl eframe::App for App {
             // if the current selected object is a character, and that character has AI zones, draw those
             // zones after all other zones, but still before characters, because we always want those to
             // be on top
-            if let SelectedObject::Character(i) = self.selected_object {
+            if let SelectedObject::Character(i) = self.selected_object && show_ai_zones {
                 if let (Some(character), Some(settings)) = (state.characters()[i].as_ref(), self.get_character_settings(i)) {
                     if self.config.should_show(character.object_type()) && settings.show_ai() {
                         for (j, ai_zone) in self.ai_zones.visible_objects(&self.config) {
-                            if ai_zone.character_index != i {
+                            if ai_zone.character_index != i || self.config.ai_behavior_filter().is_some_and(|f| !f.contains(&ai_zone.ai_zone.behavior_type)) {
                                 continue;
                             }
 
@@ -2037,8 +6921,34 @@ impl eframe::App for App {
                 }
             }
             
+            layer_timings.lap(DrawLayer::AiZones, &mut timer);
+
+            // draw every visit to this room during the current run under the regular paths
+            let show_run_paths = self.config.show_full_run_path && self.profiler.is_layer_enabled(DrawLayer::RunPaths);
+            if let (Some(recording), Some(room_id), true) = (self.active_recording.as_ref(), self.config.last_rdt, show_run_paths) {
+                for path in recording.get_run_paths_for_room(room_id) {
+                    let mut path_draw_params = self.config.get_obj_draw_params(&path, view_center);
+                    path_draw_params.stroke.width = COMPARISON_PATH_EMPHASIS_WIDTH * self.config.zoom_scale;
+                    ui.draw_game_object(&path, &path_draw_params, state);
+                }
+            }
+
+            layer_timings.lap(DrawLayer::RunPaths, &mut timer);
+
+            if self.profiler.is_layer_enabled(DrawLayer::Routes) {
+                self.draw_deaggros(ui, view_center);
+                self.draw_grab_warnings(ui, view_center);
+                self.draw_attack_traces(ui, view_center);
+                self.draw_room_spawns(ui, view_center);
+                self.draw_planned_route(ui, view_center);
+                self.draw_drawn_route(ui, view_center);
+                self.draw_corner_cut(ui, view_center);
+            }
+            layer_timings.lap(DrawLayer::Routes, &mut timer);
+
             // also draw paths before characters so the paths are under the characters
-            for (_, character) in self.characters.visible_objects(&self.config) {
+            let show_character_paths = self.profiler.is_layer_enabled(DrawLayer::CharacterPaths);
+            for (_, character) in self.characters.visible_objects(&self.config).filter(|_| show_character_paths) {
                 if !self.get_character_settings(character.index()).map(|s| s.show_path()).unwrap_or(false) {
                     continue;
                 }
@@ -2048,15 +6958,43 @@ impl eframe::App for App {
                     continue;
                 }
                 
-                if let Some(path) = self.active_recording().and_then(|r| r.get_path_for_character(character.index())) {
+                let path = self.active_recording().and_then(|r| match self.config.path_window_frames() {
+                    Some((past, future)) => r.get_path_window_for_character(character.index(), past, future),
+                    None => r.get_path_for_character(character.index()),
+                });
+                if let Some(path) = path {
                     let mut path_draw_params = self.config.get_obj_draw_params(&path, view_center);
                     path_draw_params.stroke.width = character.size.x * self.config.zoom_scale * 2.0;
                     ui.draw_game_object(&path, &path_draw_params, state);
                 }
             }
 
+            layer_timings.lap(DrawLayer::CharacterPaths, &mut timer);
+
+            // draw the selected character's predicted path alongside its actual path
+            if let SelectedObject::Character(i) = self.selected_object
+                && i != 0
+                && self.profiler.is_layer_enabled(DrawLayer::Prediction)
+                && self.get_character_settings(i).map(|s| s.show_prediction()).unwrap_or(false)
+                && let Some(prediction) = self.active_recording().and_then(|r| r.get_path_prediction_for_character(i))
+            {
+                let mut path_draw_params = self.config.get_obj_draw_params(&prediction.actual, view_center);
+                path_draw_params.stroke.width = COMPARISON_PATH_WIDTH * self.config.zoom_scale;
+                ui.draw_game_object(&prediction.actual, &path_draw_params, state);
+
+                path_draw_params.stroke.color = PREDICTED_PATH_COLOR;
+                ui.draw_game_object(&prediction.predicted, &path_draw_params, state);
+
+                for (_, pos) in &prediction.divergences {
+                    ui.painter().circle_filled(path_draw_params.transform_point(*pos), DIVERGENCE_MARKER_RADIUS, DIVERGENCE_MARKER_COLOR);
+                }
+            }
+
+            layer_timings.lap(DrawLayer::Prediction, &mut timer);
+
             // draw comparison paths if we're doing a comparison
-            if let (Some(comparison), true) = (&self.comparison, self.show_comparison_paths) {
+            let show_comparison_paths = self.show_comparison_paths && self.profiler.is_layer_enabled(DrawLayer::ComparisonPaths);
+            if let (Some(comparison), true) = (&self.comparison, show_comparison_paths) {
                 let fastest_time = comparison.fastest_time();
                 let time_range = (comparison.slowest_time() - fastest_time).max(1) as f32;
 
@@ -2105,6 +7043,8 @@ impl eframe::App for App {
                 ui.draw_game_object(path, &path_draw_params, state);
             }
             
+            layer_timings.lap(DrawLayer::ComparisonPaths, &mut timer);
+
             // draw player's equipped weapon ranges if enabled
             if let Some(range_visualization) = WeaponRangeVisualization::for_state(state) {
                 if self.config.should_show(range_visualization.object_type()) {
@@ -2115,6 +7055,20 @@ impl eframe::App for App {
                 }
             }
 
+            layer_timings.lap(DrawLayer::WeaponRanges, &mut timer);
+
+            // ghosts go under the active recording's characters
+            for ghost in &self.ghosts {
+                for character in ghost.characters() {
+                    let mut ghost_draw_params = self.config.get_obj_draw_params(&character, view_center);
+                    ghost_draw_params.set_color(ghost.color);
+                    if character.index() != 0 {
+                        ghost_draw_params.fade(GHOST_ENEMY_FADE);
+                    }
+                    ui.draw_game_object(&character, &ghost_draw_params, state);
+                }
+            }
+
             for (_, character) in self.characters.visible_objects(&self.config) {
                 let mut char_draw_params = self.config.get_obj_draw_params(character, view_center);
                 if self.adjust_draw_for_selection(&mut char_draw_params, character, character.index()) || !self.get_character_settings(character.index()).map(|s| s.show).unwrap_or(false) {
@@ -2122,10 +7076,16 @@ impl eframe::App for App {
                 }
 
                 ui.draw_game_object(character, &char_draw_params, state);
+                if self.config.show_part_hitboxes {
+                    Self::draw_part_hitboxes(ui, character, &char_draw_params);
+                }
             }
 
+            layer_timings.lap(DrawLayer::Characters, &mut timer);
+
             // draw character tooltips on top of the characters themselves
-            for (_, character) in self.characters.visible_objects(&self.config) {
+            let show_tooltips = self.profiler.is_layer_enabled(DrawLayer::Tooltips);
+            for (_, character) in self.characters.visible_objects(&self.config).filter(|_| show_tooltips) {
                 let i = character.index();
                 if self.selected_object.matches(character, i) || !self.get_character_settings(i).map(|s| s.show_tooltip()).unwrap_or(false) {
                     continue;
@@ -2136,8 +7096,10 @@ impl eframe::App for App {
                 ui.draw_game_tooltip(character, &char_draw_params, state, i);
             }
 
+            layer_timings.lap(DrawLayer::Tooltips, &mut timer);
+
             if let Some(recording) = self.active_recording() {
-                if self.config.show_sounds {
+                if self.config.show_sounds && self.profiler.is_layer_enabled(DrawLayer::Sounds) {
                     // TODO: make sound text box colors configurable
                     let sound_draw_params = DrawParams {
                         origin: view_center,
@@ -2151,13 +7113,27 @@ impl eframe::App for App {
                         draw_at_origin: false,
                     };
 
-                    for sound in recording.get_player_sounds(MAX_SOUND_AGE) {
-                        let sound_box = Self::get_sound_text_box(&sound, &sound_draw_params, ui);
+                    let max_age = self.config.sound_max_age;
+                    let mut sounds = recording.get_player_sounds(max_age);
+                    if self.config.sounds_since_enemy_roll {
+                        // enemies only react to sounds made since they last rolled, so older ones don't matter
+                        if let Some(roll_frame) = recording.last_enemy_roll_frame(max_age) {
+                            sounds.retain(|s| s.age < recording.index() - roll_frame);
+                        }
+                    }
+                    // sounds are oldest first; keep the newest ones
+                    let num_hidden = sounds.len().saturating_sub(self.config.sound_max_count);
+                    let fade_age = self.config.fade_sounds.then_some(max_age);
+
+                    for sound in &sounds[num_hidden..] {
+                        let sound_box = Self::get_sound_text_box(sound, fade_age, &sound_draw_params, ui);
                         ui.painter().add(sound_box);
                     }
                 }
             }
 
+            layer_timings.lap(DrawLayer::Sounds, &mut timer);
+
             // draw highlighted object (if any) on top
             match self.selected_object {
                 SelectedObject::None | SelectedObject::Floor(_) | SelectedObject::AiZone(_) => {}
@@ -2242,7 +7218,7 @@ impl eframe::App for App {
                     SelectedObject::Character(i) => {
                         if let (Some(character), Some(settings)) = (self.get_character(i), self.get_character_settings(i)) {
                             // if the character's tooltip setting is on, we've already drawn their tooltip
-                            if !settings.show_tooltip() {
+                            if !settings.show_tooltip() || !show_tooltips {
                                 let mut char_draw_params = self.config.get_obj_draw_params(character, view_center);
                                 char_draw_params.set_draw_origin(hover_pos);
                                 ui.draw_game_tooltip(character, &char_draw_params, state, i);
@@ -2252,6 +7228,8 @@ impl eframe::App for App {
                 }
             }
 
+            layer_timings.lap(DrawLayer::Highlights, &mut timer);
+
             // show player inputs in top right
             if let Some(state) = self.active_recording().and_then(Recording::current_state) {
                 let input_state = state.input_state();
@@ -2278,46 +7256,133 @@ impl eframe::App for App {
 
                 let aim_pos = input_origin + egui::Vec2::new(-INPUT_OFFSET, INPUT_SIZE * 3.0 + INPUT_MARGIN * 4.0);
                 Self::draw_key(ui, "Aim", aim_pos, input_state.is_aim_pressed);
+
+                if self.config.show_aim_readout && let Some(readout) = AimReadout::for_state(state) {
+                    let readout_pos = input_origin + egui::Vec2::new(-AIM_READOUT_OFFSET, INPUT_SIZE * 4.0 + INPUT_MARGIN * 6.0);
+                    let (bg, text) = text_box(readout.description(), readout_pos, VAlign::Top, TEXT_BOX_DARK, TEXT_BOX_LIGHT, ui);
+                    ui.painter().add(bg);
+                    ui.painter().add(text);
+                }
+            }
+
+            // flash the border of the view when the player walks into a hit zone
+            if let Some(flash_start) = self.hit_zone_flash_start {
+                let elapsed = flash_start.elapsed().as_secs_f32();
+                if elapsed < HIT_ZONE_FLASH_SECONDS {
+                    let color = HIT_ZONE_FLASH_COLOR.gamma_multiply(1.0 - elapsed / HIT_ZONE_FLASH_SECONDS);
+                    ui.painter().rect_stroke(ui.max_rect(), 0.0, Stroke::new(HIT_ZONE_FLASH_WIDTH, color), StrokeKind::Inside);
+                } else {
+                    self.hit_zone_flash_start = None;
+                }
+            }
+
+            // show the most recent notification across the top of the view
+            if let Some((start, ref name)) = self.notification_banner {
+                let elapsed = start.elapsed().as_secs_f32();
+                if elapsed < NOTIFICATION_SECONDS {
+                    let color = NOTIFICATION_COLOR.gamma_multiply(1.0 - elapsed / NOTIFICATION_SECONDS);
+                    let rect = ui.max_rect();
+                    ui.painter().text(
+                        egui::pos2(rect.center().x, rect.top() + NOTIFICATION_FONT_SIZE),
+                        egui::Align2::CENTER_CENTER,
+                        name,
+                        egui::FontId::proportional(NOTIFICATION_FONT_SIZE),
+                        color,
+                    );
+                } else {
+                    self.notification_banner = None;
+                }
+            }
+
+            if let Some(ref capture) = self.pending_capture {
+                let rect = ui.max_rect();
+                let pos = egui::pos2(rect.left() + CAPTURE_MARGIN, rect.top() + CAPTURE_MARGIN);
+                let (mut bg, mut text) = text_box(capture.annotation(), pos, VAlign::Top, TEXT_BOX_DARK, TEXT_BOX_LIGHT, ui);
+                // text boxes are centered on their position, but we want this one pinned to the corner
+                let offset = egui::vec2(pos.x - bg.visual_bounding_rect().left(), 0.0);
+                bg.translate(offset);
+                text.translate(offset);
+                ui.painter().add(bg);
+                ui.painter().add(text);
+            }
+
+            if self.need_user_attention {
+                self.need_user_attention = false;
+                ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(egui::UserAttentionType::Informational));
             }
         });
 
         // display modals if necessary
+        self.rdt_warning_modal(ctx);
         self.error_modal(ctx);
         self.compare_filter_window(ctx);
         self.rng_explore_window(ctx);
+        self.trick_window(ctx);
+        self.checklist_window(ctx);
+        self.fight_window(ctx);
+        self.contact_window(ctx);
+        self.enemy_timeline_window(ctx);
+        self.ammo_window(ctx);
+        self.push_window(ctx);
+        self.verify_window(ctx);
+        self.size_window(ctx);
+        self.timing_window(ctx);
+        self.rng_diff_window(ctx);
+        self.export_window(ctx);
+        self.route_window(ctx);
+        self.route_drawing_window(ctx);
+        self.corner_window(ctx);
+        self.rdt_inspector_window(ctx);
+        self.profiler_window(ctx);
+        self.server_window(ctx);
+        self.notification_window(ctx);
+        self.save_state_window(ctx);
+        self.drill_window(ctx);
+        self.progress_window(ctx);
+        self.behavior_window(ctx);
+        self.recording_loader_window(ctx);
+        self.aot_overlap_window(ctx);
+        self.ghost_window(ctx);
 
         let repaint_duration = if self.active_recording().is_some() && self.is_recording_playing {
-            let now = Instant::now();
-            let duration = now - self.last_play_tick;
-            if duration >= FRAME_DURATION {
-                let previous_room_id = self.config.last_rdt.unwrap();
-                if !self.next_recording_frame(){
-                    // if we get clamped due to reaching the end of the comparison section and
-                    // the other comparison paths are not playing, pause playback
-                    self.is_recording_playing = false;
-                } else if let Some(player) = self.get_character(0)
-                    && player.is_moving()
-                    // don't try to project normal movement when the room changes
-                    && self.config.last_rdt.unwrap() == previous_room_id {
-                    // validate our collision logic
-                    #[cfg(feature = "motion-simulation")]
-                    self.simulate_motion(player);
-                }
-
-                FRAME_DURATION
+            let frame_duration = FRAME_DURATION.div_f32(self.config.playback_speed.factor());
+            let duration = Instant::now() - self.last_play_tick;
+            if duration >= frame_duration {
+                // at high speeds, frames can come due faster than the UI redraws
+                let frames_due = ((duration.as_secs_f32() / frame_duration.as_secs_f32()) as usize).clamp(1, MAX_FRAMES_PER_UPDATE);
+                for _ in 0..frames_due {
+                    if !self.is_recording_playing {
+                        break;
+                    }
+                    self.advance_playback();
+                }
+
+                frame_duration
             } else {
                 // schedule a re-draw for the next frame
-                FRAME_DURATION - duration
+                frame_duration - duration
             }
+        } else if self.hit_zone_flash_start.is_some() || self.notification_banner.is_some() {
+            // keep animating the hit zone alert or notification
+            FRAME_DURATION
         } else {
             // schedule a re-draw after the hover time expires plus a small margin
             Duration::from_secs_f32(TOOLTIP_HOVER_SECONDS + 0.1)
         };
         
         ctx.request_repaint_after(repaint_duration);
+
+        self.publish_playback_state();
+
+        let reduced_detail = self.config.reduced_detail_budget();
+        self.profiler.end_frame(layer_timings, frame_start.elapsed(), reduced_detail);
     }
 
     fn save(&mut self, _storage: &mut dyn Storage) {
+        self.config.character_settings = self.character_settings
+            .iter()
+            .map(|(&(room_id, character_id), &settings)| SavedCharacterSettings { room_id, character_id, settings })
+            .collect();
         if let Err(e) = self.config.save() {
             eprintln!("Failed to save config: {}", e);
         }
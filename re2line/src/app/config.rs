@@ -6,7 +6,15 @@ use enum_map::{enum_map, EnumMap};
 use egui::Color32;
 use serde::{Deserialize, Serialize};
 
-use crate::character::PLAYER_COLLISION_MASK;
+use crate::ammo::AmmoBudget;
+use crate::character::{BehaviorType, PLAYER_COLLISION_MASK};
+use crate::checklist::ChecklistItem;
+use crate::notify::NotificationRule;
+use crate::record::FRAME_DURATION;
+use crate::rng::{RngBookmark, RngPreset};
+use crate::route::RouteTemplate;
+use crate::trick::TrickDefinition;
+use crate::units::{AngleUnit, DistanceUnit};
 use super::game::{DrawParams, GameObject, ObjectType};
 
 const STROKE_WIDTH: f32 = 1.0;
@@ -100,10 +108,211 @@ impl ObjectSettings {
      }
 }
 
+#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub(super) struct CharacterSettings {
+     pub show: bool,
+     pub show_tooltip: bool,
+     pub show_ai: bool,
+     pub show_path: bool,
+     pub show_rng_rolls: bool,
+     pub show_prediction: bool,
+}
+
+impl CharacterSettings {
+     pub const fn config_default(config: &Config) -> Self {
+          Self {
+               show: true,
+               show_tooltip: config.default_show_character_tooltips,
+               show_ai: true,
+               show_path: false,
+               show_rng_rolls: true,
+               show_prediction: false,
+          }
+     }
+
+     pub const fn show_tooltip(&self) -> bool {
+          self.show && self.show_tooltip
+     }
+
+     pub const fn show_ai(&self) -> bool {
+          self.show && self.show_ai
+     }
+
+     pub const fn show_path(&self) -> bool {
+          self.show && self.show_path
+     }
+
+     pub const fn show_rng_rolls(&self) -> bool {
+          self.show_rng_rolls
+     }
+
+     pub const fn show_prediction(&self) -> bool {
+          self.show && self.show_prediction
+     }
+}
+
+impl Default for CharacterSettings {
+     fn default() -> Self {
+          Self {
+               show: true,
+               show_tooltip: true,
+               show_ai: true,
+               show_path: false,
+               show_rng_rolls: true,
+               show_prediction: false,
+          }
+     }
+}
+
+/// How fast recordings play back relative to the game's frame rate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PlaybackSpeed {
+     Quarter,
+     Half,
+     #[default]
+     Normal,
+     Double,
+     Quadruple,
+}
+
+impl PlaybackSpeed {
+     pub const fn list() -> [Self; 5] {
+          [Self::Quarter, Self::Half, Self::Normal, Self::Double, Self::Quadruple]
+     }
+
+     pub const fn name(&self) -> &'static str {
+          match self {
+               Self::Quarter => "0.25x",
+               Self::Half => "0.5x",
+               Self::Normal => "1x",
+               Self::Double => "2x",
+               Self::Quadruple => "4x",
+          }
+     }
+
+     pub const fn factor(&self) -> f32 {
+          match self {
+               Self::Quarter => 0.25,
+               Self::Half => 0.5,
+               Self::Normal => 1.0,
+               Self::Double => 2.0,
+               Self::Quadruple => 4.0,
+          }
+     }
+
+     pub fn faster(&self) -> Self {
+          let list = Self::list();
+          let i = list.iter().position(|s| s == self).unwrap_or(0);
+          list[(i + 1).min(list.len() - 1)]
+     }
+
+     pub fn slower(&self) -> Self {
+          let list = Self::list();
+          let i = list.iter().position(|s| s == self).unwrap_or(0);
+          list[i.saturating_sub(1)]
+     }
+}
+
+/// Character display settings remembered for a particular character in a particular room
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(super) struct SavedCharacterSettings {
+     pub room_id: RoomId,
+     pub character_id: u8,
+     pub settings: CharacterSettings,
+}
+
+/// Which detail groups and fields to show for a type of object, and in what order
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(super) struct DetailLayout {
+     pub object_type: ObjectType,
+     /// Groups listed here are shown first, in this order
+     pub group_order: Vec<String>,
+     pub hidden_groups: Vec<String>,
+     /// Fields are identified by their label, i.e. the text before the colon
+     pub hidden_fields: Vec<String>,
+}
+
+impl DetailLayout {
+     pub const fn new(object_type: ObjectType) -> Self {
+          Self {
+               object_type,
+               group_order: Vec::new(),
+               hidden_groups: Vec::new(),
+               hidden_fields: Vec::new(),
+          }
+     }
+
+     pub fn field_label(field: &str) -> &str {
+          field.split_once(':').map(|(label, _)| label).unwrap_or(field).trim()
+     }
+
+     pub fn is_empty(&self) -> bool {
+          self.group_order.is_empty() && self.hidden_groups.is_empty() && self.hidden_fields.is_empty()
+     }
+
+     pub fn move_to_front(&mut self, group: &str) {
+          self.group_order.retain(|g| g != group);
+          self.group_order.insert(0, group.to_string());
+     }
+
+     pub fn apply(&self, details: Vec<(String, Vec<String>)>) -> Vec<(String, Vec<String>)> {
+          let mut details: Vec<_> = details.into_iter()
+               .filter(|(group, _)| !self.hidden_groups.contains(group))
+               .map(|(group, fields)| {
+                    let fields = fields.into_iter()
+                         .filter(|field| !self.hidden_fields.iter().any(|f| f == Self::field_label(field)))
+                         .collect();
+                    (group, fields)
+               })
+               .collect();
+
+          // stable sort, so groups that weren't given an order keep their original relative order
+          details.sort_by_key(|(group, _)| self.group_order.iter().position(|g| g == group).unwrap_or(usize::MAX));
+          details
+     }
+}
+
 const fn default_true() -> bool {
      true
 }
 
+const fn default_path_window_seconds() -> f32 {
+     5.0
+}
+
+const fn default_frame_budget_ms() -> f32 {
+     16.7
+}
+
+const fn default_playback_server_port() -> u16 {
+     8765
+}
+
+fn default_ai_behavior_filter() -> Vec<BehaviorType> {
+     vec![BehaviorType::Hit]
+}
+
+const fn default_fast_forward_frames() -> usize {
+     30
+}
+
+const fn default_shift_step_frames() -> usize {
+     5
+}
+
+const fn default_sound_max_age() -> usize {
+     100
+}
+
+const fn default_sound_max_count() -> usize {
+     20
+}
+
+const fn default_detail_max_rows() -> usize {
+     4
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub(super) struct Config {
      pub rdt_folder: Option<PathBuf>,
@@ -111,6 +320,14 @@ pub(super) struct Config {
      pub zoom_scale: f32,
      #[serde(default = "default_true")]
      pub show_sounds: bool,
+     #[serde(default = "default_sound_max_age")]
+     pub sound_max_age: usize,
+     #[serde(default = "default_true")]
+     pub fade_sounds: bool,
+     #[serde(default = "default_sound_max_count")]
+     pub sound_max_count: usize,
+     #[serde(default)]
+     pub sounds_since_enemy_roll: bool,
      #[serde(default)]
      pub focus_current_selected_object: bool,
      #[serde(default)]
@@ -125,6 +342,72 @@ pub(super) struct Config {
      pub show_unknown_rng: bool,
      #[serde(default)]
      pub show_all_objects: bool,
+     #[serde(default)]
+     pub show_full_run_path: bool,
+     #[serde(default)]
+     pub show_safe_area: bool,
+     #[serde(default)]
+     pub show_part_hitboxes: bool,
+     #[serde(default = "default_true")]
+     pub show_aim_readout: bool,
+     #[serde(default = "default_true")]
+     pub show_deaggros: bool,
+     #[serde(default = "default_true")]
+     pub show_grab_warnings: bool,
+     #[serde(default = "default_true")]
+     pub show_attack_traces: bool,
+     #[serde(default = "default_true")]
+     pub show_room_spawns: bool,
+     #[serde(default)]
+     pub reduced_detail: bool,
+     #[serde(default = "default_frame_budget_ms")]
+     pub frame_budget_ms: f32,
+     #[serde(default = "default_playback_server_port")]
+     pub playback_server_port: u16,
+     #[serde(default)]
+     pub hit_zone_alerts: bool,
+     #[serde(default)]
+     pub notification_rules: Vec<NotificationRule>,
+     #[serde(default)]
+     pub ammo_budget: Vec<AmmoBudget>,
+     #[serde(default)]
+     pub limit_path_window: bool,
+     #[serde(default = "default_path_window_seconds")]
+     pub path_window_past_seconds: f32,
+     #[serde(default = "default_path_window_seconds")]
+     pub path_window_future_seconds: f32,
+     #[serde(default)]
+     pub tricks: Vec<TrickDefinition>,
+     #[serde(default)]
+     pub checklist: Vec<ChecklistItem>,
+     #[serde(default)]
+     pub rng_bookmarks: Vec<RngBookmark>,
+     #[serde(default)]
+     pub rng_presets: Vec<RngPreset>,
+     #[serde(default)]
+     pub character_settings: Vec<SavedCharacterSettings>,
+     #[serde(default)]
+     pub filter_ai_behaviors: bool,
+     #[serde(default = "default_ai_behavior_filter")]
+     pub ai_behavior_filter: Vec<BehaviorType>,
+     #[serde(default = "default_fast_forward_frames")]
+     pub fast_forward_frames: usize,
+     #[serde(default = "default_shift_step_frames")]
+     pub shift_step_frames: usize,
+     #[serde(default)]
+     pub playback_speed: PlaybackSpeed,
+     #[serde(default)]
+     pub reference_route: Option<RouteTemplate>,
+     #[serde(default)]
+     pub capture_folder: Option<PathBuf>,
+     #[serde(default = "default_detail_max_rows")]
+     pub detail_max_rows: usize,
+     #[serde(default)]
+     pub detail_layouts: Vec<DetailLayout>,
+     #[serde(default)]
+     pub distance_unit: DistanceUnit,
+     #[serde(default)]
+     pub angle_unit: AngleUnit,
      pub object_settings: EnumMap<ObjectType, ObjectSettings>,
 }
 
@@ -153,6 +436,21 @@ impl Config {
           Ok(())
      }
      
+     pub fn detail_layout(&self, object_type: ObjectType) -> Option<&DetailLayout> {
+          self.detail_layouts.iter().find(|l| l.object_type == object_type)
+     }
+
+     pub fn detail_layout_mut(&mut self, object_type: ObjectType) -> &mut DetailLayout {
+          let index = match self.detail_layouts.iter().position(|l| l.object_type == object_type) {
+               Some(index) => index,
+               None => {
+                    self.detail_layouts.push(DetailLayout::new(object_type));
+                    self.detail_layouts.len() - 1
+               }
+          };
+          &mut self.detail_layouts[index]
+     }
+
      pub fn get_draw_params(&self, object_type: ObjectType, origin: egui::Pos2) -> DrawParams {
           self.object_settings[object_type].get_draw_params(origin, self.zoom_scale)
      }
@@ -175,6 +473,27 @@ impl Config {
      pub fn should_show(&self, object_type: ObjectType) -> bool {
           self.object_settings[object_type].show
      }
+     
+     /// The only AI zone behavior types that should be shown, if filtering is enabled
+     pub fn ai_behavior_filter(&self) -> Option<&[BehaviorType]> {
+          self.filter_ai_behaviors.then_some(self.ai_behavior_filter.as_slice())
+     }
+
+     pub fn path_window_frames(&self) -> Option<(usize, usize)> {
+          if !self.limit_path_window {
+               return None;
+          }
+          
+          let frame_seconds = FRAME_DURATION.as_secs_f32();
+          Some((
+               (self.path_window_past_seconds / frame_seconds) as usize,
+               (self.path_window_future_seconds / frame_seconds) as usize,
+          ))
+     }
+
+     pub fn reduced_detail_budget(&self) -> Option<f32> {
+          self.reduced_detail.then_some(self.frame_budget_ms)
+     }
 }
 
 impl Default for Config {
@@ -184,6 +503,10 @@ impl Default for Config {
                last_rdt: None,
                zoom_scale: 40.0,
                show_sounds: true,
+               sound_max_age: default_sound_max_age(),
+               fade_sounds: true,
+               sound_max_count: default_sound_max_count(),
+               sounds_since_enemy_roll: false,
                focus_current_selected_object: false,
                alternate_collision_colors: false,
                default_show_character_tooltips: true,
@@ -191,6 +514,39 @@ impl Default for Config {
                show_known_non_character_rng: true,
                show_unknown_rng: true,
                show_all_objects: false,
+               show_full_run_path: false,
+               show_safe_area: false,
+               show_part_hitboxes: false,
+               show_aim_readout: true,
+               show_deaggros: true,
+               show_grab_warnings: true,
+               show_attack_traces: true,
+               show_room_spawns: true,
+               reduced_detail: false,
+               frame_budget_ms: default_frame_budget_ms(),
+               playback_server_port: default_playback_server_port(),
+               hit_zone_alerts: false,
+               notification_rules: Vec::new(),
+               ammo_budget: Vec::new(),
+               limit_path_window: false,
+               path_window_past_seconds: default_path_window_seconds(),
+               path_window_future_seconds: default_path_window_seconds(),
+               tricks: Vec::new(),
+               checklist: Vec::new(),
+               rng_bookmarks: Vec::new(),
+               rng_presets: Vec::new(),
+               character_settings: Vec::new(),
+               filter_ai_behaviors: false,
+               ai_behavior_filter: default_ai_behavior_filter(),
+               fast_forward_frames: default_fast_forward_frames(),
+               shift_step_frames: default_shift_step_frames(),
+               playback_speed: PlaybackSpeed::Normal,
+               reference_route: None,
+               capture_folder: None,
+               detail_max_rows: default_detail_max_rows(),
+               detail_layouts: Vec::new(),
+               distance_unit: DistanceUnit::default(),
+               angle_unit: AngleUnit::default(),
                object_settings: enum_map! {
                     ObjectType::Floor => ObjectSettings::fill(Color32::from_rgb(0xa4, 0x4d, 0x68)),
                     ObjectType::Collider => ObjectSettings::stroke(Color32::from_rgb(0x63, 0xb3, 0x4d)),
@@ -219,7 +575,6 @@ impl Default for Config {
                     ObjectType::AiTacticZone => ObjectSettings::fill(Color32::from_rgba_unmultiplied(0x5e, 0x03, 0xfc, 0xb0)),
                     ObjectType::AiHitZone => ObjectSettings::fill(Color32::from_rgba_unmultiplied(0x4a, 0x04, 0x2e, 0xb0)),
                     ObjectType::WeaponRange => ObjectSettings::stroke(Color32::from_rgba_unmultiplied(41, 0, 188, 128)),
-                    ObjectType::CharacterPath => ObjectSettings::stroke(Color32::from_rgba_unmultiplied(0x57, 0xe9, 0x64, 0x80)),
                },
           }
      }
@@ -0,0 +1,137 @@
+use std::io::Cursor;
+
+use binrw::BinWrite;
+use enum_map::{Enum, EnumMap};
+use re2shared::record::{CharacterDiff, CharacterField, FrameRecord, GameField};
+
+use crate::record::Recording;
+
+// one minute of game time
+pub const DEFAULT_BUCKET_FRAMES: usize = 1800;
+// IGT, roll count, and the three change counts
+const FRAME_HEADER_SIZE: usize = 10;
+// index and change count
+const DIFF_HEADER_SIZE: usize = 2;
+
+#[derive(Debug, Enum, PartialEq, Eq, Clone, Copy)]
+pub enum FieldFamily {
+    FrameHeaders,
+    Rng,
+    GameFields,
+    Characters,
+    CharacterParts,
+    ModelParts,
+    Objects,
+}
+
+impl FieldFamily {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::FrameHeaders => "Frame headers",
+            Self::Rng => "RNG rolls",
+            Self::GameFields => "Other game fields",
+            Self::Characters => "Characters",
+            Self::CharacterParts => "Character parts",
+            Self::ModelParts => "Model part transforms",
+            Self::Objects => "Objects",
+        }
+    }
+
+    fn of_game_field(field: &GameField) -> Self {
+        match field {
+            GameField::RngRoll(..) | GameField::KnownRng { .. } | GameField::CharacterRng { .. } | GameField::ScriptRng(_) => Self::Rng,
+            _ => Self::GameFields,
+        }
+    }
+
+    fn of_character_field(field: &CharacterField) -> Self {
+        match field {
+            CharacterField::PartTranslation(..) | CharacterField::PartSize(..) | CharacterField::PartOffset(..) => Self::CharacterParts,
+            CharacterField::ModelPartTransform(..) => Self::ModelParts,
+            _ => Self::Characters,
+        }
+    }
+}
+
+fn encoded_size<T: for<'a> BinWrite<Args<'a> = ()>>(value: &T) -> usize {
+    let mut buf = Cursor::new(Vec::new());
+    match value.write_le(&mut buf) {
+        Ok(_) => buf.into_inner().len(),
+        Err(_) => 0,
+    }
+}
+
+/// Bytes contributed by each family of fields over a span of frames
+#[derive(Debug, Clone, Default)]
+pub struct SizeBucket {
+    pub start_frame: usize,
+    pub num_frames: usize,
+    pub bytes: EnumMap<FieldFamily, usize>,
+}
+
+impl SizeBucket {
+    fn add_frame(&mut self, frame: &FrameRecord) {
+        self.num_frames += 1;
+        self.bytes[FieldFamily::FrameHeaders] += FRAME_HEADER_SIZE;
+        for field in &frame.game_changes {
+            self.bytes[FieldFamily::of_game_field(field)] += encoded_size(field);
+        }
+        self.add_diffs(&frame.character_diffs, false);
+        self.add_diffs(&frame.object_diffs, true);
+    }
+
+    fn add_diffs(&mut self, diffs: &[CharacterDiff], is_object: bool) {
+        for diff in diffs {
+            let header_family = if is_object { FieldFamily::Objects } else { FieldFamily::Characters };
+            self.bytes[header_family] += DIFF_HEADER_SIZE;
+            for field in &diff.changes {
+                // model parts are broken out for objects as well, since they're the same decision in the recorder
+                let family = match FieldFamily::of_character_field(field) {
+                    FieldFamily::ModelParts => FieldFamily::ModelParts,
+                    _ if is_object => FieldFamily::Objects,
+                    family => family,
+                };
+                self.bytes[family] += encoded_size(field);
+            }
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.bytes.values().sum()
+    }
+}
+
+/// Where the bytes in a recording go, overall and over time
+#[derive(Debug, Clone, Default)]
+pub struct SizeReport {
+    pub total: SizeBucket,
+    pub buckets: Vec<SizeBucket>,
+}
+
+impl SizeReport {
+    /// Sizes are measured by re-encoding the frames, so they exclude the file header and reflect the
+    /// current encoding rather than whatever version wrote the file
+    pub fn analyze(recording: &Recording, bucket_frames: usize) -> Self {
+        let bucket_frames = bucket_frames.max(1);
+        let mut report = Self::default();
+        for (i, frame) in recording.frames().iter().enumerate() {
+            if i % bucket_frames == 0 {
+                report.buckets.push(SizeBucket { start_frame: i, ..SizeBucket::default() });
+            }
+
+            report.total.add_frame(frame);
+            if let Some(bucket) = report.buckets.last_mut() {
+                bucket.add_frame(frame);
+            }
+        }
+
+        report
+    }
+
+    pub fn bytes_per_frame(&self) -> f32 {
+        if self.total.num_frames == 0 {
+            return 0.0;
+        }
+        self.total.total() as f32 / self.total.num_frames as f32
+    }
+}
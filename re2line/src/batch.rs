@@ -0,0 +1,168 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+use residat::re2::{CharacterId, Item, Rdt};
+
+use crate::aot::EntityForm;
+use crate::app::{App, RoomId};
+use crate::inspect::read_rdt_as;
+use crate::rdt::{RdtExt, RdtFormat};
+use crate::record::Recording;
+
+pub const BATCH_USAGE: &str = "usage: re2line --batch <game folder> <analysis> <argument>
+       re2line --batch verify <recording>
+
+analyses:
+  enemy <id or name>    rooms that spawn the given enemy type
+  doors-to <room ID>    doors leading to the given room, e.g. 1010
+  item <id or name>     item pickups of the given item
+
+verify checks a recording's checksums and lists any frames that are damaged or not covered by a
+checksum. It exits with 0 if the recording is intact and 1 otherwise.";
+
+/// A question to ask of every room in the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchQuery {
+    Enemy(CharacterId),
+    DoorsTo(RoomId),
+    Item(u16),
+}
+
+/// Parse a number in decimal or, with a 0x prefix, hex
+fn parse_id(s: &str) -> Option<u16> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+impl BatchQuery {
+    pub fn parse(analysis: &str, argument: &str) -> Result<Self> {
+        Ok(match analysis {
+            "enemy" => {
+                let id = match parse_id(argument) {
+                    Some(id) => CharacterId::try_from(id as u8).ok(),
+                    None => (0..=u8::MAX)
+                        .filter_map(|id| CharacterId::try_from(id).ok())
+                        .find(|id| id.name().eq_ignore_ascii_case(argument)),
+                };
+                Self::Enemy(id.ok_or_else(|| anyhow!("Unknown enemy {}", argument))?)
+            }
+            "doors-to" => Self::DoorsTo(argument.parse()?),
+            "item" => {
+                let id = parse_id(argument).or_else(|| {
+                    (0..=u8::MAX as u16).find(|id| Item::name_from_id(*id).eq_ignore_ascii_case(argument))
+                });
+                Self::Item(id.ok_or_else(|| anyhow!("Unknown item {}", argument))?)
+            }
+            _ => bail!("Unknown analysis {}", analysis),
+        })
+    }
+
+    /// A line of the results table for each match in the room
+    fn matches(&self, rdt: &Rdt) -> Vec<String> {
+        let mut matches = Vec::new();
+        match self {
+            Self::Enemy(id) => {
+                let count = rdt.get_enemy_ids().into_iter().filter(|enemy| enemy == id).count();
+                if count > 0 {
+                    matches.push(format!("{} x{}", id.name(), count));
+                }
+            }
+            Self::DoorsTo(target) => {
+                for entity in rdt.get_entities() {
+                    if let EntityForm::Door { next_stage, next_room, next_n_floor, .. } = *entity.form()
+                        && next_stage == target.stage && next_room == target.room {
+                        matches.push(format!("Door AOT {} (target floor {})", entity.id(), next_n_floor));
+                    }
+                }
+            }
+            Self::Item(item_id) => {
+                for entity in rdt.get_entities() {
+                    if let EntityForm::Item { i_item, n_item, flag, .. } = *entity.form() && i_item == *item_id {
+                        matches.push(format!("{} x{} (AOT {}, flag {})", Item::name_from_id(i_item), n_item, entity.id(), flag));
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+/// Every room file in the game folder, for both characters
+fn game_rooms(dir: &Path) -> Result<Vec<(PathBuf, RoomId)>> {
+    let mut rooms = Vec::new();
+    for entry in dir.read_dir()? {
+        let entry = entry?;
+        let lc_name = entry.file_name().to_string_lossy().to_lowercase();
+        if lc_name == "pl0" || lc_name == "pl1" {
+            App::enumerate_rdts(entry.path(), &mut rooms)?;
+        }
+    }
+
+    if rooms.is_empty() {
+        bail!("Invalid game directory could not find RDT files");
+    }
+    rooms.sort_by(|a, b| a.1.cmp(&b.1));
+
+    Ok(rooms)
+}
+
+/// Run the query against every room in the game and return the room and description of each match
+pub fn run_batch(dir: &Path, query: BatchQuery) -> Result<Vec<(RoomId, String)>> {
+    let mut results = Vec::new();
+    for (path, room_id) in game_rooms(dir)? {
+        let (rdt, _) = match read_rdt_as(room_id, &std::fs::read(&path)?, RdtFormat::Re2) {
+            Ok(rdt) => rdt,
+            Err(e) => {
+                eprintln!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        results.extend(query.matches(&rdt).into_iter().map(|description| (room_id, description)));
+    }
+
+    Ok(results)
+}
+
+/// Check the recording's checksums and print what was found. Returns whether the recording is intact.
+pub fn verify_recording(path: &Path) -> Result<bool> {
+    let recording = Recording::read(File::open(path)?)?;
+    let integrity = recording.integrity();
+
+    for (i, session) in recording.sessions().iter().enumerate().filter(|(_, s)| !s.is_closed) {
+        println!("{} was interrupted; {} frames salvaged", session.name(i), session.frames.len());
+    }
+    for damage in &integrity.damage {
+        println!("Bytes {}-{} couldn't be read; resumed at frame {}", damage.bytes.start, damage.bytes.end, damage.frame_index);
+    }
+
+    if !integrity.is_checksummed {
+        println!("Recording was made without checksums and can't be verified");
+        return Ok(false);
+    }
+
+    println!("Verified frames: {}", integrity.verified_frames);
+    println!("Unverified frames: {}", integrity.unverified_frames());
+    for mismatch in &integrity.mismatches {
+        println!("Checksum mismatch: frames {}-{}", mismatch.start, mismatch.end.saturating_sub(1));
+    }
+    for unverified in &integrity.unverified {
+        println!("No checksum: frames {}-{}", unverified.start, unverified.end.saturating_sub(1));
+    }
+
+    let is_intact = integrity.is_intact();
+    println!("{}", if is_intact { "Intact" } else { "Corrupted or truncated" });
+    Ok(is_intact)
+}
+
+pub fn print_batch_results(results: &[(RoomId, String)]) {
+    println!("Room\tMatch");
+    for (room_id, description) in results {
+        println!("{}\t{}", room_id, description);
+    }
+    println!("{} match(es)", results.len());
+}
@@ -0,0 +1,204 @@
+use std::fs::File;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use re2shared::record::GameField;
+use re2shared::rng::RollType;
+use residat::re2::CharacterId;
+
+use crate::app::RoomId;
+use crate::record::{DoorTimes, Recording};
+use crate::rng::ROLL_DESCRIPTIONS;
+
+// rolls where an enemy decides what to do next, as opposed to rolls for stats or cosmetics
+pub const BEHAVIOR_ROLLS: [RollType; 25] = [
+    RollType::ZombieLunge25,
+    RollType::ZombieLunge50,
+    RollType::ZombieLunge50NotZero,
+    RollType::ZombieRaiseArms,
+    RollType::ZombieKnockdown25,
+    RollType::ZombieKnockdown87,
+    RollType::ZombieKnockdown93,
+    RollType::LickerJump25,
+    RollType::LickerJump37,
+    RollType::LickerJump62,
+    RollType::LickerLick50,
+    RollType::LickerConsiderAttack,
+    RollType::LickerSlash25,
+    RollType::LickerSlash50,
+    RollType::LickerThreatened50,
+    RollType::LickerLickOrJump50,
+    RollType::LickerJump75Lick25,
+    RollType::LickerRecoil25,
+    RollType::LickerJump50LowHealth,
+    RollType::SpiderPoison3In32,
+    RollType::SpiderPursue50,
+    RollType::G2Swipe50,
+    RollType::G2Slash75,
+    RollType::G2Thrust25,
+    RollType::SherryLegDrop,
+];
+
+// fewer rolls than this are too noisy to say anything about the documented odds
+pub const MIN_ROLLS_FOR_DEVIATION: usize = 20;
+// deviations past this many standard errors suggest the documented odds are wrong
+pub const SUSPICIOUS_DEVIATION: f32 = 3.0;
+
+/// How often one outcome came up compared to how often it should
+#[derive(Debug, Clone)]
+pub struct OutcomeFrequency {
+    pub outcome: String,
+    pub count: usize,
+    pub expected: f32,
+}
+
+impl OutcomeFrequency {
+    pub fn observed(&self, total: usize) -> f32 {
+        if total == 0 {
+            0.0
+        } else {
+            self.count as f32 / total as f32
+        }
+    }
+
+    /// How many standard errors the observed frequency is from the expected one
+    pub fn deviation(&self, total: usize) -> Option<f32> {
+        if total < MIN_ROLLS_FOR_DEVIATION || self.expected <= 0.0 || self.expected >= 1.0 {
+            return None;
+        }
+
+        let standard_error = (self.expected * (1.0 - self.expected) / total as f32).sqrt();
+        Some((self.observed(total) - self.expected) / standard_error)
+    }
+}
+
+/// Every outcome of one behavior roll made by one enemy slot in one room
+#[derive(Debug, Clone)]
+pub struct BehaviorTally {
+    pub room_id: RoomId,
+    pub char_index: usize,
+    pub character_id: CharacterId,
+    pub roll_type: RollType,
+    pub total: usize,
+    pub outcomes: Vec<OutcomeFrequency>,
+}
+
+impl BehaviorTally {
+    fn new(room_id: RoomId, char_index: usize, character_id: CharacterId, roll_type: RollType) -> Self {
+        // start from every possible outcome so ones that never came up still show
+        let outcomes = ROLL_DESCRIPTIONS[roll_type].cycle_distribution()
+            .into_iter()
+            .map(|(outcome, expected)| OutcomeFrequency { outcome, count: 0, expected })
+            .collect();
+
+        Self {
+            room_id,
+            char_index,
+            character_id,
+            roll_type,
+            total: 0,
+            outcomes,
+        }
+    }
+
+    fn add(&mut self, outcome: String) {
+        self.total += 1;
+        match self.outcomes.iter_mut().find(|o| o.outcome == outcome) {
+            Some(frequency) => frequency.count += 1,
+            None => self.outcomes.push(OutcomeFrequency { outcome, count: 1, expected: 0.0 }),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        ROLL_DESCRIPTIONS[self.roll_type].label(&format!("#{} {}", self.char_index, self.character_id.name()))
+    }
+
+    /// The largest deviation of any outcome from its expected frequency
+    pub fn max_deviation(&self) -> Option<f32> {
+        self.outcomes.iter()
+            .filter_map(|o| o.deviation(self.total))
+            .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+    }
+}
+
+/// Behavior roll outcomes for every enemy across a set of recordings
+#[derive(Debug, Clone, Default)]
+pub struct BehaviorReport {
+    pub tallies: Vec<BehaviorTally>,
+    pub num_recordings: usize,
+}
+
+impl BehaviorReport {
+    pub fn build(recording_paths: Vec<PathBuf>, door_times: &mut DoorTimes) -> Result<Self> {
+        let mut report = Self::default();
+        for path in recording_paths {
+            let mut recording = Recording::read(File::open(&path)?)?;
+            door_times.add_recording(&path, &recording);
+            report.add_recording(&mut recording);
+        }
+
+        report.tallies.sort_by(|a, b| {
+            a.room_id.cmp(&b.room_id)
+                .then(a.char_index.cmp(&b.char_index))
+                .then_with(|| a.label().cmp(&b.label()))
+        });
+
+        Ok(report)
+    }
+
+    /// Tally the behavior rolls made in the recording. The recording's current frame will be
+    /// changed, so the caller is responsible for restoring it if necessary.
+    pub fn add_recording(&mut self, recording: &mut Recording) {
+        self.num_recordings += 1;
+
+        let frames: Vec<(usize, Vec<(usize, RollType, u16)>)> = recording.frames()
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let rolls: Vec<_> = frame.game_changes.iter().filter_map(|change| match change {
+                    GameField::CharacterRng { char_index, roll_type, start_value } if BEHAVIOR_ROLLS.contains(roll_type) => {
+                        Some((*char_index as usize, *roll_type, *start_value))
+                    }
+                    _ => None,
+                }).collect();
+                (i, rolls)
+            })
+            .filter(|(_, rolls)| !rolls.is_empty())
+            .collect();
+
+        // frames are visited in order so each room visit's states only get built once
+        for (frame_index, rolls) in frames {
+            let Some(state) = recording.set_index(frame_index) else {
+                continue;
+            };
+
+            let room_id = state.room_id();
+            for (char_index, roll_type, start_value) in rolls {
+                let Some(Some(character)) = state.characters().get(char_index) else {
+                    continue;
+                };
+                let Some(outcome) = ROLL_DESCRIPTIONS[roll_type].outcome(start_value) else {
+                    continue;
+                };
+
+                let character_id = character.id;
+                let tally = match self.tallies.iter().position(|t| {
+                    t.room_id == room_id && t.char_index == char_index && t.character_id == character_id && t.roll_type == roll_type
+                }) {
+                    Some(i) => &mut self.tallies[i],
+                    None => {
+                        self.tallies.push(BehaviorTally::new(room_id, char_index, character_id, roll_type));
+                        self.tallies.last_mut().unwrap()
+                    }
+                };
+                tally.add(outcome);
+            }
+        }
+    }
+
+    pub fn rooms(&self) -> Vec<RoomId> {
+        let mut rooms: Vec<_> = self.tallies.iter().map(|t| t.room_id).collect();
+        rooms.dedup();
+        rooms
+    }
+}
@@ -0,0 +1,54 @@
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A frame the user marked while reviewing a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub frame_index: usize,
+    pub name: String,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl Bookmark {
+    pub const fn new(frame_index: usize, name: String) -> Self {
+        Self {
+            frame_index,
+            name,
+            note: String::new(),
+        }
+    }
+
+    /// The file next to the recording that its bookmarks are kept in
+    pub fn sidecar_path(recording_path: &Path) -> PathBuf {
+        recording_path.with_extension("bookmarks.json")
+    }
+
+    /// Read the recording's bookmarks, if it has any
+    pub fn load(recording_path: &Path) -> Result<Vec<Self>> {
+        let file = match File::open(Self::sidecar_path(recording_path)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut bookmarks: Vec<Self> = serde_json::from_reader(file)?;
+        bookmarks.sort_by_key(|b| b.frame_index);
+        Ok(bookmarks)
+    }
+
+    pub fn save(bookmarks: &[Self], recording_path: &Path) -> Result<()> {
+        let path = Self::sidecar_path(recording_path);
+        // don't leave empty files lying around next to recordings that were never bookmarked
+        if bookmarks.is_empty() && !path.exists() {
+            return Ok(());
+        }
+
+        serde_json::to_writer_pretty(File::create(path)?, bookmarks)?;
+        Ok(())
+    }
+}
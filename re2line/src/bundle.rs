@@ -0,0 +1,100 @@
+use std::io::{Cursor, Write, Seek};
+
+use anyhow::{bail, Result};
+use binrw::{binrw, BinReaderExt, BinWriterExt};
+
+use crate::app::RoomId;
+
+pub const BUNDLE_EXTENSION: &str = "r2lb";
+const BUNDLE_VERSION: u16 = 1;
+
+#[binrw]
+#[derive(Debug)]
+struct BundledRoom {
+    stage: u8,
+    room: u8,
+    player: u8,
+    #[bw(calc = data.len() as u32)]
+    size: u32,
+    #[br(count = size)]
+    data: Vec<u8>,
+}
+
+#[binrw]
+#[brw(little, magic = b"R2LB")]
+#[derive(Debug)]
+struct BundleFile {
+    version: u16,
+    #[bw(calc = rooms.len() as u32)]
+    num_rooms: u32,
+    #[br(count = num_rooms)]
+    rooms: Vec<BundledRoom>,
+    #[bw(calc = recording.len() as u32)]
+    recording_size: u32,
+    #[br(count = recording_size)]
+    recording: Vec<u8>,
+}
+
+/// A recording packaged with the rooms it visits, so it can be viewed without the game files
+#[derive(Debug, Default)]
+pub struct Bundle {
+    pub rooms: Vec<(RoomId, Vec<u8>)>,
+    pub recording: Vec<u8>,
+}
+
+impl Bundle {
+    pub fn read(data: &[u8]) -> Result<Self> {
+        let file: BundleFile = Cursor::new(data).read_le()?;
+        if file.version != BUNDLE_VERSION {
+            bail!("Unsupported bundle version {}", file.version);
+        }
+
+        Ok(Self {
+            rooms: file.rooms.into_iter().map(|r| (RoomId::new(r.stage, r.room, r.player), r.data)).collect(),
+            recording: file.recording,
+        })
+    }
+
+    pub fn write(self, mut f: impl Write + Seek) -> Result<()> {
+        let file = BundleFile {
+            version: BUNDLE_VERSION,
+            rooms: self.rooms
+                .into_iter()
+                .map(|(id, data)| BundledRoom { stage: id.stage, room: id.room, player: id.player, data })
+                .collect(),
+            recording: self.recording,
+        };
+        f.write_le(&file)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let bundle = Bundle {
+            rooms: vec![(RoomId::new(1, 0, 0), vec![1, 2, 3]), (RoomId::new(2, 0x10, 1), Vec::new())],
+            recording: vec![4, 5, 6, 7],
+        };
+
+        let mut f = Cursor::new(Vec::new());
+        bundle.write(&mut f).unwrap();
+        let bundle = Bundle::read(&f.into_inner()).unwrap();
+
+        assert_eq!(bundle.rooms, vec![(RoomId::new(1, 0, 0), vec![1, 2, 3]), (RoomId::new(2, 0x10, 1), Vec::new())]);
+        assert_eq!(bundle.recording, vec![4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_bad_version() {
+        let mut data = Vec::new();
+        Bundle::default().write(Cursor::new(&mut data)).unwrap();
+        // version immediately follows the magic
+        data[4] = 0xFF;
+        assert!(Bundle::read(&data).is_err());
+    }
+}
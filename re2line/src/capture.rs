@@ -0,0 +1,59 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use anyhow::Result;
+use egui::ColorImage;
+
+use crate::app::RoomId;
+
+/// What gets stamped onto a captured analysis frame
+#[derive(Debug, Clone)]
+pub struct CaptureInfo {
+    pub room_id: RoomId,
+    pub frame_index: Option<usize>,
+    pub igt: Option<String>,
+    /// Details of the selected object, if any
+    pub details: Vec<(String, Vec<String>)>,
+}
+
+impl CaptureInfo {
+    /// Captures are named by room, then frame and IGT, so a folder of them sorts in route order
+    pub fn file_name(&self) -> String {
+        match (self.frame_index, &self.igt) {
+            (Some(frame_index), Some(igt)) => format!("{}_f{:06}_{}.png", self.room_id, frame_index, igt.replace(':', "-")),
+            (Some(frame_index), None) => format!("{}_f{:06}.png", self.room_id, frame_index),
+            _ => format!("{}.png", self.room_id),
+        }
+    }
+
+    pub fn annotation(&self) -> String {
+        let mut lines = vec![format!("Room {}", self.room_id)];
+        if let Some(frame_index) = self.frame_index {
+            lines.push(format!("Frame {}", frame_index));
+        }
+        if let Some(ref igt) = self.igt {
+            lines.push(format!("IGT {}", igt));
+        }
+
+        for (group, fields) in &self.details {
+            lines.push(String::new());
+            lines.push(group.clone());
+            lines.extend(fields.iter().map(|field| format!("  {field}")));
+        }
+
+        lines.join("\n")
+    }
+
+    pub fn write_png(image: &ColorImage, path: impl AsRef<Path>) -> Result<()> {
+        let [width, height] = image.size;
+        let f = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(f, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data: Vec<u8> = image.pixels.iter().flat_map(|pixel| pixel.to_srgba_unmultiplied()).collect();
+        encoder.write_header()?.write_image_data(&data)?;
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use residat::re2::{CharacterId, Item, MAX_PARTS};
 use crate::app::{DrawParams, Floor, GameObject, ObjectType, WorldPos};
 use crate::collision::{CapsuleType, EllipseCollider, Motion, RectCollider};
 use crate::record::State;
+use crate::units::{Angle, Distance};
 
 mod ai;
 pub use ai::*;
@@ -28,6 +29,8 @@ const POINT_RADIUS: f32 = 3.0;
 
 const SLOW_COLOR: Color32 = Color32::from_rgba_premultiplied(255, 0, 0, 255);
 const FAST_COLOR: Color32 = Color32::from_rgba_premultiplied(0, 255, 0, 255);
+const LOCKED_COLOR: Color32 = Color32::from_rgba_premultiplied(128, 128, 128, 255);
+const MIN_PATH_FADE: f32 = 0.1;
 
 const CHARACTER_COLLISION_DENY: u16 = 0x100;
 
@@ -195,7 +198,7 @@ impl GameObject for Object {
     }
 
     fn description(&self) -> String {
-        format!("X: {} | Z: {}", self.center.x, self.center.z)
+        format!("X: {} | Z: {}", Distance(self.center.x), Distance(self.center.z))
     }
 
     fn details(&self) -> Vec<(String, Vec<String>)> {
@@ -206,11 +209,11 @@ impl GameObject for Object {
         ]));
 
         groups.push((String::from("Position"), vec![
-            format!("X: {}", self.center().x),
-            format!("Y: {}", self.center_3d().y),
-            format!("Z: {}", self.center().z),
-            format!("XR: {}", self.size.x),
-            format!("ZR: {}", self.size.z),
+            format!("X: {}", Distance(self.center().x)),
+            format!("Y: {}", Distance(self.center_3d().y)),
+            format!("Z: {}", Distance(self.center().z)),
+            format!("XR: {}", Distance(self.size.x)),
+            format!("ZR: {}", Distance(self.size.z)),
             format!("Floor: {}", self.floor),
         ]));
 
@@ -254,6 +257,11 @@ impl Part {
         self.pos = pos.into();
     }
 
+    /// Radius of the part in the XZ plane for character-vs-character collision
+    pub const fn size_offset(&self) -> UFixed16 {
+        self.size_offset
+    }
+
     pub fn set_size(&mut self, x: impl Into<Fixed32>, y: impl Into<Fixed32>, z: impl Into<Fixed32>, offset: impl Into<UFixed16>) {
         self.size = Vec3::new(x, y, z);
         self.size_offset = offset.into();
@@ -286,6 +294,7 @@ pub struct Character {
     pub type_: u8,
     pub index: usize,
     water_level: Fixed32,
+    pub motion: Option<i16>,
 }
 
 impl Character {
@@ -318,6 +327,7 @@ impl Character {
             type_: 0,
             index: usize::MAX,
             water_level: Fixed32(0),
+            motion: None,
         }
     }
 
@@ -503,6 +513,15 @@ impl Character {
         )
     }
 
+    /// Motion for this character moving from one arbitrary point to another
+    pub fn motion_between(&self, from: Vec2, to: Vec2) -> Motion {
+        Motion::new(
+            WorldPos::new(from, self.size, self.floor, self.collision_mask(), CHARACTER_COLLISION_DENY),
+            to,
+            self.part_offset(),
+        )
+    }
+
     pub fn apply_motion(&mut self, motion: &Motion) {
         let Some(part) = self.parts[0].as_mut() else {
             self.center.x = motion.to.x;
@@ -539,6 +558,10 @@ impl Character {
         )
     }
 
+    pub const fn is_pushing_enemy(&self) -> bool {
+        self.id.is_player() && matches!(self.state, [0x05, 0x00, 0x05, _])
+    }
+
     pub fn apply_velocity(&mut self) {
         let directed_velocity = Vec3::from(self.velocity.rotate_y(self.angle));
         let motion_center = self.prev_center + directed_velocity;
@@ -772,7 +795,7 @@ impl Character {
         self.id.is_zombie() && matches!(self.type_ & 0x3f, 1 | 3 | 5 | 7 | 9 | 11 | 13)
     }
 
-    fn describe_state(&self) -> String {
+    pub fn describe_state(&self) -> String {
         String::from(if self.is_crawling_zombie() {
             describe_crawling_zombie_ai_state(&self.state)
         } else if self.id.is_zombie() {
@@ -792,6 +815,30 @@ impl Character {
         })
     }
 
+    /// Whether the enemy is chasing or attacking the player, for enemies that can lose track of the
+    /// player. None if the enemy never gives up or its current state doesn't say either way (e.g.
+    /// it's being hit).
+    pub fn is_pursuing(&self) -> Option<bool> {
+        if self.state[0] != 0x01 || self.is_crawling_zombie() {
+            return None;
+        }
+
+        let substate = self.state[1];
+        Some(if self.id.is_licker() {
+            // idle and listening
+            !matches!(substate, 0x00 | 0x0E)
+        } else if self.id.is_zombie() {
+            // idle, wandering, and eating
+            !matches!(substate, 0x00 | 0x08)
+        } else if self.id == CharacterId::Dog {
+            substate != 0x00
+        } else if self.id == CharacterId::Spider {
+            !matches!(substate, 0x00 | 0x09)
+        } else {
+            return None;
+        })
+    }
+
     pub fn ai_zones(&self) -> Vec<PositionedAiZone> {
         let ai_zones = match self.id {
             CharacterId::LickerRed => &RED_LICKER_AI_ZONES[..],
@@ -856,9 +903,10 @@ impl GameObject for Character {
 
     fn description(&self) -> String {
         format!(
-            "State: {:02X} {:02X} {:02X} {:02X}\nHP: {}/{}",
+            "State: {:02X} {:02X} {:02X} {:02X}\nHP: {}/{}\nAngle: {}",
             self.state[0], self.state[1], self.state[2], self.state[3],
             self.current_health, self.max_health,
+            Angle(self.angle.0 % 0x1000).both(),
         )
     }
 
@@ -876,26 +924,31 @@ impl GameObject for Character {
         ]));
 
         groups.push((String::from("Position"), vec![
-            format!("X: {}", self.center.x),
-            format!("Y: {}", self.center.y),
-            format!("Z: {}", self.center.z),
-            format!("Angle: {:.1}°", self.angle.to_degrees() % 360.0),
+            format!("X: {}", Distance(self.center.x)),
+            format!("Y: {}", Distance(self.center.y)),
+            format!("Z: {}", Distance(self.center.z)),
+            format!("Angle: {}", Angle(self.angle.0 % 0x1000)),
             format!("Floor: {}", self.floor),
-            format!("XR: {}", self.size.x),
-            format!("ZR: {}", self.size.z),
+            format!("XR: {}", Distance(self.size.x)),
+            format!("ZR: {}", Distance(self.size.z)),
         ]));
 
         groups.push((String::from("Velocity"), vec![
-            format!("X: {}", self.velocity.x),
-            format!("Z: {}", self.velocity.z),
-            format!("Base: {}", self.velocity.len()),
-            format!("Effective: {}", (self.center - self.prev_center).len()),
+            format!("X: {}", Distance(self.velocity.x)),
+            format!("Z: {}", Distance(self.velocity.z)),
+            format!("Base: {}", Distance(self.velocity.len())),
+            format!("Effective: {}", Distance((self.center - self.prev_center).len())),
         ]));
 
-        groups.push((String::from("State"), vec![
+        let mut state = vec![
             format!("{:02X} {:02X} {:02X} {:02X}", self.state[0], self.state[1], self.state[2], self.state[3]),
             self.describe_state(),
-        ]));
+        ];
+        // only recorded in verbose recordings
+        if let Some(motion) = self.motion {
+            state.push(format!("Motion: {}", motion));
+        }
+        groups.push((String::from("State"), state));
 
         groups
     }
@@ -971,15 +1024,51 @@ impl GameObject for Character {
 #[derive(Debug, Clone)]
 pub struct CharacterPath {
     pub points: Vec<Vec2>,
+    pub locked: Vec<bool>,
     pub character_id: CharacterId,
     pub floor: Floor,
     pub limit: usize,
     pub dynamic_color: bool,
+    pub fade_center: Option<usize>,
 }
 
 impl CharacterPath {
     pub const fn new(points: Vec<Vec2>, character_id: CharacterId, floor: Floor) -> Self {
-        Self { points, character_id, floor, limit: usize::MAX, dynamic_color: true }
+        Self { points, locked: Vec::new(), character_id, floor, limit: usize::MAX, dynamic_color: true, fade_center: None }
+    }
+
+    pub fn with_fade(mut self, fade_center: usize) -> Self {
+        self.fade_center = Some(fade_center);
+        self
+    }
+
+    fn fade_factor(&self, i: usize) -> f32 {
+        let Some(center) = self.fade_center else {
+            return 1.0;
+        };
+
+        // fade out towards each end of the path independently so the current position is always
+        // fully opaque
+        let (distance, side_len) = if i <= center {
+            (center - i, center)
+        } else {
+            (i - center, self.points.len().saturating_sub(center + 1))
+        };
+
+        if side_len == 0 {
+            return 1.0;
+        }
+
+        (1.0 - distance as f32 / side_len as f32).max(MIN_PATH_FADE)
+    }
+
+    pub fn with_locked(mut self, locked: Vec<bool>) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    pub fn is_locked(&self, i: usize) -> bool {
+        self.locked.get(i).copied().unwrap_or(false)
     }
 
     pub fn len(&self) -> Fixed32 {
@@ -987,7 +1076,13 @@ impl CharacterPath {
     }
 
     pub fn max_speed(&self) -> Fixed32 {
-        self.points.windows(2).fold(Fixed32(0), |acc, p| acc.max((p[1] - p[0]).len()))
+        // movement during cutscenes and other control locks isn't representative of the
+        // character's actual speed, so leave it out
+        self.points
+            .windows(2)
+            .enumerate()
+            .filter(|(i, _)| !self.is_locked(i + 1))
+            .fold(Fixed32(0), |acc, (_, p)| acc.max((p[1] - p[0]).len()))
     }
 
     pub const fn frames(&self) -> usize {
@@ -1014,7 +1109,7 @@ impl GameObject for CharacterPath {
     }
 
     fn description(&self) -> String {
-        format!("Frames: {} | Length: {}", self.points.len(), self.len())
+        format!("Frames: {} | Length: {}", self.points.len(), Distance(self.len()))
     }
 
     fn details(&self) -> Vec<(String, Vec<String>)> {
@@ -1022,7 +1117,7 @@ impl GameObject for CharacterPath {
 
         groups.push((String::from("Path"), vec![
             format!("Frames: {}", self.points.len()),
-            format!("Length: {}", self.len()),
+            format!("Length: {}", Distance(self.len())),
         ]));
 
         groups
@@ -1036,7 +1131,7 @@ impl GameObject for CharacterPath {
         let max_speed = self.max_speed().to_f32();
         let mut shapes = Vec::new();
 
-        for segment in self.initial_segment().windows(2) {
+        for (i, segment) in self.initial_segment().windows(2).enumerate() {
             let start = segment[0];
             let end = segment[1];
             let speed = (end - start).len().to_f32();
@@ -1049,12 +1144,18 @@ impl GameObject for CharacterPath {
             let gui_end = params.transform_point(end);
 
             let mut stroke = params.stroke.clone();
-            if self.dynamic_color {
-                let t = speed / max_speed;
+            if self.dynamic_color && self.is_locked(i + 1) {
+                stroke.color = LOCKED_COLOR.gamma_multiply_u8(params.color().a());
+            } else if self.dynamic_color {
+                let t = (speed / max_speed).min(1.0);
                 let color = SLOW_COLOR.lerp_to_gamma(FAST_COLOR, t).gamma_multiply_u8(params.color().a());
                 stroke.color = color;
             }
 
+            if self.fade_center.is_some() {
+                stroke.color = stroke.color.gamma_multiply(self.fade_factor(i + 1));
+            }
+
             shapes.push(Shape::line_segment([gui_start, gui_end], stroke));
         }
 
@@ -4,10 +4,15 @@ use egui::{Color32, Shape, Stroke};
 use epaint::{CircleShape, ColorMode, PathShape, PathStroke};
 use residat::common::*;
 use residat::re2::CharacterId;
+use re2shared::rng::RollType;
+use serde::{Deserialize, Serialize};
 
 use crate::app::{DrawParams, Floor, GameObject, ObjectType};
 use crate::draw::*;
 use crate::record::State;
+use crate::rng::ROLL_SUCCESS_CHANCES;
+use super::Character;
+use crate::units::{Angle, Distance};
 
 #[derive(Debug, Clone)]
 pub enum ZoneOrigin {
@@ -34,9 +39,19 @@ impl StateMask {
             Self::Between(value1, value2) => state >= *value1 && state <= *value2,
         }
     }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Any => String::from("any"),
+            Self::Exactly(value) => format!("{value:02X}"),
+            Self::Either(value1, value2) => format!("{value1:02X} or {value2:02X}"),
+            Self::OneOf3(value1, value2, value3) => format!("{value1:02X}, {value2:02X}, or {value3:02X}"),
+            Self::Between(value1, value2) => format!("{value1:02X}-{value2:02X}"),
+        }
+    }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum BehaviorType {
     Aggro,
     Attack,
@@ -45,6 +60,10 @@ pub enum BehaviorType {
 }
 
 impl BehaviorType {
+    pub const fn list() -> [Self; 4] {
+        [Self::Aggro, Self::Attack, Self::ChangeTactic, Self::Hit]
+    }
+
     pub const fn name(&self) -> &'static str {
         match self {
             Self::Aggro => "Aggro",
@@ -53,6 +72,19 @@ impl BehaviorType {
             Self::Hit => "Hit",
         }
     }
+
+    /// How dangerous it is for the player to be standing in a zone of this type, before the odds of
+    /// the zone's rolls are taken into account.
+    ///
+    /// Hit zones mean the attack will connect, while attack zones only mean the enemy may decide
+    /// to start an attack.
+    pub const fn danger_weight(&self) -> f32 {
+        match self {
+            Self::Hit => 1.0,
+            Self::Attack => 0.5,
+            Self::Aggro | Self::ChangeTactic => 0.0,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -67,6 +99,8 @@ pub struct AiZone {
     pub state_mask: [StateMask; 4],
     pub type_mask: StateMask,
     pub origin: ZoneOrigin,
+    /// RNG rolls that decide what the character does while the player is in the zone
+    pub rolls: &'static [RollType],
 }
 
 impl AiZone {
@@ -82,6 +116,7 @@ impl AiZone {
             state_mask,
             type_mask: StateMask::Any,
             origin: ZoneOrigin::Base,
+            rolls: &[],
         }
     }
 
@@ -97,6 +132,7 @@ impl AiZone {
             state_mask,
             type_mask: StateMask::Any,
             origin: ZoneOrigin::Base,
+            rolls: &[],
         }
     }
 
@@ -112,6 +148,7 @@ impl AiZone {
             state_mask,
             type_mask: StateMask::Any,
             origin: ZoneOrigin::Base,
+            rolls: &[],
         }
     }
 
@@ -125,11 +162,30 @@ impl AiZone {
         self
     }
 
+    pub const fn with_rolls(mut self, rolls: &'static [RollType]) -> Self {
+        self.rolls = rolls;
+        self
+    }
+
     pub const fn inverted(mut self) -> Self {
         self.inverted = true;
         self
     }
 
+    /// How likely the character is to act on the zone when the player is inside it. The character
+    /// acts if any of the zone's rolls succeeds, and zones without rolls always act.
+    pub fn trigger_chance(&self) -> f32 {
+        let miss_chance: f32 = self.rolls.iter()
+            .map(|roll_type| 1.0 - ROLL_SUCCESS_CHANCES[*roll_type].unwrap_or(1.0))
+            .product();
+        1.0 - miss_chance
+    }
+
+    /// How dangerous it is for the player to be standing in the zone
+    pub fn danger_weight(&self) -> f32 {
+        self.behavior_type.danger_weight() * self.trigger_chance()
+    }
+
     pub fn gui_shape(&self, angle: Fixed32, pos: Vec2, mut draw_params: DrawParams, state: &State) -> Shape {
         let facing_angle = angle.to_radians();
 
@@ -251,10 +307,10 @@ impl GameObject for PositionedAiZone {
 
     fn description(&self) -> String {
         format!(
-            "Arc: {:.1}° | Angle: {:.1}° | Radius: {}\n{}",
-            self.ai_zone.half_angle.to_degrees() * 2.0,
-            self.angle.to_degrees(),
-            self.ai_zone.radius,
+            "Arc: {} | Angle: {} | Radius: {}\n{}",
+            Angle(self.ai_zone.half_angle.0 as i32 * 2).both(),
+            Angle(self.angle).both(),
+            Distance(self.ai_zone.radius),
             self.ai_zone.description
         )
     }
@@ -264,9 +320,9 @@ impl GameObject for PositionedAiZone {
 
         groups.push((String::from("AI Zone"), vec![
             format!("Behavior: {}", self.ai_zone.behavior_type.name()),
-            format!("Arc: {:.1}°", self.ai_zone.half_angle.to_degrees() * 2.0),
-            format!("Angle: {:.1}°", self.angle.to_degrees()),
-            format!("Radius: {}", self.ai_zone.radius),
+            format!("Arc: {}", Angle(self.ai_zone.half_angle.0 as i32 * 2)),
+            format!("Angle: {}", Angle(self.angle)),
+            format!("Radius: {}", Distance(self.ai_zone.radius)),
             format!("Inverted: {}", self.ai_zone.inverted),
         ]));
 
@@ -478,7 +534,7 @@ pub const G2_AI_ZONES: [AiZone; 35] = [
         Fixed16(0x400),
         UFixed16(3000),
         [StateMask::Exactly(0x01), StateMask::Any, StateMask::Any, StateMask::Any],
-    ).with_type_mask(StateMask::Exactly(0x13)),
+    ).with_type_mask(StateMask::Exactly(0x13)).with_rolls(&[RollType::G2Swipe50]),
     AiZone::arc(
         "Slash",
         "Birkin will slash strongly at you with his claw",
@@ -502,7 +558,7 @@ pub const G2_AI_ZONES: [AiZone; 35] = [
         Fixed16(0x270),
         UFixed16(3400),
         [StateMask::Exactly(0x01), StateMask::Any, StateMask::Any, StateMask::Any],
-    ).with_type_mask(StateMask::Exactly(0x05)),
+    ).with_type_mask(StateMask::Exactly(0x05)).with_rolls(&[RollType::G2Thrust25]),
     AiZone::arc(
         "Retreat",
         "Birkin will retreat a short distance from you",
@@ -534,7 +590,7 @@ pub const G2_AI_ZONES: [AiZone; 35] = [
         Fixed16(0x270),
         UFixed16(3400),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x01), StateMask::Any, StateMask::Any],
-    ).with_type_mask(StateMask::Either(0x00, 0x03)),
+    ).with_type_mask(StateMask::Either(0x00, 0x03)).with_rolls(&[RollType::G2Thrust25]),
     AiZone::arc(
         "Disengagement attack",
         "Birkin will attack if you attempt to run away",
@@ -576,7 +632,7 @@ pub const G2_AI_ZONES: [AiZone; 35] = [
         Fixed16(0x400),
         UFixed16(3000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x05), StateMask::Any, StateMask::Any],
-    ).with_type_mask(StateMask::OneOf3(0x00, 0x01, 0x03)),
+    ).with_type_mask(StateMask::OneOf3(0x00, 0x01, 0x03)).with_rolls(&[RollType::G2Swipe50]),
     AiZone::arc(
         "Attack",
         "Birkin will attack at the sound of a running footstep if you are in fine health",
@@ -631,14 +687,14 @@ pub const G2_AI_ZONES: [AiZone; 35] = [
         BehaviorType::Attack,
         UFixed16(3400),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x03), StateMask::Any, StateMask::Any],
-    ).with_type_mask(StateMask::Either(0x00, 0x03)),
+    ).with_type_mask(StateMask::Either(0x00, 0x03)).with_rolls(&[RollType::G2Slash75]),
     AiZone::circle(
         "Slash",
         "Birkin has a 75% chance to slash at you at the end of his animation",
         BehaviorType::Attack,
         UFixed16(3400),
         [StateMask::Exactly(0x01), StateMask::Any, StateMask::Any, StateMask::Any],
-    ).with_type_mask(StateMask::Exactly(0x11)),
+    ).with_type_mask(StateMask::Exactly(0x11)).with_rolls(&[RollType::G2Slash75]),
     AiZone::circle(
         "Hit",
         "Birkin's attack will hit you",
@@ -710,7 +766,7 @@ pub const SPIDER_AI_ZONES: [AiZone; 8] = [
         Fixed16(0x80),
         UFixed16(6000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x00), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::SpiderPoison3In32]),
     AiZone::circle(
         "Stop pursuit",
         "Spider will stop pursuing its target",
@@ -916,7 +972,7 @@ pub const BLACK_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x300),
         UFixed16(2000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x06), StateMask::Any, StateMask::Any],
-    ).inverted(),
+    ).inverted().with_rolls(&[RollType::LickerRecoil25]),
     AiZone::circle(
         "Pursuit aggro",
         "Licker may attack",
@@ -932,7 +988,7 @@ pub const BLACK_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x600),
         UFixed16(10000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0F), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::LickerJump62]),
     AiZone::arc(
         "Caution jump 37.5%",
         "Licker has a 37.5% chance to jump at you if below fine health",
@@ -940,7 +996,7 @@ pub const BLACK_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x200),
         UFixed16(6500),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0F), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::LickerJump37]),
     AiZone::arc(
         "Jump 37.5%",
         "Licker has a 37.5% chance to jump at you", // <= 100 HP
@@ -948,7 +1004,7 @@ pub const BLACK_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x100),
         UFixed16(6500),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0F), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::LickerJump37]),
     AiZone::arc(
         "Lick 50%",
         "Licker has a 50% chance to lick at you",
@@ -956,7 +1012,7 @@ pub const BLACK_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x200),
         UFixed16(4500),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0F), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::LickerLick50]),
     AiZone::arc(
         "Lick",
         "Licker will lick at you",
@@ -971,7 +1027,7 @@ pub const BLACK_LICKER_AI_ZONES: [AiZone; 24] = [
         BehaviorType::Attack,
         UFixed16(2500),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0F), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::LickerSlash25]),
     AiZone::arc(
         "Slash 50%",
         "Licker has a 50% chance to slash at you",
@@ -979,7 +1035,7 @@ pub const BLACK_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x100),
         UFixed16(2500),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0F), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::LickerSlash50]),
 ];
 
 pub const RED_LICKER_AI_ZONES: [AiZone; 24] = [
@@ -1057,7 +1113,7 @@ pub const RED_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x300),
         UFixed16(2000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x06), StateMask::Any, StateMask::Any],
-    ).inverted(),
+    ).inverted().with_rolls(&[RollType::LickerRecoil25]),
     // TODO: implement a minimum radius, as the below zones should have breaks between them
     AiZone::arc(
         "Jump",
@@ -1066,7 +1122,7 @@ pub const RED_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x600),
         UFixed16(10000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0F), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::LickerJump62, RollType::LickerJump25]),
     AiZone::arc(
         "Jump",
         "Licker has a random chance to jump (fine health = 62.5% to jump; lower health = 25% to jump)",
@@ -1074,7 +1130,7 @@ pub const RED_LICKER_AI_ZONES: [AiZone; 24] = [
         Fixed16(0x100),
         UFixed16(6500),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0F), StateMask::Any, StateMask::Any],
-    ),
+    ).with_rolls(&[RollType::LickerJump62, RollType::LickerJump25]),
     AiZone::arc(
         "Lick",
         "Licker will lick at you",
@@ -1184,6 +1240,87 @@ pub const CRAWLING_ZOMBIE_AI_ZONES: [AiZone; 1] = [
     ),
 ];
 
+pub const ZOMBIE_GRAB_ZONE: AiZone = AiZone::arc(
+    "Bite",
+    "Zombie will bite you if you are within this zone",
+    BehaviorType::Hit,
+    Fixed16(0x200),
+    UFixed16(1200),
+    [StateMask::Exactly(0x01), StateMask::Either(0x01, 0x02), StateMask::Any, StateMask::Any],
+);
+
+// how far ahead to project the player's and zombie's movement when predicting grabs
+pub const GRAB_PREDICTION_FRAMES: i32 = 30;
+// how far an enemy pursuing the player is assumed to turn towards them each frame
+const PURSUIT_TURN_RATE: i32 = 0x20;
+
+/// Turn from one angle towards another the shortest way round, by no more than the given amount
+fn turn_toward(angle: Fixed32, target: Fixed32, max_turn: i32) -> Fixed32 {
+    let difference = ((target.0 - angle.0 + 0x800) & 0xfff) - 0x800;
+    Fixed32(angle.0 + difference.clamp(-max_turn, max_turn))
+}
+
+/// The direction the AI model expects an enemy to move on the next frame, given its current state
+/// and where the player is. An enemy that's pursuing the player turns towards them as it moves;
+/// anything else keeps going the way it's facing.
+pub fn predict_enemy_heading(enemy: &Character, player_pos: Option<Vec2>) -> Fixed32 {
+    match (enemy.is_pursuing(), player_pos) {
+        (Some(true), Some(player_pos)) => {
+            let target = enemy.center().angle_between(&player_pos);
+            turn_toward(enemy.angle, target, PURSUIT_TURN_RATE)
+        }
+        _ => enemy.angle,
+    }
+}
+
+/// Whether a zombie will get a grab on the player in the next few frames if both keep moving the
+/// way they are now
+#[derive(Debug, Clone)]
+pub struct GrabPrediction {
+    pub character_index: usize,
+    pub pos: Vec2,
+    pub angle: Fixed32,
+    pub are_arms_raised: bool,
+    /// First frame from now that the player would be in the grab zone
+    pub frames_until_grab: Option<usize>,
+}
+
+impl GrabPrediction {
+    /// Predict a grab for a zombie, or None if the zombie isn't in a state where it can grab
+    pub fn for_zombie(zombie: &Character, player: &Character) -> Option<Self> {
+        if !zombie.id.is_zombie() || zombie.is_crawling_zombie() || !zombie.is_enabled() {
+            return None;
+        }
+
+        let are_arms_raised = match zombie.state {
+            [0x01, 0x02, _, _] => true,
+            [0x01, 0x01, _, _] => false,
+            // not walking, so it can't grab right now
+            _ => return None,
+        };
+
+        let player_velocity = player.velocity.rotate_y(player.angle);
+        let zombie_velocity = zombie.velocity.rotate_y(zombie.angle);
+        let frames_until_grab = (0..=GRAB_PREDICTION_FRAMES).find(|&t| {
+            let player_pos = player.center() + Vec2::new(Fixed32(player_velocity.x.0 * t), Fixed32(player_velocity.z.0 * t));
+            let zombie_pos = zombie.center() + Vec2::new(Fixed32(zombie_velocity.x.0 * t), Fixed32(zombie_velocity.z.0 * t));
+            ZOMBIE_GRAB_ZONE.is_point_in_zone(player_pos - zombie_pos, zombie.angle)
+        }).map(|t| t as usize);
+
+        Some(Self {
+            character_index: zombie.index(),
+            pos: zombie.center(),
+            angle: zombie.angle,
+            are_arms_raised,
+            frames_until_grab,
+        })
+    }
+
+    pub fn gui_shape(&self, draw_params: DrawParams, state: &State) -> Shape {
+        ZOMBIE_GRAB_ZONE.gui_shape(self.angle, self.pos, draw_params, state)
+    }
+}
+
 pub const ZOMBIE_AI_ZONES: [AiZone; 10] = [
     AiZone::circle(
         "Passive aggro",
@@ -1207,7 +1344,7 @@ pub const ZOMBIE_AI_ZONES: [AiZone; 10] = [
         Fixed16(0x400),
         UFixed16(3500),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x01), StateMask::Any, StateMask::Any],
-    ).inverted(),
+    ).inverted().with_rolls(&[RollType::ZombieLunge25]),
     AiZone::circle(
         "Wander aggro",
         "Zombie will begin to pursue you if you enter this zone while the zombie is wandering",
@@ -1222,7 +1359,7 @@ pub const ZOMBIE_AI_ZONES: [AiZone; 10] = [
         Fixed16(800),
         UFixed16(3000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x00), StateMask::Any, StateMask::Any],
-    ).inverted(),
+    ).inverted().with_rolls(&[RollType::ZombieLunge50]),
     AiZone::arc(
         "Raised arm lunge",
         "Zombie has a 50% chance to lunge at you each sound",
@@ -1230,7 +1367,7 @@ pub const ZOMBIE_AI_ZONES: [AiZone; 10] = [
         Fixed16(0x400),
         UFixed16(3000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x02), StateMask::Any, StateMask::Any],
-    ).inverted(),
+    ).inverted().with_rolls(&[RollType::ZombieLunge50]),
     AiZone::arc(
         "Aggro near lunge",
         "Zombie has a 50% chance to lunge at you each sound, in addition to the aggro far lunge chance",
@@ -1238,7 +1375,7 @@ pub const ZOMBIE_AI_ZONES: [AiZone; 10] = [
         Fixed16(0x400),
         UFixed16(2500),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x01), StateMask::Any, StateMask::Any],
-    ).inverted(),
+    ).inverted().with_rolls(&[RollType::ZombieLunge50]),
     AiZone::arc(
         "Near lunge",
         "Zombie has a second 50% chance to lunge at you each sound, in addition to the far lunge chance",
@@ -1246,7 +1383,7 @@ pub const ZOMBIE_AI_ZONES: [AiZone; 10] = [
         Fixed16(0x400),
         UFixed16(2000),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x00), StateMask::Any, StateMask::Any],
-    ).inverted(),
+    ).inverted().with_rolls(&[RollType::ZombieLunge50NotZero]),
     AiZone::arc(
         "Lunge bite",
         "Zombie will bite you if you are within this zone",
@@ -1255,14 +1392,7 @@ pub const ZOMBIE_AI_ZONES: [AiZone; 10] = [
         UFixed16(1300),
         [StateMask::Exactly(0x01), StateMask::Exactly(0x0C), StateMask::Exactly(0x03), StateMask::Any],
     ),
-    AiZone::arc(
-        "Bite",
-        "Zombie will bite you if you are within this zone",
-        BehaviorType::Hit,
-        Fixed16(0x200),
-        UFixed16(1200),
-        [StateMask::Exactly(0x01), StateMask::Either(0x01, 0x02), StateMask::Any, StateMask::Any],
-    ),
+    ZOMBIE_GRAB_ZONE,
     // TODO: puke attack; don't understand all the conditions here
     // could include zone for zombie raising its arms, but doesn't seem super useful
     // could include zone within which zombie will keep its arms raised until the timer expires, but that also doesn't seem super useful
@@ -6,6 +6,7 @@ use residat::re2::{AimZone, HitBounds, Item, WeaponRange};
 use crate::app::{DrawParams, Floor, GameObject, ObjectType};
 use crate::character::CharacterType;
 use crate::record::State;
+use crate::units::{Angle, Degrees, Distance};
 
 // FIXME: we're not taking the y-axis into account
 
@@ -351,10 +352,10 @@ impl GameObject for WeaponRangeVisualization {
         
         for (i, bounds) in self.aim_range.iter().enumerate() {
             groups.push((format!("Bounds {}", i), vec![
-                format!("X: {}", bounds.0.x),
-                format!("Z: {}", bounds.0.z),
-                format!("X Size: {}", bounds.1.x),
-                format!("Z Size: {}", bounds.1.z),
+                format!("X: {}", Distance(bounds.0.x)),
+                format!("Z: {}", Distance(bounds.0.z)),
+                format!("X Size: {}", Distance(bounds.1.x)),
+                format!("Z Size: {}", Distance(bounds.1.z)),
             ]));
         }
         
@@ -382,4 +383,78 @@ impl GameObject for WeaponRangeVisualization {
 
         Shape::Vec(shapes)
     }
-}
\ No newline at end of file
+}
+/// The enemy the player's shots are most likely to go toward and where it is relative to their aim
+#[derive(Debug, Clone)]
+pub struct AimTarget {
+    pub index: usize,
+    pub name: &'static str,
+    pub distance: Fixed32,
+    /// Angle from the player's facing to the target, negative when the target is clockwise
+    pub offset: Fixed32,
+    /// How wide the target appears from the player's position, in degrees
+    pub angular_size: f32,
+}
+
+/// Angles relevant to lining up a shot while the player is aiming
+#[derive(Debug, Clone)]
+pub struct AimReadout {
+    pub facing: Fixed32,
+    pub target: Option<AimTarget>,
+}
+
+impl AimReadout {
+    /// The readout for the current state, if the player is aiming. Auto-aim is approximated by
+    /// choosing the nearest enemy.
+    pub fn for_state(state: &State) -> Option<Self> {
+        let player = state.characters()[0].as_ref()?;
+        if !matches!(player.state, [0x01, 0x05, _, _]) {
+            return None;
+        }
+
+        let pos = player.center();
+        let target = state.characters()
+            .iter()
+            .flatten()
+            .filter(|c| c.type_() == CharacterType::Enemy && c.is_enabled())
+            .min_by_key(|c| (c.center() - pos).len().0)
+            .map(|enemy| {
+                let distance = (enemy.center() - pos).len();
+                let delta = (pos.angle_between(&enemy.center()) - player.angle).0 & 0xfff;
+                let offset = Fixed32(if delta >= 0x800 { delta - 0x1000 } else { delta });
+                let radius = enemy.size.x.max(enemy.size.z).0 as f32;
+                let angular_size = if distance.0 > 0 {
+                    (radius / distance.0 as f32).atan().to_degrees() * 2.0
+                } else {
+                    360.0
+                };
+
+                AimTarget {
+                    index: enemy.index(),
+                    name: enemy.name(),
+                    distance,
+                    offset,
+                    angular_size,
+                }
+            });
+
+        Some(Self {
+            facing: player.angle,
+            target,
+        })
+    }
+
+    pub fn description(&self) -> String {
+        let facing = self.facing.0 & 0xfff;
+        let mut text = format!("Facing: {}", Angle(facing).both());
+        match self.target {
+            Some(ref target) => {
+                text.push_str(&format!("\nTarget: #{} {} at {}", target.index, target.name, Distance(target.distance)));
+                text.push_str(&format!("\nOffset: {}", Angle(target.offset).both()));
+                text.push_str(&format!("\nTarget width: {}", Angle(Degrees(target.angular_size)).both()));
+            }
+            None => text.push_str("\nNo target"),
+        }
+        text
+    }
+}
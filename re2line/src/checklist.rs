@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::aot::Entity;
+use crate::app::RoomId;
+use crate::compare::Checkpoint;
+use crate::record::Recording;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChecklistTask {
+    /// Land this many hits on enemies
+    Shots(usize),
+    Aot(u8),
+}
+
+impl ChecklistTask {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Shots(_) => "Shots",
+            Self::Aot(_) => "AOT",
+        }
+    }
+}
+
+/// Something that should get done on every visit to a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub name: String,
+    pub room_id: RoomId,
+    pub task: ChecklistTask,
+}
+
+impl ChecklistItem {
+    pub fn new(name: String, room_id: RoomId) -> Self {
+        Self {
+            name,
+            room_id,
+            task: ChecklistTask::Shots(1),
+        }
+    }
+
+    /// The frame this item was done on during the given visit, if it was. The recording's current
+    /// frame may be changed, so the caller is responsible for restoring it if necessary.
+    pub fn find_completion(&self, recording: &mut Recording, visit: Range<usize>, entities: &[Entity]) -> Option<usize> {
+        match self.task {
+            ChecklistTask::Shots(count) => recording.damage_events()
+                .iter()
+                .filter(|d| !d.is_player() && visit.contains(&d.frame_index))
+                .nth(count.saturating_sub(1))
+                .map(|d| d.frame_index),
+            ChecklistTask::Aot(aot) => {
+                for i in visit {
+                    let state = recording.set_index(i)?;
+                    if Checkpoint::Aot(aot).matches(state, entities) {
+                        return Some(i);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// One visit to a room and when each of its checklist items was done
+#[derive(Debug, Clone)]
+pub struct VisitChecklist {
+    pub frames: Range<usize>,
+    /// Index of the item in the config's checklist and the frame it was done on
+    pub completions: Vec<(usize, Option<usize>)>,
+}
+
+impl VisitChecklist {
+    pub fn num_completed(&self) -> usize {
+        self.completions.iter().filter(|(_, frame)| frame.is_some()).count()
+    }
+
+    /// When the item was done, if it had been by the given frame
+    pub fn completed_by(&self, item_index: usize, frame_index: usize) -> Option<usize> {
+        self.completions.iter()
+            .find(|(i, _)| *i == item_index)
+            .and_then(|(_, frame)| *frame)
+            .filter(|frame| *frame <= frame_index)
+    }
+}
+
+/// How much of the room's checklist got done on each visit during one run
+#[derive(Debug, Clone)]
+pub struct RunChecklist {
+    pub name: String,
+    pub frames: Range<usize>,
+    pub visits: Vec<VisitChecklist>,
+}
+
+impl RunChecklist {
+    /// Check every visit to the room in the recording against the given items and group the
+    /// visits by run. The recording's current frame will be changed, so the caller is responsible
+    /// for restoring it if necessary.
+    pub fn analyze(items: &[(usize, &ChecklistItem)], room_id: RoomId, recording: &mut Recording, entities: &[Entity]) -> Vec<Self> {
+        let mut runs: Vec<Self> = recording.run_summaries()
+            .into_iter()
+            .map(|run| Self { name: run.name(), frames: run.frames, visits: Vec::new() })
+            .collect();
+
+        let visits: Vec<_> = recording.room_visits()
+            .into_iter()
+            .filter(|(state, _)| state.room_id() == room_id)
+            .map(|(_, range)| range)
+            .collect();
+
+        for visit in visits {
+            let completions = items.iter()
+                .map(|(i, item)| (*i, item.find_completion(recording, visit.clone(), entities)))
+                .collect();
+            let Some(run) = runs.iter_mut().find(|r| r.frames.contains(&visit.start)) else {
+                continue;
+            };
+            run.visits.push(VisitChecklist { frames: visit, completions });
+        }
+
+        runs.retain(|r| !r.visits.is_empty());
+        runs
+    }
+
+    pub fn visit_at(&self, frame_index: usize) -> Option<&VisitChecklist> {
+        self.visits.iter().find(|v| v.frames.contains(&frame_index))
+    }
+}
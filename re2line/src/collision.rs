@@ -2,6 +2,7 @@ use residat::common::{Fixed32, Vec2};
 
 use crate::app::{DrawParams, Floor, GameObject, ObjectType, WorldPos};
 use crate::record::State;
+use crate::units::Distance;
 
 #[derive(Debug, Clone)]
 pub struct Motion {
@@ -282,6 +283,10 @@ impl RectCollider {
         self.pos.collision_mask
     }
     
+    pub const fn world_pos(&self) -> &WorldPos {
+        &self.pos
+    }
+    
     pub const fn with_special_rect_type(mut self, special_rect_type: SpecialRectType) -> Self {
         self.special_rect_type = special_rect_type;
         self
@@ -1031,6 +1036,30 @@ impl Collider {
         })
     }
 
+    /// The points where the collider's edges meet, which the player can cut around
+    pub fn corners(&self) -> Vec<Vec2> {
+        let bounds = match self {
+            Self::Quad(quad) => return vec![quad.p1, quad.p2, quad.p3, quad.p4],
+            Self::Rect(RectCollider { pos, .. })
+            | Self::Diamond(DiamondCollider { pos, .. })
+            | Self::Ellipse(EllipseCollider { pos, .. })
+            | Self::Triangle(TriangleCollider { pos, .. }) => pos,
+        };
+
+        let (x, z, w, h) = (bounds.pos.x.0, bounds.pos.z.0, bounds.size.x.0, bounds.size.z.0);
+        let point = |px: i32, pz: i32| Vec2::new(Fixed32(px), Fixed32(pz));
+        match self {
+            Self::Diamond(_) => vec![point(x + w / 2, z), point(x + w, z + h / 2), point(x + w / 2, z + h), point(x, z + h / 2)],
+            // offsets are in screen space, where z is flipped
+            Self::Triangle(triangle) => triangle.offsets()
+                .into_iter()
+                .map(|(ox, oy)| point(x + (ox * w as f32) as i32, z + ((1.0 - oy) * h as f32) as i32))
+                .collect(),
+            // for ellipses, the bounding box is the best we can do
+            _ => vec![point(x, z), point(x + w, z), point(x + w, z + h), point(x, z + h)],
+        }
+    }
+
     pub fn clip_motion(&self, motion: &Motion) -> Vec2 {
         match self {
             Self::Rect(rect) => rect.clip_motion(motion),
@@ -1071,14 +1100,14 @@ impl GameObject for Collider {
     fn description(&self) -> String {
         match self {
             Self::Quad(quad) => {
-                format!("X1: {: >6} | Z1: {: >6}\nX2: {: >6} | Z2: {: >6}\nX3: {: >6} | Z3: {: >6}\nX4: {: >6} | Z4: {: >6}\n", quad.p1.x, quad.p1.z, quad.p2.x, quad.p2.z, quad.p3.x, quad.p3.z, quad.p4.x, quad.p4.z)
+                format!("X1: {: >6} | Z1: {: >6}\nX2: {: >6} | Z2: {: >6}\nX3: {: >6} | Z3: {: >6}\nX4: {: >6} | Z4: {: >6}\n", Distance(quad.p1.x), Distance(quad.p1.z), Distance(quad.p2.x), Distance(quad.p2.z), Distance(quad.p3.x), Distance(quad.p3.z), Distance(quad.p4.x), Distance(quad.p4.z))
             }
             Self::Rect(RectCollider { pos, .. })
             | Self::Diamond(DiamondCollider { pos, .. })
             | Self::Ellipse(EllipseCollider { pos, .. })
             | Self::Triangle(TriangleCollider { pos, .. })
             => {
-                format!("X: {: >6} | Z: {: >6}\nW: {: >6} | H: {: >6}", pos.pos.x, pos.pos.z, pos.size.x, pos.size.z)
+                format!("X: {: >6} | Z: {: >6}\nW: {: >6} | H: {: >6}", Distance(pos.pos.x), Distance(pos.pos.z), Distance(pos.size.x), Distance(pos.size.z))
             }
         }
     }
@@ -1094,14 +1123,14 @@ impl GameObject for Collider {
         match self {
             Self::Quad(quad) => {
                 groups.push((label, vec![
-                    format!("X1: {}", quad.p1.x),
-                    format!("Z1: {}", quad.p1.z),
-                    format!("X2: {}", quad.p2.x),
-                    format!("Z2: {}", quad.p2.z),
-                    format!("X3: {}", quad.p3.x),
-                    format!("Z3: {}", quad.p3.z),
-                    format!("X4: {}", quad.p4.x),
-                    format!("Z4: {}", quad.p4.z),
+                    format!("X1: {}", Distance(quad.p1.x)),
+                    format!("Z1: {}", Distance(quad.p1.z)),
+                    format!("X2: {}", Distance(quad.p2.x)),
+                    format!("Z2: {}", Distance(quad.p2.z)),
+                    format!("X3: {}", Distance(quad.p3.x)),
+                    format!("Z3: {}", Distance(quad.p3.z)),
+                    format!("X4: {}", Distance(quad.p4.x)),
+                    format!("Z4: {}", Distance(quad.p4.z)),
                     format!("Floor: {}", quad.floor),
                 ]));
             }
@@ -1111,10 +1140,10 @@ impl GameObject for Collider {
             | Self::Triangle(TriangleCollider { pos, .. })
             => {
                 let mut params = vec![
-                    format!("X: {}", pos.pos.x),
-                    format!("Z: {}", pos.pos.z),
-                    format!("W: {}", pos.size.x),
-                    format!("H: {}", pos.size.z),
+                    format!("X: {}", Distance(pos.pos.x)),
+                    format!("Z: {}", Distance(pos.pos.z)),
+                    format!("W: {}", Distance(pos.size.x)),
+                    format!("H: {}", Distance(pos.size.z)),
                     format!("Floor: {}", pos.floor),
                 ];
                 if self.collision_mask() != 0xFFFF {
@@ -1135,10 +1164,10 @@ impl GameObject for Collider {
                 let center_z = ellipse.pos.pos.z + z_radius;
                 
                 groups.push((label, vec![
-                    format!("CX: {}", center_x),
-                    format!("CZ: {}", center_z),
-                    format!("RX: {}", x_radius),
-                    format!("RZ: {}", z_radius),
+                    format!("CX: {}", Distance(center_x)),
+                    format!("CZ: {}", Distance(center_z)),
+                    format!("RX: {}", Distance(x_radius)),
+                    format!("RZ: {}", Distance(z_radius)),
                 ]));
             }
             Self::Triangle(tri) => {
@@ -1152,12 +1181,12 @@ impl GameObject for Collider {
                 let z3 = tri.pos.pos.z + if offsets[2].1 > 0.0 { tri.pos.size.z } else { Fixed32(0) };
                 
                 groups.push((label, vec![
-                    format!("X1: {}", x1),
-                    format!("Z1: {}", z1),
-                    format!("X2: {}", x2),
-                    format!("Z2: {}", z2),
-                    format!("X3: {}", x3),
-                    format!("Z3: {}", z3),
+                    format!("X1: {}", Distance(x1)),
+                    format!("Z1: {}", Distance(z1)),
+                    format!("X2: {}", Distance(x2)),
+                    format!("Z2: {}", Distance(z2)),
+                    format!("X3: {}", Distance(x3)),
+                    format!("Z3: {}", Distance(z3)),
                 ]));
             }
             Self::Diamond(diamond) => {
@@ -1169,14 +1198,14 @@ impl GameObject for Collider {
                 let width = diamond.pos.size.x;
                 let height = diamond.pos.size.z;
                 groups.push((label, vec![
-                    format!("X1: {}", x + radius_x),
-                    format!("Z1: {}", z),
-                    format!("X2: {}", x + width),
-                    format!("Z2: {}", z + radius_z),
-                    format!("X3: {}", x + radius_x),
-                    format!("Z3: {}", z + height),
-                    format!("X4: {}", x),
-                    format!("Z4: {}", z + radius_z),
+                    format!("X1: {}", Distance(x + radius_x)),
+                    format!("Z1: {}", Distance(z)),
+                    format!("X2: {}", Distance(x + width)),
+                    format!("Z2: {}", Distance(z + radius_z)),
+                    format!("X3: {}", Distance(x + radius_x)),
+                    format!("Z3: {}", Distance(z + height)),
+                    format!("X4: {}", Distance(x)),
+                    format!("Z4: {}", Distance(z + radius_z)),
                 ]));
             }
             Self::Rect(rect) => {
@@ -1186,12 +1215,12 @@ impl GameObject for Collider {
                 let fz = rect.pos.pos.z + rect.pos.size.z;
                 
                 groups.push((label, vec![
-                    format!("X2: {}", fx),
-                    format!("Z2: {}", nz),
-                    format!("X3: {}", fx),
-                    format!("Z3: {}", fz),
-                    format!("X4: {}", nx),
-                    format!("Z4: {}", fz),
+                    format!("X2: {}", Distance(fx)),
+                    format!("Z2: {}", Distance(nz)),
+                    format!("X3: {}", Distance(fx)),
+                    format!("Z3: {}", Distance(fz)),
+                    format!("X4: {}", Distance(nx)),
+                    format!("Z4: {}", Distance(fz)),
                 ]));
             }
             Self::Quad(_) => {} // no need for calculated for quad since all points are included in params
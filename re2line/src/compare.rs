@@ -4,11 +4,12 @@ use std::path::PathBuf;
 use std::rc::Rc;
 
 use anyhow::{anyhow, bail, Result};
+use residat::common::Vec2;
 
 use crate::aot::Entity;
 use crate::app::{GameObject, RoomId};
 use crate::character::CharacterPath;
-use crate::record::{Recording, State};
+use crate::record::{DoorTimes, Recording, Scenario, State};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Checkpoint {
@@ -44,12 +45,46 @@ impl Checkpoint {
     }
 }
 
+/// A section of a room visit marked by where the player was at its first and last frames, so the
+/// same section can be found in other recordings
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentBounds {
+    pub start_frame: usize,
+    pub end_frame: usize,
+    pub start: Vec2,
+    pub end: Vec2,
+}
+
+impl SegmentBounds {
+    pub fn from_frames(recording: &Recording, frames: Range<usize>) -> Option<Self> {
+        let end_frame = frames.end.checked_sub(1)?;
+        Some(Self {
+            start_frame: frames.start,
+            end_frame,
+            start: recording.player_position(frames.start)?,
+            end: recording.player_position(end_frame)?,
+        })
+    }
+
+    /// Find the part of a path that passes closest to the start point and then closest to the end
+    /// point
+    fn find_in(&self, points: &[Vec2]) -> Option<Range<usize>> {
+        let start = points.iter().enumerate().min_by_key(|(_, p)| (**p - self.start).len().0)?.0;
+        let end = start + points[start..].iter().enumerate().min_by_key(|(_, p)| (**p - self.end).len().0)?.0;
+        Some(start..end + 1)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RoomFilter {
     pub room_id: RoomId,
     pub entrance_id: Option<RoomId>,
     pub exit_id: Option<RoomId>,
     pub checkpoints: Vec<Checkpoint>,
+    /// Only compare this section of the room instead of the whole visit
+    pub segment: Option<SegmentBounds>,
+    /// Skip visits where a save was loaded partway through the room
+    pub exclude_mid_room_loads: bool,
 }
 
 impl RoomFilter {
@@ -59,6 +94,8 @@ impl RoomFilter {
             entrance_id,
             exit_id,
             checkpoints,
+            segment: None,
+            exclude_mid_room_loads: false,
         }
     }
 
@@ -75,6 +112,7 @@ impl RoomFilter {
         let mut checkpoints = self.checkpoints.iter();
         let mut next_checkpoint = checkpoints.next();
         let mut start_index = 0usize;
+        let mut start_rng_position = 0usize;
         let mut end_index = usize::MAX;
 
         recording.set_index(0);
@@ -91,6 +129,7 @@ impl RoomFilter {
 
                 last_room_id = state.room_id();
                 start_index = state.frame_index();
+                start_rng_position = state.rng_position();
                 checkpoints = self.checkpoints.iter();
                 next_checkpoint = checkpoints.next();
 
@@ -124,15 +163,50 @@ impl RoomFilter {
             }
 
             if next_checkpoint.is_none() {
+                if self.exclude_mid_room_loads && recording.mid_room_loads(start_index..end_index).next().is_some() {
+                    recording.next_room();
+                    continue;
+                }
+
                 // we've fulfilled all the checkpoint criteria; extract the run
+                let current_scenario = state.scenario();
                 recording.set_index(end_index - 1);
                 if let Some(mut route) = recording.get_path_for_character(0) {
                     route.limit = 0;
                     route.dynamic_color = false;
+                    let mut run_start = start_index;
+                    let mut run_rng_position = start_rng_position;
+                    if let Some(ref segment) = self.segment {
+                        let Some(section) = segment.find_in(&route.points) else {
+                            recording.next();
+                            continue;
+                        };
+
+                        route.points = route.points[section.clone()].to_vec();
+                        route.locked = route.locked.get(section.clone()).map(<[bool]>::to_vec).unwrap_or_default();
+                        run_start = start_index + section.start;
+                        run_rng_position = recording.set_index(run_start).map(State::rng_position).unwrap_or(0);
+                    }
+
+                    let danger = recording.get_danger_exposure(run_start..run_start + route.frames());
+                    let scenario = match recording.run_summary(run_start) {
+                        Some(summary) => summary.scenario,
+                        None => current_scenario,
+                    };
+                    let last_frame = run_start + route.frames().saturating_sub(1);
+                    let end_rng_position = recording.set_index(last_frame).map(State::rng_position).unwrap_or(0);
+                    recording.set_index(end_index - 1);
+                    let num_rng_rolls = recording.count_rng_rolls(run_start..run_start + route.frames());
                     runs.push(Run {
                         source_path: Rc::clone(&recording_path),
-                        frame_index: start_index,
+                        frame_index: run_start,
+                        distances: Run::route_distances(&route),
                         route,
+                        danger,
+                        scenario,
+                        num_rng_rolls,
+                        start_rng_position: run_rng_position,
+                        end_rng_position,
                         included: true,
                     });
                 }
@@ -180,15 +254,74 @@ impl LoadedRecording {
     }
 }
 
+/// How comparison playback decides where each run should be relative to the active one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    /// Every run has played the same number of frames
+    #[default]
+    Frames,
+    /// Every run has covered the same fraction of its route's length
+    Progress,
+}
+
+impl Alignment {
+    pub const fn list() -> [Self; 2] {
+        [Self::Frames, Self::Progress]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Frames => "Elapsed frames",
+            Self::Progress => "Route progress",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Run {
     source_path: Rc<PathBuf>,
     frame_index: usize,
     route: CharacterPath,
+    danger: f32,
+    scenario: Scenario,
+    num_rng_rolls: usize,
+    start_rng_position: usize,
+    end_rng_position: usize,
+    // distance travelled along the route as of each point
+    distances: Vec<f32>,
     included: bool,
 }
 
 impl Run {
+    fn route_distances(route: &CharacterPath) -> Vec<f32> {
+        let mut distance = 0.0;
+        let mut distances = Vec::with_capacity(route.points.len());
+        for (i, point) in route.points.iter().enumerate() {
+            if i > 0 {
+                distance += (*point - route.points[i - 1]).len().to_f32();
+            }
+            distances.push(distance);
+        }
+        distances
+    }
+
+    /// Fraction of the route's length covered after the given number of frames
+    pub fn progress_at(&self, frames: usize) -> f32 {
+        let total = self.distances.last().copied().unwrap_or(0.0);
+        if total <= 0.0 {
+            return if frames >= self.len() { 1.0 } else { 0.0 };
+        }
+
+        let i = frames.min(self.distances.len().saturating_sub(1));
+        self.distances.get(i).copied().unwrap_or(0.0) / total
+    }
+
+    /// Number of frames it took to cover the given fraction of the route's length
+    pub fn frames_to_progress(&self, progress: f32) -> usize {
+        let total = self.distances.last().copied().unwrap_or(0.0);
+        self.distances.partition_point(|d| *d < progress * total)
+    }
+
     pub const fn set_included(&mut self, included: bool) {
         self.included = included;
     }
@@ -205,6 +338,27 @@ impl Run {
         self.route.frames()
     }
 
+    pub const fn danger(&self) -> f32 {
+        self.danger
+    }
+
+    pub const fn scenario(&self) -> Scenario {
+        self.scenario
+    }
+
+    pub const fn num_rng_rolls(&self) -> usize {
+        self.num_rng_rolls
+    }
+
+    pub const fn start_rng_position(&self) -> usize {
+        self.start_rng_position
+    }
+
+    /// Where the RNG was left for whatever comes next
+    pub const fn end_rng_position(&self) -> usize {
+        self.end_rng_position
+    }
+
     pub fn range(&self) -> Range<usize> {
         self.frame_index..self.frame_index + self.route.frames()
     }
@@ -221,14 +375,16 @@ pub struct Comparison {
     active_run_index: usize,
     playback_index: usize,
     include_exclusions_in_statistics: bool,
+    alignment: Alignment,
 }
 
 impl Comparison {
-    pub fn load_runs(recording_paths: Vec<PathBuf>, filter: &RoomFilter, entities: &[Entity]) -> Result<Self> {
+    pub fn load_runs(recording_paths: Vec<PathBuf>, filter: &RoomFilter, entities: &[Entity], door_times: &mut DoorTimes) -> Result<Self> {
         let mut loaded = None;
         let mut runs = Vec::new();
         for recording_path in recording_paths {
             let mut recording = LoadedRecording::load(recording_path)?;
+            door_times.add_recording(&recording.path, &recording.recording);
             filter.get_runs(Rc::clone(&recording.path), &mut recording.recording, entities, &mut runs);
             loaded = Some(recording);
         }
@@ -250,7 +406,8 @@ impl Comparison {
             loaded_recording,
             active_run_index: 0,
             playback_index: 0,
-            include_exclusions_in_statistics: false,       
+            include_exclusions_in_statistics: false,
+            alignment: Alignment::Frames,
         })
     }
     
@@ -295,6 +452,26 @@ impl Comparison {
         }
     }
 
+    /// Number of runs and average time for each scenario represented in the comparison
+    pub fn scenario_statistics(&self) -> Vec<(Scenario, usize, usize)> {
+        let mut stats: Vec<(Scenario, usize, usize)> = Vec::new();
+        for run in &self.runs {
+            if !run.is_included() && !self.include_exclusions_in_statistics {
+                continue;
+            }
+
+            match stats.iter_mut().find(|(scenario, _, _)| *scenario == run.scenario) {
+                Some((_, count, total)) => {
+                    *count += 1;
+                    *total += run.len();
+                }
+                None => stats.push((run.scenario, 1, run.len())),
+            }
+        }
+
+        stats.into_iter().map(|(scenario, count, total)| (scenario, count, total / count)).collect()
+    }
+
     pub const fn recording(&self) -> &Recording {
         &self.loaded_recording.recording
     }
@@ -325,13 +502,41 @@ impl Comparison {
     
     pub fn set_playback_index(&mut self, index: usize) {
         self.playback_index = index;
-        for run in &mut self.runs {
-            run.route.limit = self.playback_index;
+        match self.alignment {
+            Alignment::Frames => {
+                for run in &mut self.runs {
+                    run.route.limit = self.playback_index;
+                }
+            }
+            Alignment::Progress => {
+                // the playback index is frames into the active run; everyone else is placed at the
+                // same fraction of the way along their own route
+                let progress = self.runs[self.active_run_index].progress_at(self.playback_index);
+                for (i, run) in self.runs.iter_mut().enumerate() {
+                    run.route.limit = if i == self.active_run_index {
+                        self.playback_index
+                    } else {
+                        run.frames_to_progress(progress)
+                    };
+                }
+            }
         }
     }
-    
+
     pub fn is_playback_complete(&self) -> bool {
-        self.playback_index >= self.slowest_time()
+        match self.alignment {
+            Alignment::Frames => self.playback_index >= self.slowest_time(),
+            Alignment::Progress => self.playback_index >= self.active_run().len(),
+        }
+    }
+
+    pub const fn alignment(&self) -> Alignment {
+        self.alignment
+    }
+
+    pub fn set_alignment(&mut self, alignment: Alignment) {
+        self.alignment = alignment;
+        self.set_playback_index(self.playback_index);
     }
     
     pub const fn include_exclusions_in_statistics(&self) -> bool {
@@ -0,0 +1,250 @@
+use std::ops::Range;
+
+use residat::common::{Fixed32, Vec2};
+
+use crate::app::RoomId;
+use crate::character::{Character, CharacterType};
+use crate::collision::Collider;
+use crate::plan::DrawnRoute;
+use crate::record::Recording;
+
+// how long after a push ends to measure where the player was trying to get to
+const PUSH_LOOKAHEAD_FRAMES: usize = 30;
+// extra room to leave between the player and the enemy when stepping around them
+const SIDESTEP_MARGIN: i32 = 100;
+
+/// A stretch of consecutive frames where the player and an enemy pushed each other around
+#[derive(Debug, Clone)]
+pub struct ContactEvent {
+    pub room_id: RoomId,
+    pub frames: Range<usize>,
+    pub enemy_index: usize,
+    pub enemy_name: &'static str,
+    /// How far the enemy pushed the player over the whole contact
+    pub player_displacement: Vec2,
+    /// How far the player pushed the enemy over the whole contact
+    pub enemy_displacement: Vec2,
+}
+
+impl ContactEvent {
+    pub fn name(&self) -> String {
+        format!("{} ({}): {} #{}", self.room_id, self.frames.start, self.enemy_name, self.enemy_index)
+    }
+
+    fn displacement(mover: &Character, receiver: &Character) -> Option<Vec2> {
+        let mut moved = mover.clone_for_collision();
+        let before = moved.center();
+        moved.collide_with_character(receiver).then(|| moved.center() - before)
+    }
+
+    /// Find every frame where the player and an enemy collided. The recording's current frame
+    /// will be changed, so the caller is responsible for restoring it if necessary.
+    pub fn analyze(recording: &mut Recording) -> Vec<Self> {
+        let visits: Vec<_> = recording.room_visits()
+            .into_iter()
+            .map(|(state, range)| (state.room_id(), range))
+            .collect();
+
+        let mut events: Vec<Self> = Vec::new();
+        for (room_id, visit) in visits {
+            // events still in progress as of the previous frame, by enemy index
+            let mut ongoing: Vec<usize> = Vec::new();
+            for i in visit {
+                let Some(state) = recording.set_index(i) else {
+                    ongoing.clear();
+                    continue;
+                };
+
+                let characters = state.characters();
+                let Some(Some(player)) = characters.first() else {
+                    ongoing.clear();
+                    continue;
+                };
+
+                let mut still_ongoing = Vec::new();
+                for enemy in characters.iter().flatten().filter(|c| c.type_() == CharacterType::Enemy) {
+                    let player_displacement = Self::displacement(player, enemy);
+                    let enemy_displacement = Self::displacement(enemy, player);
+                    if player_displacement.is_none() && enemy_displacement.is_none() {
+                        continue;
+                    }
+
+                    let player_displacement = player_displacement.unwrap_or_else(Vec2::zero);
+                    let enemy_displacement = enemy_displacement.unwrap_or_else(Vec2::zero);
+                    let enemy_index = enemy.index();
+
+                    let event_index = ongoing.iter()
+                        .copied()
+                        .find(|e| events[*e].enemy_index == enemy_index);
+                    match event_index {
+                        Some(e) => {
+                            let event = &mut events[e];
+                            event.frames.end = i + 1;
+                            event.player_displacement = event.player_displacement + player_displacement;
+                            event.enemy_displacement = event.enemy_displacement + enemy_displacement;
+                            still_ongoing.push(e);
+                        }
+                        None => {
+                            still_ongoing.push(events.len());
+                            events.push(Self {
+                                room_id,
+                                frames: i..i + 1,
+                                enemy_index,
+                                enemy_name: enemy.name(),
+                                player_displacement,
+                                enemy_displacement,
+                            });
+                        }
+                    }
+                }
+
+                ongoing = still_ongoing;
+            }
+        }
+
+        events
+    }
+
+    pub fn player_distance(&self) -> Fixed32 {
+        self.player_displacement.len()
+    }
+}
+
+/// A time the player shoved an enemy out of the way, compared to stepping around them instead
+#[derive(Debug, Clone)]
+pub struct PushEvent {
+    pub room_id: RoomId,
+    pub frames: Range<usize>,
+    pub enemy_name: Option<&'static str>,
+    pub start: Vec2,
+    pub end: Vec2,
+    /// The frame we assume the player was trying to reach the position of by pushing
+    pub target_frame: usize,
+    pub target: Vec2,
+    /// Estimated frames to get to the target by going around the enemy, if there's a way around
+    pub sidestep_frames: Option<usize>,
+    /// Whether walls were taken into account when checking for a way around
+    pub checked_walls: bool,
+}
+
+impl PushEvent {
+    pub const fn push_frames(&self) -> usize {
+        self.frames.end - self.frames.start
+    }
+
+    pub fn displacement(&self) -> Fixed32 {
+        (self.end - self.start).len()
+    }
+
+    pub const fn recorded_frames(&self) -> usize {
+        self.target_frame - self.frames.start
+    }
+
+    /// How many frames a sidestep would have saved. Negative if the push was faster.
+    pub fn sidestep_savings(&self) -> Option<isize> {
+        self.sidestep_frames.map(|f| self.recorded_frames() as isize - f as isize)
+    }
+
+    fn sidestep(
+        start: Vec2, target: Vec2, player: &Character, enemy: &Character, colliders: Option<(&[Collider], Vec2)>,
+        speed: Option<Fixed32>, turn_rate: Option<Fixed32>,
+    ) -> Option<usize> {
+        let direction = target - start;
+        let length = direction.len().0;
+        if length == 0 {
+            return None;
+        }
+
+        let clearance = player.size.x.0.max(player.size.z.0) + enemy.size.x.0.max(enemy.size.z.0) + SIDESTEP_MARGIN;
+        let offset_x = -direction.z.0 as i64 * clearance as i64 / length as i64;
+        let offset_z = direction.x.0 as i64 * clearance as i64 / length as i64;
+        let enemy_pos = enemy.center();
+        let (walls, room_center): (&[Collider], Vec2) = colliders.unwrap_or((&[], Vec2::zero()));
+
+        [1i64, -1]
+            .into_iter()
+            .map(|side| Vec2::new(Fixed32(enemy_pos.x.0 + (offset_x * side) as i32), Fixed32(enemy_pos.z.0 + (offset_z * side) as i32)))
+            .filter_map(|waypoint| {
+                let legs = DrawnRoute { points: vec![start, waypoint, target] }.legs(Some(player), walls, room_center, speed, turn_rate);
+                if legs.iter().any(|leg| leg.blocked_at.is_some()) {
+                    return None;
+                }
+                legs.iter().map(|leg| leg.frames).sum::<Option<usize>>()
+            })
+            .min()
+    }
+
+    /// Find every time the player pushed an enemy. Walls are only checked in the given room,
+    /// since that's the only one whose collision we have loaded. The recording's current frame
+    /// will be changed, so the caller is responsible for restoring it if necessary.
+    pub fn analyze(recording: &mut Recording, loaded_room: Option<(RoomId, &[Collider], Vec2)>) -> Vec<Self> {
+        let visits: Vec<_> = recording.room_visits()
+            .into_iter()
+            .map(|(state, range)| (state.room_id(), range))
+            .collect();
+        let speed = recording.player_run_speed();
+        let turn_rate = recording.player_turn_rate();
+
+        let mut events = Vec::new();
+        for (room_id, visit) in visits {
+            let colliders = loaded_room
+                .filter(|(loaded_id, _, _)| *loaded_id == room_id)
+                .map(|(_, colliders, center)| (colliders, center));
+
+            // the player and nearest enemy at the start of the push currently in progress
+            let mut current: Option<(usize, Vec2, Character, Option<Character>)> = None;
+            let mut pushes = Vec::new();
+            for i in visit.clone() {
+                let state = recording.set_index(i);
+                let player = state.and_then(|s| s.characters().first()).and_then(Option::as_ref);
+                let is_pushing = player.is_some_and(Character::is_pushing_enemy);
+
+                match (is_pushing, current.is_some()) {
+                    (true, false) => {
+                        let (Some(state), Some(player)) = (state, player) else {
+                            continue;
+                        };
+                        let enemy = state.characters()
+                            .iter()
+                            .flatten()
+                            .filter(|c| c.type_() == CharacterType::Enemy && c.is_enabled())
+                            .min_by_key(|c| (c.center() - player.center()).len().0)
+                            .cloned();
+                        current = Some((i, player.center(), player.clone(), enemy));
+                    }
+                    (false, true) => {
+                        pushes.push((current.take().unwrap(), i));
+                    }
+                    _ => (),
+                }
+            }
+
+            if let Some(push) = current.take() {
+                pushes.push((push, visit.end));
+            }
+
+            for ((start_frame, start, player, enemy), end_frame) in pushes {
+                let end = recording.player_position(end_frame.min(visit.end - 1)).unwrap_or(start);
+                // a push that lasts until the player leaves the room ends one frame past the visit
+                let target_frame = (end_frame + PUSH_LOOKAHEAD_FRAMES).min(visit.end - 1);
+                let target = recording.player_position(target_frame).unwrap_or(end);
+                let sidestep_frames = enemy.as_ref()
+                    .and_then(|enemy| Self::sidestep(start, target, &player, enemy, colliders, speed, turn_rate));
+
+                events.push(Self {
+                    room_id,
+                    frames: start_frame..end_frame,
+                    enemy_name: enemy.map(|e| e.name()),
+                    start,
+                    end,
+                    target_frame,
+                    target,
+                    sidestep_frames,
+                    checked_walls: colliders.is_some(),
+                });
+            }
+        }
+
+        events
+    }
+}
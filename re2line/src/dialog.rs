@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+// native file dialogs aren't available in the browser, where the viewer is read-only and files are
+// loaded by dropping them onto the page instead
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_folder() -> Option<PathBuf> {
+    rfd::FileDialog::new().pick_folder()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_file(filter_name: &str, extensions: &[&str]) -> Option<PathBuf> {
+    rfd::FileDialog::new().add_filter(filter_name, extensions).pick_file()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_files(filter_name: &str, extensions: &[&str]) -> Option<Vec<PathBuf>> {
+    rfd::FileDialog::new().add_filter(filter_name, extensions).pick_files()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_file(filter_name: &str, extensions: &[&str], file_name: &str) -> Option<PathBuf> {
+    rfd::FileDialog::new().add_filter(filter_name, extensions).set_file_name(file_name).save_file()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn pick_folder() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn pick_file(_filter_name: &str, _extensions: &[&str]) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn pick_files(_filter_name: &str, _extensions: &[&str]) -> Option<Vec<PathBuf>> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_file(_filter_name: &str, _extensions: &[&str], _file_name: &str) -> Option<PathBuf> {
+    None
+}
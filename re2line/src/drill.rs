@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use anyhow::Result;
+
+use crate::app::RoomId;
+use crate::record::{DoorTimes, Recording};
+
+/// One pass through a segment in one of the recordings
+#[derive(Debug, Clone)]
+pub struct SegmentAttempt {
+    pub source_path: Rc<PathBuf>,
+    pub frame_index: usize,
+    pub frames: usize,
+}
+
+impl SegmentAttempt {
+    pub fn identifier(&self) -> String {
+        let name = self.source_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        format!("{}:{}", name, self.frame_index)
+    }
+}
+
+/// A room along with the room it was entered from, if it wasn't the start of a new game, and the
+/// room it was exited to
+pub type SegmentKey = (RoomId, Option<RoomId>, RoomId);
+
+pub fn segment_name(key: SegmentKey) -> String {
+    let (room_id, entrance_id, exit_id) = key;
+    match entrance_id {
+        Some(entrance_id) => format!("{} ({} → {})", room_id, entrance_id, exit_id),
+        None => format!("{} (new game → {})", room_id, exit_id),
+    }
+}
+
+/// Every complete pass through a room in the recording
+pub fn segment_attempts(recording: &Recording, source_path: &Rc<PathBuf>) -> Vec<(SegmentKey, SegmentAttempt)> {
+    let mut attempts = Vec::new();
+    let visits = recording.room_visits();
+    for (i, (state, range)) in visits.iter().enumerate() {
+        // the recording ended or the game was reset before the player left the room
+        let Some((next_state, _)) = visits.get(i + 1) else {
+            continue;
+        };
+        if next_state.is_new_game_start() {
+            continue;
+        }
+
+        // a new game doesn't come from anywhere
+        let entrance_id = match i.checked_sub(1) {
+            Some(j) if !state.is_new_game_start() => Some(visits[j].0.room_id()),
+            _ => None,
+        };
+        let key = (state.room_id(), entrance_id, next_state.room_id());
+        attempts.push((key, SegmentAttempt {
+            source_path: Rc::clone(source_path),
+            frame_index: range.start,
+            frames: range.len(),
+        }));
+    }
+
+    attempts
+}
+
+/// A room, entered and exited a particular way, that's worth practicing
+#[derive(Debug, Clone)]
+pub struct Drill {
+    pub room_id: RoomId,
+    pub entrance_id: Option<RoomId>,
+    pub exit_id: RoomId,
+    /// Every attempt at the segment, fastest first
+    pub attempts: Vec<SegmentAttempt>,
+}
+
+impl Drill {
+    pub fn name(&self) -> String {
+        segment_name((self.room_id, self.entrance_id, self.exit_id))
+    }
+
+    pub fn fastest(&self) -> &SegmentAttempt {
+        &self.attempts[0]
+    }
+
+    pub fn slowest(&self) -> &SegmentAttempt {
+        &self.attempts[self.attempts.len() - 1]
+    }
+
+    pub fn average_frames(&self) -> f32 {
+        self.attempts.iter().map(|a| a.frames).sum::<usize>() as f32 / self.attempts.len() as f32
+    }
+
+    pub fn std_dev(&self) -> f32 {
+        let average = self.average_frames();
+        let variance = self.attempts.iter()
+            .map(|a| (a.frames as f32 - average).powi(2))
+            .sum::<f32>() / self.attempts.len() as f32;
+        variance.sqrt()
+    }
+
+    /// How many frames an average attempt loses to the best one
+    pub fn average_loss(&self) -> f32 {
+        self.average_frames() - self.fastest().frames as f32
+    }
+
+    /// Drills with a higher priority have more time to gain from practice
+    pub fn priority(&self) -> f32 {
+        self.average_loss() + self.std_dev()
+    }
+
+    /// Find every segment that was attempted at least `min_attempts` times across the given
+    /// recordings and order them by priority, highest first
+    pub fn generate(recording_paths: Vec<PathBuf>, min_attempts: usize, door_times: &mut DoorTimes) -> Result<Vec<Self>> {
+        let mut segments: HashMap<SegmentKey, Vec<SegmentAttempt>> = HashMap::new();
+        for path in recording_paths {
+            let recording = Recording::read(File::open(&path)?)?;
+            door_times.add_recording(&path, &recording);
+
+            let source_path = Rc::new(path);
+            for (key, attempt) in segment_attempts(&recording, &source_path) {
+                segments.entry(key).or_default().push(attempt);
+            }
+        }
+
+        let mut drills: Vec<_> = segments.into_iter()
+            .filter(|(_, attempts)| attempts.len() >= min_attempts.max(1))
+            .map(|((room_id, entrance_id, exit_id), mut attempts)| {
+                attempts.sort_by_key(|a| a.frames);
+                Self { room_id, entrance_id, exit_id, attempts }
+            })
+            .collect();
+        drills.sort_by(|a, b| b.priority().total_cmp(&a.priority()));
+
+        Ok(drills)
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use residat::re2::{Item, NUM_CHARACTERS};
+
+use crate::app::RoomId;
+use crate::character::CharacterType;
+use crate::record::Recording;
+
+// how recently the player must have attacked for a kill to be credited to them. this covers the
+// delay between pulling the trigger and the enemy's health actually dropping.
+const KILL_ATTRIBUTION_FRAMES: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyFate {
+    /// The enemy's health ran out, with the weapon the player last attacked with if it was recent
+    Killed(Option<Item>),
+    /// The enemy was removed while the player was still in the room, e.g. by a room script
+    Despawned,
+    /// The enemy was still alive when the player left the room
+    Avoided,
+}
+
+impl Display for EnemyFate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Killed(Some(weapon)) => write!(f, "Killed with {}", weapon.name()),
+            Self::Killed(None) => write!(f, "Killed by something other than the player"),
+            Self::Despawned => write!(f, "Despawned"),
+            Self::Avoided => write!(f, "Avoided"),
+        }
+    }
+}
+
+/// The time an enemy spent in the room, from when it appeared to when it died, disappeared, or
+/// the player left
+#[derive(Debug, Clone)]
+pub struct EnemyLifetime {
+    pub room_id: RoomId,
+    /// First frame of the room visit the enemy belonged to
+    pub visit_start: usize,
+    pub enemy_index: usize,
+    pub enemy_name: &'static str,
+    pub frames: Range<usize>,
+    pub fate: EnemyFate,
+}
+
+impl EnemyLifetime {
+    pub fn name(&self) -> String {
+        format!("{} #{}: {}", self.enemy_name, self.enemy_index, self.fate)
+    }
+
+    pub const fn is_killed(&self) -> bool {
+        matches!(self.fate, EnemyFate::Killed(_))
+    }
+
+    /// Find when each enemy in the recording appeared and what became of it. The recording's
+    /// current frame will be changed, so the caller is responsible for restoring it if necessary.
+    pub fn analyze(recording: &mut Recording) -> Vec<Self> {
+        let visits: Vec<_> = recording.room_visits()
+            .into_iter()
+            .map(|(state, range)| (state.room_id(), range))
+            .collect();
+
+        let mut lifetimes: Vec<Self> = Vec::new();
+        for (room_id, visit) in visits {
+            // the lifetime each character slot is currently tracking
+            let mut active: [Option<usize>; NUM_CHARACTERS] = [None; NUM_CHARACTERS];
+            let mut last_attack: Option<(usize, Item)> = None;
+            for i in visit.clone() {
+                let Some(state) = recording.set_index(i) else {
+                    continue;
+                };
+
+                if let Some(weapon) = state.player_attack() {
+                    last_attack = Some((i, weapon));
+                }
+
+                let characters = state.characters();
+                for (slot, character) in characters.iter().enumerate().skip(1) {
+                    let enemy = character.as_ref().filter(|c| c.type_() == CharacterType::Enemy);
+                    match (enemy, active[slot]) {
+                        (Some(enemy), None) => {
+                            // don't count corpses that were already there when we arrived
+                            if enemy.current_health() > 0 {
+                                active[slot] = Some(lifetimes.len());
+                                lifetimes.push(Self {
+                                    room_id,
+                                    visit_start: visit.start,
+                                    enemy_index: slot,
+                                    enemy_name: enemy.name(),
+                                    frames: i..i + 1,
+                                    fate: EnemyFate::Avoided,
+                                });
+                            }
+                        }
+                        (Some(enemy), Some(index)) => {
+                            let lifetime = &mut lifetimes[index];
+                            if lifetime.is_killed() {
+                                continue;
+                            }
+
+                            lifetime.frames.end = i + 1;
+                            if enemy.current_health() <= 0 {
+                                let weapon = last_attack
+                                    .filter(|(frame, _)| i - *frame <= KILL_ATTRIBUTION_FRAMES)
+                                    .map(|(_, weapon)| weapon);
+                                lifetime.fate = EnemyFate::Killed(weapon);
+                            }
+                        }
+                        (None, Some(index)) => {
+                            let lifetime = &mut lifetimes[index];
+                            if !lifetime.is_killed() {
+                                lifetime.fate = EnemyFate::Despawned;
+                            }
+                            active[slot] = None;
+                        }
+                        (None, None) => (),
+                    }
+                }
+            }
+        }
+
+        lifetimes
+    }
+}
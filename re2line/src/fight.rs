@@ -0,0 +1,146 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use residat::re2::Item;
+
+use crate::app::{GameObject, RoomId};
+use crate::character::{CharacterType, WeaponRangeVisualization};
+use crate::record::{Recording, State};
+
+// how long the player can hold their fire with an enemy in range before we flag it. this is
+// longer than the firing animation of any weapon, so frames spent recovering from the previous
+// shot won't be flagged.
+const HELD_FIRE_FRAMES: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShotFlagKind {
+    HeldFire(usize),
+    OutOfRange,
+}
+
+impl Display for ShotFlagKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HeldFire(frames) => write!(f, "Enemy in range for {} frame(s) without a shot", frames),
+            Self::OutOfRange => write!(f, "Shot with no enemy in range"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShotFlag {
+    pub kind: ShotFlagKind,
+    pub frame_index: usize,
+}
+
+/// Shot discipline for one room visit in which the player aimed a weapon
+#[derive(Debug, Clone)]
+pub struct FightSummary {
+    pub room_id: RoomId,
+    pub frames: Range<usize>,
+    pub weapons: Vec<Item>,
+    pub aim_frames: usize,
+    pub in_range_frames: usize,
+    pub shots: usize,
+    pub shots_in_range: usize,
+    pub flags: Vec<ShotFlag>,
+}
+
+impl FightSummary {
+    fn new(room_id: RoomId, frames: Range<usize>) -> Self {
+        Self {
+            room_id,
+            frames,
+            weapons: Vec::new(),
+            aim_frames: 0,
+            in_range_frames: 0,
+            shots: 0,
+            shots_in_range: 0,
+            flags: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        let weapons: Vec<_> = self.weapons.iter().map(|w| w.name()).collect();
+        format!("{} ({}): {}", self.room_id, self.frames.start, weapons.join(", "))
+    }
+
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.shots > 0).then(|| self.shots_in_range as f32 / self.shots as f32 * 100.0)
+    }
+
+    fn end_held_fire(&mut self, held_since: Option<usize>, frame_index: usize) {
+        let Some(start) = held_since else {
+            return;
+        };
+
+        let frames = frame_index - start;
+        if frames >= HELD_FIRE_FRAMES {
+            self.flags.push(ShotFlag { kind: ShotFlagKind::HeldFire(frames), frame_index: start });
+        }
+    }
+
+    fn is_enemy_in_range(state: &State, range: &WeaponRangeVisualization) -> bool {
+        state.characters()
+            .iter()
+            .flatten()
+            .filter(|c| c.type_() == CharacterType::Enemy && c.current_health() > 0 && c.floor().matches(range.floor))
+            .any(|c| range.contains_point(c.center()))
+    }
+
+    /// Audit each fight in the recording. The recording's current frame will be changed, so the
+    /// caller is responsible for restoring it if necessary.
+    pub fn analyze(recording: &mut Recording) -> Vec<Self> {
+        let visits: Vec<_> = recording.room_visits()
+            .into_iter()
+            .map(|(state, range)| (state.room_id(), range))
+            .collect();
+
+        let mut fights = Vec::new();
+        for (room_id, visit) in visits {
+            let mut fight = Self::new(room_id, visit.clone());
+            let mut held_since = None;
+            for i in visit.clone() {
+                let range = recording.set_index(i).and_then(|state| Some((state, WeaponRangeVisualization::for_state(state)?)));
+                let Some((state, range)) = range else {
+                    fight.end_held_fire(held_since.take(), i);
+                    continue;
+                };
+
+                fight.aim_frames += 1;
+                if !fight.weapons.contains(&range.weapon) {
+                    fight.weapons.push(range.weapon);
+                }
+
+                let is_in_range = Self::is_enemy_in_range(state, &range);
+                let is_shot = state.input_state_this_frame().is_action_pressed;
+                if is_in_range {
+                    fight.in_range_frames += 1;
+                }
+
+                if is_shot {
+                    fight.shots += 1;
+                    if is_in_range {
+                        fight.shots_in_range += 1;
+                    } else {
+                        fight.flags.push(ShotFlag { kind: ShotFlagKind::OutOfRange, frame_index: i });
+                    }
+                }
+
+                if is_in_range && !is_shot {
+                    held_since.get_or_insert(i);
+                } else {
+                    fight.end_held_fire(held_since.take(), i);
+                }
+            }
+
+            fight.end_held_fire(held_since, visit.end);
+            if fight.aim_frames > 0 {
+                fight.flags.sort_by_key(|flag| flag.frame_index);
+                fights.push(fight);
+            }
+        }
+
+        fights
+    }
+}
@@ -0,0 +1,100 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
+use egui::Color32;
+
+use crate::app::RoomId;
+use crate::character::Character;
+use crate::record::Recording;
+
+// colors handed out to ghosts in the order they're added
+pub const GHOST_COLORS: [Color32; 4] = [
+    Color32::from_rgb(0x00, 0xE5, 0xFF),
+    Color32::from_rgb(0xFF, 0x40, 0xC0),
+    Color32::from_rgb(0xFF, 0xA0, 0x00),
+    Color32::from_rgb(0xA0, 0xFF, 0x40),
+];
+
+/// Another recording played back on the map alongside the active one, kept in step by room visit
+#[derive(Debug)]
+pub struct GhostRecording {
+    pub path: PathBuf,
+    pub recording: Recording,
+    pub color: Color32,
+    pub show_enemies: bool,
+    /// The ghost's visit to the room the active recording is in, if it has one
+    visit: Option<Range<usize>>,
+}
+
+impl GhostRecording {
+    pub fn new(path: PathBuf, recording: Recording, color: Color32) -> Self {
+        Self {
+            path,
+            recording,
+            color,
+            show_enemies: true,
+            visit: None,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+
+    fn visits_to(recording: &Recording, room_id: RoomId) -> Vec<Range<usize>> {
+        recording.room_visits()
+            .into_iter()
+            .filter(|(state, _)| state.room_id() == room_id)
+            .map(|(_, range)| range)
+            .collect()
+    }
+
+    /// Move to the same number of frames into the matching visit to the active recording's
+    /// current room. Visits are matched by how many times the room had been visited before, and
+    /// if the ghost visited the room fewer times, its last visit is used.
+    pub fn sync(&mut self, active: &Recording) {
+        self.visit = None;
+        let Some(room_id) = active.current_state().map(|s| s.room_id()) else {
+            return;
+        };
+
+        let index = active.index();
+        let active_visits = Self::visits_to(active, room_id);
+        let Some(ordinal) = active_visits.iter().position(|v| v.contains(&index)) else {
+            return;
+        };
+
+        let ghost_visits = Self::visits_to(&self.recording, room_id);
+        let Some(visit) = ghost_visits.get(ordinal).or(ghost_visits.last()) else {
+            return;
+        };
+
+        // the ghost stays on the last frame of its visit if it left the room sooner
+        let offset = index - active_visits[ordinal].start;
+        self.recording.set_index((visit.start + offset).min(visit.end - 1));
+        self.visit = Some(visit.clone());
+    }
+
+    /// The ghost's visit to the current room, if it has one
+    pub fn visit(&self) -> Option<&Range<usize>> {
+        self.visit.as_ref()
+    }
+
+    /// Characters to draw for the ghost's current frame
+    pub fn characters(&self) -> Vec<Character> {
+        let Some(state) = self.visit.as_ref().and(self.recording.current_state()) else {
+            return Vec::new();
+        };
+
+        state.characters()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i == 0 || self.show_enemies)
+            .filter_map(|(i, character)| {
+                let mut character = character.clone()?;
+                character.set_index(i);
+                Some(character)
+            })
+            .collect()
+    }
+}
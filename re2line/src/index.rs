@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use residat::re2::{CharacterId, Item};
+
+use crate::aot::EntityForm;
+use crate::app::RoomId;
+use crate::inspect::read_rdt_as;
+use crate::rdt::{RdtExt, RdtFormat};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexKind {
+    Item { item_id: u16, count: u16 },
+    Enemy(CharacterId),
+}
+
+/// An item pickup or scripted enemy spawn somewhere in the game
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    pub room_id: RoomId,
+    pub kind: IndexKind,
+}
+
+impl IndexEntry {
+    pub fn name(&self) -> String {
+        match self.kind {
+            IndexKind::Item { item_id, .. } => Item::name_from_id(item_id).to_string(),
+            IndexKind::Enemy(id) => id.name().to_string(),
+        }
+    }
+
+    pub const fn is_item(&self) -> bool {
+        matches!(self.kind, IndexKind::Item { .. })
+    }
+}
+
+/// Every item AOT and enemy spawn across all the rooms in the game folder
+#[derive(Debug, Default)]
+pub struct GameIndex {
+    entries: Vec<IndexEntry>,
+    num_failed_rooms: usize,
+}
+
+impl GameIndex {
+    pub fn build<'a>(rooms: impl Iterator<Item = &'a (PathBuf, RoomId)>) -> Self {
+        let mut index = Self::default();
+        for (path, room_id) in rooms {
+            let rdt = std::fs::read(path).map_err(anyhow::Error::from).and_then(|data| read_rdt_as(*room_id, &data, RdtFormat::Re2));
+            let Ok((rdt, _)) = rdt else {
+                index.num_failed_rooms += 1;
+                continue;
+            };
+
+            for entity in rdt.get_entities() {
+                if let EntityForm::Item { i_item, n_item, .. } = *entity.form() {
+                    index.entries.push(IndexEntry { room_id: *room_id, kind: IndexKind::Item { item_id: i_item, count: n_item } });
+                }
+            }
+            for id in rdt.get_enemy_ids() {
+                index.entries.push(IndexEntry { room_id: *room_id, kind: IndexKind::Enemy(id) });
+            }
+        }
+
+        index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Rooms that couldn't be read while building the index
+    pub const fn num_failed_rooms(&self) -> usize {
+        self.num_failed_rooms
+    }
+
+    /// Entries whose name contains the filter, grouped by name in alphabetical order
+    pub fn grouped(&self, is_item: bool, filter: &str) -> Vec<(String, Vec<&IndexEntry>)> {
+        let filter = filter.to_lowercase();
+        let mut groups: Vec<(String, Vec<&IndexEntry>)> = Vec::new();
+        for entry in self.entries.iter().filter(|e| e.is_item() == is_item) {
+            let name = entry.name();
+            if !name.to_lowercase().contains(&filter) {
+                continue;
+            }
+
+            match groups.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, entries)) => entries.push(entry),
+                None => groups.push((name, vec![entry])),
+            }
+        }
+
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        groups
+    }
+}
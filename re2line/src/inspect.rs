@@ -0,0 +1,416 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use residat::re2::Rdt;
+
+use crate::app::RoomId;
+use crate::rdt::{RdtFormat, RdtLayout};
+
+// the header has 8 bytes of counts followed by the section offsets
+const HEADER_COUNTS_SIZE: usize = 8;
+const NUM_SECTIONS: usize = 23;
+const HEADER_SIZE: usize = HEADER_COUNTS_SIZE + NUM_SECTIONS * 4;
+const HEX_BYTES_PER_LINE: usize = 16;
+
+const SECTION_NAMES: [&str; NUM_SECTIONS] = [
+    "Sound attributes",
+    "Sound header (VH)",
+    "Sound bank (VB)",
+    "Sound header 2 (VH)",
+    "Sound bank 2 (VB)",
+    "OTA",
+    "Collision",
+    "Camera positions",
+    "Camera switches",
+    "Lights",
+    "Models",
+    "Floors",
+    "Blocks",
+    "Messages (Japanese)",
+    "Messages",
+    "Camera scrolls",
+    "Init script",
+    "Exec script",
+    "Sprite IDs",
+    "Sprite data",
+    "Sprite textures",
+    "Model textures",
+    "Animations",
+];
+
+const HEADER_COUNT_NAMES: [&str; HEADER_COUNTS_SIZE] = [
+    "Sprites",
+    "Cameras",
+    "Models",
+    "Items",
+    "Doors",
+    "Rooms",
+    "Reverb",
+    "Unknown",
+];
+
+// when a room fails to load, sections are dropped in this order until it loads. sections re2line
+// doesn't use come first so we keep as much of the room as possible.
+const SKIP_ORDER: [usize; NUM_SECTIONS] = [
+    0, 1, 2, 3, 4, 18, 19, 20, 21, 10, 9, 13, 14, 15, 12, 7, 8, 5, 22, 17, 16, 11, 6,
+];
+
+fn section_offset(data: &[u8], index: usize) -> usize {
+    let start = HEADER_COUNTS_SIZE + index * 4;
+    u32::from_le_bytes([data[start], data[start + 1], data[start + 2], data[start + 3]]) as usize
+}
+
+fn set_section_offset(data: &mut [u8], index: usize, offset: usize) {
+    let start = HEADER_COUNTS_SIZE + index * 4;
+    data[start..start + 4].copy_from_slice(&(offset as u32).to_le_bytes());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    OutOfBounds,
+    Unparseable,
+    UnsupportedFormat,
+}
+
+impl SkipReason {
+    pub const fn description(&self) -> &'static str {
+        match self {
+            Self::OutOfBounds => "Offset is past the end of the file",
+            Self::Unparseable => "Could not be parsed",
+            Self::UnsupportedFormat => "Not supported for this game yet",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SkippedSection {
+    pub name: &'static str,
+    pub offset: usize,
+    pub reason: SkipReason,
+}
+
+/// Sections that had to be left out to get a room to load
+#[derive(Debug, Clone)]
+pub struct RdtWarnings {
+    pub room_id: RoomId,
+    pub error: String,
+    pub skipped: Vec<SkippedSection>,
+}
+
+/// Check that a file from another game has the RDT header we expect: every section offset must
+/// point past the header and inside the file.
+fn check_header_layout(data: &[u8], layout: &dyn RdtLayout) -> Result<()> {
+    if data.len() < HEADER_SIZE {
+        bail!("File is too small to be an RDT ({} bytes)", data.len());
+    }
+
+    for (i, name) in SECTION_NAMES.iter().enumerate() {
+        let offset = section_offset(data, i);
+        if offset != 0 && (offset < HEADER_SIZE || offset >= data.len()) {
+            bail!(
+                "File doesn't look like an {} RDT: {} offset {:#X} is outside the file",
+                layout.name(), name, offset,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Read an RDT from one of the games that share RE2's RDT layout, leaving out any sections that
+/// are laid out differently in that game
+pub fn read_rdt_as(room_id: RoomId, data: &[u8], format: RdtFormat) -> Result<(Rdt, Option<RdtWarnings>)> {
+    let layout = format.layout();
+    let incompatible = layout.incompatible_sections();
+    if incompatible.is_empty() {
+        return read_rdt_tolerant(room_id, data);
+    }
+
+    check_header_layout(data, layout)?;
+
+    let mut patched = data.to_vec();
+    let mut skipped = Vec::new();
+    for i in incompatible.iter().copied() {
+        let offset = section_offset(&patched, i);
+        if offset != 0 {
+            set_section_offset(&mut patched, i, 0);
+            skipped.push(SkippedSection { name: SECTION_NAMES[i], offset, reason: SkipReason::UnsupportedFormat });
+        }
+    }
+
+    let (rdt, warnings) = read_rdt_tolerant(room_id, &patched)?;
+    let warnings = match warnings {
+        Some(mut warnings) => {
+            skipped.append(&mut warnings.skipped);
+            RdtWarnings { skipped, ..warnings }
+        }
+        None => RdtWarnings {
+            room_id,
+            error: format!("{} rooms are only partially supported", layout.name()),
+            skipped,
+        },
+    };
+
+    Ok((rdt, Some(warnings)))
+}
+
+/// Read an RDT, dropping any sections that prevent it from parsing rather than failing outright
+pub fn read_rdt_tolerant(room_id: RoomId, data: &[u8]) -> Result<(Rdt, Option<RdtWarnings>)> {
+    let error = match Rdt::read(Cursor::new(data)) {
+        Ok(rdt) => return Ok((rdt, None)),
+        Err(e) => anyhow::Error::from(e),
+    };
+
+    if data.len() < HEADER_SIZE {
+        return Err(error);
+    }
+
+    let mut patched = data.to_vec();
+    let mut skipped = Vec::new();
+    for (i, name) in SECTION_NAMES.iter().copied().enumerate() {
+        let offset = section_offset(&patched, i);
+        if offset >= patched.len() {
+            set_section_offset(&mut patched, i, 0);
+            skipped.push(SkippedSection { name, offset, reason: SkipReason::OutOfBounds });
+        }
+    }
+
+    let mut rdt = Rdt::read(Cursor::new(&patched)).ok();
+    let mut blanked = Vec::new();
+    for i in SKIP_ORDER {
+        if rdt.is_some() {
+            break;
+        }
+
+        let offset = section_offset(&patched, i);
+        if offset == 0 {
+            continue;
+        }
+
+        set_section_offset(&mut patched, i, 0);
+        blanked.push((i, offset));
+        rdt = Rdt::read(Cursor::new(&patched)).ok();
+    }
+
+    let Some(mut rdt) = rdt else {
+        return Err(error);
+    };
+
+    // we may have dropped more than we needed to, so put back anything the room still loads with
+    for (i, offset) in blanked {
+        set_section_offset(&mut patched, i, offset);
+        match Rdt::read(Cursor::new(&patched)) {
+            Ok(restored) => rdt = restored,
+            Err(_) => {
+                set_section_offset(&mut patched, i, 0);
+                skipped.push(SkippedSection { name: SECTION_NAMES[i], offset, reason: SkipReason::Unparseable });
+            }
+        }
+    }
+
+    Ok((rdt, Some(RdtWarnings { room_id, error: error.to_string(), skipped })))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeAs {
+    Hex,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+}
+
+impl DecodeAs {
+    pub const fn list() -> [Self; 6] {
+        [Self::Hex, Self::U8, Self::I16, Self::U16, Self::I32, Self::U32]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Hex => "Hex",
+            Self::U8 => "u8",
+            Self::I16 => "i16",
+            Self::U16 => "u16",
+            Self::I32 => "i32",
+            Self::U32 => "u32",
+        }
+    }
+
+    const fn size(&self) -> usize {
+        match self {
+            Self::Hex | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 => 4,
+        }
+    }
+
+    const fn values_per_line(&self) -> usize {
+        match self {
+            Self::Hex => HEX_BYTES_PER_LINE,
+            _ => 8,
+        }
+    }
+
+    pub const fn num_lines(&self, data: &[u8]) -> usize {
+        data.len().div_ceil(self.size() * self.values_per_line())
+    }
+
+    fn format_value(&self, bytes: &[u8]) -> String {
+        match (self, bytes) {
+            (Self::Hex, [b]) => format!("{:02X}", b),
+            (Self::U8, [b]) => format!("{:>3}", b),
+            (Self::I16, &[a, b]) => format!("{:>6}", i16::from_le_bytes([a, b])),
+            (Self::U16, &[a, b]) => format!("{:>5}", u16::from_le_bytes([a, b])),
+            (Self::I32, &[a, b, c, d]) => format!("{:>11}", i32::from_le_bytes([a, b, c, d])),
+            (Self::U32, &[a, b, c, d]) => format!("{:>10}", u32::from_le_bytes([a, b, c, d])),
+            // trailing bytes that don't make up a full value
+            _ => bytes.iter().map(|b| format!("{:02X}", b)).collect(),
+        }
+    }
+
+    /// Format one line of the section, starting from its offset in the section
+    pub fn format_line(&self, data: &[u8], line: usize) -> String {
+        let line_size = self.size() * self.values_per_line();
+        let start = line * line_size;
+        let end = (start + line_size).min(data.len());
+        let Some(bytes) = data.get(start..end) else {
+            return String::new();
+        };
+
+        let values: Vec<_> = bytes.chunks(self.size()).map(|v| self.format_value(v)).collect();
+        let mut text = format!("{:08X}  {}", start, values.join(" "));
+        if *self == Self::Hex {
+            let ascii: String = bytes.iter().map(|b| if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' }).collect();
+            text = format!("{:<width$}  |{}|", text, ascii, width = 10 + HEX_BYTES_PER_LINE * 3 - 1);
+        }
+
+        text
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RawSection {
+    pub name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// An RDT read straight from disk for inspecting its layout, independent of how much of it
+/// re2line actually understands
+#[derive(Debug)]
+pub struct RawRdt {
+    room_id: RoomId,
+    data: Vec<u8>,
+    sections: Vec<RawSection>,
+}
+
+impl RawRdt {
+    pub fn read(room_id: RoomId, path: &Path) -> Result<Self> {
+        Self::from_data(room_id, fs::read(path)?)
+    }
+
+    pub fn from_data(room_id: RoomId, data: Vec<u8>) -> Result<Self> {
+        if data.len() < HEADER_SIZE {
+            bail!("File is too small to be an RDT ({} bytes)", data.len());
+        }
+
+        let offsets: Vec<usize> = (0..NUM_SECTIONS).map(|i| section_offset(&data, i)).collect();
+
+        // sections aren't necessarily stored in header order, so each one runs until whichever
+        // section starts next
+        let mut sections = Vec::with_capacity(NUM_SECTIONS);
+        for (i, offset) in offsets.iter().copied().enumerate() {
+            let size = if offset == 0 || offset >= data.len() {
+                0
+            } else {
+                let end = offsets.iter().copied().filter(|o| *o > offset).min().unwrap_or(data.len()).min(data.len());
+                end - offset
+            };
+
+            sections.push(RawSection { name: SECTION_NAMES[i], offset, size });
+        }
+
+        Ok(Self { room_id, data, sections })
+    }
+
+    pub const fn room_id(&self) -> RoomId {
+        self.room_id
+    }
+
+    pub fn file_size(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn header_counts(&self) -> impl Iterator<Item = (&'static str, u8)> + '_ {
+        HEADER_COUNT_NAMES.into_iter().zip(self.data[..HEADER_COUNTS_SIZE].iter().copied())
+    }
+
+    pub fn sections(&self) -> &[RawSection] {
+        &self.sections
+    }
+
+    pub fn section_data(&self, index: usize) -> &[u8] {
+        let Some(section) = self.sections.get(index) else {
+            return &[];
+        };
+
+        &self.data[section.offset..section.offset + section.size]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECTION_COLLISION: usize = 6;
+    const SECTION_INIT_SCRIPT: usize = 16;
+    const SECTION_EXEC_SCRIPT: usize = 17;
+
+    // an RE3 room with no sections other than its scripts, which RE2's script parser can't read
+    fn re3_rdt() -> Vec<u8> {
+        let mut data = vec![0u8; HEADER_SIZE];
+        // RE3 opcodes that aren't valid RE2 instructions
+        let init_offset = data.len();
+        data.extend_from_slice(&[0xFF; 16]);
+        let exec_offset = data.len();
+        data.extend_from_slice(&[0xFE; 16]);
+        set_section_offset(&mut data, SECTION_INIT_SCRIPT, init_offset);
+        set_section_offset(&mut data, SECTION_EXEC_SCRIPT, exec_offset);
+        data
+    }
+
+    #[test]
+    fn test_read_re3_rdt() {
+        let room_id = RoomId::new(0, 0x00, 0);
+        let (_, warnings) = read_rdt_as(room_id, &re3_rdt(), RdtFormat::Re3).unwrap();
+        let warnings = warnings.unwrap();
+
+        let skipped: Vec<_> = warnings.skipped.iter().map(|s| (s.name, s.reason)).collect();
+        assert_eq!(skipped, [
+            ("Init script", SkipReason::UnsupportedFormat),
+            ("Exec script", SkipReason::UnsupportedFormat),
+        ]);
+        assert_eq!(warnings.error, "RE3 rooms are only partially supported");
+    }
+
+    #[test]
+    fn test_reject_unexpected_header_layout() {
+        let mut data = re3_rdt();
+        let size = data.len();
+        set_section_offset(&mut data, SECTION_COLLISION, size + 0x100);
+        assert!(read_rdt_as(RoomId::zero(), &data, RdtFormat::Re3).is_err());
+
+        set_section_offset(&mut data, SECTION_COLLISION, 4);
+        assert!(read_rdt_as(RoomId::zero(), &data, RdtFormat::Re3).is_err());
+    }
+
+    #[test]
+    fn test_re3_room_id_from_file_name() {
+        let layout = RdtFormat::Re3.layout();
+        assert_eq!(layout.room_id_from_file_name("R10A.RDT"), Some(RoomId::new(0, 0x0A, 0)));
+        assert_eq!(layout.room_id_from_file_name("ROOM10A0.RDT"), None);
+    }
+}
@@ -0,0 +1,103 @@
+use std::ops::Range;
+
+use re2shared::rng::RollType;
+use residat::re2::CharacterId;
+
+use crate::app::GameObject;
+use crate::character::{AiZone, PositionedAiZone};
+use crate::record::Recording;
+use crate::rng::ROLL_DESCRIPTIONS;
+
+/// The odds of each outcome of one of a zone's rolls
+#[derive(Debug, Clone)]
+pub struct RollOdds {
+    pub roll_type: RollType,
+    pub label: String,
+    pub outcomes: Vec<(String, f32)>,
+}
+
+/// Everything known about the selected AI zone, plus where it fired in the recording
+#[derive(Debug, Clone)]
+pub struct ZoneCard {
+    pub zone: &'static AiZone,
+    pub character_id: CharacterId,
+    pub character_index: usize,
+    pub odds: Vec<RollOdds>,
+    /// Stretches of frames where the zone was active with the player inside it. None until the
+    /// recording has been searched.
+    pub firings: Option<Vec<Range<usize>>>,
+}
+
+impl ZoneCard {
+    pub fn new(zone: &PositionedAiZone) -> Self {
+        let odds = zone.ai_zone.rolls.iter().map(|roll_type| {
+            let description = &ROLL_DESCRIPTIONS[*roll_type];
+            RollOdds {
+                roll_type: *roll_type,
+                label: description.label(zone.character_id.name()),
+                outcomes: description.cycle_distribution(),
+            }
+        }).collect();
+
+        Self {
+            zone: zone.ai_zone,
+            character_id: zone.character_id,
+            character_index: zone.character_index,
+            odds,
+            firings: None,
+        }
+    }
+
+    pub fn is_for(&self, zone: &PositionedAiZone) -> bool {
+        std::ptr::eq(self.zone, zone.ai_zone) && self.character_index == zone.character_index && self.character_id == zone.character_id
+    }
+
+    /// Find every stretch of frames in visits to the current room where the same character had
+    /// this zone active with the player inside it
+    pub fn find_firings(&mut self, recording: &mut Recording) {
+        let Some(room_id) = recording.current_state().map(|s| s.room_id()) else {
+            self.firings = Some(Vec::new());
+            return;
+        };
+
+        let visits: Vec<_> = recording.room_visits()
+            .into_iter()
+            .filter(|(state, _)| state.room_id() == room_id)
+            .map(|(_, range)| range)
+            .collect();
+
+        let mut firings: Vec<Range<usize>> = Vec::new();
+        for visit in visits {
+            for i in visit {
+                let Some(state) = recording.set_index(i) else {
+                    continue;
+                };
+
+                let characters = state.characters();
+                let Some(Some(player)) = characters.first() else {
+                    continue;
+                };
+                let Some(Some(character)) = characters.get(self.character_index) else {
+                    continue;
+                };
+                if character.id != self.character_id {
+                    continue;
+                }
+
+                let is_firing = character.ai_zones()
+                    .iter()
+                    .any(|z| std::ptr::eq(z.ai_zone, self.zone) && z.contains_point(player.center()));
+                if !is_firing {
+                    continue;
+                }
+
+                match firings.last_mut() {
+                    Some(range) if range.end == i => range.end = i + 1,
+                    _ => firings.push(i..i + 1),
+                }
+            }
+        }
+
+        self.firings = Some(firings);
+    }
+}
@@ -0,0 +1,81 @@
+use std::io::BufReader;
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{bail, Result};
+use binrw::BinReaderExt;
+use binrw::io::NoSeek;
+use re2shared::record::{FrameRecord, RecordHeader, LIVE_PORT, RECORD_VERSION};
+
+use crate::record::State;
+
+#[derive(Debug, Default)]
+struct Shared {
+    latest: Mutex<Option<(FrameRecord, State)>>,
+    error: Mutex<Option<String>>,
+    is_connected: AtomicBool,
+}
+
+/// A connection to re2fr running in the game, receiving each frame as it's played
+#[derive(Debug)]
+pub struct LiveConnection {
+    stream: TcpStream,
+    shared: Arc<Shared>,
+}
+
+impl LiveConnection {
+    pub fn connect() -> Result<Self> {
+        let stream = TcpStream::connect(("127.0.0.1", LIVE_PORT))?;
+        let reader = stream.try_clone()?;
+
+        let shared = Arc::new(Shared::default());
+        shared.is_connected.store(true, Ordering::Relaxed);
+        let thread_shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            if let Err(e) = Self::read_loop(reader, &thread_shared) {
+                *thread_shared.error.lock().unwrap() = Some(e.to_string());
+            }
+            thread_shared.is_connected.store(false, Ordering::Relaxed);
+        });
+
+        Ok(Self { stream, shared })
+    }
+
+    fn read_loop(stream: TcpStream, shared: &Shared) -> Result<()> {
+        let mut reader = NoSeek::new(BufReader::new(stream));
+        let header: RecordHeader = reader.read_le()?;
+        if header.version != RECORD_VERSION {
+            bail!("The game is recording version {} but this version of re2line expects {}", header.version, RECORD_VERSION);
+        }
+
+        let mut state = State::empty();
+        loop {
+            let frame: FrameRecord = reader.read_le()?;
+            state = state.make_next_state(&frame);
+            *shared.latest.lock().unwrap() = Some((frame, state.clone()));
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.shared.is_connected.load(Ordering::Relaxed)
+    }
+
+    /// The most recent frame received since the last call, if any
+    pub fn take_latest(&self) -> Option<(FrameRecord, State)> {
+        self.shared.latest.lock().unwrap().take()
+    }
+
+    /// Why the connection was lost, if it was due to an error
+    pub fn take_error(&self) -> Option<String> {
+        self.shared.error.lock().unwrap().take()
+    }
+}
+
+impl Drop for LiveConnection {
+    fn drop(&mut self) {
+        // unblocks the reader thread so it can exit
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
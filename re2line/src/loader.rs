@@ -0,0 +1,73 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::Result;
+
+use crate::record::Recording;
+
+// how much of the recording to make available for playback while the rest is still being read
+const PREVIEW_FRAMES: usize = 30 * 60 * 2;
+
+#[derive(Debug, Default)]
+struct Shared {
+    // fraction of the file that's been read, stored as the bits of an f32
+    progress: AtomicU32,
+    preview: Mutex<Option<Recording>>,
+    result: Mutex<Option<Result<Recording>>>,
+}
+
+/// A recording being read on a worker thread so the UI stays responsive. The beginning of the
+/// recording is read first so it can be played while the rest loads.
+#[derive(Debug)]
+pub struct RecordingLoader {
+    path: PathBuf,
+    shared: Arc<Shared>,
+}
+
+impl RecordingLoader {
+    pub fn start(path: PathBuf) -> Self {
+        let shared = Arc::new(Shared::default());
+        let thread_shared = Arc::clone(&shared);
+        let thread_path = path.clone();
+        thread::spawn(move || {
+            let result = Self::read(&thread_path, &thread_shared);
+            *thread_shared.result.lock().unwrap() = Some(result);
+        });
+
+        Self { path, shared }
+    }
+
+    fn read(path: &Path, shared: &Shared) -> Result<Recording> {
+        // if the preview fails, the full read will report the error
+        if let Ok(preview) = Recording::read_partial(File::open(path)?, PREVIEW_FRAMES) {
+            // nothing left to read
+            if preview.frames().len() < PREVIEW_FRAMES {
+                return Ok(preview);
+            }
+            *shared.preview.lock().unwrap() = Some(preview);
+        }
+
+        Recording::read_with_progress(File::open(path)?, |progress| shared.progress.store(progress.to_bits(), Ordering::Relaxed))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn progress(&self) -> f32 {
+        f32::from_bits(self.shared.progress.load(Ordering::Relaxed))
+    }
+
+    /// The beginning of the recording, once it's been read, to play while the rest loads
+    pub fn take_preview(&self) -> Option<Recording> {
+        self.shared.preview.lock().unwrap().take()
+    }
+
+    /// The recording, or the error that kept it from being read, once the worker is done
+    pub fn take_result(&self) -> Option<Result<Recording>> {
+        self.shared.result.lock().unwrap().take()
+    }
+}
@@ -1,6 +1,10 @@
+#[cfg(not(target_arch = "wasm32"))]
 use std::env;
+#[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
 
+mod ammo;
+mod bandwidth;
 mod animation;
 mod app;
 mod collision;
@@ -12,11 +16,79 @@ mod character;
 mod record;
 mod draw;
 mod rng;
+mod trick;
+mod checklist;
+mod fight;
+mod route;
+mod plan;
+mod inspect;
+mod contact;
+mod profile;
+mod server;
+mod live;
+mod bundle;
+mod capture;
+mod dialog;
+mod plugin;
+mod notify;
+mod setup;
+mod savestate;
+mod drill;
+mod encounter;
+mod units;
+mod index;
+#[cfg(not(target_arch = "wasm32"))]
+mod batch;
+mod timing;
+mod knowledge;
+mod rngdiff;
+mod ghost;
+mod progress;
+mod bookmark;
+mod behavior;
+mod overlap;
+mod loader;
+mod notes;
 
+#[cfg(not(target_arch = "wasm32"))]
 fn make_eframe_error(e: anyhow::Error) -> eframe::Error {
     eframe::Error::AppCreation(std::io::Error::new(std::io::ErrorKind::Other, e).into())
 }
 
+/// Run a batch analysis from the command line and return the process exit code
+#[cfg(not(target_arch = "wasm32"))]
+fn run_batch_command(args: &[String]) -> i32 {
+    if let [command, recording] = args && command == "verify" {
+        return match batch::verify_recording(&PathBuf::from(recording)) {
+            Ok(true) => 0,
+            Ok(false) => 1,
+            Err(e) => {
+                eprintln!("{}", e);
+                1
+            }
+        };
+    }
+
+    let [game_folder, analysis, argument] = args else {
+        eprintln!("{}", batch::BATCH_USAGE);
+        return 2;
+    };
+
+    let result = batch::BatchQuery::parse(analysis, argument)
+        .and_then(|query| batch::run_batch(&PathBuf::from(game_folder), query));
+    match result {
+        Ok(results) => {
+            batch::print_batch_results(&results);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result {
     //rng::sim::simulate_bus_rng();
     //rng::sim::find_runs();
@@ -26,6 +98,9 @@ fn main() -> eframe::Result {
     //return Ok(());
     
     let args: Vec<String> = env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "--batch") {
+        std::process::exit(run_batch_command(&args[2..]));
+    }
 
     let mut app = app::App::new().map_err(make_eframe_error)?;
     if args.len() > 1 {
@@ -40,3 +115,29 @@ fn main() -> eframe::Result {
     
     eframe::run_native(app::APP_NAME, eframe::NativeOptions::default(), Box::new(|_| Ok(Box::new(app))))
 }
+
+/// Entry point for the web viewer. See index.html for how to build it.
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use eframe::wasm_bindgen::JsCast;
+
+    wasm_bindgen_futures::spawn_local(async {
+        let document = web_sys::window().expect("No window").document().expect("No document");
+        let canvas = document
+            .get_element_by_id("re2line_canvas")
+            .expect("Missing re2line_canvas element")
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .expect("re2line_canvas is not a canvas");
+
+        let result = match app::App::new() {
+            Ok(app) => eframe::WebRunner::new()
+                .start(canvas, eframe::WebOptions::default(), Box::new(|_| Ok(Box::new(app))))
+                .await,
+            Err(e) => Err(e.to_string().into()),
+        };
+
+        if let Err(e) = result {
+            web_sys::console::error_1(&e);
+        }
+    });
+}
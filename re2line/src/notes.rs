@@ -0,0 +1,73 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::record::Recording;
+
+/// A link to a frame of the recording file. There's no standard way to point into a file, so the
+/// frame goes in the fragment.
+fn frame_link(path: Option<&Path>, frame_index: usize) -> String {
+    let Some(path) = path else {
+        return format!("frame {frame_index}");
+    };
+
+    let path = path.to_string_lossy().replace('\\', "/");
+    let slash = if path.starts_with('/') { "" } else { "/" };
+    // angle brackets let the link contain spaces
+    format!("[frame {frame_index}](<file://{slash}{path}#frame={frame_index}>)")
+}
+
+fn timestamp(recording: &Recording, frame_index: usize) -> String {
+    recording.frames().get(frame_index).map(|f| f.time()).unwrap_or_default()
+}
+
+/// Write the recording's bookmarks, markers, runs, and room visits as a Markdown document
+pub fn write_notes(recording: &Recording, path: Option<&Path>, mut f: impl Write) -> Result<()> {
+    let title = path
+        .and_then(Path::file_name)
+        .map_or_else(|| String::from("Recording"), |n| n.to_string_lossy().into_owned());
+
+    writeln!(f, "# {title}")?;
+
+    let start_times: Vec<_> = recording.sessions().iter().filter_map(|s| s.start_time.as_deref()).collect();
+    if !start_times.is_empty() {
+        writeln!(f, "\nRecorded {}", start_times.join(", "))?;
+    }
+
+    writeln!(f, "\n## Bookmarks\n")?;
+    if recording.bookmarks().is_empty() {
+        writeln!(f, "None")?;
+    }
+    for bookmark in recording.bookmarks() {
+        writeln!(f, "- `{}` {}: **{}**", timestamp(recording, bookmark.frame_index), frame_link(path, bookmark.frame_index), bookmark.name)?;
+        for line in bookmark.note.lines().filter(|l| !l.trim().is_empty()) {
+            writeln!(f, "  {line}")?;
+        }
+    }
+
+    if !recording.markers().is_empty() {
+        writeln!(f, "\n## Markers\n")?;
+        for marker in recording.markers() {
+            writeln!(f, "- `{}` {}: {} in {}", timestamp(recording, marker.frame_index), frame_link(path, marker.frame_index), marker.name, marker.room_id)?;
+        }
+    }
+
+    let runs = recording.run_summaries();
+    if !runs.is_empty() {
+        writeln!(f, "\n## Runs\n")?;
+        for run in runs {
+            writeln!(f, "- `{}` {}: {} ({} frames)", timestamp(recording, run.frames.start), frame_link(path, run.frames.start), run.name(), run.frames.len())?;
+        }
+    }
+
+    let visits = recording.room_visits();
+    if !visits.is_empty() {
+        writeln!(f, "\n## Rooms\n")?;
+        for (state, range) in visits {
+            writeln!(f, "- `{}` {}: {} ({} frames)", timestamp(recording, range.start), frame_link(path, range.start), state.room_id(), range.len())?;
+        }
+    }
+
+    Ok(())
+}
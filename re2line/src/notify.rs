@@ -0,0 +1,185 @@
+use anyhow::Result;
+#[cfg(not(target_arch = "wasm32"))]
+use rodio::{OutputStream, OutputStreamHandle, Source};
+#[cfg(not(target_arch = "wasm32"))]
+use rodio::source::SineWave;
+use serde::{Deserialize, Serialize};
+
+use crate::character::CharacterType;
+use crate::record::State;
+
+#[cfg(not(target_arch = "wasm32"))]
+const CUE_DURATION: std::time::Duration = std::time::Duration::from_millis(200);
+#[cfg(not(target_arch = "wasm32"))]
+const CUE_VOLUME: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleCondition {
+    RngIndexInRange { min: usize, max: usize },
+    EnemyHealthBelow(i16),
+    PlayerHealthBelow(i16),
+    EnemyCountAtLeast(usize),
+}
+
+impl RuleCondition {
+    /// Each kind of condition with some reasonable starting values
+    pub const fn list() -> [Self; 4] {
+        [
+            Self::RngIndexInRange { min: 0, max: 0 },
+            Self::EnemyHealthBelow(50),
+            Self::PlayerHealthBelow(100),
+            Self::EnemyCountAtLeast(1),
+        ]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::RngIndexInRange { .. } => "RNG index in range",
+            Self::EnemyHealthBelow(_) => "Any enemy HP below",
+            Self::PlayerHealthBelow(_) => "Player HP below",
+            Self::EnemyCountAtLeast(_) => "Enemy count at least",
+        }
+    }
+
+    pub const fn is_same_kind(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::RngIndexInRange { .. }, Self::RngIndexInRange { .. })
+            | (Self::EnemyHealthBelow(_), Self::EnemyHealthBelow(_))
+            | (Self::PlayerHealthBelow(_), Self::PlayerHealthBelow(_))
+            | (Self::EnemyCountAtLeast(_), Self::EnemyCountAtLeast(_))
+        )
+    }
+
+    pub fn matches(&self, state: &State) -> bool {
+        let mut enemies = state.characters()
+            .iter()
+            .flatten()
+            .filter(|c| c.type_() == CharacterType::Enemy && c.is_enabled());
+
+        match self {
+            Self::RngIndexInRange { min, max } => (*min..=*max).contains(&state.rng_index()),
+            Self::EnemyHealthBelow(health) => enemies.any(|c| c.current_health() < *health),
+            Self::PlayerHealthBelow(health) => matches!(state.characters().first(), Some(Some(player)) if player.current_health() < *health),
+            Self::EnemyCountAtLeast(count) => enemies.count() >= *count,
+        }
+    }
+}
+
+/// A tone played when a rule starts matching, so different rules can be told apart by ear
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SoundCue {
+    Low,
+    Medium,
+    High,
+}
+
+impl SoundCue {
+    pub const fn list() -> [Self; 3] {
+        [Self::Low, Self::Medium, Self::High]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Low => "Low tone",
+            Self::Medium => "Medium tone",
+            Self::High => "High tone",
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    const fn frequency(&self) -> f32 {
+        match self {
+            Self::Low => 330.0,
+            Self::Medium => 660.0,
+            Self::High => 1320.0,
+        }
+    }
+}
+
+/// Plays sound cues. The audio device is only opened the first time a cue plays.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+pub struct CuePlayer {
+    // the stream stops playing when it's dropped, so it has to be kept around
+    output: Option<(OutputStream, OutputStreamHandle)>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl CuePlayer {
+    pub fn play(&mut self, cue: SoundCue) -> Result<()> {
+        let (_, handle) = match self.output {
+            Some(ref output) => output,
+            None => self.output.insert(OutputStream::try_default()?),
+        };
+
+        let tone = SineWave::new(cue.frequency()).take_duration(CUE_DURATION).amplify(CUE_VOLUME);
+        handle.play_raw(tone)?;
+        Ok(())
+    }
+}
+
+/// The web viewer has no audio output, so cues are silent there
+#[cfg(target_arch = "wasm32")]
+#[derive(Default)]
+pub struct CuePlayer;
+
+#[cfg(target_arch = "wasm32")]
+impl CuePlayer {
+    pub fn play(&mut self, _cue: SoundCue) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A condition to watch for during playback, with how loudly to announce it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRule {
+    pub name: String,
+    pub condition: RuleCondition,
+    pub is_enabled: bool,
+    /// Also ask the OS to get the user's attention, e.g. by flashing the taskbar
+    pub request_attention: bool,
+    #[serde(default)]
+    pub sound: Option<SoundCue>,
+}
+
+impl NotificationRule {
+    pub const fn new(name: String) -> Self {
+        Self {
+            name,
+            condition: RuleCondition::EnemyHealthBelow(50),
+            is_enabled: true,
+            request_attention: false,
+            sound: None,
+        }
+    }
+}
+
+/// Keeps track of which rules were already satisfied so we only notify when a rule starts matching
+#[derive(Debug, Default)]
+pub struct RuleTracker {
+    was_matching: Vec<bool>,
+}
+
+impl RuleTracker {
+    pub fn reset(&mut self) {
+        self.was_matching.clear();
+    }
+
+    /// Check the rules against a new state, returning the indexes of the rules that just started
+    /// matching
+    pub fn update(&mut self, rules: &[NotificationRule], state: &State) -> Vec<usize> {
+        self.was_matching.resize(rules.len(), false);
+
+        let mut triggered = Vec::new();
+        for (i, rule) in rules.iter().enumerate() {
+            let is_matching = rule.is_enabled && rule.condition.matches(state);
+            if is_matching && !self.was_matching[i] {
+                triggered.push(i);
+            }
+            self.was_matching[i] = is_matching;
+        }
+
+        triggered
+    }
+}
@@ -0,0 +1,128 @@
+use residat::common::{Fixed32, Vec2};
+
+use crate::aot::Entity;
+use crate::app::{GameObject, ObjectType};
+
+// spacing of the grid of points used to estimate how much of two AOTs' areas are shared
+const SAMPLE_SPACING: i32 = 50;
+// an AOT with at least this much of its area inside a higher-priority one is treated as fully
+// shadowed, since the sampling can't resolve the last sliver of an edge
+const FULLY_SHADOWED_FRACTION: f32 = 0.98;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapKind {
+    /// The lower-priority AOT can never fire because the higher-priority one covers all of it
+    Shadowed,
+    /// Both AOTs fire the same way, so the higher-priority one wins where they overlap
+    Overlapping,
+    /// One fires on entry and the other on the action button, so which one fires in the shared
+    /// area depends on how the player gets there
+    Conflicting,
+}
+
+impl OverlapKind {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Shadowed => "Shadowed",
+            Self::Overlapping => "Overlapping",
+            Self::Conflicting => "Conflicting activation",
+        }
+    }
+}
+
+/// Two player-triggered AOTs whose areas overlap
+#[derive(Debug, Clone)]
+pub struct AotOverlap {
+    /// Index of the higher-priority entity
+    pub first: usize,
+    /// Index of the lower-priority entity
+    pub second: usize,
+    pub kind: OverlapKind,
+    /// How much of the lower-priority AOT's area is also inside the higher-priority one
+    pub shared_fraction: f32,
+}
+
+/// Player-triggered AOTs in the order the game checks them, and every pair of them that overlaps
+#[derive(Debug, Clone, Default)]
+pub struct AotOverlapReport {
+    /// Entity indexes, highest priority first
+    pub priority: Vec<usize>,
+    pub overlaps: Vec<AotOverlap>,
+}
+
+impl AotOverlapReport {
+    pub fn analyze(entities: &[Entity]) -> Self {
+        // the game walks the AOT slots in order and stops at the first one that fires
+        let mut priority: Vec<usize> = entities.iter()
+            .enumerate()
+            .filter(|(_, e)| e.sce().is_trigger() && e.can_object_type_trigger(ObjectType::Player))
+            .map(|(i, _)| i)
+            .collect();
+        priority.sort_by_key(|i| (entities[*i].id(), *i));
+
+        let mut overlaps = Vec::new();
+        for (n, &first) in priority.iter().enumerate() {
+            for &second in &priority[n + 1..] {
+                if let Some(overlap) = Self::compare(entities, first, second) {
+                    overlaps.push(overlap);
+                }
+            }
+        }
+
+        Self { priority, overlaps }
+    }
+
+    fn bounds(entity: &Entity) -> (i32, i32, i32, i32) {
+        entity.collider().corners().into_iter().fold(
+            (i32::MAX, i32::MAX, i32::MIN, i32::MIN),
+            |(min_x, min_z, max_x, max_z), corner| {
+                (min_x.min(corner.x.0), min_z.min(corner.z.0), max_x.max(corner.x.0), max_z.max(corner.z.0))
+            },
+        )
+    }
+
+    fn sample_points(bounds: (i32, i32, i32, i32)) -> impl Iterator<Item = Vec2> {
+        let (min_x, min_z, max_x, max_z) = bounds;
+        (min_x..=max_x).step_by(SAMPLE_SPACING as usize)
+            .flat_map(move |x| (min_z..=max_z).step_by(SAMPLE_SPACING as usize).map(move |z| Vec2::new(Fixed32(x), Fixed32(z))))
+    }
+
+    fn compare(entities: &[Entity], first: usize, second: usize) -> Option<AotOverlap> {
+        let (a, b) = (&entities[first], &entities[second]);
+        if !a.floor().matches(b.floor()) {
+            return None;
+        }
+
+        let (a_min_x, a_min_z, a_max_x, a_max_z) = Self::bounds(a);
+        let b_bounds = Self::bounds(b);
+        let (b_min_x, b_min_z, b_max_x, b_max_z) = b_bounds;
+        let shared_bounds = (a_min_x.max(b_min_x), a_min_z.max(b_min_z), a_max_x.min(b_max_x), a_max_z.min(b_max_z));
+        if shared_bounds.0 > shared_bounds.2 || shared_bounds.1 > shared_bounds.3 {
+            return None;
+        }
+
+        let num_shared = Self::sample_points(shared_bounds).filter(|p| a.contains_point(*p) && b.contains_point(*p)).count();
+        if num_shared == 0 {
+            return None;
+        }
+
+        // very thin AOTs may not have any sample points inside them at all
+        let num_second = Self::sample_points(b_bounds).filter(|p| b.contains_point(*p)).count().max(num_shared);
+        let shared_fraction = num_shared as f32 / num_second as f32;
+
+        let kind = if a.is_trigger_on_action() != b.is_trigger_on_action() || a.is_trigger_on_enter() != b.is_trigger_on_enter() {
+            OverlapKind::Conflicting
+        } else if shared_fraction >= FULLY_SHADOWED_FRACTION {
+            OverlapKind::Shadowed
+        } else {
+            OverlapKind::Overlapping
+        };
+
+        Some(AotOverlap { first, second, kind, shared_fraction })
+    }
+
+    /// Where the entity falls in the order the game checks AOTs, starting from 1
+    pub fn rank(&self, entity_index: usize) -> Option<usize> {
+        self.priority.iter().position(|i| *i == entity_index).map(|i| i + 1)
+    }
+}
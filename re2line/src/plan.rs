@@ -0,0 +1,396 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use residat::common::{Fixed32, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{GameObject, RoomId};
+use crate::character::Character;
+use crate::collision::Collider;
+use crate::record::DoorTimes;
+
+// how far apart to check for collisions along a leg when we don't know how fast the player moves
+const DEFAULT_STEP: Fixed32 = Fixed32(100);
+// how far collision can push the player off the line before we consider the leg blocked
+const BLOCKED_TOLERANCE: Fixed32 = Fixed32(10);
+// how far from a corner the recorded path is considered to be taking that corner
+const CORNER_RADIUS: f32 = 2000.0;
+// extra room to leave between the player and the corner, doubled each time the line is still blocked
+const CORNER_MARGIN: f32 = 25.0;
+const MAX_CORNER_ATTEMPTS: usize = 5;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WaypointRecord {
+    room: String,
+    x: i32,
+    z: i32,
+    // only present in exported character paths; ignored when importing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    frame: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Waypoint {
+    pub room_id: RoomId,
+    pub pos: Vec2,
+}
+
+impl TryFrom<WaypointRecord> for Waypoint {
+    type Error = anyhow::Error;
+
+    fn try_from(value: WaypointRecord) -> Result<Self> {
+        Ok(Self {
+            room_id: RoomId::from_str(value.room.trim())?,
+            pos: Vec2::new(Fixed32(value.x), Fixed32(value.z)),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedLeg {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub distance: Fixed32,
+    /// Estimated frames to reach the end of this leg from the first waypoint in the room
+    pub total_frames: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DrawnLeg {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub distance: Fixed32,
+    /// The direction the player has to face to follow this leg
+    pub heading: Fixed32,
+    /// How far the player has to turn at the start of this leg, in degrees
+    pub turn_degrees: f32,
+    pub frames: Option<usize>,
+    /// Where collision stops the player from following this leg, if anywhere
+    pub blocked_at: Option<Vec2>,
+}
+
+/// A route drawn by clicking waypoints on the map
+#[derive(Debug, Clone, Default)]
+pub struct DrawnRoute {
+    pub points: Vec<Vec2>,
+}
+
+impl DrawnRoute {
+    fn angle_of(v: Vec2) -> i32 {
+        Vec2::zero().angle_between(&v).0 & 0xfff
+    }
+
+    /// Walk each leg with the movement model, checking whether collision would get in the way
+    fn find_block(start: Vec2, end: Vec2, step: Fixed32, player: &Character, colliders: &[Collider], room_center: Vec2) -> Option<Vec2> {
+        let delta = end - start;
+        let distance = delta.len();
+        let num_steps = (distance.0 / step.0.max(1)).max(1);
+        let mut pos = start;
+        for i in 1..=num_steps {
+            let target = Vec2::new(
+                Fixed32(start.x.0 + (delta.x.0 as i64 * i as i64 / num_steps as i64) as i32),
+                Fixed32(start.z.0 + (delta.z.0 as i64 * i as i64 / num_steps as i64) as i32),
+            );
+
+            let mut motion = player.motion_between(pos, target);
+            motion.origin.set_quadrant_mask(room_center);
+            for collider in colliders {
+                if collider.floor().matches(player.floor()) {
+                    motion.to = collider.clip_motion(&motion);
+                }
+            }
+
+            if (motion.to - target).len() > BLOCKED_TOLERANCE {
+                return Some(motion.to);
+            }
+            pos = target;
+        }
+
+        None
+    }
+
+    pub fn legs(&self, player: Option<&Character>, colliders: &[Collider], room_center: Vec2, speed: Option<Fixed32>, turn_rate: Option<Fixed32>) -> Vec<DrawnLeg> {
+        let speed = speed.filter(|s| s.0 > 0);
+        let turn_rate = turn_rate.filter(|r| r.0 > 0);
+        let mut legs = Vec::with_capacity(self.points.len().saturating_sub(1));
+        let mut last_angle = None;
+        for pair in self.points.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            let distance = (end - start).len();
+            let angle = Self::angle_of(end - start);
+            let turn = last_angle.map(|last: i32| {
+                let delta = (angle - last) & 0xfff;
+                delta.min(0x1000 - delta)
+            }).unwrap_or(0);
+            last_angle = Some(angle);
+
+            let move_frames = speed.map(|s| (distance.0 as f32 / s.0 as f32).ceil() as usize);
+            let turn_frames = match turn_rate {
+                Some(rate) => Some((turn as f32 / rate.0 as f32).ceil() as usize),
+                None if turn == 0 => Some(0),
+                None => None,
+            };
+            let blocked_at = player.and_then(|p| Self::find_block(start, end, speed.unwrap_or(DEFAULT_STEP), p, colliders, room_center));
+
+            legs.push(DrawnLeg {
+                start,
+                end,
+                distance,
+                heading: Fixed32(angle),
+                turn_degrees: Fixed32(turn).to_degrees(),
+                frames: move_frames.zip(turn_frames).map(|(m, t)| m + t),
+                blocked_at,
+            });
+        }
+
+        legs
+    }
+}
+
+/// The tightest line around a collider's corner compared to the line the player actually took
+#[derive(Debug, Clone)]
+pub struct CornerCut {
+    pub corner: Vec2,
+    pub entry_frame: usize,
+    pub exit_frame: usize,
+    pub optimal: Vec<DrawnLeg>,
+}
+
+impl CornerCut {
+    pub const fn recorded_frames(&self) -> usize {
+        self.exit_frame - self.entry_frame
+    }
+
+    pub fn optimal_frames(&self) -> Option<usize> {
+        self.optimal.iter().map(|leg| leg.frames).sum()
+    }
+
+    pub fn lost_frames(&self) -> Option<isize> {
+        self.optimal_frames().map(|f| self.recorded_frames() as isize - f as isize)
+    }
+
+    fn distance(a: Vec2, b: Vec2) -> f32 {
+        (b - a).len().0 as f32
+    }
+
+    /// Find where the recorded path goes around the corner of the collider closest to it and
+    /// work out the tightest collision-free line between the same entry and exit points
+    pub fn analyze(
+        path: &[(usize, Vec2)], collider: &Collider, player: &Character, colliders: &[Collider], room_center: Vec2,
+        speed: Option<Fixed32>, turn_rate: Option<Fixed32>,
+    ) -> Option<Self> {
+        let corners = collider.corners();
+        let (closest, corner) = corners.iter()
+            .flat_map(|corner| path.iter().enumerate().map(move |(i, (_, pos))| (i, *corner, Self::distance(*pos, *corner))))
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(i, corner, _)| (i, corner))?;
+
+        let mut entry = closest;
+        while entry > 0 && Self::distance(path[entry - 1].1, corner) <= CORNER_RADIUS {
+            entry -= 1;
+        }
+        let mut exit = closest;
+        while exit + 1 < path.len() && Self::distance(path[exit + 1].1, corner) <= CORNER_RADIUS {
+            exit += 1;
+        }
+        if entry == exit {
+            return None;
+        }
+
+        let (start, end) = (path[entry].1, path[exit].1);
+
+        // push the corner away from the middle of the collider by enough to clear the player
+        let num_corners = corners.len() as i32;
+        let center = Vec2::new(
+            Fixed32(corners.iter().map(|c| c.x.0).sum::<i32>() / num_corners),
+            Fixed32(corners.iter().map(|c| c.z.0).sum::<i32>() / num_corners),
+        );
+        let outward = corner - center;
+        let outward_len = (outward.len().0 as f32).max(1.0);
+        let clearance = player.size.x.0.max(player.size.z.0) as f32;
+
+        let mut candidates = vec![vec![start, end]];
+        let mut margin = CORNER_MARGIN;
+        for _ in 0..MAX_CORNER_ATTEMPTS {
+            let scale = (clearance + margin) / outward_len;
+            let waypoint = Vec2::new(
+                Fixed32(corner.x.0 + (outward.x.0 as f32 * scale) as i32),
+                Fixed32(corner.z.0 + (outward.z.0 as f32 * scale) as i32),
+            );
+            candidates.push(vec![start, waypoint, end]);
+            margin *= 2.0;
+        }
+
+        let optimal = candidates.into_iter()
+            .map(|points| DrawnRoute { points }.legs(Some(player), colliders, room_center, speed, turn_rate))
+            .find(|legs| legs.iter().all(|leg| leg.blocked_at.is_none()))?;
+
+        Some(Self {
+            corner,
+            entry_frame: path[entry].0,
+            exit_frame: path[exit].0,
+            optimal,
+        })
+    }
+}
+
+/// A route planned outside of re2line, as a list of waypoints per room
+#[derive(Debug, Clone)]
+pub struct PlannedRoute {
+    pub name: String,
+    waypoints: Vec<Waypoint>,
+}
+
+impl PlannedRoute {
+    fn parse_csv(text: &str) -> Result<Vec<WaypointRecord>> {
+        let mut records = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<_> = line.split(',').map(str::trim).collect();
+            let [room, x, z, ..] = fields[..] else {
+                bail!("Line {}: expected room, x, and z", i + 1);
+            };
+
+            let (Ok(x), Ok(z)) = (x.parse(), z.parse()) else {
+                // allow a header row
+                if i == 0 {
+                    continue;
+                }
+                bail!("Line {}: invalid coordinates", i + 1);
+            };
+
+            records.push(WaypointRecord { room: room.to_string(), x, z, frame: None });
+        }
+
+        Ok(records)
+    }
+
+    /// Load a route from a JSON array of {"room", "x", "z"} objects or a CSV file with room, x, and
+    /// z columns
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let records: Vec<WaypointRecord> = if is_json {
+            serde_json::from_str(&text)?
+        } else {
+            Self::parse_csv(&text)?
+        };
+
+        let waypoints = records.into_iter().map(Waypoint::try_from).collect::<Result<Vec<_>>>()?;
+        if waypoints.is_empty() {
+            return Err(anyhow!("Route contains no waypoints"));
+        }
+
+        Ok(Self {
+            name: path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+            waypoints,
+        })
+    }
+
+    /// Write a character's path as a polyline with a frame index per point, in either of the
+    /// formats load understands, so it can be plotted elsewhere or imported as a planned route
+    pub fn export_path(path: &Path, room_id: RoomId, points: &[(usize, Vec2)]) -> Result<()> {
+        let records: Vec<WaypointRecord> = points.iter()
+            .map(|(frame, pos)| WaypointRecord { room: room_id.to_string(), x: pos.x.0, z: pos.z.0, frame: Some(*frame) })
+            .collect();
+
+        let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let text = if is_json {
+            serde_json::to_string_pretty(&records)?
+        } else {
+            let mut text = String::from("room,x,z,frame\n");
+            for record in &records {
+                text.push_str(&format!("{},{},{},{}\n", record.room, record.x, record.z, record.frame.unwrap_or_default()));
+            }
+            text
+        };
+
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn rooms(&self) -> Vec<RoomId> {
+        let mut rooms: Vec<RoomId> = Vec::new();
+        for waypoint in &self.waypoints {
+            if !rooms.contains(&waypoint.room_id) {
+                rooms.push(waypoint.room_id);
+            }
+        }
+
+        rooms
+    }
+
+    pub fn room_waypoints(&self, room_id: RoomId) -> impl Iterator<Item = Vec2> + '_ {
+        self.waypoints.iter().filter(move |w| w.room_id == room_id).map(|w| w.pos)
+    }
+
+    /// Split the route through a room into legs, estimating how long each takes at the given speed
+    pub fn legs(&self, room_id: RoomId, speed: Option<Fixed32>) -> Vec<PlannedLeg> {
+        let points: Vec<_> = self.room_waypoints(room_id).collect();
+        let mut distance_so_far = 0.0f32;
+        let mut legs = Vec::with_capacity(points.len().saturating_sub(1));
+        for pair in points.windows(2) {
+            let distance = (pair[1] - pair[0]).len();
+            distance_so_far += distance.to_f32();
+            legs.push(PlannedLeg {
+                start: pair[0],
+                end: pair[1],
+                distance,
+                total_frames: speed.filter(|s| s.0 > 0).map(|s| (distance_so_far / s.to_f32()).ceil() as usize),
+            });
+        }
+
+        legs
+    }
+
+    /// Each place the route goes through a door, with the average frames the transition took in the
+    /// loaded recordings, if any of them went through it
+    pub fn door_transitions(&self, door_times: &DoorTimes) -> Vec<(RoomId, RoomId, Option<f32>)> {
+        self.waypoints.windows(2)
+            .filter(|pair| pair[0].room_id != pair[1].room_id)
+            .map(|pair| {
+                let (from, to) = (pair[0].room_id, pair[1].room_id);
+                (from, to, door_times.average_frames(from, to).map(|(frames, _)| frames))
+            })
+            .collect()
+    }
+
+    /// Estimated frames for the whole route: the time to walk each room plus the average time of
+    /// each door transition between them. None if any part of the route can't be estimated.
+    pub fn total_frames(&self, speed: Option<Fixed32>, door_times: &DoorTimes) -> Option<usize> {
+        let mut total = 0.0f32;
+        for room_id in self.rooms() {
+            if let Some(leg) = self.legs(room_id, speed).last() {
+                total += leg.total_frames? as f32;
+            }
+        }
+
+        for (_, _, frames) in self.door_transitions(door_times) {
+            total += frames?;
+        }
+
+        Some(total.round() as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let records = PlannedRoute::parse_csv("room,x,z\n\n101, -1500, 2000\n102,0,0,extra\n").unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].room.as_str(), records[0].x, records[0].z), ("101", -1500, 2000));
+        assert_eq!(records[1].room, "102");
+
+        assert!(PlannedRoute::parse_csv("101,0,0\n102,a,0").is_err());
+        assert!(PlannedRoute::parse_csv("101,0").is_err());
+    }
+}
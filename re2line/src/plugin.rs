@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use egui::Ui;
+
+use crate::app::RoomId;
+use crate::character::CharacterType;
+use crate::record::{Recording, State};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod external;
+
+/// What a plugin gets to look at each time its tab is drawn
+#[derive(Debug, Clone, Copy)]
+pub struct PluginContext<'a> {
+    pub room_id: Option<RoomId>,
+    pub recording: Option<&'a Recording>,
+    pub recording_path: Option<&'a Path>,
+    /// The state at the recording's current frame
+    pub state: Option<&'a State>,
+}
+
+impl PluginContext<'_> {
+    pub fn frame_index(&self) -> Option<usize> {
+        self.recording.map(Recording::index)
+    }
+}
+
+/// Something a plugin wants the app to do in response to its UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginAction {
+    JumpToFrame(usize),
+}
+
+/// An analysis that contributes its own tab to the browser panel
+pub trait Plugin {
+    fn name(&self) -> &str;
+
+    /// Whether the tab should only be shown while a recording is open
+    fn needs_recording(&self) -> bool {
+        true
+    }
+
+    fn show(&mut self, ui: &mut Ui, context: &PluginContext) -> Option<PluginAction>;
+}
+
+/// All plugins built into this copy of the app, followed by any external plugins found in the plugin
+/// folder. To build a plugin in, implement the Plugin trait and add it to this list. External
+/// plugins are JSON files like {"name": "My tab", "command": "python", "args": ["my_tab.py"]}; see
+/// ExternalPlugin for what the program is given and how its output is shown.
+pub fn registered_plugins() -> Vec<Box<dyn Plugin>> {
+    let mut plugins: Vec<Box<dyn Plugin>> = vec![
+        Box::new(FrameSummary),
+    ];
+    plugins.extend(external_plugins());
+    plugins
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn external_plugins() -> Vec<Box<dyn Plugin>> {
+    external::discover_plugins().into_iter().map(|p| Box::new(p) as Box<dyn Plugin>).collect()
+}
+
+// the web viewer can't run other programs
+#[cfg(target_arch = "wasm32")]
+fn external_plugins() -> Vec<Box<dyn Plugin>> {
+    Vec::new()
+}
+
+/// A minimal example plugin showing an overview of the current frame
+struct FrameSummary;
+
+impl Plugin for FrameSummary {
+    fn name(&self) -> &str {
+        "Frame"
+    }
+
+    fn show(&mut self, ui: &mut Ui, context: &PluginContext) -> Option<PluginAction> {
+        let (Some(recording), Some(state)) = (context.recording, context.state) else {
+            ui.label("No frame loaded");
+            return None;
+        };
+
+        let mut action = None;
+        let index = recording.index();
+        let num_frames = recording.frames().len();
+
+        egui::Grid::new("plugin_frame_summary").num_columns(2).striped(true).show(ui, |ui| {
+            ui.label("Frame");
+            ui.label(format!("{} / {}", index, num_frames));
+            ui.end_row();
+
+            ui.label("Room");
+            ui.label(state.room_id().to_string());
+            ui.end_row();
+
+            ui.label("RNG index");
+            ui.label(state.rng_index().to_string());
+            ui.end_row();
+
+            let characters = state.characters();
+            if let Some(Some(player)) = characters.first() {
+                let center = player.center();
+                ui.label("Player");
+                ui.label(format!("({}, {}) {} HP", center.x, center.z, player.current_health()));
+                ui.end_row();
+            }
+
+            ui.label("Enemies");
+            ui.label(characters.iter().flatten().filter(|c| c.type_() == CharacterType::Enemy).count().to_string());
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(index > 0, egui::Button::new("Previous")).clicked() {
+                action = Some(PluginAction::JumpToFrame(index - 1));
+            }
+
+            if ui.add_enabled(index + 1 < num_frames, egui::Button::new("Next")).clicked() {
+                action = Some(PluginAction::JumpToFrame(index + 1));
+            }
+        });
+
+        action
+    }
+}
@@ -0,0 +1,233 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use egui::{Color32, RichText, Ui};
+use serde::{Deserialize, Serialize};
+
+use super::{Plugin, PluginAction, PluginContext};
+
+const PLUGIN_DIR_NAME: &str = "re2line_plugins";
+// a plugin that takes longer than this is assumed to be stuck and is killed
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(30);
+// how often to check whether a running plugin has finished
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+type PluginOutput = Result<Vec<String>, String>;
+
+/// How to run a plugin, from a JSON file in the plugin folder
+#[derive(Debug, Clone, Deserialize)]
+struct PluginManifest {
+    name: String,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default = "default_needs_recording")]
+    needs_recording: bool,
+}
+
+const fn default_needs_recording() -> bool {
+    true
+}
+
+/// What the plugin program is given on stdin
+#[derive(Debug, Serialize)]
+struct PluginInput<'a> {
+    room: Option<String>,
+    recording: Option<&'a Path>,
+    frame: Option<usize>,
+    num_frames: Option<usize>,
+}
+
+fn read_pipe(mut pipe: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+/// A plugin program running on a worker thread so a slow plugin doesn't freeze the viewer
+#[derive(Debug)]
+struct PluginRun {
+    result: Arc<Mutex<Option<PluginOutput>>>,
+}
+
+impl PluginRun {
+    fn start(manifest: PluginManifest, dir: PathBuf, input: Vec<u8>) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let thread_result = Arc::clone(&result);
+        thread::spawn(move || {
+            let output = Self::run(&manifest, &dir, &input).map_err(|e| e.to_string());
+            *thread_result.lock().unwrap() = Some(output);
+        });
+
+        Self { result }
+    }
+
+    fn run(manifest: &PluginManifest, dir: &Path, input: &[u8]) -> Result<Vec<String>> {
+        let mut child = Command::new(&manifest.command)
+            .args(&manifest.args)
+            .current_dir(dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // a plugin that doesn't care about its input may exit without reading it
+            let _ = stdin.write_all(input);
+        }
+
+        // drain the output as it comes so a plugin that prints a lot can't fill the pipe and stall
+        let stdout = child.stdout.take().map(read_pipe);
+        let stderr = child.stderr.take().map(read_pipe);
+
+        let start = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if start.elapsed() > PLUGIN_TIMEOUT {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!("{} didn't finish within {} seconds", manifest.name, PLUGIN_TIMEOUT.as_secs());
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        let stdout = stdout.and_then(|h| h.join().ok()).unwrap_or_default();
+        let stderr = stderr.and_then(|h| h.join().ok()).unwrap_or_default();
+        if !status.success() {
+            bail!("{} failed ({}): {}", manifest.name, status, String::from_utf8_lossy(&stderr).trim());
+        }
+
+        Ok(String::from_utf8_lossy(&stdout).lines().map(str::to_string).collect())
+    }
+
+    /// The plugin's output, or why it failed, once it's done
+    fn take_result(&self) -> Option<PluginOutput> {
+        self.result.lock().unwrap().take()
+    }
+}
+
+/// A plugin that runs an external program when its Run button is clicked. The program gets the
+/// current room, recording path, and frame as JSON on stdin, and each line it prints is shown in the
+/// tab. Lines of the form "<frame index>\t<text>" jump to that frame when clicked.
+#[derive(Debug)]
+pub struct ExternalPlugin {
+    manifest: PluginManifest,
+    dir: PathBuf,
+    output: PluginOutput,
+    running: Option<PluginRun>,
+}
+
+impl ExternalPlugin {
+    fn load(path: &Path) -> Result<Self> {
+        let manifest: PluginManifest = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Ok(Self {
+            manifest,
+            dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            output: Ok(Vec::new()),
+            running: None,
+        })
+    }
+
+    fn start(&mut self, context: &PluginContext) -> Result<()> {
+        let input = serde_json::to_vec(&PluginInput {
+            room: context.room_id.map(|id| id.to_string()),
+            recording: context.recording_path,
+            frame: context.frame_index(),
+            num_frames: context.recording.map(|r| r.frames().len()),
+        })?;
+
+        self.running = Some(PluginRun::start(self.manifest.clone(), self.dir.clone(), input));
+        Ok(())
+    }
+}
+
+impl Plugin for ExternalPlugin {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn needs_recording(&self) -> bool {
+        self.manifest.needs_recording
+    }
+
+    fn show(&mut self, ui: &mut Ui, context: &PluginContext) -> Option<PluginAction> {
+        if let Some(output) = self.running.as_ref().and_then(PluginRun::take_result) {
+            self.output = output;
+            self.running = None;
+        }
+
+        if self.running.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Running...");
+            });
+            // keep checking until the plugin finishes
+            ui.ctx().request_repaint_after(POLL_INTERVAL);
+        } else if ui.button("Run").clicked()
+            && let Err(e) = self.start(context) {
+            self.output = Err(e.to_string());
+        }
+
+        let lines = match self.output {
+            Ok(ref lines) => lines,
+            Err(ref e) => {
+                ui.label(RichText::new(e).color(Color32::RED));
+                return None;
+            }
+        };
+
+        let mut action = None;
+        for line in lines {
+            let link = line.split_once('\t').and_then(|(frame, text)| Some((frame.trim().parse().ok()?, text)));
+            match link {
+                Some((frame_index, text)) => {
+                    if ui.selectable_label(false, text).clicked() {
+                        action = Some(PluginAction::JumpToFrame(frame_index));
+                    }
+                }
+                None => {
+                    ui.label(line);
+                }
+            }
+        }
+
+        action
+    }
+}
+
+/// Folder that plugin manifests are loaded from, next to the config file
+fn plugin_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(PLUGIN_DIR_NAME))
+}
+
+/// Load every plugin manifest in the plugin folder. Plugins that fail to load are skipped.
+pub fn discover_plugins() -> Vec<ExternalPlugin> {
+    let Some(Ok(entries)) = plugin_dir().map(|dir| dir.read_dir()) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+        .collect();
+    paths.sort();
+
+    paths.into_iter().filter_map(|path| match ExternalPlugin::load(&path) {
+        Ok(plugin) => Some(plugin),
+        Err(e) => {
+            eprintln!("Failed to load plugin {}: {}", path.display(), e);
+            None
+        }
+    }).collect()
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use enum_map::{Enum, EnumMap};
+use web_time::Instant;
+
+// weight of the newest frame in the running averages
+const AVERAGE_WEIGHT: f32 = 0.1;
+// how many frames to wait after changing the detail level before changing it again, so the
+// averages have time to catch up
+const DETAIL_COOLDOWN_FRAMES: usize = 30;
+
+#[derive(Debug, Enum, PartialEq, Eq, Clone, Copy)]
+pub enum DrawLayer {
+    Floors,
+    SafeArea,
+    Colliders,
+    Entities,
+    Objects,
+    AiZones,
+    RunPaths,
+    Routes,
+    CharacterPaths,
+    Prediction,
+    ComparisonPaths,
+    WeaponRanges,
+    Characters,
+    Tooltips,
+    Sounds,
+    Highlights,
+    /// Everything outside the map view: menus, side panels, windows, and playback
+    Interface,
+}
+
+impl DrawLayer {
+    pub const fn list() -> [Self; 17] {
+        [
+            Self::Floors, Self::SafeArea, Self::Colliders, Self::Entities, Self::Objects, Self::AiZones,
+            Self::RunPaths, Self::Routes, Self::CharacterPaths, Self::Prediction, Self::ComparisonPaths,
+            Self::WeaponRanges, Self::Characters, Self::Tooltips, Self::Sounds, Self::Highlights, Self::Interface,
+        ]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Floors => "Floors",
+            Self::SafeArea => "Safe area",
+            Self::Colliders => "Colliders",
+            Self::Entities => "Entities",
+            Self::Objects => "Objects",
+            Self::AiZones => "AI zones",
+            Self::RunPaths => "Full run paths",
+            Self::Routes => "Routes",
+            Self::CharacterPaths => "Character paths",
+            Self::Prediction => "Path prediction",
+            Self::ComparisonPaths => "Comparison paths",
+            Self::WeaponRanges => "Weapon ranges",
+            Self::Characters => "Characters",
+            Self::Tooltips => "Tooltips",
+            Self::Sounds => "Sounds",
+            Self::Highlights => "Highlights",
+            Self::Interface => "Interface",
+        }
+    }
+
+    /// Whether reduced detail mode is allowed to turn this layer off
+    pub const fn is_optional(&self) -> bool {
+        matches!(
+            self,
+            Self::SafeArea | Self::AiZones | Self::RunPaths | Self::Routes | Self::CharacterPaths
+            | Self::Prediction | Self::ComparisonPaths | Self::Tooltips | Self::Sounds
+        )
+    }
+}
+
+/// Time spent in each layer over a single frame
+#[derive(Debug, Default)]
+pub struct LayerTimings {
+    times: EnumMap<DrawLayer, Duration>,
+}
+
+impl LayerTimings {
+    /// Charge the time since the timer was last reset to the given layer and reset it
+    pub fn lap(&mut self, layer: DrawLayer, timer: &mut Instant) {
+        let now = Instant::now();
+        self.times[layer] += now - *timer;
+        *timer = now;
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LayerStats {
+    pub last_ms: f32,
+    pub average_ms: f32,
+    pub max_ms: f32,
+}
+
+impl LayerStats {
+    fn add(&mut self, duration: Duration, is_first: bool) {
+        let ms = duration.as_secs_f32() * 1000.0;
+        self.last_ms = ms;
+        self.average_ms = if is_first { ms } else { self.average_ms + (ms - self.average_ms) * AVERAGE_WEIGHT };
+        self.max_ms = self.max_ms.max(ms);
+    }
+}
+
+/// Tracks how long each layer of the map view takes to build each frame. This only covers the time
+/// we spend in update; egui's tessellation and rendering happen afterward and aren't included.
+#[derive(Debug, Default)]
+pub struct FrameProfiler {
+    stats: EnumMap<DrawLayer, LayerStats>,
+    total: LayerStats,
+    num_frames: usize,
+    // optional layers turned off by reduced detail mode, most recently disabled last
+    disabled: Vec<DrawLayer>,
+    frames_since_change: usize,
+}
+
+impl FrameProfiler {
+    pub fn stats(&self, layer: DrawLayer) -> &LayerStats {
+        &self.stats[layer]
+    }
+
+    pub const fn total(&self) -> &LayerStats {
+        &self.total
+    }
+
+    pub fn is_layer_enabled(&self, layer: DrawLayer) -> bool {
+        !self.disabled.contains(&layer)
+    }
+
+    pub fn disabled_layers(&self) -> &[DrawLayer] {
+        &self.disabled
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Record a completed frame and, if reduced detail is on, adjust which layers are drawn to
+    /// try to keep the frame time within the budget
+    pub fn end_frame(&mut self, mut timings: LayerTimings, total: Duration, reduced_detail: Option<f32>) {
+        let measured: Duration = timings.times.values().sum();
+        timings.times[DrawLayer::Interface] = total.saturating_sub(measured);
+
+        let is_first = self.num_frames == 0;
+        for (layer, time) in timings.times {
+            // don't let a disabled layer's zero time drag down the average it was disabled for
+            if self.is_layer_enabled(layer) {
+                self.stats[layer].add(time, is_first);
+            }
+        }
+        self.total.add(total, is_first);
+        self.num_frames += 1;
+        self.frames_since_change += 1;
+
+        let Some(budget_ms) = reduced_detail else {
+            self.disabled.clear();
+            return;
+        };
+
+        if self.frames_since_change < DETAIL_COOLDOWN_FRAMES {
+            return;
+        }
+
+        if self.total.average_ms > budget_ms {
+            let most_expensive = DrawLayer::list()
+                .into_iter()
+                .filter(|layer| layer.is_optional() && self.is_layer_enabled(*layer))
+                .max_by(|a, b| self.stats[*a].average_ms.total_cmp(&self.stats[*b].average_ms));
+            if let Some(layer) = most_expensive {
+                self.disabled.push(layer);
+                self.frames_since_change = 0;
+            }
+        } else if let Some(layer) = self.disabled.last()
+            // only bring a layer back if it would still fit based on what it cost before
+            && self.total.average_ms + self.stats[*layer].average_ms < budget_ms {
+            self.disabled.pop();
+            self.frames_since_change = 0;
+        }
+    }
+}
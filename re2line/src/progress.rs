@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use anyhow::Result;
+
+use crate::drill::{segment_attempts, segment_name, SegmentAttempt, SegmentKey};
+use crate::record::{DoorTimes, Recording};
+
+// number of attempts averaged together for each point of the rolling average
+pub const ROLLING_AVERAGE_ATTEMPTS: usize = 5;
+
+/// An attempt at a segment along with when the session it was recorded in started
+#[derive(Debug, Clone)]
+pub struct DatedAttempt {
+    pub date: String,
+    pub attempt: SegmentAttempt,
+}
+
+impl DatedAttempt {
+    /// The calendar day, without the time
+    pub fn day(&self) -> &str {
+        self.date.split_once(' ').map_or(self.date.as_str(), |(day, _)| day)
+    }
+}
+
+/// Attempts at a segment recorded on the same day
+#[derive(Debug, Clone)]
+pub struct DaySummary {
+    pub day: String,
+    pub num_attempts: usize,
+    pub best: usize,
+    pub average: f32,
+}
+
+/// Every dated attempt at a segment, oldest first
+#[derive(Debug, Clone)]
+pub struct SegmentProgress {
+    pub key: SegmentKey,
+    pub attempts: Vec<DatedAttempt>,
+}
+
+impl SegmentProgress {
+    pub fn name(&self) -> String {
+        segment_name(self.key)
+    }
+
+    pub fn best(&self) -> Option<&DatedAttempt> {
+        self.attempts.iter().min_by_key(|a| a.attempt.frames)
+    }
+
+    /// Attempt number and length of each attempt
+    pub fn times(&self) -> Vec<[f64; 2]> {
+        self.attempts.iter()
+            .enumerate()
+            .map(|(i, a)| [(i + 1) as f64, a.attempt.frames as f64])
+            .collect()
+    }
+
+    /// Attempt number and the best time as of that attempt
+    pub fn pb_progression(&self) -> Vec<[f64; 2]> {
+        let mut best = usize::MAX;
+        self.attempts.iter()
+            .enumerate()
+            .map(|(i, a)| {
+                best = best.min(a.attempt.frames);
+                [(i + 1) as f64, best as f64]
+            })
+            .collect()
+    }
+
+    /// Attempt number and the average of the attempts up to and including it, once there are
+    /// enough attempts to fill the window
+    pub fn rolling_average(&self) -> Vec<[f64; 2]> {
+        self.attempts.windows(ROLLING_AVERAGE_ATTEMPTS)
+            .enumerate()
+            .map(|(i, window)| {
+                let total: usize = window.iter().map(|a| a.attempt.frames).sum();
+                [(i + ROLLING_AVERAGE_ATTEMPTS) as f64, total as f64 / window.len() as f64]
+            })
+            .collect()
+    }
+
+    /// How many frames faster the latest rolling average is than the first one
+    pub fn improvement(&self) -> Option<f32> {
+        let averages = self.rolling_average();
+        let (first, last) = (averages.first()?, averages.last()?);
+        Some((first[1] - last[1]) as f32)
+    }
+
+    pub fn daily_summaries(&self) -> Vec<DaySummary> {
+        let mut summaries: Vec<DaySummary> = Vec::new();
+        for attempt in &self.attempts {
+            let frames = attempt.attempt.frames;
+            match summaries.last_mut() {
+                Some(summary) if summary.day == attempt.day() => {
+                    // average is kept as a running total until the day is finished
+                    summary.num_attempts += 1;
+                    summary.best = summary.best.min(frames);
+                    summary.average += frames as f32;
+                }
+                _ => summaries.push(DaySummary { day: attempt.day().to_string(), num_attempts: 1, best: frames, average: frames as f32 }),
+            }
+        }
+
+        for summary in &mut summaries {
+            summary.average /= summary.num_attempts as f32;
+        }
+
+        summaries
+    }
+}
+
+/// Segment times across a set of recordings, in the order they were recorded
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReport {
+    pub segments: Vec<SegmentProgress>,
+    /// Attempts left out because their session has no start time, as in exported recordings
+    pub num_undated: usize,
+}
+
+impl ProgressReport {
+    /// Track every segment that was attempted at least `min_attempts` times across the given
+    /// recordings, most attempted first
+    pub fn build(recording_paths: Vec<PathBuf>, min_attempts: usize, door_times: &mut DoorTimes) -> Result<Self> {
+        let mut report = Self::default();
+        let mut segments: HashMap<SegmentKey, Vec<DatedAttempt>> = HashMap::new();
+        for path in recording_paths {
+            let recording = Recording::read(File::open(&path)?)?;
+            door_times.add_recording(&path, &recording);
+
+            let source_path = Rc::new(path);
+            for (key, attempt) in segment_attempts(&recording, &source_path) {
+                let session = &recording.sessions()[recording.session_index(attempt.frame_index)];
+                let Some(ref date) = session.start_time else {
+                    report.num_undated += 1;
+                    continue;
+                };
+
+                segments.entry(key).or_default().push(DatedAttempt { date: date.clone(), attempt });
+            }
+        }
+
+        report.segments = segments.into_iter()
+            .filter(|(_, attempts)| attempts.len() >= min_attempts.max(1))
+            .map(|(key, mut attempts)| {
+                // dates are zero-padded, so they sort chronologically as strings
+                attempts.sort_by(|a, b| a.date.cmp(&b.date).then(a.attempt.frame_index.cmp(&b.attempt.frame_index)));
+                SegmentProgress { key, attempts }
+            })
+            .collect();
+        report.segments.sort_by(|a, b| b.attempts.len().cmp(&a.attempts.len()).then_with(|| a.name().cmp(&b.name())));
+
+        Ok(report)
+    }
+
+    /// Every recording file directly in the given folder
+    pub fn recordings_in(dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for entry in dir.read_dir()? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("bin")) {
+                paths.push(path);
+            }
+        }
+
+        Ok(paths)
+    }
+}
@@ -1,9 +1,12 @@
+use std::str::FromStr;
+
 use anyhow::anyhow;
 use residat::common::Vec2;
-use residat::re2::{Collider, Instruction, Rdt};
+use residat::re2::{CharacterId, Collider, Instruction, Rdt};
 
 use crate::aot::Entity;
 use crate::app::Floor as FloorId;
+use crate::app::RoomId;
 use crate::app::WorldPos;
 use crate::collision;
 use crate::script::InstructionExt;
@@ -51,12 +54,109 @@ impl TryFrom<u16> for CollisionShape {
     }
 }
 
+// RDT header indexes of the sections whose layout differs between the games
+const SECTION_INIT_SCRIPT: usize = 16;
+const SECTION_EXEC_SCRIPT: usize = 17;
+const SECTION_ANIMATIONS: usize = 22;
+
+/// The parts of a game's RDT layout that differ from RE2's. The games share the RDT header and the
+/// collision and floor formats, so the RE2 parser can read those sections for all of them.
+pub trait RdtLayout {
+    fn name(&self) -> &'static str;
+
+    /// Header indexes of sections that RE2's parser can't read for this game
+    fn incompatible_sections(&self) -> &'static [usize] {
+        &[]
+    }
+
+    /// Guess the room ID from an RDT's file name
+    fn room_id_from_file_name(&self, file_name: &str) -> Option<RoomId>;
+}
+
+fn file_stem(file_name: &str) -> Option<String> {
+    Some(file_name.to_uppercase().split('.').next()?.to_string())
+}
+
+pub struct Re2Layout;
+
+impl RdtLayout for Re2Layout {
+    fn name(&self) -> &'static str {
+        "RE2"
+    }
+
+    /// e.g. ROOM1000.RDT
+    fn room_id_from_file_name(&self, file_name: &str) -> Option<RoomId> {
+        RoomId::from_str(file_stem(file_name)?.strip_prefix("ROOM")?.get(..4)?).ok()
+    }
+}
+
+/// The RE2 prototype, commonly called RE1.5
+pub struct Re15Layout;
+
+impl RdtLayout for Re15Layout {
+    fn name(&self) -> &'static str {
+        "RE1.5"
+    }
+
+    // the scripts use a different set of opcodes, and the animation format changed before release.
+    // AOTs are set up by the scripts, so they aren't available for these rooms.
+    fn incompatible_sections(&self) -> &'static [usize] {
+        &[SECTION_INIT_SCRIPT, SECTION_EXEC_SCRIPT, SECTION_ANIMATIONS]
+    }
+
+    fn room_id_from_file_name(&self, file_name: &str) -> Option<RoomId> {
+        Re2Layout.room_id_from_file_name(file_name)
+    }
+}
+
+pub struct Re3Layout;
+
+impl RdtLayout for Re3Layout {
+    fn name(&self) -> &'static str {
+        "RE3"
+    }
+
+    fn incompatible_sections(&self) -> &'static [usize] {
+        &[SECTION_INIT_SCRIPT, SECTION_EXEC_SCRIPT, SECTION_ANIMATIONS]
+    }
+
+    /// e.g. R100.RDT. RE3 has no separate rooms per character, so we treat everything as player 0.
+    fn room_id_from_file_name(&self, file_name: &str) -> Option<RoomId> {
+        RoomId::from_str(&format!("{}0", file_stem(file_name)?.strip_prefix('R')?.get(..3)?)).ok()
+    }
+}
+
+/// Games whose RDTs can be opened in the viewer
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RdtFormat {
+    Re2,
+    Re15,
+    Re3,
+}
+
+impl RdtFormat {
+    pub const fn list() -> [Self; 3] {
+        [Self::Re2, Self::Re15, Self::Re3]
+    }
+
+    pub const fn layout(&self) -> &'static dyn RdtLayout {
+        match self {
+            Self::Re2 => &Re2Layout,
+            Self::Re15 => &Re15Layout,
+            Self::Re3 => &Re3Layout,
+        }
+    }
+}
+
 pub trait RdtExt {
     fn get_floors(&self) -> Vec<collision::Collider>;
 
     fn get_colliders(&self) -> Vec<collision::Collider>;
 
     fn get_entities(&self) -> Vec<Entity>;
+
+    /// The character ID of every enemy the room's scripts can spawn
+    fn get_enemy_ids(&self) -> Vec<CharacterId>;
 }
 
 fn get_script_entities(vec: &mut Vec<Entity>, script: &[Instruction]) {
@@ -150,4 +250,17 @@ impl RdtExt for Rdt {
 
         entities
     }
+
+    fn get_enemy_ids(&self) -> Vec<CharacterId> {
+        let mut enemy_ids = Vec::new();
+
+        for function in self.init_script() {
+            enemy_ids.extend(function.iter().filter_map(Instruction::enemy_id));
+        }
+        for function in self.exec_script() {
+            enemy_ids.extend(function.iter().filter_map(Instruction::enemy_id));
+        }
+
+        enemy_ids
+    }
 }
\ No newline at end of file
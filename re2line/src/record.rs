@@ -1,21 +1,28 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
-use std::io::{Cursor, Read, Seek};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::mem::{discriminant, Discriminant};
 use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::{bail, Result};
-use binrw::BinReaderExt;
+use binrw::{BinReaderExt, BinWriterExt};
+use chrono::NaiveDateTime;
+use re2shared::checksum::crc32_update;
 use re2shared::record::*;
 use re2shared::rng::RollType;
 use residat::common::*;
-use residat::re2::{CharacterId, NUM_CHARACTERS, NUM_OBJECTS};
+use residat::re2::{CharacterId, Item, NUM_CHARACTERS, NUM_OBJECTS};
 
 use crate::app::{Floor, GameObject, RoomId};
+use crate::bookmark::Bookmark;
 use crate::character::*;
 use crate::rng::{RNG_SEQUENCE, ROLL_DESCRIPTIONS, RollDescription};
 
 pub const FRAME_DURATION: Duration = Duration::from_micros(1000000 / 30);
+// how session start times are displayed
+const SESSION_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
 const KEY_FORWARD: u32 = 0x01;
 const KEY_RIGHT: u32 = 0x02;
@@ -24,8 +31,12 @@ const KEY_LEFT: u32 = 0x08;
 const KEY_ACTION: u32 = 0x80;
 const KEY_AIM: u32 = 0x100;
 const KEY_RUN_CANCEL: u32 = 0x200;
+const KEY_DIRECTIONS: u32 = KEY_FORWARD | KEY_RIGHT | KEY_BACK | KEY_LEFT;
 
+const FLAGS1_STATUS_SCREEN: u32 = 0x00000010;
+const FLAGS1_MAP_SCREEN: u32 = 0x00000040;
 const FLAGS1_FORCE_CUT: u32 = 0x00000100;
+const FLAGS1_EXAMINE: u32 = 0x00000800;
 const FLAGS1_EX_BATTLE: u32 = 0x00004000;
 const FLAGS1_LOADING_SCREEN: u32 = 0x00020000;
 const FLAGS1_ACTION_PRESSED: u32 = 0x00200000;
@@ -37,6 +48,29 @@ const FLAGS2_TOFU: u32 = 0x00000001;
 const FLAGS2_4TH_SURVIVOR: u32 = 0x00000008;
 const FLAGS2_EX_BATTLE: u32 = 0x01000000;
 const FLAGS2_LOADING_SCREEN: u32 = 0x02000000;
+const FLAGS2_MESSAGE: u32 = 0x00000100;
+
+// session boundary written to exported recordings, with the timestamp zeroed out
+const ANONYMOUS_SESSION_START: GameField = GameField::SessionStart { year: 0, month: 0, day: 0, hour: 0, minute: 0, second: 0 };
+
+// per-frame movement beyond this is a teleport (e.g. loading into a room) rather than running
+const MAX_PLAUSIBLE_SPEED: Fixed32 = Fixed32(1000);
+// likewise for turning; anything this big is a quick turn or a cutscene snapping the player around
+const MAX_PLAUSIBLE_TURN_RATE: i32 = 0x100;
+
+// how far a character can end up from where its velocity says it should be before we consider the
+// movement to have diverged from the motion model
+const PREDICTION_DIVERGENCE_THRESHOLD: Fixed32 = Fixed32(100);
+// how long a newly-pressed key can go without any response from the player before we consider it dropped
+const DROPPED_INPUT_FRAMES: usize = 2;
+// aim presses shorter than this are assumed to be accidental
+const AIM_TAP_FRAMES: usize = 3;
+// frames between state snapshots taken within a room
+const SNAPSHOT_INTERVAL: usize = 1800;
+// rooms longer than this are only replayed from the nearest snapshot instead of all at once. the
+// rest of the room's states aren't available, so paths and sound history are cut off at the
+// snapshot.
+const LONG_ROOM_FRAMES: usize = 18000;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Scenario {
@@ -69,6 +103,552 @@ impl Display for Scenario {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ControlLock {
+    Cutscene,
+    Door,
+    PickUp,
+    Stairs,
+    Climb,
+}
+
+impl ControlLock {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Cutscene => "Cutscene",
+            Self::Door => "Door",
+            Self::PickUp => "Pick up",
+            Self::Stairs => "Stairs",
+            Self::Climb => "Climb",
+        }
+    }
+}
+
+impl Display for ControlLock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Menu {
+    Inventory,
+    Examine,
+    Map,
+}
+
+impl Menu {
+    pub const fn list() -> [Self; 3] {
+        [Self::Inventory, Self::Examine, Self::Map]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Inventory => "Inventory",
+            Self::Examine => "Examine",
+            Self::Map => "Map",
+        }
+    }
+}
+
+impl Display for Menu {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MenuRange {
+    pub menu: Menu,
+    pub frames: Range<usize>,
+}
+
+/// Frames spent in each kind of menu
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MenuTime {
+    pub inventory: usize,
+    pub examine: usize,
+    pub map: usize,
+}
+
+impl MenuTime {
+    pub const fn total(&self) -> usize {
+        self.inventory + self.examine + self.map
+    }
+
+    pub const fn frames(&self, menu: Menu) -> usize {
+        match menu {
+            Menu::Inventory => self.inventory,
+            Menu::Examine => self.examine,
+            Menu::Map => self.map,
+        }
+    }
+
+    const fn frames_mut(&mut self, menu: Menu) -> &mut usize {
+        match menu {
+            Menu::Inventory => &mut self.inventory,
+            Menu::Examine => &mut self.examine,
+            Menu::Map => &mut self.map,
+        }
+    }
+}
+
+impl Display for MenuTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "inventory {}, examine {}, map {}", self.inventory, self.examine, self.map)
+    }
+}
+
+/// A dialog or message box from the time it appeared until the player dismissed it
+#[derive(Debug, Clone)]
+pub struct MessagePrompt {
+    pub room_id: RoomId,
+    pub frames: Range<usize>,
+    /// The first frame the player pressed the action button while the message was up
+    pub first_press: Option<usize>,
+}
+
+impl MessagePrompt {
+    /// Frames from the message appearing to the player's first attempt to dismiss it
+    pub fn reaction_frames(&self) -> Option<usize> {
+        self.first_press.map(|frame| frame - self.frames.start)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ControlLockRange {
+    pub lock: ControlLock,
+    pub frames: Range<usize>,
+}
+
+/// The game jumped to a saved point, either from the title screen or by restoring a save state
+#[derive(Debug, Clone)]
+pub struct LoadEvent {
+    pub frame_index: usize,
+    pub room_id: RoomId,
+}
+
+impl LoadEvent {
+    fn from_frame(frame: &FrameRecord, state: &State) -> Option<Self> {
+        frame.game_changes.iter()
+            .any(|change| matches!(change, GameField::GameLoaded))
+            .then(|| Self { frame_index: state.frame_index, room_id: state.room_id })
+    }
+}
+
+/// A named marker placed with a hotkey while recording
+#[derive(Debug, Clone)]
+pub struct MarkerEvent {
+    pub frame_index: usize,
+    pub room_id: RoomId,
+    pub name: String,
+}
+
+impl MarkerEvent {
+    fn from_frame<'a>(frame: &'a FrameRecord, state: &State) -> impl Iterator<Item = Self> + 'a {
+        let (frame_index, room_id) = (state.frame_index, state.room_id);
+        frame.game_changes.iter().filter_map(move |change| match change {
+            GameField::Marker { name } => Some(Self { frame_index, room_id, name: name.clone() }),
+            _ => None,
+        })
+    }
+}
+
+/// The enemies the recorder saw when the player entered a room
+#[derive(Debug, Clone)]
+pub struct RoomSpawnEvent {
+    pub frame_index: usize,
+    pub room_id: RoomId,
+    pub spawns: Vec<EnemySpawn>,
+}
+
+impl RoomSpawnEvent {
+    fn from_frame<'a>(frame: &'a FrameRecord, state: &State) -> impl Iterator<Item = Self> + 'a {
+        let (frame_index, room_id) = (state.frame_index, state.room_id);
+        frame.game_changes.iter().filter_map(move |change| match change {
+            GameField::RoomSpawns { spawns } => Some(Self { frame_index, room_id, spawns: spawns.clone() }),
+            _ => None,
+        })
+    }
+}
+
+// how long after the player attacks an enemy losing health is still credited to that attack
+pub const DAMAGE_ATTRIBUTION_FRAMES: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageKind {
+    Bite,
+    Slash,
+    Gunshot,
+    Knife,
+    Other,
+}
+
+impl DamageKind {
+    pub const fn list() -> [Self; 5] {
+        [Self::Bite, Self::Slash, Self::Gunshot, Self::Knife, Self::Other]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Bite => "Bite",
+            Self::Slash => "Slash",
+            Self::Gunshot => "Gunshot",
+            Self::Knife => "Knife",
+            Self::Other => "Other",
+        }
+    }
+
+    /// Best guess at how the player was hurt, based on the closest enemy
+    fn from_attacker(attacker: Option<CharacterId>) -> Self {
+        match attacker {
+            Some(id) if id.is_zombie() || matches!(id, CharacterId::Dog | CharacterId::Spider) => Self::Bite,
+            Some(CharacterId::LickerRed | CharacterId::LickerBlack) => Self::Slash,
+            _ => Self::Other,
+        }
+    }
+
+    fn from_weapon(weapon: Option<Item>) -> Self {
+        match weapon {
+            Some(Item::Knife) => Self::Knife,
+            Some(_) => Self::Gunshot,
+            None => Self::Other,
+        }
+    }
+}
+
+impl Display for DamageKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A character lost health
+#[derive(Debug, Clone)]
+pub struct DamageEvent {
+    pub frame_index: usize,
+    pub room_id: RoomId,
+    pub character_index: usize,
+    pub character_name: &'static str,
+    pub damage: i16,
+    pub health_after: i16,
+    pub kind: DamageKind,
+}
+
+impl DamageEvent {
+    pub const fn is_player(&self) -> bool {
+        self.character_index == 0
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InputAnomalyKind {
+    DroppedInput,
+    AimTap,
+}
+
+impl InputAnomalyKind {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::DroppedInput => "Dropped input",
+            Self::AimTap => "Aim tap",
+        }
+    }
+}
+
+impl Display for InputAnomalyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InputAnomaly {
+    pub kind: InputAnomalyKind,
+    pub frame_index: usize,
+    pub description: String,
+}
+
+fn describe_keys(flags: u32) -> String {
+    let names: Vec<&str> = [(KEY_FORWARD, "Forward"), (KEY_BACK, "Back"), (KEY_LEFT, "Left"), (KEY_RIGHT, "Right"), (KEY_AIM, "Aim")]
+        .into_iter()
+        .filter_map(|(key, name)| (flags & key != 0).then_some(name))
+        .collect();
+    names.join("+")
+}
+
+#[derive(Debug, Default)]
+struct InputAnomalyDetector {
+    anomalies: Vec<InputAnomaly>,
+    // frame, keys, and the player's state and angle when the keys were pressed
+    dropped_candidate: Option<(usize, u32, [u8; 4], Fixed32)>,
+    aim_start: Option<usize>,
+}
+
+impl InputAnomalyDetector {
+    fn push(&mut self, kind: InputAnomalyKind, frame_index: usize, description: String) {
+        self.anomalies.push(InputAnomaly { kind, frame_index, description });
+    }
+
+    fn update(&mut self, state: &State) {
+        let frame_index = state.frame_index;
+        let (Some(player), None) = (state.characters[0].as_ref(), state.control_lock()) else {
+            self.dropped_candidate = None;
+            self.aim_start = None;
+            return;
+        };
+
+        if let Some((start, keys, player_state, angle)) = self.dropped_candidate {
+            if state.input_flags & keys != keys || player.state != player_state || player.angle != angle {
+                self.dropped_candidate = None;
+            } else if frame_index - start >= DROPPED_INPUT_FRAMES {
+                self.push(
+                    InputAnomalyKind::DroppedInput,
+                    start,
+                    format!("{} held for {} frames with no response", describe_keys(keys), DROPPED_INPUT_FRAMES),
+                );
+                self.dropped_candidate = None;
+            }
+        }
+        let new_keys = state.input_flags_this_frame & (KEY_DIRECTIONS | KEY_AIM);
+        if self.dropped_candidate.is_none() && new_keys != 0 {
+            self.dropped_candidate = Some((frame_index, new_keys, player.state, player.angle));
+        }
+
+        if state.input_flags & KEY_AIM != 0 {
+            self.aim_start.get_or_insert(frame_index);
+        } else if let Some(start) = self.aim_start.take() {
+            let num_frames = frame_index - start;
+            if num_frames < AIM_TAP_FRAMES {
+                self.push(InputAnomalyKind::AimTap, start, format!("Aim held for {} frame(s)", num_frames));
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<InputAnomaly> {
+        self.anomalies.sort_by_key(|a| a.frame_index);
+        self.anomalies
+    }
+}
+
+/// An enemy that was chasing the player gave up
+#[derive(Debug, Clone)]
+pub struct DeaggroEvent {
+    pub frame_index: usize,
+    pub room_id: RoomId,
+    pub character_index: usize,
+    pub character_id: CharacterId,
+    pub pursuit_start: usize,
+    /// Random rolls the enemy made while it was in pursuit
+    pub num_rolls: usize,
+    pub pos: Vec2,
+    pub player_pos: Vec2,
+}
+
+impl DeaggroEvent {
+    pub fn distance(&self) -> Fixed32 {
+        (self.player_pos - self.pos).len()
+    }
+
+    pub const fn pursuit_frames(&self) -> usize {
+        self.frame_index - self.pursuit_start
+    }
+}
+
+#[derive(Debug, Default)]
+struct PursuitTracker {
+    events: Vec<DeaggroEvent>,
+    // start frame, enemy type, and roll count of each character slot's current pursuit
+    pursuits: HashMap<usize, (usize, CharacterId, usize)>,
+}
+
+impl PursuitTracker {
+    fn update(&mut self, state: &State, frame: &FrameRecord) {
+        let player_pos = match state.characters[0] {
+            Some(ref player) if state.room_index != 0 => player.center(),
+            _ => {
+                self.pursuits.clear();
+                return;
+            }
+        };
+
+        for change in &frame.game_changes {
+            if let GameField::CharacterRng { char_index, .. } = change
+                && let Some((_, _, num_rolls)) = self.pursuits.get_mut(&(*char_index as usize)) {
+                *num_rolls += 1;
+            }
+        }
+
+        for (i, character) in state.characters.iter().enumerate().skip(1) {
+            let Some(character) = character else {
+                self.pursuits.remove(&i);
+                continue;
+            };
+
+            match (character.is_pursuing(), self.pursuits.get(&i).copied()) {
+                // a different enemy took over the slot
+                (_, Some((_, id, _))) if id != character.id => {
+                    self.pursuits.remove(&i);
+                }
+                (Some(true), None) => {
+                    self.pursuits.insert(i, (state.frame_index, character.id, 0));
+                }
+                (Some(false), Some((pursuit_start, character_id, num_rolls))) => {
+                    self.events.push(DeaggroEvent {
+                        frame_index: state.frame_index,
+                        room_id: state.room_id,
+                        character_index: i,
+                        character_id,
+                        pursuit_start,
+                        num_rolls,
+                        pos: character.center(),
+                        player_pos,
+                    });
+                    self.pursuits.remove(&i);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn finish(self) -> Vec<DeaggroEvent> {
+        self.events
+    }
+}
+
+// how many frames after an attack's hit zones go away the player's damage is still credited to it
+const ATTACK_HIT_GRACE_FRAMES: usize = 2;
+
+/// The path an enemy's attacking part took while one of its hit zones was active, for an attack
+/// that hurt the player
+#[derive(Debug, Clone)]
+pub struct AttackTrace {
+    pub room_id: RoomId,
+    pub character_index: usize,
+    pub character_id: CharacterId,
+    pub zone_name: &'static str,
+    /// Frames during which the hit zone was active
+    pub frames: Range<usize>,
+    /// Position of the zone's origin on each active frame
+    pub points: Vec<Vec2>,
+    /// Frame the player lost health
+    pub hit_frame: usize,
+}
+
+impl AttackTrace {
+    /// The part of the trace up to and including the given frame
+    pub fn points_until(&self, frame_index: usize) -> &[Vec2] {
+        let len = (frame_index + 1).saturating_sub(self.frames.start).min(self.points.len());
+        &self.points[..len]
+    }
+}
+
+#[derive(Debug)]
+struct TraceInProgress {
+    trace: AttackTrace,
+    was_player_inside: bool,
+    is_hit: bool,
+}
+
+#[derive(Debug, Default)]
+struct AttackTracer {
+    traces: Vec<AttackTrace>,
+    // traces in progress by character index
+    active: HashMap<usize, TraceInProgress>,
+    // traces that have ended without a hit but might still be credited with one
+    ended: Vec<TraceInProgress>,
+}
+
+impl AttackTracer {
+    fn end_trace(&mut self, in_progress: TraceInProgress) {
+        if in_progress.is_hit {
+            self.traces.push(in_progress.trace);
+        } else if in_progress.was_player_inside {
+            self.ended.push(in_progress);
+        }
+    }
+
+    fn update(&mut self, state: &State, player_hurt: bool) {
+        let frame_index = state.frame_index;
+        let player_pos = match state.characters[0] {
+            Some(ref player) if state.room_index != 0 => player.center(),
+            _ => {
+                for (_, in_progress) in self.active.drain() {
+                    if in_progress.is_hit {
+                        self.traces.push(in_progress.trace);
+                    }
+                }
+                self.ended.clear();
+                return;
+            }
+        };
+
+        for (i, character) in state.characters.iter().enumerate().skip(1) {
+            let zone = character.as_ref().and_then(|c| {
+                c.ai_zones().into_iter().find(|z| z.ai_zone.behavior_type == BehaviorType::Hit)
+            });
+
+            let Some(zone) = zone else {
+                if let Some(in_progress) = self.active.remove(&i) {
+                    self.end_trace(in_progress);
+                }
+                continue;
+            };
+
+            let is_player_inside = zone.contains_point(player_pos);
+            match self.active.get_mut(&i) {
+                Some(in_progress) if in_progress.trace.character_id == zone.character_id && in_progress.trace.zone_name == zone.ai_zone.name => {
+                    in_progress.trace.frames.end = frame_index + 1;
+                    in_progress.trace.points.push(zone.pos);
+                    in_progress.was_player_inside |= is_player_inside;
+                }
+                _ => {
+                    if let Some(in_progress) = self.active.remove(&i) {
+                        self.end_trace(in_progress);
+                    }
+                    self.active.insert(i, TraceInProgress {
+                        trace: AttackTrace {
+                            room_id: state.room_id,
+                            character_index: i,
+                            character_id: zone.character_id,
+                            zone_name: zone.ai_zone.name,
+                            frames: frame_index..frame_index + 1,
+                            points: vec![zone.pos],
+                            hit_frame: frame_index,
+                        },
+                        was_player_inside: is_player_inside,
+                        is_hit: false,
+                    });
+                }
+            }
+        }
+
+        if player_hurt {
+            // credit the hit to an attack the player was caught in, preferring one that's still going
+            let active = self.active.values_mut().find(|t| t.was_player_inside && !t.is_hit);
+            if let Some(in_progress) = active {
+                in_progress.trace.hit_frame = frame_index;
+                in_progress.is_hit = true;
+            } else if let Some(mut in_progress) = self.ended.pop() {
+                in_progress.trace.hit_frame = frame_index;
+                self.traces.push(in_progress.trace);
+            }
+        }
+
+        self.ended.retain(|t| frame_index < t.trace.frames.end + ATTACK_HIT_GRACE_FRAMES);
+    }
+
+    fn finish(mut self) -> Vec<AttackTrace> {
+        for (_, in_progress) in self.active.drain() {
+            if in_progress.is_hit {
+                self.traces.push(in_progress.trace);
+            }
+        }
+        self.traces.sort_by_key(|t| t.frames.start);
+        self.traces
+    }
+}
+
 #[repr(transparent)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct SoundEnvironment(u8);
@@ -118,8 +698,12 @@ pub struct PlayerSound {
 pub struct RoomStats {
     pub num_frames: usize,
     pub total_time: Duration,
+    pub num_locked_frames: usize,
+    pub active_time: Duration,
     pub num_rng_rolls: usize,
     pub rng_position: usize,
+    pub num_lag_frames: usize,
+    pub menu_time: MenuTime,
 }
 
 #[derive(Debug, Clone)]
@@ -161,6 +745,7 @@ pub struct State {
     input_flags: u32,
     input_flags_this_frame: u32,
     is_new_game_start: bool,
+    is_session_start: bool,
 }
 
 impl State {
@@ -180,6 +765,7 @@ impl State {
             input_flags: 0,
             input_flags_this_frame: 0,
             is_new_game_start: false,
+            is_session_start: false,
         }
     }
 
@@ -192,6 +778,8 @@ impl State {
         let mut input_flags = self.input_flags;
         let mut input_flags_this_frame = self.input_flags_this_frame;
         let mut is_new_game_start = false;
+        let mut is_session_start = false;
+        let mut is_keyframe = false;
         for change in &record.game_changes {
             match change {
                 GameField::GameFlags1(flags) => game_flags = *flags,
@@ -204,11 +792,18 @@ impl State {
                 GameField::KeysDown(flags) => input_flags = *flags,
                 GameField::KeysDownThisFrame(flags) => input_flags_this_frame = *flags,
                 GameField::NewGame => is_new_game_start = true,
+                GameField::SessionStart { .. } => is_session_start = true,
+                GameField::Keyframe(_) => is_keyframe = true,
                 _ => (),
             }
         }
 
-        let mut characters = self.characters.clone();
+        // a new session or keyframe records everything from scratch, so nothing from before carries over
+        let mut characters = if is_session_start || is_keyframe {
+            [const { None }; NUM_CHARACTERS]
+        } else {
+            self.characters.clone()
+        };
         for diff in &record.character_diffs {
             let index = diff.index as usize;
             let character = &mut characters[index];
@@ -259,7 +854,7 @@ impl State {
                         character.set_model_part_center(*i as usize, pos);
                     }
                     CharacterField::MotionAngle(angle) => character.angle = angle.to_32(),
-                    CharacterField::Motion(_) => (), // seems like this might not be something useful?
+                    CharacterField::Motion(motion) => character.motion = Some(*motion),
                     CharacterField::Size(width, height) => {
                         character.set_size(*width, *height);
                     }
@@ -283,7 +878,11 @@ impl State {
             }
         }
 
-        let mut objects = self.objects.clone();
+        let mut objects = if is_session_start || is_keyframe {
+            [const { None }; NUM_OBJECTS]
+        } else {
+            self.objects.clone()
+        };
         for diff in &record.object_diffs {
             let index = diff.index as usize;
             let object = &mut objects[index];
@@ -347,7 +946,7 @@ impl State {
             0
         };
 
-        let room_index = if room_id == self.room_id && self.room_index < usize::MAX {
+        let room_index = if room_id == self.room_id && self.room_index < usize::MAX && !is_session_start {
             self.room_index + 1
         } else {
             0
@@ -366,6 +965,7 @@ impl State {
             input_flags,
             input_flags_this_frame,
             is_new_game_start,
+            is_session_start,
         }
     }
 
@@ -373,6 +973,11 @@ impl State {
         self.room_id
     }
 
+    /// Where the RNG is in its sequence as of this frame
+    pub fn rng_position(&self) -> usize {
+        RNG_SEQUENCE.iter().position(|r| *r == (self.rng_value & 0x7fff)).unwrap_or(0)
+    }
+
     pub fn characters(&self) -> &[Option<Character>] {
         &self.characters
     }
@@ -409,6 +1014,10 @@ impl State {
         self.is_new_game_start
     }
 
+    pub const fn is_session_start(&self) -> bool {
+        self.is_session_start
+    }
+
     pub const fn is_cut_forced(&self) -> bool {
         self.game_flags & FLAGS1_FORCE_CUT != 0
     }
@@ -429,6 +1038,10 @@ impl State {
         self.game_flags2 & FLAGS2_TOFU != 0
     }
     
+    pub const fn is_message_shown(&self) -> bool {
+        self.game_flags2 & FLAGS2_MESSAGE != 0
+    }
+
     pub const fn is_loading_screen(&self) -> bool {
         self.game_flags & FLAGS1_LOADING_SCREEN != 0 && self.game_flags2 & FLAGS2_LOADING_SCREEN != 0
     }
@@ -441,34 +1054,106 @@ impl State {
         self.game_flags2 & FLAGS2_EX_BATTLE != 0
     }
     
-    pub const fn scenario(&self) -> Scenario {
-        if self.is_ex_battle() {
-            Scenario::ExBattle
-        } else if self.is_4th_survivor() {
-            match self.is_tofu() {
-                true => Scenario::Tofu,
-                false => Scenario::Hunk,
-            }
+    /// The menu the player has open, if any. Examining an item happens from the inventory, so it
+    /// takes priority.
+    pub const fn menu(&self) -> Option<Menu> {
+        if self.game_flags & FLAGS1_EXAMINE != 0 {
+            Some(Menu::Examine)
+        } else if self.game_flags & FLAGS1_MAP_SCREEN != 0 {
+            Some(Menu::Map)
+        } else if self.game_flags & FLAGS1_STATUS_SCREEN != 0 {
+            Some(Menu::Inventory)
         } else {
-            match (self.is_claire_scenario(), self.is_b_scenario()) {
-                (true, true) => Scenario::ClaireB,
-                (true, false) => Scenario::ClaireA,
-                (false, true) => Scenario::LeonB,
-                (false, false) => Scenario::LeonA,
-            }
+            None
         }
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum RollCategory {
-    Character(u8),
-    NonCharacter,
-    Unknown,
-}
+    pub fn control_lock(&self) -> Option<ControlLock> {
+        if self.is_loading_screen() {
+            return Some(ControlLock::Door);
+        }
 
-#[derive(Debug, Clone)]
-pub struct RngDescription {
+        let Some(ref player) = self.characters[0] else {
+            return Some(ControlLock::Cutscene);
+        };
+
+        match player.state {
+            [0x01, 0x06, _, _] => Some(ControlLock::PickUp),
+            [0x01, 0x07, _, _] => Some(ControlLock::Stairs),
+            [0x01, 0x08, _, _] => Some(ControlLock::Climb),
+            // normal movement, damage, death, and grabs are all still gameplay
+            [0x01 | 0x02 | 0x03 | 0x05, _, _, _] => None,
+            // anything else means a script has taken control of the player
+            _ => Some(ControlLock::Cutscene),
+        }
+    }
+
+    /// The weapon the player attacked with on this frame, if they did
+    pub fn player_attack(&self) -> Option<Item> {
+        let player = self.characters[0].as_ref()?;
+        if !matches!(player.state, [0x01, 0x05, _, _]) || !self.input_state_this_frame().is_action_pressed {
+            return None;
+        }
+
+        player.equipped_item()
+    }
+
+    pub fn ai_zones_containing_player(&self) -> Vec<PositionedAiZone> {
+        let Some(ref player) = self.characters[0] else {
+            return Vec::new();
+        };
+
+        let pos = player.center();
+        let floor = player.floor();
+        self.characters
+            .iter()
+            .skip(1)
+            .flatten()
+            .flat_map(Character::ai_zones)
+            .filter(|zone| zone.floor.matches(floor) && zone.contains_point(pos))
+            .collect()
+    }
+
+    pub fn danger_level(&self) -> f32 {
+        // only count the most dangerous zone for each enemy so overlapping zones from the same enemy
+        // don't stack
+        let mut danger_by_character = [0.0f32; NUM_CHARACTERS];
+        for zone in self.ai_zones_containing_player() {
+            let danger = &mut danger_by_character[zone.character_index];
+            *danger = danger.max(zone.ai_zone.danger_weight());
+        }
+
+        danger_by_character.iter().sum()
+    }
+
+    pub const fn scenario(&self) -> Scenario {
+        if self.is_ex_battle() {
+            Scenario::ExBattle
+        } else if self.is_4th_survivor() {
+            match self.is_tofu() {
+                true => Scenario::Tofu,
+                false => Scenario::Hunk,
+            }
+        } else {
+            match (self.is_claire_scenario(), self.is_b_scenario()) {
+                (true, true) => Scenario::ClaireB,
+                (true, false) => Scenario::ClaireA,
+                (false, true) => Scenario::LeonB,
+                (false, false) => Scenario::LeonA,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollCategory {
+    Character(u8),
+    NonCharacter,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct RngDescription {
     pub description: String,
     pub category: RollCategory,
     pub roll_type: Option<RollType>,
@@ -635,24 +1320,339 @@ impl FrameRng {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    pub scenario: Scenario,
+    pub start_room: RoomId,
+    pub is_new_game: bool,
+    pub frames: Range<usize>,
+    pub menu_time: MenuTime,
+}
+
+impl RunSummary {
+    pub fn name(&self) -> String {
+        if self.is_new_game {
+            self.scenario.to_string()
+        } else {
+            format!("{} (partial, from {})", self.scenario, self.start_room)
+        }
+    }
+}
+
+fn character_field_key(field: &CharacterField) -> (Discriminant<CharacterField>, u8) {
+    let part = match field {
+        CharacterField::PartTranslation(i, _) | CharacterField::ModelPartTransform(i, _) | CharacterField::PartSize(i, ..) => *i,
+        _ => 0,
+    };
+
+    (discriminant(field), part)
+}
+
+fn squash_diffs<'a>(diffs: impl Iterator<Item = &'a CharacterDiff>, num_slots: usize) -> Vec<CharacterDiff> {
+    let mut slots: Vec<Option<Vec<CharacterField>>> = vec![None; num_slots];
+    for diff in diffs {
+        let slot = &mut slots[diff.index as usize];
+        for change in &diff.changes {
+            if matches!(change, CharacterField::Removed) {
+                *slot = None;
+                break;
+            }
+
+            let fields = slot.get_or_insert_with(Vec::new);
+            let key = character_field_key(change);
+            match fields.iter_mut().find(|f| character_field_key(f) == key) {
+                Some(field) => *field = change.clone(),
+                None => fields.push(change.clone()),
+            }
+        }
+    }
+
+    slots.into_iter()
+        .enumerate()
+        .filter_map(|(i, fields)| Some(CharacterDiff::new(i, fields?)))
+        .collect()
+}
+
+/// Collapse a run of frames into a single frame that produces the same state as the last one
+fn squash_frames(frames: &[FrameRecord]) -> Option<FrameRecord> {
+    let last = frames.last()?;
+
+    // per-frame events only count for the last frame; everything else keeps its latest value
+    let mut game_changes: Vec<GameField> = Vec::new();
+    for change in frames.iter().flat_map(|f| &f.game_changes) {
+        if change.is_event() {
+            continue;
+        }
+
+        match game_changes.iter_mut().find(|c| discriminant(&**c) == discriminant(change)) {
+            Some(existing) => *existing = change.clone(),
+            None => game_changes.push(change.clone()),
+        }
+    }
+    // session starts, checksums, and the like describe the original file, not the squashed frame
+    game_changes.extend(last.game_changes.iter().filter(|c| c.is_event() && !c.is_file_structure()).cloned());
+
+    Some(FrameRecord {
+        igt_seconds: last.igt_seconds,
+        igt_frames: last.igt_frames,
+        num_rng_rolls: last.num_rng_rolls,
+        game_changes,
+        character_diffs: squash_diffs(frames.iter().flat_map(|f| &f.character_diffs), NUM_CHARACTERS),
+        object_diffs: squash_diffs(frames.iter().flat_map(|f| &f.object_diffs), NUM_OBJECTS),
+    })
+}
+
+/// Results of checking a recording's checksums for signs of corruption or truncation. The checksums
+/// aren't keyed, so they catch accidental damage but not a deliberately edited file.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingIntegrity {
+    pub is_checksummed: bool,
+    pub verified_frames: usize,
+    // frame ranges that no checksum covers, such as the end of a session that was cut off
+    pub unverified: Vec<Range<usize>>,
+    // frame ranges whose checksum didn't match
+    pub mismatches: Vec<Range<usize>>,
+    // parts of the file that couldn't be decoded and were skipped to get to the next keyframe
+    pub damage: Vec<DamagedRange>,
+}
+
+impl RecordingIntegrity {
+    pub fn is_intact(&self) -> bool {
+        self.is_checksummed && self.mismatches.is_empty() && self.damage.is_empty() && self.unverified.is_empty()
+    }
+
+    pub fn unverified_frames(&self) -> usize {
+        self.unverified.iter().map(Range::len).sum()
+    }
+
+    fn add_unverified(&mut self, frames: Range<usize>) {
+        if !frames.is_empty() {
+            self.unverified.push(frames);
+        }
+    }
+}
+
+/// Bytes of a recording that couldn't be decoded
+#[derive(Debug, Clone)]
+pub struct DamagedRange {
+    /// The first frame decoded after the damage
+    pub frame_index: usize,
+    pub bytes: Range<usize>,
+}
+
+// how much more of the file to read each time parsing gets past what's been read so far
+const READ_CHUNK_SIZE: usize = 1 << 20;
+
+/// A stream that keeps everything read from it, so checksums can look back over frames that have
+/// already been parsed without the whole file having to be read up front
+struct ReadBuffer<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+}
+
+impl<R: Read + Seek> ReadBuffer<R> {
+    fn new(mut inner: R) -> std::io::Result<Self> {
+        let start = inner.stream_position()?;
+        let len = (inner.seek(SeekFrom::End(0))? - start) as usize;
+        inner.seek(SeekFrom::Start(start))?;
+        Ok(Self { inner, buf: Vec::new(), pos: 0, len })
+    }
+
+    /// Read from the underlying stream until at least `len` bytes have been read or it runs out
+    fn fill_to(&mut self, len: usize) -> std::io::Result<()> {
+        if len > self.buf.len() {
+            let needed = (len - self.buf.len()).max(READ_CHUNK_SIZE);
+            (&mut self.inner).take(needed as u64).read_to_end(&mut self.buf)?;
+        }
+        Ok(())
+    }
+
+    fn read_all(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.read_to_end(&mut self.buf)?;
+        Ok(&self.buf)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    const fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<R: Read + Seek> Read for ReadBuffer<R> {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        self.fill_to(self.pos + out.len())?;
+        let available = self.buf.get(self.pos..).unwrap_or_default();
+        let num_read = available.len().min(out.len());
+        out[..num_read].copy_from_slice(&available[..num_read]);
+        self.pos += num_read;
+        Ok(num_read)
+    }
+}
+
+impl<R: Read + Seek> Seek for ReadBuffer<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => Some(offset as usize),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset as isize),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset as isize),
+        };
+        self.pos = pos.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "Seek before the start of the recording"))?;
+        Ok(self.pos as u64)
+    }
+}
+
+/// Offset of the first keyframe that starts at or after the given offset
+fn find_keyframe(buf: &[u8], from: usize) -> Option<usize> {
+    // the Keyframe field exactly as the recorder writes it
+    let mut pattern = Cursor::new(Vec::new());
+    pattern.write_le(&GameField::Keyframe(KEYFRAME_SIGNATURE)).ok()?;
+    let pattern = pattern.into_inner();
+    let search_start = from + KEYFRAME_FIELD_OFFSET;
+    buf.get(search_start..)?
+        .windows(pattern.len())
+        .position(|window| window == pattern.as_slice())
+        .map(|i| search_start + i - KEYFRAME_FIELD_OFFSET)
+}
+
+/// One launch of the game recorded into the file
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub start_time: Option<String>,
+    pub frames: Range<usize>,
+    /// Whether the recorder closed the session cleanly. Sessions from recorders that don't write
+    /// footers are assumed to have been.
+    pub is_closed: bool,
+}
+
+impl Session {
+    pub fn name(&self, index: usize) -> String {
+        match self.start_time {
+            Some(ref start_time) => format!("Session #{} - {}", index + 1, start_time),
+            None => format!("Session #{}", index + 1),
+        }
+    }
+
+    /// The local time the session started, if the recorder wrote it down
+    pub fn start_datetime(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(self.start_time.as_deref()?, SESSION_TIME_FORMAT).ok()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DoorTransition {
+    pub from: RoomId,
+    pub to: RoomId,
+    pub frame_index: usize,
+    pub frames: usize,
+}
+
+/// Replays the recording a chunk at a time to take state snapshots in long rooms
+#[derive(Debug, Default)]
+struct SnapshotBuilder {
+    next_frame: usize,
+    next_checkpoint: usize,
+    room_start: usize,
+    state: Option<State>,
+}
+
+/// Average door transition times, keyed by source and destination room
+#[derive(Debug, Default)]
+pub struct DoorTimes {
+    times: HashMap<(RoomId, RoomId), (usize, usize)>,
+    sources: HashSet<PathBuf>,
+}
+
+impl DoorTimes {
+    pub fn add_recording(&mut self, path: &Path, recording: &Recording) {
+        if !self.sources.insert(path.to_path_buf()) {
+            // we've already counted this recording
+            return;
+        }
+
+        for transition in recording.door_transitions() {
+            let (total, count) = self.times.entry((transition.from, transition.to)).or_default();
+            *total += transition.frames;
+            *count += 1;
+        }
+    }
+
+    /// The average number of frames and the number of samples for the given transition
+    pub fn average_frames(&self, from: RoomId, to: RoomId) -> Option<(f32, usize)> {
+        let (total, count) = self.times.get(&(from, to))?;
+        Some((*total as f32 / *count as f32, *count))
+    }
+
+    pub fn num_recordings(&self) -> usize {
+        self.sources.len()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PathPrediction {
+    pub actual: CharacterPath,
+    pub predicted: CharacterPath,
+    pub divergences: Vec<(usize, Vec2)>,
+    pub max_error: Fixed32,
+}
+
 #[derive(Debug)]
 pub struct Recording {
     frames: Vec<FrameRecord>,
     states: Vec<State>,
     checkpoints: Vec<State>, // one checkpoint per room transition
+    snapshots: Vec<State>,
+    // None once every snapshot has been taken
+    snapshot_builder: Option<SnapshotBuilder>,
+    control_locks: Vec<ControlLockRange>,
+    menus: Vec<MenuRange>,
+    prompts: Vec<MessagePrompt>,
+    markers: Vec<MarkerEvent>,
+    bookmarks: Vec<Bookmark>,
+    room_spawns: Vec<RoomSpawnEvent>,
+    loads: Vec<LoadEvent>,
+    keyframes: Vec<usize>,
+    damage_events: Vec<DamageEvent>,
+    player_positions: Vec<Option<Vec2>>,
+    player_angles: Vec<Option<Fixed32>>,
+    input_anomalies: Vec<InputAnomaly>,
+    deaggros: Vec<DeaggroEvent>,
+    attack_traces: Vec<AttackTrace>,
+    sessions: Vec<Session>,
+    integrity: RecordingIntegrity,
+    header_flags: u16,
     index: usize,
     range: Range<usize>,
+    // the frames of the current room that states are currently available for
+    state_range: Range<usize>,
 }
 
 impl Recording {
-    pub fn read(mut f: impl Read + Seek + BinReaderExt) -> Result<Self> {
-        // reading the entire file into memory and then parsing it is SIGNIFICANTLY faster than
-        // parsing directly from disk
-        let mut buf = Vec::new();
-        f.read_to_end(&mut buf)?;
+    pub fn read(f: impl Read + Seek + BinReaderExt) -> Result<Self> {
+        Self::read_with_progress(f, |_| ())
+    }
+
+    /// Read a recording, reporting how much of the file has been read so far as a fraction from
+    /// 0 to 1
+    pub fn read_with_progress(f: impl Read + Seek + BinReaderExt, progress: impl Fn(f32)) -> Result<Self> {
+        Self::read_frames(f, usize::MAX, progress)
+    }
+
+    /// Read only the beginning of a recording, up to the given number of frames. Only as much of
+    /// the file as those frames take up is read.
+    pub fn read_partial(f: impl Read + Seek + BinReaderExt, max_frames: usize) -> Result<Self> {
+        Self::read_frames(f, max_frames, |_| ())
+    }
 
-        let size = buf.len() as u64;
-        let mut f = Cursor::new(buf);
+    fn read_frames(f: impl Read + Seek + BinReaderExt, max_frames: usize, progress: impl Fn(f32)) -> Result<Self> {
+        // reading the file into memory in large chunks and then parsing it is SIGNIFICANTLY faster
+        // than parsing directly from disk
+        let mut f = ReadBuffer::new(f)?;
+        let size = f.len() as u64;
 
         let header: RecordHeader = f.read_le()?;
         if header.version == 0 || header.version > RECORD_VERSION {
@@ -662,32 +1662,247 @@ impl Recording {
         let mut state = State::empty();
         let mut frames: Vec<FrameRecord> = Vec::new();
         let mut checkpoints: Vec<State> = Vec::new();
+        let mut control_locks: Vec<ControlLockRange> = Vec::new();
+        let mut menus: Vec<MenuRange> = Vec::new();
+        let mut prompts: Vec<MessagePrompt> = Vec::new();
+        let mut markers: Vec<MarkerEvent> = Vec::new();
+        let mut room_spawns: Vec<RoomSpawnEvent> = Vec::new();
+        let mut loads: Vec<LoadEvent> = Vec::new();
+        let mut keyframes: Vec<usize> = Vec::new();
+        let mut damage_events: Vec<DamageEvent> = Vec::new();
+        // the most recent frame the player attacked on and the weapon they used
+        let mut last_attack: Option<(usize, Item)> = None;
+        let mut player_positions: Vec<Option<Vec2>> = Vec::new();
+        let mut player_angles: Vec<Option<Fixed32>> = Vec::new();
+        let mut anomaly_detector = InputAnomalyDetector::default();
+        let mut pursuit_tracker = PursuitTracker::default();
+        let mut attack_tracer = AttackTracer::default();
+        // recordings from before sessions were tracked won't have a session start, so assume the
+        // first session starts at the beginning of the file
+        let writes_footers = header.has_flag(HEADER_FLAG_SESSION_FOOTERS);
+        let mut sessions = vec![Session { start_time: None, frames: 0..0, is_closed: !writes_footers }];
+        let mut integrity = RecordingIntegrity {
+            is_checksummed: header.is_checksummed(),
+            ..RecordingIntegrity::default()
+        };
+        // byte offset and frame index where the frames covered by the next checksum begin
+        let mut segment_start = (f.stream_position()? as usize, 0usize);
+        // whether damage was skipped since the last checksum
+        let mut is_resumed = false;
         let mut max_room_size = 0usize;
-        while f.stream_position()? < size {
-            let frame = match header.version {
-                1 => {
-                    let frame_v1: FrameRecordV1 = f.read_le()?;
-                    frame_v1.into()
-                }
-                2 => f.read_le()?,
+        while f.stream_position()? < size && frames.len() < max_frames {
+            let frame_start = f.stream_position()? as usize;
+            progress(frame_start as f32 / size as f32);
+            let frame: binrw::BinResult<FrameRecord> = match header.version {
+                1 => f.read_le::<FrameRecordV1>().map(Into::into),
+                2 | 3 => f.read_le(),
                 _ => unreachable!(),
             };
+            let mut frame = match frame {
+                Ok(frame) => frame,
+                // nothing to salvage
+                Err(e) if frames.is_empty() => return Err(e.into()),
+                Err(_) => {
+                    // the file is damaged or was cut off, so pick back up at the next keyframe if there is one
+                    let resume = find_keyframe(f.read_all()?, frame_start + 1);
+                    let resume_offset = resume.unwrap_or(size as usize);
+                    integrity.damage.push(DamagedRange { frame_index: frames.len(), bytes: frame_start..resume_offset });
+                    let Some(resume) = resume else {
+                        break;
+                    };
+                    f.seek(SeekFrom::Start(resume as u64))?;
+                    segment_start = (resume, frames.len());
+                    is_resumed = true;
+                    continue;
+                }
+            };
+            let frame_index = frames.len();
+            for change in &frame.game_changes {
+                match change {
+                    // a new session starts a new checksum chain, so anything since the previous checksum can't be verified
+                    GameField::SessionStart { .. } => {
+                        integrity.add_unverified(segment_start.1..frame_index);
+                        segment_start = (frame_start, frame_index);
+                        is_resumed = false;
+                    }
+                    GameField::Checksum(checksum) => {
+                        let (start_offset, start_frame) = segment_start;
+                        if start_offset == frame_start {
+                            // the keyframe we resumed at checksums the frames before it, which were skipped
+                        } else if crc32_update(0, &f.bytes()[start_offset..frame_start]) == *checksum {
+                            integrity.verified_frames += frame_index - start_frame;
+                        } else if is_resumed {
+                            // recorders that don't start a new chain at each keyframe carry the chain
+                            // on from before the damage, so these frames can't be checked
+                            integrity.add_unverified(start_frame..frame_index);
+                        } else {
+                            integrity.mismatches.push(start_frame..frame_index);
+                        }
+                        segment_start = (frame_start, frame_index);
+                        is_resumed = false;
+                    }
+                    _ => (),
+                }
+            }
+            // the footer isn't a frame of the recording, it just tells us the session was closed cleanly
+            // and checksums the session's last few frames
+            if frame.game_changes.iter().any(|c| matches!(c, GameField::SessionEnd { .. })) {
+                if let Some(session) = sessions.last_mut() {
+                    session.is_closed = true;
+                }
+                continue;
+            }
+            if !header.has_flag(HEADER_FLAG_VERBOSE_CHARACTERS) {
+                // motion IDs are only kept up to date in verbose recordings, so the ones in keyframes are stale
+                for diff in &mut frame.character_diffs {
+                    diff.changes.retain(|c| !matches!(c, CharacterField::Motion(_)));
+                }
+            }
+            let last_health: [Option<(CharacterId, i16)>; NUM_CHARACTERS] = std::array::from_fn(|i| {
+                state.characters[i].as_ref().map(|c| (c.id, c.current_health()))
+            });
             state = state.make_next_state(&frame);
+            if state.is_session_start {
+                let start_time = frame.game_changes.iter().find_map(|change| match change {
+                    // exported recordings have their timestamps zeroed out
+                    GameField::SessionStart { year: 0, .. } => None,
+                    GameField::SessionStart { year, month, day, hour, minute, second } => {
+                        Some(format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}"))
+                    }
+                    _ => None,
+                });
+
+                let session = sessions.last_mut().unwrap();
+                if state.frame_index == 0 {
+                    session.start_time = start_time;
+                } else {
+                    session.frames.end = state.frame_index;
+                    sessions.push(Session { start_time, frames: state.frame_index..state.frame_index, is_closed: !writes_footers });
+                }
+            }
+            anomaly_detector.update(&state);
+            pursuit_tracker.update(&state, &frame);
             if state.room_index >= max_room_size {
                 max_room_size = state.room_index + 1;
             }
             if state.room_index == 0 {
                 checkpoints.push(state.clone());
             }
+            if let Some(lock) = state.control_lock() {
+                let extends_last = control_locks.last().is_some_and(|r| r.lock == lock && r.frames.end == state.frame_index);
+                if extends_last {
+                    control_locks.last_mut().unwrap().frames.end += 1;
+                } else {
+                    control_locks.push(ControlLockRange { lock, frames: state.frame_index..state.frame_index + 1 });
+                }
+            }
+            if let Some(menu) = state.menu() {
+                let extends_last = menus.last().is_some_and(|r| r.menu == menu && r.frames.end == state.frame_index);
+                if extends_last {
+                    menus.last_mut().unwrap().frames.end += 1;
+                } else {
+                    menus.push(MenuRange { menu, frames: state.frame_index..state.frame_index + 1 });
+                }
+            }
+            if state.is_message_shown() {
+                let is_action_pressed = state.input_state_this_frame().is_action_pressed;
+                match prompts.last_mut() {
+                    Some(prompt) if prompt.frames.end == state.frame_index && prompt.room_id == state.room_id => {
+                        prompt.frames.end += 1;
+                        if is_action_pressed {
+                            prompt.first_press.get_or_insert(state.frame_index);
+                        }
+                    }
+                    _ => prompts.push(MessagePrompt {
+                        room_id: state.room_id,
+                        frames: state.frame_index..state.frame_index + 1,
+                        // a press on the frame the message appears is what opened it, not a skip
+                        first_press: None,
+                    }),
+                }
+            }
+            loads.extend(LoadEvent::from_frame(&frame, &state));
+            markers.extend(MarkerEvent::from_frame(&frame, &state));
+            room_spawns.extend(RoomSpawnEvent::from_frame(&frame, &state));
+            if frame.game_changes.iter().any(|c| matches!(c, GameField::Keyframe(_))) {
+                keyframes.push(state.frame_index);
+            }
+            if let Some(weapon) = state.player_attack() {
+                last_attack = Some((state.frame_index, weapon));
+            }
+            if state.room_index != 0 && !state.is_session_start {
+                for (i, (character, last)) in state.characters.iter().zip(last_health).enumerate() {
+                    let (Some(character), Some((last_id, last_health))) = (character, last) else {
+                        continue;
+                    };
+                    let health = character.current_health();
+                    if character.id != last_id || health >= last_health {
+                        continue;
+                    }
+
+                    let kind = if i == 0 {
+                        let pos = character.center();
+                        let attacker = state.characters.iter().skip(1).flatten()
+                            .filter(|c| c.type_() == CharacterType::Enemy)
+                            .min_by_key(|c| (c.center() - pos).len().0)
+                            .map(|c| c.id);
+                        DamageKind::from_attacker(attacker)
+                    } else {
+                        let weapon = last_attack
+                            .filter(|(frame, _)| state.frame_index - *frame <= DAMAGE_ATTRIBUTION_FRAMES)
+                            .map(|(_, weapon)| weapon);
+                        DamageKind::from_weapon(weapon)
+                    };
+                    damage_events.push(DamageEvent {
+                        frame_index: state.frame_index,
+                        room_id: state.room_id,
+                        character_index: i,
+                        character_name: character.name(),
+                        damage: last_health - health,
+                        health_after: health,
+                        kind,
+                    });
+                }
+            }
+            let player_hurt = damage_events.iter()
+                .rev()
+                .take_while(|e| e.frame_index == state.frame_index)
+                .any(DamageEvent::is_player);
+            attack_tracer.update(&state, player_hurt);
+            player_positions.push(state.characters[0].as_ref().map(Character::center));
+            player_angles.push(state.characters[0].as_ref().map(|c| c.angle));
             frames.push(frame);
         }
 
+        sessions.last_mut().unwrap().frames.end = frames.len();
+        integrity.add_unverified(segment_start.1..frames.len());
+
         let mut recording = Self {
             frames,
             index: 0,
-            states: Vec::with_capacity(max_room_size),
+            states: Vec::with_capacity(max_room_size.min(LONG_ROOM_FRAMES)),
             checkpoints,
+            snapshots: Vec::new(),
+            snapshot_builder: Some(SnapshotBuilder::default()),
+            control_locks,
+            menus,
+            prompts,
+            markers,
+            bookmarks: Vec::new(),
+            room_spawns,
+            loads,
+            keyframes,
+            damage_events,
+            player_positions,
+            player_angles,
+            input_anomalies: anomaly_detector.finish(),
+            deaggros: pursuit_tracker.finish(),
+            attack_traces: attack_tracer.finish(),
+            sessions,
+            integrity,
+            header_flags: header.flags,
             range: 0..0,
+            state_range: 0..0,
         };
         // initialize state
         recording.set_index(0);
@@ -695,6 +1910,44 @@ impl Recording {
         Ok(recording)
     }
 
+    /// A recording of just the latest frame streamed from a running game
+    pub fn live(frame: FrameRecord, mut state: State) -> Self {
+        // the stream keeps no history, so this is always the first and only frame
+        state.frame_index = 0;
+        let player = state.characters[0].as_ref();
+        let player_positions = vec![player.map(Character::center)];
+        let player_angles = vec![player.map(|c| c.angle)];
+        let markers = MarkerEvent::from_frame(&frame, &state).collect();
+
+        Self {
+            frames: vec![frame],
+            index: 0,
+            states: vec![state],
+            checkpoints: Vec::new(),
+            snapshots: Vec::new(),
+            snapshot_builder: None,
+            control_locks: Vec::new(),
+            menus: Vec::new(),
+            prompts: Vec::new(),
+            markers,
+            bookmarks: Vec::new(),
+            room_spawns: Vec::new(),
+            loads: Vec::new(),
+            keyframes: Vec::new(),
+            damage_events: Vec::new(),
+            player_positions,
+            player_angles,
+            input_anomalies: Vec::new(),
+            deaggros: Vec::new(),
+            attack_traces: Vec::new(),
+            sessions: vec![Session { start_time: None, frames: 0..1, is_closed: true }],
+            integrity: RecordingIntegrity::default(),
+            header_flags: 0,
+            range: 0..1,
+            state_range: 0..1,
+        }
+    }
+
     pub fn frames(&self) -> &[FrameRecord] {
         &self.frames
     }
@@ -704,12 +1957,68 @@ impl Recording {
     }
 
     pub fn current_state(&self) -> Option<&State> {
-        if !self.range.contains(&self.index) {
+        if !self.state_range.contains(&self.index) {
             return None;
         }
 
-        let room_index = self.index - self.range.start;
-        self.states.get(room_index)
+        self.states.get(self.index - self.state_range.start)
+    }
+
+    /// The state of the given frame if it's available, or otherwise the closest snapshot before it
+    /// in the current room
+    fn state_near(&self, index: usize) -> Option<&State> {
+        if self.state_range.contains(&index) {
+            return self.states.get(index - self.state_range.start);
+        }
+
+        let i = self.snapshots.partition_point(|s| s.frame_index <= index);
+        self.snapshots[..i].last()
+            .filter(|s| s.frame_index > self.range.start)
+            .or_else(|| self.checkpoints.iter().find(|c| c.frame_index == self.range.start))
+    }
+
+    /// Take the next batch of state snapshots, replaying at most the given number of frames.
+    /// Returns true once the whole recording has been indexed.
+    pub fn build_snapshots(&mut self, max_frames: usize) -> bool {
+        let Some(ref mut builder) = self.snapshot_builder else {
+            return true;
+        };
+
+        let end = (builder.next_frame + max_frames).min(self.frames.len());
+        for i in builder.next_frame..end {
+            let state = match self.checkpoints.get(builder.next_checkpoint) {
+                Some(checkpoint) if checkpoint.frame_index == i => {
+                    builder.next_checkpoint += 1;
+                    builder.room_start = i;
+                    checkpoint.clone()
+                }
+                _ => match builder.state.take() {
+                    Some(state) => state.make_next_state(&self.frames[i]),
+                    // no room has started yet
+                    None => continue,
+                },
+            };
+
+            let room_offset = i - builder.room_start;
+            if room_offset > 0 && room_offset % SNAPSHOT_INTERVAL == 0 {
+                self.snapshots.push(state.clone());
+            }
+            builder.state = Some(state);
+        }
+        builder.next_frame = end;
+
+        if end >= self.frames.len() {
+            self.snapshot_builder = None;
+        }
+        self.snapshot_builder.is_none()
+    }
+
+    /// How much of the recording has been indexed for seeking, from 0 to 1
+    pub fn snapshot_progress(&self) -> f32 {
+        match self.snapshot_builder {
+            Some(ref builder) if !self.frames.is_empty() => builder.next_frame as f32 / self.frames.len() as f32,
+            _ => 1.0,
+        }
     }
 
     pub fn peek_next_room(&self) -> Option<&State> {
@@ -752,7 +2061,7 @@ impl Recording {
             self.index = self.frames.len();
         }
 
-        if !self.range.contains(&index) {
+        if !self.state_range.contains(&index) {
             let mut last_state = None;
             let mut end_index = None;
             for checkpoint in &self.checkpoints {
@@ -767,9 +2076,24 @@ impl Recording {
                 return None;
             };
 
-            let start_index = state.frame_index;
-            let end_index = end_index.unwrap_or(self.frames.len());
-            self.range = start_index..end_index;
+            let room_start = state.frame_index;
+            let room_end = end_index.unwrap_or(self.frames.len());
+            self.range = room_start..room_end;
+
+            // in long rooms, only replay from the closest snapshot up to the next one
+            let mut start_index = room_start;
+            let mut end_index = room_end;
+            if room_end - room_start > LONG_ROOM_FRAMES {
+                let i = self.snapshots.partition_point(|s| s.frame_index <= index);
+                if let Some(snapshot) = self.snapshots[..i].last().filter(|s| s.frame_index > room_start) {
+                    state = snapshot.clone();
+                    start_index = snapshot.frame_index;
+                }
+                if let Some(next) = self.snapshots.get(i).filter(|s| s.frame_index < room_end) {
+                    end_index = next.frame_index;
+                }
+            }
+            self.state_range = start_index..end_index;
 
             self.states.clear();
             self.states.push(state.clone());
@@ -789,13 +2113,250 @@ impl Recording {
     pub const fn room_range(&self) -> &Range<usize> {
         &self.range
     }
+
+    pub fn control_locks(&self) -> &[ControlLockRange] {
+        &self.control_locks
+    }
+
+    pub fn control_lock_at(&self, index: usize) -> Option<ControlLock> {
+        let i = self.control_locks.partition_point(|r| r.frames.end <= index);
+        self.control_locks.get(i).filter(|r| r.frames.contains(&index)).map(|r| r.lock)
+    }
+
+    pub fn menus(&self) -> &[MenuRange] {
+        &self.menus
+    }
+
+    pub fn count_menu_frames(&self, range: Range<usize>) -> MenuTime {
+        let mut time = MenuTime::default();
+        for menu_range in &self.menus {
+            *time.frames_mut(menu_range.menu) += menu_range.frames.end.min(range.end).saturating_sub(menu_range.frames.start.max(range.start));
+        }
+        time
+    }
+
+    pub fn prompts(&self) -> &[MessagePrompt] {
+        &self.prompts
+    }
+
+    /// Message prompts that appeared during the given range of frames
+    pub fn prompts_in(&self, range: Range<usize>) -> &[MessagePrompt] {
+        let start = self.prompts.partition_point(|p| p.frames.start < range.start);
+        let end = self.prompts.partition_point(|p| p.frames.start < range.end);
+        &self.prompts[start..end]
+    }
+
+    /// Menu time in each room visit that had any, along with the visit's room and frames
+    pub fn menu_time_by_room(&self) -> Vec<(RoomId, Range<usize>, MenuTime)> {
+        self.room_visits()
+            .into_iter()
+            .map(|(state, range)| (state.room_id(), range.clone(), self.count_menu_frames(range)))
+            .filter(|(_, _, time)| time.total() > 0)
+            .collect()
+    }
+
+    pub fn markers(&self) -> &[MarkerEvent] {
+        &self.markers
+    }
+
+    /// The user's bookmarks, in frame order
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    pub fn bookmarks_mut(&mut self) -> &mut [Bookmark] {
+        &mut self.bookmarks
+    }
+
+    pub fn set_bookmarks(&mut self, mut bookmarks: Vec<Bookmark>) {
+        bookmarks.sort_by_key(|b| b.frame_index);
+        self.bookmarks = bookmarks;
+    }
+
+    /// Add a bookmark, keeping them in frame order, and return its index
+    pub fn add_bookmark(&mut self, bookmark: Bookmark) -> usize {
+        let i = self.bookmarks.partition_point(|b| b.frame_index <= bookmark.frame_index);
+        self.bookmarks.insert(i, bookmark);
+        i
+    }
+
+    pub fn remove_bookmark(&mut self, index: usize) {
+        if index < self.bookmarks.len() {
+            self.bookmarks.remove(index);
+        }
+    }
+
+    pub fn loads(&self) -> &[LoadEvent] {
+        &self.loads
+    }
+
+    /// Loads that landed partway through a room visit rather than at its start
+    pub fn mid_room_loads(&self, visit: Range<usize>) -> impl Iterator<Item = &LoadEvent> {
+        self.loads.iter().filter(move |load| load.frame_index > visit.start && load.frame_index < visit.end)
+    }
+
+    pub fn room_spawns(&self) -> &[RoomSpawnEvent] {
+        &self.room_spawns
+    }
+
+    /// The spawns recorded for the room visit containing the given frame
+    pub fn room_spawns_at(&self, index: usize) -> Option<&RoomSpawnEvent> {
+        let i = self.checkpoints.partition_point(|c| c.frame_index <= index).checked_sub(1)?;
+        let visit_start = self.checkpoints[i].frame_index;
+        let visit_end = self.checkpoints.get(i + 1).map_or(self.frames.len(), |c| c.frame_index);
+        self.room_spawns.iter().find(|e| (visit_start..visit_end).contains(&e.frame_index))
+    }
+
+    /// Frames that record the full game state
+    pub fn keyframes(&self) -> &[usize] {
+        &self.keyframes
+    }
+
+    pub fn damage_events(&self) -> &[DamageEvent] {
+        &self.damage_events
+    }
+
+    pub fn is_control_locked(&self, index: usize) -> bool {
+        self.control_lock_at(index).is_some()
+    }
+
+    pub fn input_anomalies(&self) -> &[InputAnomaly] {
+        &self.input_anomalies
+    }
+
+    pub fn deaggros(&self) -> &[DeaggroEvent] {
+        &self.deaggros
+    }
+
+    pub fn attack_traces(&self) -> &[AttackTrace] {
+        &self.attack_traces
+    }
+
+    /// Write a copy of the recording containing only the given frame ranges. Each range starts with
+    /// a keyframe so it can be played back on its own. Session timestamps and checksums are
+    /// always stripped; the checksums wouldn't match the exported frames anyway.
+    pub fn export(&self, ranges: &[Range<usize>], mut f: impl Write + Seek) -> Result<()> {
+        // the exported frames contain the same features, but no checksums or footers
+        let header = RecordHeader::with_flags(self.header_flags & !(HEADER_FLAG_CHECKSUM | HEADER_FLAG_SESSION_FOOTERS));
+        f.write_le(&header)?;
+
+        for range in ranges {
+            let end = range.end.min(self.frames.len());
+            if range.start >= end {
+                continue;
+            }
+
+            // the start of each range also becomes the start of a new session so state from the
+            // previous range doesn't leak into it
+            let session_start = self.sessions[self.session_index(range.start)].frames.start;
+            let Some(mut keyframe) = squash_frames(&self.frames[session_start..=range.start]) else {
+                continue;
+            };
+            keyframe.game_changes.insert(0, ANONYMOUS_SESSION_START);
+            f.write_le(&keyframe)?;
+
+            for frame in &self.frames[range.start + 1..end] {
+                let mut frame = frame.clone();
+                frame.game_changes.retain(|c| !matches!(c, GameField::Checksum(_)));
+                for change in &mut frame.game_changes {
+                    if matches!(change, GameField::SessionStart { .. }) {
+                        *change = ANONYMOUS_SESSION_START;
+                    }
+                }
+                f.write_le(&frame)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub const fn integrity(&self) -> &RecordingIntegrity {
+        &self.integrity
+    }
+
+    /// Parts of the game state the recorder was configured not to capture
+    pub fn omitted_features(&self) -> Vec<&'static str> {
+        [
+            (HEADER_FLAG_NO_CHARACTERS, "characters"),
+            (HEADER_FLAG_NO_OBJECTS, "objects"),
+            (HEADER_FLAG_NO_TIMING, "frame timing"),
+            (HEADER_FLAG_NO_RNG, "RNG rolls"),
+            (HEADER_FLAG_NO_SOUNDS, "sounds"),
+            (HEADER_FLAG_NO_MODEL_PARTS, "model part transforms"),
+        ].into_iter().filter(|(flag, _)| self.header_flags & flag != 0).map(|(_, name)| name).collect()
+    }
+
+    pub const fn is_verbose(&self) -> bool {
+        self.header_flags & HEADER_FLAG_VERBOSE_CHARACTERS != 0
+    }
+
+    pub fn sessions(&self) -> &[Session] {
+        &self.sessions
+    }
+
+    /// The frame that was being recorded at the given local time. This assumes the game ran at a
+    /// steady 30 FPS, so it drifts over a long session.
+    pub fn frame_at_time(&self, time: NaiveDateTime) -> Option<usize> {
+        self.sessions.iter().find_map(|session| {
+            let elapsed = (time - session.start_datetime()?).to_std().ok()?;
+            let offset = (elapsed.as_secs_f64() / FRAME_DURATION.as_secs_f64()) as usize;
+            (offset < session.frames.len()).then_some(session.frames.start + offset)
+        })
+    }
+
+    pub fn session_index(&self, frame_index: usize) -> usize {
+        self.sessions.iter().rposition(|s| s.frames.start <= frame_index).unwrap_or(0)
+    }
+
+    pub fn count_control_locked_frames(&self, range: Range<usize>) -> usize {
+        self.control_locks
+            .iter()
+            .map(|r| r.frames.end.min(range.end).saturating_sub(r.frames.start.max(range.start)))
+            .sum()
+    }
     
+    pub fn count_rng_rolls(&self, range: Range<usize>) -> usize {
+        self.frames[range.start..range.end.min(self.frames.len())]
+            .iter()
+            .map(|frame| {
+                frame.game_changes
+                    .iter()
+                    .filter(|change| matches!(change, GameField::RngRoll(_, _) | GameField::KnownRng { .. } | GameField::CharacterRng { .. }))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Count the frames the game dropped while playing the given range of recorded frames
+    pub fn count_lag_frames(&self, range: Range<usize>) -> usize {
+        self.frames[range]
+            .iter()
+            .flat_map(|frame| &frame.game_changes)
+            .map(|change| match change {
+                GameField::LagFrames(frames) => *frames as usize,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Lag frames in each room visit that had any, along with the visit's room and frames
+    pub fn lag_by_room(&self) -> Vec<(RoomId, Range<usize>, usize)> {
+        self.room_visits()
+            .into_iter()
+            .map(|(state, range)| (state.room_id(), range.clone(), self.count_lag_frames(range)))
+            .filter(|(_, _, lag)| *lag > 0)
+            .collect()
+    }
+
     pub fn get_rng_descriptions(&self) -> Vec<FrameRng> {
         let mut frames = Vec::new();
         let end = self.index.min(self.frames.len() - 1);
         for i in self.range.start..=end {
             let frame_record = &self.frames[i];
-            let state = &self.states[i - self.range.start];
+            // only character names are needed, so a snapshot is close enough in long rooms
+            let Some(state) = self.state_near(i) else {
+                continue;
+            };
             
             let mut frame_rng = FrameRng::new(i, frame_record.time());
             for change in &frame_record.game_changes {
@@ -831,10 +2392,10 @@ impl Recording {
     
     pub fn get_player_sounds(&self, max_age: usize) -> Vec<PlayerSound> {
         let mut sounds = Vec::new();
-        let start = (self.index - max_age.min(self.index)).max(self.range.start);
+        let start = (self.index - max_age.min(self.index)).max(self.state_range.start);
         let end = self.index.min(self.frames.len() - 1);
         for i in start..=end {
-            let state = &self.states[i - self.range.start];
+            let state = &self.states[i - self.state_range.start];
             if let Some(mut sound) = state.player_sounds() {
                 sound.age = self.index - i;
                 sounds.push(sound);
@@ -843,42 +2404,332 @@ impl Recording {
         
         sounds
     }
+
+    /// The most recent frame up to the current one, within the given number of frames, on which an
+    /// enemy rolled RNG
+    pub fn last_enemy_roll_frame(&self, max_age: usize) -> Option<usize> {
+        let start = (self.index - max_age.min(self.index)).max(self.range.start);
+        let end = self.index.min(self.frames.len() - 1);
+        (start..=end).rev().find(|&i| {
+            self.frames[i].game_changes.iter().any(|change| matches!(change, GameField::CharacterRng { char_index, .. } if *char_index != 0))
+        })
+    }
     
     pub fn get_room_stats(&self) -> RoomStats {
+        let num_locked_frames = self.count_control_locked_frames(self.range.clone());
         RoomStats {
             num_frames: self.range.len(),
             total_time: FRAME_DURATION * (self.range.len() as u32),
-            num_rng_rolls: self.frames[self.range.start..self.range.end]
-                .iter()
-                .map(|frame| {
-                    frame.game_changes
-                        .iter()
-                        .filter(|change| matches!(change, GameField::RngRoll(_, _) | GameField::KnownRng { .. } | GameField::CharacterRng { .. }))
-                        .count()
-                })
-                .sum(),
-            rng_position: RNG_SEQUENCE.iter().position(|r| *r == (self.states[0].rng_value & 0x7fff)).unwrap_or(0),
+            num_locked_frames,
+            active_time: FRAME_DURATION * ((self.range.len() - num_locked_frames) as u32),
+            num_rng_rolls: self.count_rng_rolls(self.range.clone()),
+            rng_position: self.checkpoints.iter()
+                .find(|c| c.frame_index == self.range.start)
+                .unwrap_or(&self.states[0])
+                .rng_position(),
+            num_lag_frames: self.count_lag_frames(self.range.clone()),
+            menu_time: self.count_menu_frames(self.range.clone()),
         }
     }
     
-    pub fn get_path_for_character(&self, index: usize) -> Option<CharacterPath> {
+    fn get_character_state_range(&self, index: usize, max_past: usize, max_future: usize) -> Option<(usize, usize, usize)> {
         let character = self.current_state()?.characters().get(index)?.as_ref()?;
-        let current_index = self.index - self.range.start;
+        let is_same_character = |i: usize| self.states[i].characters()[index].as_ref().map(|c| c.id) == Some(character.id);
+        let current_index = self.index - self.state_range.start;
         let mut start_index = current_index;
-        while start_index > 0 && self.states[start_index - 1].characters()[index].as_ref().map(|c| c.id) == Some(character.id) {
+        while start_index > 0 && current_index - start_index < max_past && is_same_character(start_index - 1) {
             start_index -= 1;
         }
+
+        let mut end_index = current_index;
+        while end_index + 1 < self.states.len() && end_index - current_index < max_future && is_same_character(end_index + 1) {
+            end_index += 1;
+        }
+
+        Some((start_index, current_index, end_index))
+    }
+
+    fn get_character_path(&self, index: usize, max_past: usize, max_future: usize) -> Option<(CharacterPath, usize)> {
+        let character = self.current_state()?.characters().get(index)?.as_ref()?;
+        let (start_index, current_index, end_index) = self.get_character_state_range(index, max_past, max_future)?;
         
-        let mut points = Vec::with_capacity(current_index - start_index + 1);
-        for i in start_index..=current_index {
+        let mut points = Vec::with_capacity(end_index - start_index + 1);
+        let mut locked = Vec::with_capacity(end_index - start_index + 1);
+        for i in start_index..=end_index {
             let Some(state_char) = self.states[i].characters()[index].as_ref() else {
                 continue;
             };
             
             points.push(state_char.center());
+            locked.push(self.is_control_locked(self.state_range.start + i));
         }
         
-        Some(CharacterPath::new(points, character.id, character.floor()))
+        let path = CharacterPath::new(points, character.id, character.floor()).with_locked(locked);
+        Some((path, current_index - start_index))
+    }
+    
+    pub fn get_path_for_character(&self, index: usize) -> Option<CharacterPath> {
+        self.get_character_path(index, usize::MAX, 0).map(|(path, _)| path)
+    }
+
+    /// Each point of the character's path through the current room along with its frame index
+    pub fn get_path_points_for_character(&self, index: usize) -> Vec<(usize, Vec2)> {
+        let Some((start_index, _, end_index)) = self.get_character_state_range(index, usize::MAX, usize::MAX) else {
+            return Vec::new();
+        };
+
+        (start_index..=end_index)
+            .filter_map(|i| Some((self.state_range.start + i, self.states[i].characters()[index].as_ref()?.center())))
+            .collect()
+    }
+
+    pub fn get_path_window_for_character(&self, index: usize, max_past: usize, max_future: usize) -> Option<CharacterPath> {
+        let (path, current_point) = self.get_character_path(index, max_past, max_future)?;
+        Some(path.with_fade(current_point))
+    }
+
+    /// The character's path through the room so far next to the path the AI model predicts. Each
+    /// predicted step starts from where the character actually was on the previous frame, moves
+    /// at the speed it actually moved, and heads where the model says its AI state and the
+    /// player's position would send it.
+    pub fn get_path_prediction_for_character(&self, index: usize) -> Option<PathPrediction> {
+        let character = self.current_state()?.characters().get(index)?.as_ref()?;
+        let (start_index, current_index, _) = self.get_character_state_range(index, usize::MAX, 0)?;
+
+        let mut actual_points = Vec::with_capacity(current_index - start_index + 1);
+        let mut predicted_points = Vec::with_capacity(current_index - start_index + 1);
+        let mut divergences = Vec::new();
+        let mut max_error = Fixed32(0);
+        // the heading predicted from the previous frame
+        let mut heading = None;
+        for i in start_index..=current_index {
+            let characters = self.states[i].characters();
+            let Some(state_char) = characters[index].as_ref() else {
+                continue;
+            };
+
+            let pos = state_char.center();
+            match (actual_points.last().copied(), predicted_points.last().copied(), heading) {
+                (Some(prev_actual), Some(prev_predicted), Some(heading)) => {
+                    let step = state_char.velocity.rotate_y(heading);
+                    predicted_points.push(prev_predicted + step);
+
+                    // compare against a single step from the actual previous position so one
+                    // divergence doesn't cause every following frame to be flagged as well
+                    let error = (pos - (prev_actual + step)).len();
+                    max_error = max_error.max(error);
+                    let frame_index = self.state_range.start + i;
+                    if error > PREDICTION_DIVERGENCE_THRESHOLD && !self.is_control_locked(frame_index) {
+                        divergences.push((frame_index, pos));
+                    }
+                }
+                _ => predicted_points.push(pos),
+            }
+
+            actual_points.push(pos);
+            let player_pos = characters[0].as_ref().map(Character::center);
+            heading = Some(predict_enemy_heading(state_char, player_pos));
+        }
+
+        let actual = CharacterPath::new(actual_points, character.id, character.floor());
+        let mut predicted = CharacterPath::new(predicted_points, character.id, character.floor());
+        predicted.dynamic_color = false;
+
+        Some(PathPrediction {
+            actual,
+            predicted,
+            divergences,
+            max_error,
+        })
+    }
+
+    /// Total danger exposure over the given range of frames, in danger-weighted seconds
+    pub fn get_danger_exposure(&mut self, range: Range<usize>) -> f32 {
+        let mut exposure = 0.0;
+        for i in range {
+            let Some(state) = self.set_index(i) else {
+                break;
+            };
+
+            exposure += state.danger_level();
+        }
+
+        exposure * FRAME_DURATION.as_secs_f32()
+    }
+
+    pub fn run_range(&self, index: usize) -> Range<usize> {
+        let mut start = 0;
+        let mut end = self.frames.len();
+        for checkpoint in &self.checkpoints {
+            if !checkpoint.is_new_game_start {
+                continue;
+            }
+
+            if checkpoint.frame_index <= index {
+                start = checkpoint.frame_index;
+            } else {
+                end = checkpoint.frame_index;
+                break;
+            }
+        }
+
+        start..end
+    }
+
+    /// Classify the run containing the given frame by the scenario most of its rooms were played in
+    pub fn run_summary(&self, index: usize) -> Option<RunSummary> {
+        let frames = self.run_range(index);
+        let mut rooms = self.checkpoints.iter().filter(|c| frames.contains(&c.frame_index));
+        let first = rooms.next()?;
+
+        // the scenario flags may not be settled on the very first frame of a new game, so go by
+        // whichever scenario the majority of the run's rooms agree on
+        let mut votes: HashMap<Scenario, usize> = HashMap::new();
+        *votes.entry(first.scenario()).or_default() += 1;
+        for checkpoint in rooms {
+            *votes.entry(checkpoint.scenario()).or_default() += 1;
+        }
+        let scenario = votes.into_iter().max_by_key(|(_, count)| *count).map(|(scenario, _)| scenario)?;
+
+        Some(RunSummary {
+            scenario,
+            start_room: first.room_id,
+            is_new_game: first.is_new_game_start,
+            menu_time: self.count_menu_frames(frames.clone()),
+            frames,
+        })
+    }
+
+    pub fn run_summaries(&self) -> Vec<RunSummary> {
+        self.checkpoints
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| *i == 0 || c.is_new_game_start)
+            .filter_map(|(_, c)| self.run_summary(c.frame_index))
+            .collect()
+    }
+
+    pub fn room_visits(&self) -> Vec<(&State, Range<usize>)> {
+        self.checkpoints
+            .iter()
+            .enumerate()
+            .map(|(i, checkpoint)| {
+                let end = self.checkpoints.get(i + 1).map(|c| c.frame_index).unwrap_or(self.frames.len());
+                (checkpoint, checkpoint.frame_index..end)
+            })
+            .collect()
+    }
+
+    pub fn player_position(&self, index: usize) -> Option<Vec2> {
+        self.player_positions.get(index).copied().flatten()
+    }
+
+    /// The player's typical running speed in units per frame, measured from their movement while
+    /// not control-locked
+    pub fn player_run_speed(&self) -> Option<Fixed32> {
+        let mut speeds: Vec<Fixed32> = self.player_positions
+            .windows(2)
+            .enumerate()
+            .filter(|(i, _)| !self.is_control_locked(i + 1))
+            .filter_map(|(_, pair)| Some((pair[1]? - pair[0]?).len()))
+            .filter(|speed| speed.0 > 0 && *speed < MAX_PLAUSIBLE_SPEED)
+            .collect();
+
+        if speeds.is_empty() {
+            return None;
+        }
+
+        // most movement in a speedrun is running, so a high percentile should land on the run speed
+        // without being thrown off by the odd collision push
+        speeds.sort();
+        Some(speeds[speeds.len() * 9 / 10])
+    }
+
+    /// The player's typical turning speed in angle units per frame, measured the same way as
+    /// player_run_speed
+    pub fn player_turn_rate(&self) -> Option<Fixed32> {
+        let mut rates: Vec<Fixed32> = self.player_angles
+            .windows(2)
+            .enumerate()
+            .filter(|(i, _)| !self.is_control_locked(i + 1))
+            .filter_map(|(_, pair)| {
+                // angles wrap at 0x1000
+                let delta = (pair[1]?.0 - pair[0]?.0) & 0xfff;
+                Some(Fixed32(delta.min(0x1000 - delta)))
+            })
+            .filter(|rate| rate.0 > 0 && rate.0 < MAX_PLAUSIBLE_TURN_RATE)
+            .collect();
+
+        if rates.is_empty() {
+            return None;
+        }
+
+        rates.sort();
+        Some(rates[rates.len() * 9 / 10])
+    }
+
+    pub fn get_player_path(&self, range: Range<usize>) -> Option<CharacterPath> {
+        let mut points = Vec::with_capacity(range.len());
+        let mut locked = Vec::with_capacity(range.len());
+        for i in range {
+            let Some(pos) = self.player_positions.get(i).copied().flatten() else {
+                continue;
+            };
+
+            points.push(pos);
+            locked.push(self.is_control_locked(i));
+        }
+
+        if points.len() < 2 {
+            return None;
+        }
+
+        Some(CharacterPath::new(points, CharacterId::Unknown, Floor::ANY).with_locked(locked))
+    }
+
+    pub fn get_run_paths_for_room(&self, room_id: RoomId) -> Vec<CharacterPath> {
+        let run_range = self.run_range(self.index);
+        self.room_visits()
+            .into_iter()
+            .filter(|(checkpoint, range)| checkpoint.room_id == room_id && run_range.contains(&range.start))
+            .filter_map(|(checkpoint, range)| {
+                let mut path = self.get_player_path(range)?;
+                if let Some(ref player) = checkpoint.characters[0] {
+                    path.character_id = player.id;
+                }
+                Some(path)
+            })
+            .collect()
+    }
+
+    /// Door transitions measured from the player losing control in one room to regaining it in the next
+    pub fn door_transitions(&self) -> Vec<DoorTransition> {
+        let mut transitions = Vec::new();
+        for (i, pair) in self.checkpoints.windows(2).enumerate() {
+            let (prev, checkpoint) = (&pair[0], &pair[1]);
+            if checkpoint.is_new_game_start {
+                continue;
+            }
+
+            let next_bound = self.checkpoints.get(i + 2).map_or(self.frames.len(), |c| c.frame_index);
+            let mut start = checkpoint.frame_index;
+            while start > prev.frame_index && self.is_control_locked(start - 1) {
+                start -= 1;
+            }
+            let mut end = checkpoint.frame_index;
+            while end < next_bound && self.is_control_locked(end) {
+                end += 1;
+            }
+
+            transitions.push(DoorTransition {
+                from: prev.room_id,
+                to: checkpoint.room_id,
+                frame_index: start,
+                frames: end - start,
+            });
+        }
+
+        transitions
     }
 
     pub fn timeline(&self) -> Vec<Vec<(String, &State)>> {
@@ -900,4 +2751,157 @@ impl Recording {
 
         timeline
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(igt_frames: u8, game_changes: Vec<GameField>) -> FrameRecord {
+        FrameRecord {
+            igt_seconds: 0,
+            igt_frames,
+            num_rng_rolls: 0,
+            game_changes,
+            character_diffs: Vec::new(),
+            object_diffs: Vec::new(),
+        }
+    }
+
+    fn write_recording(frames: &[FrameRecord]) -> Vec<u8> {
+        let mut f = Cursor::new(Vec::new());
+        f.write_le(&RecordHeader::with_flags(0)).unwrap();
+        for frame in frames {
+            f.write_le(frame).unwrap();
+        }
+        f.into_inner()
+    }
+
+    #[test]
+    fn test_squash_frames() {
+        let frames = [
+            frame(0, vec![ANONYMOUS_SESSION_START, GameField::StageIndex(1), GameField::Rng(5), GameField::Checksum(0)]),
+            frame(1, vec![GameField::RngRoll(1, 6), GameField::Rng(6), GameField::LagFrames(2)]),
+            frame(2, vec![GameField::Keyframe(KEYFRAME_SIGNATURE), GameField::Rng(7), GameField::LagFrames(1)]),
+        ];
+
+        let squashed = squash_frames(&frames).unwrap();
+        assert_eq!(squashed.igt_frames, 2);
+        // state keeps its latest value, events only come from the last frame, and nothing about
+        // the original file survives
+        assert!(
+            matches!(squashed.game_changes[..], [GameField::StageIndex(1), GameField::Rng(7), GameField::LagFrames(1)]),
+            "{:?}", squashed.game_changes,
+        );
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let frames: Vec<_> = (0..6u8).map(|i| frame(i, vec![GameField::Rng(i as u16)])).collect();
+        let recording = Recording::read(Cursor::new(write_recording(&frames))).unwrap();
+        assert_eq!(recording.frames().len(), 6);
+
+        let mut exported = Cursor::new(Vec::new());
+        recording.export(&[2..4], &mut exported).unwrap();
+        let exported = Recording::read(Cursor::new(exported.into_inner())).unwrap();
+
+        assert_eq!(exported.frames().len(), 2);
+        assert_eq!(exported.frames()[0].igt_frames, 2);
+        assert!(matches!(exported.frames()[0].game_changes[..], [GameField::SessionStart { .. }, GameField::Rng(2)]));
+        assert!(matches!(exported.frames()[1].game_changes[..], [GameField::Rng(3)]));
+    }
+
+    #[test]
+    fn test_read_partial() {
+        let frames: Vec<_> = (0..6u8).map(|i| frame(i, vec![GameField::Rng(i as u16)])).collect();
+        let recording = Recording::read_partial(Cursor::new(write_recording(&frames)), 4).unwrap();
+        assert_eq!(recording.frames().len(), 4);
+        assert_eq!(recording.frames()[3].igt_frames, 3);
+    }
+
+    #[test]
+    fn test_read_buffer_reads_lazily() {
+        let data = vec![7u8; READ_CHUNK_SIZE * 3];
+        let mut f = ReadBuffer::new(Cursor::new(data)).unwrap();
+        let mut out = [0u8; 16];
+        f.read_exact(&mut out).unwrap();
+        assert_eq!(out, [7u8; 16]);
+        assert_eq!(f.bytes().len(), READ_CHUNK_SIZE);
+        assert_eq!(f.seek(SeekFrom::End(-1)).unwrap() as usize, READ_CHUNK_SIZE * 3 - 1);
+        assert_eq!(f.bytes().len(), READ_CHUNK_SIZE);
+        assert_eq!(f.read(&mut out).unwrap(), 1);
+        assert_eq!(f.read(&mut out).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_find_keyframe() {
+        let mut f = Cursor::new(Vec::new());
+        f.write_le(&frame(0, vec![GameField::Rng(1)])).unwrap();
+        let keyframe_offset = f.position() as usize;
+        f.write_le(&frame(1, vec![GameField::Keyframe(KEYFRAME_SIGNATURE), GameField::Rng(2)])).unwrap();
+        let buf = f.into_inner();
+
+        assert_eq!(find_keyframe(&buf, 0), Some(keyframe_offset));
+        assert_eq!(find_keyframe(&buf, keyframe_offset + 1), None);
+    }
+
+    #[test]
+    fn test_keyframe_recovery() {
+        let mut buf = write_recording(&[frame(0, vec![GameField::Rng(1)])]);
+        let damaged_offset = buf.len();
+        let mut f = Cursor::new(Vec::new());
+        f.write_le(&frame(1, vec![GameField::Rng(2)])).unwrap();
+        f.write_le(&frame(2, vec![GameField::Keyframe(KEYFRAME_SIGNATURE), GameField::Rng(3)])).unwrap();
+        buf.extend(f.into_inner());
+        // an unknown field type makes the second frame unreadable
+        buf[damaged_offset + KEYFRAME_FIELD_OFFSET] = 0xEE;
+
+        let recording = Recording::read(Cursor::new(buf)).unwrap();
+        assert_eq!(recording.frames().len(), 2);
+        assert_eq!(recording.frames()[1].igt_frames, 2);
+
+        let damage = &recording.integrity().damage;
+        assert_eq!(damage.len(), 1);
+        assert_eq!(damage[0].frame_index, 1);
+        assert_eq!(damage[0].bytes.start, damaged_offset);
+    }
+
+    /// A damaged frame followed by a keyframe and a frame checksumming everything since the keyframe
+    fn recovered_recording(keyframe_changes: Vec<GameField>) -> Vec<u8> {
+        let mut buf = write_recording(&[frame(0, vec![GameField::Rng(1)])]);
+        let damaged_offset = buf.len();
+        let mut f = Cursor::new(Vec::new());
+        f.write_le(&frame(1, vec![GameField::Rng(2)])).unwrap();
+        let keyframe_offset = damaged_offset + f.position() as usize;
+        f.write_le(&frame(2, keyframe_changes)).unwrap();
+        buf.extend(f.into_inner());
+        buf[damaged_offset + KEYFRAME_FIELD_OFFSET] = 0xEE;
+
+        let checksum = crc32_update(0, &buf[keyframe_offset..]);
+        let mut f = Cursor::new(Vec::new());
+        f.write_le(&frame(3, vec![GameField::Rng(4), GameField::Checksum(checksum)])).unwrap();
+        buf.extend(f.into_inner());
+        buf
+    }
+
+    #[test]
+    fn test_checksums_after_keyframe_recovery() {
+        // the keyframe checksums the frames before it and starts a new chain
+        let buf = recovered_recording(vec![GameField::Keyframe(KEYFRAME_SIGNATURE), GameField::Rng(3), GameField::Checksum(0x1234)]);
+        let recording = Recording::read(Cursor::new(buf)).unwrap();
+        let integrity = recording.integrity();
+        assert!(integrity.mismatches.is_empty(), "{:?}", integrity.mismatches);
+        assert_eq!(integrity.verified_frames, 1);
+
+        // an older recorder carries the chain on from before the damage, which can't be checked
+        let mut buf = recovered_recording(vec![GameField::Keyframe(KEYFRAME_SIGNATURE), GameField::Rng(3)]);
+        // the checksum is followed by the two diff counts
+        let checksum_offset = buf.len() - 6;
+        buf[checksum_offset..checksum_offset + 4].copy_from_slice(&0x1234u32.to_le_bytes());
+        let recording = Recording::read(Cursor::new(buf)).unwrap();
+        let integrity = recording.integrity();
+        assert!(integrity.mismatches.is_empty(), "{:?}", integrity.mismatches);
+        assert_eq!(integrity.verified_frames, 0);
+        assert_eq!(integrity.unverified[0], 1..2);
+    }
+}
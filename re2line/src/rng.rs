@@ -1,8 +1,12 @@
+use std::io::Write;
+use std::path::Path;
 use std::sync::LazyLock;
 
+use anyhow::Result;
 use enum_map::{EnumMap, enum_map};
 use re2shared::rng::RollType;
 use residat::common::Fixed16;
+use serde::{Deserialize, Serialize};
 
 pub mod sim;
 
@@ -622,6 +626,43 @@ fn water_splash(seed: u16) -> String {
     format!("{}", roll8(seed).overflowing_mul(4).0)
 }
 
+/// A named position in the RNG sequence to come back to later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RngBookmark {
+    pub name: String,
+    pub rng_index: usize,
+}
+
+/// A saved Explore RNG search, which can be shared with other people as a JSON file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RngPreset {
+    pub name: String,
+    /// Roll type by name, since the numbering changes as rolls are added
+    pub roll_type: String,
+    pub outcomes: Vec<String>,
+    pub range_min: isize,
+    pub range_max: isize,
+    pub by_desired_outcome: bool,
+    pub run_threshold: f64,
+    pub run_window_size: usize,
+}
+
+impl RngPreset {
+    pub fn roll_type(&self) -> Option<RollType> {
+        ROLL_DESCRIPTIONS.iter().map(|(roll_type, _)| roll_type).find(|roll_type| format!("{roll_type:?}") == self.roll_type)
+    }
+
+    pub fn load(path: &Path) -> Result<Vec<Self>> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    pub fn write(presets: &[Self], f: impl Write) -> Result<()> {
+        serde_json::to_writer_pretty(f, presets)?;
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct RollDescription {
     description: &'static str,
@@ -690,6 +731,39 @@ impl RollDescription {
         }
     }
     
+    /// How often each outcome comes up over the RNG's whole cycle, most common first
+    pub fn cycle_distribution(&self) -> Vec<(String, f32)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for seed in RNG_SEQUENCE {
+            let Some(outcome) = self.outcome(seed) else {
+                return Vec::new();
+            };
+
+            match counts.iter_mut().find(|(o, _)| *o == outcome) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((outcome, 1)),
+            }
+        }
+
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts.into_iter()
+            .map(|(outcome, count)| (outcome, count as f32 / RNG_SEQUENCE.len() as f32))
+            .collect()
+    }
+
+    /// How often a success-or-failure roll succeeds over the RNG's whole cycle
+    pub fn success_chance(&self) -> Option<f32> {
+        if self.result_options.as_slice() != BOOL_OPTIONS {
+            return None;
+        }
+
+        let success = self.cycle_distribution()
+            .into_iter()
+            .find(|(outcome, _)| outcome == BOOL_OPTIONS[0])
+            .map_or(0.0, |(_, chance)| chance);
+        Some(success)
+    }
+
     pub fn options(&self) -> &[&'static str] {
         &self.result_options
     }
@@ -785,6 +859,11 @@ pub static ROLL_DESCRIPTIONS: LazyLock<EnumMap<RollType, RollDescription>> = Laz
     }
 });
 
+// walking the whole RNG cycle is too slow to do every frame, so the odds are worked out once
+pub static ROLL_SUCCESS_CHANCES: LazyLock<EnumMap<RollType, Option<f32>>> = LazyLock::new(|| {
+    EnumMap::from_fn(|roll_type| ROLL_DESCRIPTIONS[roll_type].success_chance())
+});
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -801,4 +880,11 @@ mod tests {
         }
         panic!("RNG did not loop");
     }
+
+    #[test]
+    fn test_success_chance() {
+        let lunge = ROLL_SUCCESS_CHANCES[RollType::ZombieLunge25].unwrap();
+        assert!((lunge - 0.25).abs() < 0.01, "lunge chance was {lunge}");
+        assert_eq!(ROLL_SUCCESS_CHANCES[RollType::ZombieHealth], None);
+    }
 }
\ No newline at end of file
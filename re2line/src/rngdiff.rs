@@ -0,0 +1,117 @@
+use std::ops::Range;
+
+use re2shared::record::{FrameRecord, GameField};
+use re2shared::rng::RollType;
+
+use crate::record::{Recording, State};
+
+/// What made an RNG roll, regardless of the value it got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollSource {
+    Unknown(u32),
+    Known(RollType),
+    Character(u8, RollType),
+}
+
+impl RollSource {
+    fn from_field(field: &GameField) -> Option<(Self, u16)> {
+        match field {
+            GameField::RngRoll(address, value) => Some((Self::Unknown(*address), *value)),
+            GameField::KnownRng { roll_type, start_value } => Some((Self::Known(*roll_type), *start_value)),
+            GameField::CharacterRng { char_index, roll_type, start_value } => Some((Self::Character(*char_index, *roll_type), *start_value)),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Unknown(address) => format!("{address:08X}"),
+            Self::Known(roll_type) => format!("{roll_type:?}"),
+            Self::Character(char_index, roll_type) => format!("#{char_index} {roll_type:?}"),
+        }
+    }
+}
+
+/// One roll made during a compared stretch of frames
+#[derive(Debug, Clone, Copy)]
+pub struct ComparedRoll {
+    /// Frames since the start of the compared stretch
+    pub frame_offset: usize,
+    pub source: RollSource,
+    pub value: u16,
+}
+
+impl ComparedRoll {
+    fn list(frames: &[FrameRecord]) -> Vec<Self> {
+        frames.iter()
+            .enumerate()
+            .flat_map(|(frame_offset, frame)| {
+                frame.game_changes.iter().filter_map(move |change| {
+                    RollSource::from_field(change).map(|(source, value)| Self { frame_offset, source, value })
+                })
+            })
+            .collect()
+    }
+
+    fn matches(&self, other: &Self) -> bool {
+        self.frame_offset == other.frame_offset && self.source == other.source && self.value == other.value
+    }
+}
+
+/// Two recordings' RNG rolls over a visit to the same room, lined up frame by frame from the
+/// start of each visit
+#[derive(Debug, Clone)]
+pub struct RngDiff {
+    pub left_frames: Range<usize>,
+    pub right_frames: Range<usize>,
+    pub left_rng_position: usize,
+    pub right_rng_position: usize,
+    pub left_rolls: Vec<ComparedRoll>,
+    pub right_rolls: Vec<ComparedRoll>,
+    /// Index of the first roll that was made on a different frame, by something else, or from a
+    /// different seed
+    pub first_difference: Option<usize>,
+    /// Frame offset and how many more rolls the left recording had made than the right since the
+    /// start of the visit
+    pub roll_offsets: Vec<[f64; 2]>,
+}
+
+impl RngDiff {
+    pub fn compare(left: &Recording, left_visit: &(&State, Range<usize>), right: &Recording, right_visit: &(&State, Range<usize>)) -> Self {
+        let left_frames = &left.frames()[left_visit.1.clone()];
+        let right_frames = &right.frames()[right_visit.1.clone()];
+        let left_rolls = ComparedRoll::list(left_frames);
+        let right_rolls = ComparedRoll::list(right_frames);
+
+        let first_difference = (0..left_rolls.len().max(right_rolls.len())).find(|i| {
+            match (left_rolls.get(*i), right_rolls.get(*i)) {
+                (Some(l), Some(r)) => !l.matches(r),
+                _ => true,
+            }
+        });
+
+        // the frame's roll count includes rolls that weren't recorded individually
+        let mut roll_offsets = Vec::with_capacity(left_frames.len().min(right_frames.len()));
+        let mut offset = 0isize;
+        for (i, (l, r)) in left_frames.iter().zip(right_frames).enumerate() {
+            offset += l.num_rng_rolls as isize - r.num_rng_rolls as isize;
+            roll_offsets.push([i as f64, offset as f64]);
+        }
+
+        Self {
+            left_frames: left_visit.1.clone(),
+            right_frames: right_visit.1.clone(),
+            left_rng_position: left_visit.0.rng_position(),
+            right_rng_position: right_visit.0.rng_position(),
+            left_rolls,
+            right_rolls,
+            first_difference,
+            roll_offsets,
+        }
+    }
+
+    /// How many more rolls the left recording had made than the right by the end of the shorter visit
+    pub fn final_offset(&self) -> isize {
+        self.roll_offsets.last().map_or(0, |p| p[1] as isize)
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app::RoomId;
+use crate::record::Recording;
+
+/// The order of rooms visited in a run
+fn room_sequence(recording: &Recording, run_index: usize) -> Vec<(RoomId, usize)> {
+    let frames = recording.run_range(run_index);
+    let mut rooms: Vec<(RoomId, usize)> = Vec::new();
+    for (state, range) in recording.room_visits() {
+        if !frames.contains(&range.start) {
+            continue;
+        }
+
+        // a new session or a loading screen can start a new visit without actually changing rooms
+        if rooms.last().is_some_and(|(room_id, _)| *room_id == state.room_id()) {
+            continue;
+        }
+
+        rooms.push((state.room_id(), range.start));
+    }
+
+    rooms
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteDeviation {
+    Skipped(RoomId),
+    Extra(RoomId, usize),
+}
+
+impl Display for RouteDeviation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skipped(room_id) => write!(f, "Skipped {}", room_id),
+            Self::Extra(room_id, frame_index) => write!(f, "Unexpected visit to {} ({})", room_id, frame_index),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RouteMatch {
+    pub source_path: PathBuf,
+    pub run_frame: usize,
+    pub similarity: f32,
+    pub deviations: Vec<RouteDeviation>,
+}
+
+impl RouteMatch {
+    pub fn name(&self) -> String {
+        let file_name = self.source_path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        format!("{} ({}): {:.0}%", file_name, self.run_frame, self.similarity * 100.0)
+    }
+}
+
+/// A reference route that other runs are scored against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteTemplate {
+    pub name: String,
+    pub rooms: Vec<RoomId>,
+}
+
+impl RouteTemplate {
+    pub fn from_run(name: String, recording: &Recording, run_index: usize) -> Self {
+        Self {
+            name,
+            rooms: room_sequence(recording, run_index).into_iter().map(|(room_id, _)| room_id).collect(),
+        }
+    }
+
+    /// Score how closely a run's room order follows this route, based on the longest common
+    /// subsequence of rooms between the two
+    pub fn score_run(&self, source_path: &Path, recording: &Recording, run_index: usize) -> RouteMatch {
+        let rooms = room_sequence(recording, run_index);
+        let (n, m) = (self.rooms.len(), rooms.len());
+
+        let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if self.rooms[i] == rooms[j].0 {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut deviations = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && self.rooms[i] == rooms[j].0 {
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                deviations.push(RouteDeviation::Extra(rooms[j].0, rooms[j].1));
+                j += 1;
+            } else {
+                deviations.push(RouteDeviation::Skipped(self.rooms[i]));
+                i += 1;
+            }
+        }
+
+        let similarity = if n + m > 0 {
+            (lcs[0][0] * 2) as f32 / (n + m) as f32
+        } else {
+            1.0
+        };
+
+        RouteMatch {
+            source_path: source_path.to_path_buf(),
+            run_frame: recording.run_range(run_index).start,
+            similarity,
+            deviations,
+        }
+    }
+
+    /// Score every run in the given recordings, best match first
+    pub fn score_recordings(&self, recording_paths: &[PathBuf]) -> Result<Vec<RouteMatch>> {
+        let mut matches = Vec::new();
+        for path in recording_paths {
+            let recording = Recording::read(File::open(path)?)?;
+            for summary in recording.run_summaries() {
+                matches.push(self.score_run(path, &recording, summary.frames.start));
+            }
+        }
+
+        matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        Ok(matches)
+    }
+}
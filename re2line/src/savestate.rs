@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::{DateTime, Local, NaiveDateTime};
+
+use crate::app::RoomId;
+use crate::record::Recording;
+
+pub const SAVE_STATE_EXTENSIONS: [&str; 14] = [
+    "sav", "state", "sst", "mcr", "ss0", "ss1", "ss2", "ss3", "ss4", "ss5", "ss6", "ss7", "ss8", "ss9",
+];
+
+/// A save state or save file the user has associated with a point in the recording
+#[derive(Debug, Clone)]
+pub struct SaveStateLink {
+    pub path: PathBuf,
+    pub room_id: Option<RoomId>,
+    pub igt_seconds: Option<u32>,
+    /// When the file was last written, in local time
+    pub modified: Option<NaiveDateTime>,
+    /// The recorded frame that best matches the room and IGT, if any
+    pub frame_index: Option<usize>,
+}
+
+impl SaveStateLink {
+    /// Create a link for the given file, guessing the room and IGT from its name. Names like
+    /// `1010_12m34s.sav` or `claire 1010 1h02m03s.ss0` are understood. Emulators name their files
+    /// after the game instead, so the file's modification time is kept to fall back on.
+    pub fn from_path(path: PathBuf) -> Self {
+        let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let tokens: Vec<&str> = name.split(|c: char| !c.is_ascii_alphanumeric()).filter(|t| !t.is_empty()).collect();
+
+        let room_id = tokens.iter()
+            .filter(|t| t.len() == 4)
+            .find_map(|t| RoomId::from_str(t).ok());
+        let igt_seconds = tokens.iter().find_map(|t| Self::parse_igt(t));
+        let modified = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|time| DateTime::<Local>::from(time).naive_local());
+
+        Self {
+            path,
+            room_id,
+            igt_seconds,
+            modified,
+            frame_index: None,
+        }
+    }
+
+    fn parse_igt(token: &str) -> Option<u32> {
+        let token = token.to_lowercase();
+        let token = token.strip_suffix('s')?;
+        let (rest, seconds) = token.split_once('m')?;
+        let (hours, minutes) = rest.split_once('h').unwrap_or(("0", rest));
+
+        let hours: u32 = hours.parse().ok()?;
+        let minutes: u32 = minutes.parse().ok()?;
+        let seconds: u32 = seconds.parse().ok()?;
+        Some(hours * 3600 + minutes * 60 + seconds)
+    }
+
+    pub fn name(&self) -> String {
+        self.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+
+    /// Find the frame in the recording that this save state corresponds to. With an IGT, we pick
+    /// the frame in the room whose IGT is closest; otherwise we use the first entry to the room.
+    /// Without a room, we look for the frame that was being recorded when the file was written.
+    pub fn correlate(&mut self, recording: &Recording) {
+        self.frame_index = None;
+        let Some(room_id) = self.room_id else {
+            self.frame_index = self.modified.and_then(|time| recording.frame_at_time(time));
+            return;
+        };
+
+        let frames = recording.frames();
+        let mut candidates = recording.room_visits()
+            .into_iter()
+            .filter(|(state, _)| state.room_id() == room_id)
+            .flat_map(|(_, range)| range);
+
+        self.frame_index = match self.igt_seconds {
+            Some(igt) => candidates.min_by_key(|i| frames[*i].igt_seconds.abs_diff(igt)),
+            None => candidates.next(),
+        };
+    }
+}
@@ -1,5 +1,5 @@
 use residat::common::Vec2;
-use residat::re2::Instruction;
+use residat::re2::{CharacterId, Instruction};
 
 use crate::aot::{Entity, EntityForm};
 use crate::app::{Floor, WorldPos};
@@ -7,6 +7,8 @@ use crate::collision::{CapsuleType, Collider, QuadCollider, RectCollider};
 
 pub trait InstructionExt {
     fn to_entity(&self) -> Option<Entity>;
+
+    fn enemy_id(&self) -> Option<CharacterId>;
 }
 
 impl InstructionExt for Instruction {
@@ -94,4 +96,11 @@ impl InstructionExt for Instruction {
             _ => return None,
         })
     }
+
+    fn enemy_id(&self) -> Option<CharacterId> {
+        match self {
+            Self::SceEmSet { id, .. } => CharacterId::try_from(*id).ok(),
+            _ => None,
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,233 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::character::{Character, PositionedAiZone};
+
+// how often idle threads check whether the server has been stopped
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Serialize)]
+pub struct CharacterSnapshot {
+    pub index: usize,
+    pub name: &'static str,
+    pub x: i32,
+    pub z: i32,
+    pub angle: i32,
+    pub health: i16,
+    pub state: String,
+}
+
+impl From<&Character> for CharacterSnapshot {
+    fn from(character: &Character) -> Self {
+        let center = character.center();
+        Self {
+            index: character.index(),
+            name: character.name(),
+            x: center.x.0,
+            z: center.z.0,
+            angle: character.angle.0,
+            health: character.current_health(),
+            state: character.describe_state(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiZoneSnapshot {
+    pub character_index: usize,
+    pub name: &'static str,
+    pub behavior: &'static str,
+    pub x: i32,
+    pub z: i32,
+    pub angle: i32,
+    pub radius: u16,
+    pub half_angle: i16,
+    pub offset_angle: i16,
+    pub inverted: bool,
+}
+
+impl From<&PositionedAiZone> for AiZoneSnapshot {
+    fn from(zone: &PositionedAiZone) -> Self {
+        Self {
+            character_index: zone.character_index,
+            name: zone.ai_zone.name,
+            behavior: zone.ai_zone.behavior_type.name(),
+            x: zone.pos.x.0,
+            z: zone.pos.z.0,
+            angle: zone.angle.0,
+            radius: zone.ai_zone.radius.0,
+            half_angle: zone.ai_zone.half_angle.0,
+            offset_angle: zone.ai_zone.offset_angle.0,
+            inverted: zone.ai_zone.inverted,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PlaybackSnapshot {
+    pub room: Option<String>,
+    pub frame_index: usize,
+    pub num_frames: usize,
+    pub is_playing: bool,
+    pub characters: Vec<CharacterSnapshot>,
+    pub ai_zones: Vec<AiZoneSnapshot>,
+}
+
+#[derive(Debug, Default)]
+struct Published {
+    json: String,
+    version: u64,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    published: Mutex<Published>,
+    changed: Condvar,
+    is_stopped: AtomicBool,
+}
+
+impl Shared {
+    fn is_stopped(&self) -> bool {
+        self.is_stopped.load(Ordering::Relaxed)
+    }
+
+    fn current(&self) -> (String, u64) {
+        let published = self.published.lock().unwrap();
+        (published.json.clone(), published.version)
+    }
+
+    /// Wait until the published state is newer than the given version
+    fn wait_for_change(&self, version: u64) -> Option<(String, u64)> {
+        let mut published = self.published.lock().unwrap();
+        while published.version == version {
+            if self.is_stopped() {
+                return None;
+            }
+            published = self.changed.wait_timeout(published, POLL_INTERVAL).unwrap().0;
+        }
+
+        Some((published.json.clone(), published.version))
+    }
+}
+
+/// A small local HTTP server that mirrors playback state for external tools like stream overlays.
+/// `GET /state` returns the current state as JSON, and `GET /events` is a Server-Sent Events stream
+/// that sends the state again every time it changes. Positions and angles are in game units.
+#[derive(Debug)]
+pub struct PlaybackServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+}
+
+impl PlaybackServer {
+    pub fn start(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let shared = Arc::new(Shared::default());
+        let thread_shared = Arc::clone(&shared);
+        thread::spawn(move || Self::accept_loop(listener, thread_shared));
+
+        Ok(Self { addr, shared })
+    }
+
+    pub const fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Make a new state available to clients. Clients are only notified if something changed.
+    pub fn publish(&self, snapshot: &PlaybackSnapshot) {
+        let Ok(json) = serde_json::to_string(snapshot) else {
+            return;
+        };
+
+        let mut published = self.shared.published.lock().unwrap();
+        if published.json != json {
+            published.json = json;
+            published.version += 1;
+            self.shared.changed.notify_all();
+        }
+    }
+
+    fn accept_loop(listener: TcpListener, shared: Arc<Shared>) {
+        while !shared.is_stopped() {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let client_shared = Arc::clone(&shared);
+                    thread::spawn(move || {
+                        if let Err(e) = Self::handle_client(stream, &client_shared) {
+                            eprintln!("Playback server client error: {}", e);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                Err(e) => {
+                    eprintln!("Playback server stopped: {}", e);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn handle_client(stream: TcpStream, shared: &Shared) -> Result<()> {
+        stream.set_nonblocking(false)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        // skip the rest of the headers; we don't need any of them
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let mut stream = stream;
+        let mut parts = request_line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some("GET"), Some("/state")) => {
+                let (json, _) = shared.current();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    json.len(), json,
+                )?;
+            }
+            (Some("GET"), Some("/events")) => {
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nAccess-Control-Allow-Origin: *\r\nConnection: keep-alive\r\n\r\n",
+                )?;
+
+                let (mut json, mut version) = shared.current();
+                loop {
+                    write!(stream, "data: {}\n\n", json)?;
+                    stream.flush()?;
+                    let Some(next) = shared.wait_for_change(version) else {
+                        break;
+                    };
+                    (json, version) = next;
+                }
+            }
+            _ => {
+                write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for PlaybackServer {
+    fn drop(&mut self) {
+        self.shared.is_stopped.store(true, Ordering::Relaxed);
+        self.shared.changed.notify_all();
+    }
+}
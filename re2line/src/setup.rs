@@ -0,0 +1,67 @@
+use std::io::Write;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::character::CharacterType;
+use crate::record::Recording;
+use crate::server::CharacterSnapshot;
+
+/// Everything needed to recreate the conditions at a room entry for practice. Inventory isn't
+/// included because the recorder doesn't capture it.
+#[derive(Debug, Serialize)]
+pub struct SetupCard {
+    pub room: String,
+    pub entered_from: Option<String>,
+    pub frame_index: usize,
+    pub game_time: String,
+    pub scenario: String,
+    pub rng_index: usize,
+    pub player: Option<CharacterSnapshot>,
+    pub enemies: Vec<CharacterSnapshot>,
+    pub others: Vec<CharacterSnapshot>,
+}
+
+impl SetupCard {
+    /// Describe the state at the start of the room visit containing the given frame
+    pub fn for_room_entry(recording: &Recording, frame_index: usize) -> Result<Self> {
+        let visits = recording.room_visits();
+        let visit_index = visits.iter()
+            .position(|(_, range)| range.contains(&frame_index))
+            .ok_or_else(|| anyhow!("Frame {frame_index} is not part of any room visit"))?;
+        let (state, range) = &visits[visit_index];
+        let entered_from = visit_index.checked_sub(1)
+            .map(|i| visits[i].0)
+            // a new game doesn't come from anywhere
+            .filter(|_| !state.is_new_game_start())
+            .map(|previous| previous.room_id().to_string());
+
+        let characters = state.characters();
+        let player = characters.first().and_then(Option::as_ref).map(CharacterSnapshot::from);
+        let (enemies, others) = characters.iter()
+            .flatten()
+            .filter(|c| c.index() != 0)
+            .partition::<Vec<_>, _>(|c| c.type_() == CharacterType::Enemy);
+
+        Ok(Self {
+            room: state.room_id().to_string(),
+            entered_from,
+            frame_index: range.start,
+            game_time: recording.frames().get(range.start).map(|f| f.time()).unwrap_or_default(),
+            scenario: state.scenario().to_string(),
+            rng_index: state.rng_position(),
+            player,
+            enemies: enemies.into_iter().map(CharacterSnapshot::from).collect(),
+            others: others.into_iter().map(CharacterSnapshot::from).collect(),
+        })
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("setup_{}_{}.json", self.room, self.frame_index)
+    }
+
+    pub fn write(&self, f: impl Write) -> Result<()> {
+        serde_json::to_writer_pretty(f, self)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use re2shared::record::GameField;
+
+use crate::record::{Recording, FRAME_DURATION};
+
+// points on the FPS graph are averaged over this many frames
+pub const FPS_WINDOW_FRAMES: usize = 30;
+// a frame that takes this much longer than it should is a lag frame
+const LAG_FRAME_RATIO: f32 = 1.5;
+// windows where real time is off from the frame count by more than this fraction are flagged
+const DIVERGENCE_THRESHOLD: f32 = 0.1;
+// flagged sections shorter than this are just a few lag frames, which are already listed
+const MIN_DIVERGENT_FRAMES: usize = 90;
+
+/// A stretch of the recording where the game didn't run at 30fps
+#[derive(Debug, Clone)]
+pub struct DivergentSection {
+    pub frames: Range<usize>,
+    pub real_time: Duration,
+}
+
+impl DivergentSection {
+    pub fn expected_time(&self) -> Duration {
+        FRAME_DURATION * self.frames.len() as u32
+    }
+
+    /// How far real time ran ahead of (positive) or behind (negative) the frame count, in percent
+    pub fn drift_percent(&self) -> f32 {
+        let expected = self.expected_time().as_secs_f32();
+        (self.real_time.as_secs_f32() - expected) / expected * 100.0
+    }
+}
+
+/// How the recording's frames lined up with wall-clock time
+#[derive(Debug, Clone, Default)]
+pub struct TimingReport {
+    pub num_timed_frames: usize,
+    pub real_time: Duration,
+    /// Frame index and wall-clock time of frames that took too long
+    pub lag_frames: Vec<(usize, Duration)>,
+    /// Frame index and average FPS of each window of frames
+    pub fps: Vec<[f64; 2]>,
+    pub divergent_sections: Vec<DivergentSection>,
+}
+
+impl TimingReport {
+    pub fn analyze(recording: &Recording) -> Self {
+        let mut report = Self::default();
+        let lag_threshold = FRAME_DURATION.mul_f32(LAG_FRAME_RATIO);
+        let mut window_start = 0usize;
+        let mut window_frames = 0usize;
+        let mut window_time = Duration::ZERO;
+        let mut pending_section: Option<DivergentSection> = None;
+
+        for (i, frame) in recording.frames().iter().enumerate() {
+            let Some(frame_time) = frame.game_changes.iter().find_map(|change| match change {
+                GameField::FrameTime(micros) => Some(Duration::from_micros(*micros as u64)),
+                _ => None,
+            }) else {
+                continue;
+            };
+
+            report.num_timed_frames += 1;
+            report.real_time += frame_time;
+            if frame_time > lag_threshold {
+                report.lag_frames.push((i, frame_time));
+            }
+
+            if window_frames == 0 {
+                window_start = i;
+            }
+            window_frames += 1;
+            window_time += frame_time;
+            if window_frames < FPS_WINDOW_FRAMES {
+                continue;
+            }
+
+            let seconds = window_time.as_secs_f64();
+            if seconds > 0.0 {
+                report.fps.push([window_start as f64, window_frames as f64 / seconds]);
+            }
+
+            let expected = FRAME_DURATION * window_frames as u32;
+            let drift = (window_time.as_secs_f32() - expected.as_secs_f32()).abs() / expected.as_secs_f32();
+            if drift > DIVERGENCE_THRESHOLD {
+                match pending_section {
+                    Some(ref mut section) if section.frames.end == window_start => {
+                        section.frames.end = i + 1;
+                        section.real_time += window_time;
+                    }
+                    _ => {
+                        report.push_section(pending_section.take());
+                        pending_section = Some(DivergentSection { frames: window_start..i + 1, real_time: window_time });
+                    }
+                }
+            }
+
+            window_frames = 0;
+            window_time = Duration::ZERO;
+        }
+        report.push_section(pending_section);
+
+        report
+    }
+
+    fn push_section(&mut self, section: Option<DivergentSection>) {
+        if let Some(section) = section && section.frames.len() >= MIN_DIVERGENT_FRAMES {
+            self.divergent_sections.push(section);
+        }
+    }
+
+    /// Real time covered by the timed frames if the game had run at exactly 30fps
+    pub fn expected_time(&self) -> Duration {
+        FRAME_DURATION * self.num_timed_frames as u32
+    }
+
+    pub fn average_fps(&self) -> f32 {
+        if self.real_time.is_zero() {
+            return 0.0;
+        }
+        self.num_timed_frames as f32 / self.real_time.as_secs_f32()
+    }
+}
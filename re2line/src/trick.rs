@@ -0,0 +1,245 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::aot::Entity;
+use crate::app::RoomId;
+use crate::compare::Checkpoint;
+use crate::record::{InputState, Recording};
+
+// how far past the end of the window we'll keep looking for a late input
+const MAX_LATE_FRAMES: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrickTrigger {
+    RoomEntry,
+    ControlRegained,
+    Aot(u8),
+}
+
+impl TrickTrigger {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::RoomEntry => "Room entry",
+            Self::ControlRegained => "Control regained",
+            Self::Aot(_) => "AOT",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrickInput {
+    Action,
+    Aim,
+    RunCancel,
+    Forward,
+    Backward,
+    Left,
+    Right,
+}
+
+impl TrickInput {
+    pub const fn list() -> [Self; 7] {
+        [Self::Action, Self::Aim, Self::RunCancel, Self::Forward, Self::Backward, Self::Left, Self::Right]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Action => "Action",
+            Self::Aim => "Aim",
+            Self::RunCancel => "Run/cancel",
+            Self::Forward => "Forward",
+            Self::Backward => "Backward",
+            Self::Left => "Left",
+            Self::Right => "Right",
+        }
+    }
+
+    /// Whether the game acts on the input for as long as it's held, as opposed to only on the
+    /// frame it's pressed, so holding it before the trigger is as good as pressing it on time
+    pub const fn is_held_input(&self) -> bool {
+        !matches!(self, Self::Action)
+    }
+
+    pub const fn is_pressed(&self, input: &InputState) -> bool {
+        match self {
+            Self::Action => input.is_action_pressed,
+            Self::Aim => input.is_aim_pressed,
+            Self::RunCancel => input.is_run_cancel_pressed,
+            Self::Forward => input.is_forward_pressed,
+            Self::Backward => input.is_backward_pressed,
+            Self::Left => input.is_left_pressed,
+            Self::Right => input.is_right_pressed,
+        }
+    }
+}
+
+impl Display for TrickInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Tricks that apply in every room, checked alongside the user's own definitions
+pub const BUILTIN_TRICKS: [BuiltinTrick; 2] = [
+    BuiltinTrick {
+        name: "Move on the first frame of control",
+        description: "Every frame spent standing still after a door or cutscene is lost time. Holding forward through the transition counts.",
+        trigger: TrickTrigger::ControlRegained,
+        input: TrickInput::Forward,
+        window_frames: 1,
+    },
+    BuiltinTrick {
+        name: "Run on the first frame of control",
+        description: "Holding run as control returns starts the run without a walking step. Holding it through the transition counts.",
+        trigger: TrickTrigger::ControlRegained,
+        input: TrickInput::RunCancel,
+        window_frames: 1,
+    },
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinTrick {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub trigger: TrickTrigger,
+    pub input: TrickInput,
+    pub window_frames: usize,
+}
+
+impl BuiltinTrick {
+    /// The trick as it applies to the given room
+    pub fn definition(&self, room_id: RoomId) -> TrickDefinition {
+        TrickDefinition {
+            name: self.name.to_string(),
+            room_id,
+            trigger: self.trigger,
+            input: self.input,
+            window_frames: self.window_frames,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrickOutcome {
+    Hit(usize),
+    Early(usize),
+    Late(usize),
+    NoInput,
+}
+
+impl Display for TrickOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hit(offset) => write!(f, "Hit on frame {} of window", offset + 1),
+            Self::Early(frames) => write!(f, "Missed, {} frame(s) early", frames),
+            Self::Late(frames) => write!(f, "Missed, {} frame(s) late", frames),
+            Self::NoInput => write!(f, "Missed, no input"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrickAttempt {
+    pub trigger_frame: usize,
+    pub outcome: TrickOutcome,
+}
+
+/// A trick that requires pressing an input within a window of frames after some trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrickDefinition {
+    pub name: String,
+    pub room_id: RoomId,
+    pub trigger: TrickTrigger,
+    pub input: TrickInput,
+    pub window_frames: usize,
+}
+
+impl TrickDefinition {
+    pub fn new(name: String, room_id: RoomId) -> Self {
+        Self {
+            name,
+            room_id,
+            trigger: TrickTrigger::RoomEntry,
+            input: TrickInput::Action,
+            window_frames: 1,
+        }
+    }
+
+    /// Find each attempt at this trick in the recording. The recording's current frame will be
+    /// changed, so the caller is responsible for restoring it if necessary.
+    pub fn find_attempts(&self, recording: &mut Recording, entities: &[Entity]) -> Vec<TrickAttempt> {
+        let visits: Vec<_> = recording.room_visits()
+            .into_iter()
+            .filter(|(state, _)| state.room_id() == self.room_id)
+            .map(|(_, range)| range)
+            .collect();
+
+        let mut attempts = Vec::new();
+        for visit in visits {
+            let mut last_press = None;
+            let mut trigger_frame = None;
+            let mut first_press = None;
+            let mut was_locked = true;
+            for i in visit.clone() {
+                let Some(state) = recording.set_index(i) else {
+                    break;
+                };
+
+                let is_pressed = self.input.is_pressed(&state.input_state_this_frame());
+                let is_locked = state.control_lock().is_some();
+                let Some(trigger) = trigger_frame else {
+                    let is_triggered = match self.trigger {
+                        TrickTrigger::RoomEntry => i == visit.start,
+                        TrickTrigger::ControlRegained => was_locked && !is_locked,
+                        TrickTrigger::Aot(aot) => Checkpoint::Aot(aot).matches(state, entities),
+                    };
+                    was_locked = is_locked;
+
+                    if is_triggered {
+                        trigger_frame = Some(i);
+                        let is_held = self.input.is_held_input() && self.input.is_pressed(&state.input_state());
+                        if is_pressed || is_held {
+                            first_press = Some(i);
+                            break;
+                        }
+                    } else if is_pressed {
+                        last_press = Some(i);
+                    }
+                    continue;
+                };
+
+                if is_pressed {
+                    first_press = Some(i);
+                    break;
+                }
+
+                // no point scanning the rest of the room once we're well past the window
+                if i - trigger >= self.window_frames + MAX_LATE_FRAMES {
+                    break;
+                }
+            }
+
+            let Some(trigger) = trigger_frame else {
+                continue;
+            };
+
+            let early = last_press.map(|p| trigger - p);
+            let outcome = match first_press.map(|p| p - trigger) {
+                Some(offset) if offset < self.window_frames => TrickOutcome::Hit(offset),
+                Some(offset) => {
+                    let late = offset + 1 - self.window_frames;
+                    match early {
+                        Some(early) if early < late => TrickOutcome::Early(early),
+                        _ => TrickOutcome::Late(late),
+                    }
+                }
+                None => early.map_or(TrickOutcome::NoInput, TrickOutcome::Early),
+            };
+
+            attempts.push(TrickAttempt { trigger_frame: trigger, outcome });
+        }
+
+        attempts
+    }
+}
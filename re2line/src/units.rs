@@ -0,0 +1,250 @@
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use residat::common::{Fixed16, Fixed32, UFixed16};
+use serde::{Deserialize, Serialize};
+
+// the game has no official unit scale, but treating 1000 units as a meter puts characters and
+// rooms at believable sizes
+const UNITS_PER_METER: f32 = 1000.0;
+// the player's collision diameter
+const PLAYER_WIDTH: f32 = 900.0;
+// a full turn in the game's native angle units
+const NATIVE_FULL_TURN: f32 = 4096.0;
+
+static DISTANCE_UNIT: AtomicU8 = AtomicU8::new(DistanceUnit::Raw as u8);
+static ANGLE_UNIT: AtomicU8 = AtomicU8::new(AngleUnit::Degrees as u8);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum DistanceUnit {
+    #[default]
+    Raw,
+    Meters,
+    PlayerWidths,
+}
+
+impl DistanceUnit {
+    pub const fn list() -> [Self; 3] {
+        [Self::Raw, Self::Meters, Self::PlayerWidths]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Raw => "Raw units",
+            Self::Meters => "Meters",
+            Self::PlayerWidths => "Player widths",
+        }
+    }
+
+    const fn suffix(&self) -> &'static str {
+        match self {
+            Self::Raw => "",
+            Self::Meters => "m",
+            Self::PlayerWidths => "pw",
+        }
+    }
+
+    const fn scale(&self) -> f32 {
+        match self {
+            Self::Raw => 1.0,
+            Self::Meters => UNITS_PER_METER,
+            Self::PlayerWidths => PLAYER_WIDTH,
+        }
+    }
+
+    /// The unit distances are currently being displayed in
+    pub fn current() -> Self {
+        match DISTANCE_UNIT.load(Ordering::Relaxed) {
+            1 => Self::Meters,
+            2 => Self::PlayerWidths,
+            _ => Self::Raw,
+        }
+    }
+
+    pub fn make_current(self) {
+        DISTANCE_UNIT.store(self as u8, Ordering::Relaxed);
+    }
+
+    /// Format a distance in game units in this unit
+    pub fn format(&self, units: f32) -> String {
+        match self {
+            Self::Raw => format!("{:.0}", units),
+            _ => format!("{:.2}{}", units / self.scale(), self.suffix()),
+        }
+    }
+}
+
+/// Values that measure a distance in game units
+pub trait GameUnits: Display {
+    fn units(&self) -> f32;
+}
+
+impl GameUnits for Fixed32 {
+    fn units(&self) -> f32 {
+        self.0 as f32
+    }
+}
+
+impl GameUnits for Fixed16 {
+    fn units(&self) -> f32 {
+        self.0 as f32
+    }
+}
+
+impl GameUnits for UFixed16 {
+    fn units(&self) -> f32 {
+        self.0 as f32
+    }
+}
+
+impl GameUnits for i32 {
+    fn units(&self) -> f32 {
+        *self as f32
+    }
+}
+
+/// Displays a distance in the unit the user has chosen. Raw units are displayed exactly as the
+/// underlying value would be.
+#[derive(Debug, Clone, Copy)]
+pub struct Distance<T>(pub T);
+
+impl<T: GameUnits> Display for Distance<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match DistanceUnit::current() {
+            DistanceUnit::Raw => self.0.fmt(f),
+            unit => f.pad(&unit.format(self.0.units())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Native,
+}
+
+impl AngleUnit {
+    pub const fn list() -> [Self; 2] {
+        [Self::Degrees, Self::Native]
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Degrees => "Degrees",
+            Self::Native => "Native (0x1000 per turn)",
+        }
+    }
+
+    const fn other(&self) -> Self {
+        match self {
+            Self::Degrees => Self::Native,
+            Self::Native => Self::Degrees,
+        }
+    }
+
+    /// The unit angles are currently being displayed in
+    pub fn current() -> Self {
+        match ANGLE_UNIT.load(Ordering::Relaxed) {
+            1 => Self::Native,
+            _ => Self::Degrees,
+        }
+    }
+
+    pub fn make_current(self) {
+        ANGLE_UNIT.store(self as u8, Ordering::Relaxed);
+    }
+
+    /// Format an angle given in degrees in this unit
+    pub fn format(&self, degrees: f32, sign_plus: bool) -> String {
+        match (self, sign_plus) {
+            (Self::Degrees, false) => format!("{:.1}°", degrees),
+            (Self::Degrees, true) => format!("{:+.1}°", degrees),
+            (Self::Native, false) => format!("{:.0}", degrees / 360.0 * NATIVE_FULL_TURN),
+            (Self::Native, true) => format!("{:+.0}", degrees / 360.0 * NATIVE_FULL_TURN),
+        }
+    }
+}
+
+/// Values that measure an angle in the game's native units
+pub trait GameAngle {
+    fn native(&self) -> i32;
+
+    fn degrees(&self) -> f32 {
+        self.native() as f32 * 360.0 / NATIVE_FULL_TURN
+    }
+}
+
+impl GameAngle for Fixed32 {
+    fn native(&self) -> i32 {
+        self.0
+    }
+}
+
+impl GameAngle for Fixed16 {
+    fn native(&self) -> i32 {
+        self.0 as i32
+    }
+}
+
+impl GameAngle for i32 {
+    fn native(&self) -> i32 {
+        *self
+    }
+}
+
+/// An angle in degrees, for values the game doesn't store natively
+#[derive(Debug, Clone, Copy)]
+pub struct Degrees(pub f32);
+
+impl GameAngle for Degrees {
+    fn native(&self) -> i32 {
+        (self.0 / 360.0 * NATIVE_FULL_TURN).round() as i32
+    }
+
+    fn degrees(&self) -> f32 {
+        self.0
+    }
+}
+
+/// Displays an angle in the convention the user has chosen
+#[derive(Debug, Clone, Copy)]
+pub struct Angle<T>(pub T);
+
+impl<T: GameAngle> Angle<T> {
+    /// The angle in the chosen convention followed by the other one, for tooltips
+    pub fn both(&self) -> String {
+        let unit = AngleUnit::current();
+        let degrees = self.0.degrees();
+        format!("{} ({})", unit.format(degrees, false), unit.other().format(degrees, false))
+    }
+}
+
+impl<T: GameAngle> Display for Angle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(&AngleUnit::current().format(self.0.degrees(), f.sign_plus()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_format() {
+        assert_eq!(DistanceUnit::Raw.format(1234.4), "1234");
+        assert_eq!(DistanceUnit::Meters.format(1500.0), "1.50m");
+        assert_eq!(DistanceUnit::PlayerWidths.format(1800.0), "2.00pw");
+    }
+
+    #[test]
+    fn test_angle_conversion() {
+        assert_eq!(Fixed16(0x800).degrees(), 180.0);
+        assert_eq!(Degrees(90.0).native(), 0x400);
+        assert_eq!(AngleUnit::Native.format(90.0, false), "1024");
+        assert_eq!(AngleUnit::Degrees.format(-45.0, true), "-45.0°");
+        assert_eq!(AngleUnit::Degrees.format(45.0, true), "+45.0°");
+    }
+}
@@ -0,0 +1,29 @@
+const CRC32_POLYNOMIAL: u32 = 0xEDB88320;
+
+/// Continue a CRC-32 calculation over more data. Start with a CRC of 0.
+pub fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ CRC32_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32_update(0, b"123456789"), 0xCBF43926);
+        assert_eq!(crc32_update(crc32_update(0, b"1234"), b"56789"), 0xCBF43926);
+    }
+}
@@ -1,2 +1,3 @@
+pub mod checksum;
 pub mod record;
 pub mod rng;
@@ -4,8 +4,41 @@ use residat::re2::VSYNCS_PER_SECOND;
 
 use crate::rng::RollType;
 
-pub const RECORD_VERSION: u16 = 2;
+pub const RECORD_VERSION: u16 = 3;
+// frames periodically include a checksum of the frames written since the last one
+pub const HEADER_FLAG_CHECKSUM: u16 = 1;
+// the recorder was configured to leave these out, so their absence doesn't mean anything about the game
+pub const HEADER_FLAG_NO_CHARACTERS: u16 = 2;
+pub const HEADER_FLAG_NO_OBJECTS: u16 = 4;
+pub const HEADER_FLAG_NO_RNG: u16 = 8;
+pub const HEADER_FLAG_NO_SOUNDS: u16 = 0x10;
+pub const HEADER_FLAG_NO_MODEL_PARTS: u16 = 0x20;
+// character fields that are normally skipped, like motion IDs and every model part transform, are recorded
+pub const HEADER_FLAG_VERBOSE_CHARACTERS: u16 = 0x40;
+// sessions that were closed cleanly end with a SessionEnd footer, so one without it was interrupted
+pub const HEADER_FLAG_SESSION_FOOTERS: u16 = 0x80;
+pub const HEADER_FLAG_NO_TIMING: u16 = 0x200;
 pub const MAX_CHARACTER_CHANGES: usize = 21; // this is kind of arbitrary now because there can be multiple PartTransforms and ModelPartTransforms
+pub const KEYFRAME_SIGNATURE: u32 = 0x4D52464B; // "KFRM"
+// a keyframe's Keyframe field comes right after the IGT, RNG roll count, and game field count
+pub const KEYFRAME_FIELD_OFFSET: usize = 8;
+/// Local port the recorder streams frames on while the game is running
+pub const LIVE_PORT: u16 = 8766;
+
+/// A character as it was when the player entered the room
+#[binrw]
+#[derive(Debug, Clone)]
+pub struct EnemySpawn {
+    pub char_index: u8,
+    pub id: u8,
+    pub type_: u8,
+    pub state: [u8; 4],
+    pub flags: u32,
+    pub pos: VECTOR,
+    pub angle: Fixed16,
+    pub velocity: SVECTOR,
+    pub health: i16,
+}
 
 // these enum variants are out of order because it's more efficient for binrw to have the most
 // common variants first
@@ -40,6 +73,8 @@ pub enum GameField {
     RngRoll(u32, u16),
     #[brw(magic = 0u8)] KeysDown(u32),
     #[brw(magic = 1u8)] KeysDownThisFrame(u32),
+    // wall-clock microseconds since the previous frame was recorded
+    #[brw(magic = 28u8)] FrameTime(u32),
     #[brw(magic = 13u8)] GameFlags1(u32),
     #[brw(magic = 14u8)] GameFlags2(u32),
     #[brw(magic = 7u8)]
@@ -64,10 +99,75 @@ pub enum GameField {
     #[brw(magic = 5u8)] StageOffset(u8),
     #[brw(magic = 9u8)]
     ScriptRng(u16),
+    // local time the recorder was started, marking the beginning of a new session in the file
+    #[brw(magic = 16u8)]
+    SessionStart {
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    },
+    // CRC-32 of the serialized frames since the last checksum or session start
+    #[brw(magic = 17u8)] Checksum(u32),
+    // number of frames the game fell behind its target frame rate before this frame
+    #[brw(magic = 18u8)] LagFrames(u8),
+    // a named marker the user placed while recording
+    #[brw(magic = 23u8)]
+    Marker {
+        #[bw(calc = name.len() as u8)]
+        name_len: u8,
+        #[br(count = name_len, try_map = String::from_utf8)]
+        #[bw(map = |name: &String| name.as_bytes().to_vec())]
+        name: String,
+    },
+    // the characters the room's init script set up, recorded once on the frame the player enters
+    // the room
+    #[brw(magic = 31u8)]
+    RoomSpawns {
+        #[bw(calc = spawns.len() as u8)]
+        num_spawns: u8,
+        #[br(count = num_spawns)]
+        spawns: Vec<EnemySpawn>,
+    },
+    // the game clock jumped to a saved time, from loading a save or restoring a save state
+    #[brw(magic = 34u8)] GameLoaded,
+    // this frame records the full state, so a reader can start decoding here. always the first
+    // game field, and always KEYFRAME_SIGNATURE, so damaged files can be scanned for the next one.
+    #[brw(magic = 26u8)] Keyframe(u32),
+    // written in a frame of its own when the recorder closes the file cleanly. the frame isn't part
+    // of the recording itself.
+    #[brw(magic = 27u8)]
+    SessionEnd {
+        num_frames: u32,
+        // byte offsets of the session's keyframes in the file
+        #[bw(calc = keyframe_offsets.len() as u32)]
+        num_keyframes: u32,
+        #[br(count = num_keyframes)]
+        keyframe_offsets: Vec<u32>,
+    },
+}
+
+impl GameField {
+    /// Whether the field describes something that happened on its frame rather than a value that
+    /// carries over to later frames
+    pub const fn is_event(&self) -> bool {
+        matches!(self,
+            Self::RngRoll(..) | Self::KnownRng { .. } | Self::CharacterRng { .. } | Self::ScriptRng(_)
+            | Self::NewGame | Self::LagFrames(_) | Self::Marker { .. }
+            | Self::FrameTime(_) | Self::RoomSpawns { .. } | Self::GameLoaded
+        ) || self.is_file_structure()
+    }
+
+    /// Whether the field describes the recording file itself rather than the game
+    pub const fn is_file_structure(&self) -> bool {
+        matches!(self, Self::SessionStart { .. } | Self::Checksum(_) | Self::Keyframe(_) | Self::SessionEnd { .. })
+    }
 }
 
 #[binrw]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CharacterDiff {
     pub index: u8,
     #[bw(calc = changes.len() as u8)]
@@ -111,7 +211,7 @@ pub struct FrameRecordV1 {
 }
 
 #[binrw]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FrameRecord {
     pub igt_seconds: u32,
     pub igt_frames: u8,
@@ -160,12 +260,27 @@ impl From<FrameRecordV1> for FrameRecord {
 #[derive(Debug)]
 pub struct RecordHeader {
     pub version: u16,
+    #[br(if(version >= 3))]
+    pub flags: u16,
 }
 
 impl RecordHeader {
     pub const fn new() -> Self {
+        Self::with_flags(HEADER_FLAG_CHECKSUM)
+    }
+
+    pub const fn with_flags(flags: u16) -> Self {
         Self {
             version: RECORD_VERSION,
+            flags,
         }
     }
+
+    pub const fn has_flag(&self, flag: u16) -> bool {
+        self.flags & flag != 0
+    }
+
+    pub const fn is_checksummed(&self) -> bool {
+        self.has_flag(HEADER_FLAG_CHECKSUM)
+    }
 }
\ No newline at end of file